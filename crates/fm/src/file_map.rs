@@ -1,5 +1,5 @@
 use codespan_reporting::files::{SimpleFile, SimpleFiles};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::FileManager;
 
@@ -19,6 +19,10 @@ impl PathString {
     pub fn from_path(p: PathBuf) -> Self {
         PathString(p)
     }
+
+    pub fn as_path(&self) -> &Path {
+        &self.0
+    }
 }
 impl From<PathBuf> for PathString {
     fn from(pb: PathBuf) -> PathString {
@@ -64,6 +68,13 @@ impl FileMap {
     pub fn get_file(&self, file_id: FileId) -> Option<File> {
         self.0.get(file_id.0).map(File)
     }
+    pub fn get_path(&self, file_id: FileId) -> Option<&Path> {
+        self.0.get(file_id.0).map(|file| file.name().as_path())
+    }
+    pub fn find_path(&self, path: &Path) -> Option<FileId> {
+        let files = (0..).map_while(|file_id| self.0.get(file_id));
+        files.zip(0..).find(|(file, _)| file.name().as_path() == path).map(|(_, id)| FileId(id))
+    }
 }
 
 impl Default for FileMap {
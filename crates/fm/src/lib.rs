@@ -28,6 +28,16 @@ pub enum FileType {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 struct VirtualPath(PathBuf);
 
+/// The reasons the file of a module declaration may not be found.
+/// Each lists the candidate paths which were looked at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModuleFileError {
+    /// None of the candidate files exist
+    NotFound(Vec<PathBuf>),
+    /// More than one of the candidate files exist
+    Ambiguous(Vec<PathBuf>),
+}
+
 #[derive(Debug, Default)]
 pub struct FileManager {
     file_map: file_map::FileMap,
@@ -39,12 +49,13 @@ impl FileManager {
     // XXX: Maybe use a AsRef<Path> here, for API ergonomics
     pub fn add_file(&mut self, path_to_file: &Path, file_type: FileType) -> Option<FileId> {
         let source = file_reader::read_file_to_string(path_to_file).ok()?;
+        Some(self.add_file_with_source(path_to_file, source, file_type))
+    }
 
-        let file_id = self.file_map.add_file(path_to_file.to_path_buf().into(), source);
-        let path_to_file = virtualize_path(path_to_file, file_type);
-        self.register_path(file_id, path_to_file);
-
-        Some(file_id)
+    fn add_file_with_source(&mut self, path: &Path, source: String, file_type: FileType) -> FileId {
+        let file_id = self.file_map.add_file(path.to_path_buf().into(), source);
+        self.register_path(file_id, virtualize_path(path, file_type));
+        file_id
     }
 
     fn register_path(&mut self, file_id: FileId, path: VirtualPath) {
@@ -57,44 +68,84 @@ impl FileManager {
         assert!(old_value.is_none(), "ice: the same path was inserted into the file manager twice");
     }
 
-    pub fn fetch_file(&mut self, file_id: FileId) -> File {
+    pub fn fetch_file(&self, file_id: FileId) -> File {
         // Unwrap as we ensure that all file_id's map to a corresponding file in the file map
         self.file_map.get_file(file_id).unwrap()
     }
+
+    /// Returns the path the file was read from
+    pub fn file_path(&self, file_id: FileId) -> &Path {
+        // Unwrap as we ensure that all file_id's map to a corresponding file in the file map
+        self.file_map.get_path(file_id).unwrap()
+    }
+
+    /// Returns the id of the file read from the given path, if it was added to the file manager
+    pub fn find_file(&self, path: &Path) -> Option<FileId> {
+        self.file_map.find_path(path)
+    }
+
     fn path(&mut self, file_id: FileId) -> &Path {
         // Unwrap as we ensure that all file_ids are created by the file manager
         // So all file_ids will points to a corresponding path
         self.id_to_path.get(&file_id).unwrap().0.as_path()
     }
 
-    pub fn resolve_path(&mut self, anchor: FileId, mod_name: &str) -> Result<FileId, String> {
-        let mut candidate_files = Vec::new();
-
+    /// Finds the file of the module `mod_name` declared in the file `anchor`.
+    ///
+    /// The submodules of `dir/foo.nr` and of `dir/foo/mod.nr` are both looked for in `dir/foo`,
+    /// where a submodule `bar` may either be in `dir/foo/bar.nr` or in `dir/foo/bar/mod.nr`.
+    pub fn resolve_path(
+        &mut self,
+        anchor: FileId,
+        mod_name: &str,
+    ) -> Result<FileId, ModuleFileError> {
         let dir = self.path(anchor).to_path_buf();
 
-        candidate_files.push(dir.join(format!("{mod_name}.{FILE_EXTENSION}")));
-
-        for candidate in candidate_files.iter() {
-            if let Some(file_id) = self.add_file(candidate, FileType::Normal) {
-                return Ok(file_id);
+        let candidate_files = vec![
+            dir.join(format!("{mod_name}.{FILE_EXTENSION}")),
+            dir.join(mod_name).join(format!("mod.{FILE_EXTENSION}")),
+        ];
+
+        let mut found_files: Vec<_> = candidate_files
+            .iter()
+            .filter_map(|candidate| {
+                let source = file_reader::read_file_to_string(candidate).ok()?;
+                Some((candidate, source))
+            })
+            .collect();
+
+        match found_files.len() {
+            0 => Err(ModuleFileError::NotFound(candidate_files)),
+            1 => {
+                let (path, source) = found_files.remove(0);
+                Ok(self.add_file_with_source(path, source, FileType::Normal))
+            }
+            _ => {
+                let paths = found_files.into_iter().map(|(path, _)| path.clone()).collect();
+                Err(ModuleFileError::Ambiguous(paths))
             }
         }
-
-        Err(candidate_files.remove(0).as_os_str().to_str().unwrap().to_owned())
     }
 }
 
 /// Takes a path to a noir file. This will panic on paths to directories
-/// Returns
-/// For Normal filetypes, given "src/mod.nr" this method returns "src/mod"
-/// For Root filetypes, given "src/mod.nr" this method returns "src"
+/// Returns the directory the submodules of the file are looked for in.
+/// For Normal filetypes, given "src/foo.nr" this method returns "src/foo"
+/// For Normal filetypes named mod.nr, given "src/foo/mod.nr" this method returns "src/foo"
+/// For Root filetypes, given "src/main.nr" this method returns "src"
 fn virtualize_path(path: &Path, file_type: FileType) -> VirtualPath {
     let mut path = path.to_path_buf();
+    let mod_file_name = format!("mod.{FILE_EXTENSION}");
+    let is_mod_file = path.file_name().map_or(false, |name| name == mod_file_name.as_str());
     let path = match file_type {
         FileType::Root => {
             path.pop();
             path
         }
+        FileType::Normal if is_mod_file => {
+            path.pop();
+            path
+        }
         FileType::Normal => {
             let base = path.parent().unwrap();
             let path_no_ext: PathBuf =
@@ -177,4 +228,49 @@ mod tests {
         // Now check for files in it's subdirectory
         fm.resolve_path(sub_dir_file_id, "foo").unwrap();
     }
+
+    #[test]
+    fn path_resolve_mod_file() {
+        let mut fm = FileManager::default();
+
+        // We have:
+        // - dir/lib.nr
+        // - dir/foo/mod.nr
+        // - dir/foo/bar/mod.nr
+        // - dir/foo/bar/baz.nr
+        let dir = tempdir().unwrap();
+        let file_path = dummy_file_path(&dir, "lib.nr");
+        std::fs::create_dir_all(dir.path().join("foo").join("bar")).unwrap();
+        dummy_file_path(&dir, "foo/mod.nr");
+        dummy_file_path(&dir, "foo/bar/mod.nr");
+        dummy_file_path(&dir, "foo/bar/baz.nr");
+
+        let file_id = fm.add_file(&file_path, FileType::Root).unwrap();
+        let foo_file_id = fm.resolve_path(file_id, "foo").unwrap();
+        assert!(fm.path(foo_file_id).ends_with("foo"));
+
+        let bar_file_id = fm.resolve_path(foo_file_id, "bar").unwrap();
+        let baz_file_id = fm.resolve_path(bar_file_id, "baz").unwrap();
+        assert_eq!(fm.file_path(baz_file_id), dir.path().join("foo/bar/baz.nr"));
+    }
+
+    #[test]
+    fn path_resolve_lists_candidates() {
+        let mut fm = FileManager::default();
+
+        let dir = tempdir().unwrap();
+        let file_path = dummy_file_path(&dir, "lib.nr");
+        let file_id = fm.add_file(&file_path, FileType::Root).unwrap();
+
+        let expected = vec![dir.path().join("foo.nr"), dir.path().join("foo/mod.nr")];
+        let error = fm.resolve_path(file_id, "foo").unwrap_err();
+        assert_eq!(error, ModuleFileError::NotFound(expected.clone()));
+
+        // A module may not be in both places at once
+        std::fs::create_dir_all(dir.path().join("foo")).unwrap();
+        dummy_file_path(&dir, "foo.nr");
+        dummy_file_path(&dir, "foo/mod.nr");
+        let error = fm.resolve_path(file_id, "foo").unwrap_err();
+        assert_eq!(error, ModuleFileError::Ambiguous(expected));
+    }
 }
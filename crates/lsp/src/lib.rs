@@ -0,0 +1,473 @@
+#![forbid(unsafe_code)]
+#![warn(unused_crate_dependencies, unused_extern_crates)]
+#![warn(unreachable_pub)]
+#![warn(clippy::semicolon_if_nothing_returned)]
+
+//! A language server for Noir, which speaks the language server protocol over stdio.
+//!
+//! Each package is checked whenever one of its files is opened or saved. The errors and
+//! warnings of the check are published to the client, and the checked package is kept around
+//! to answer go-to-definition and hover requests on its files.
+
+mod position;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use fm::FileId;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidOpenTextDocument, DidSaveTextDocument, Notification as _, PublishDiagnostics, ShowMessage,
+};
+use lsp_types::request::{GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, GotoDefinitionParams, GotoDefinitionResponse, Hover,
+    HoverContents, HoverParams, HoverProviderCapability, MarkupContent, MarkupKind, MessageType,
+    OneOf, PublishDiagnosticsParams, Range, ServerCapabilities, ShowMessageParams,
+    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, Url,
+};
+use noirc_driver::Driver;
+use noirc_errors::{CustomDiagnostic, DiagnosticKind, Location};
+use noirc_frontend::graph::LOCAL_CRATE;
+use noirc_frontend::node_interner::DefinitionKind;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LspError {
+    #[error(transparent)]
+    ProtocolError(#[from] lsp_server::ProtocolError),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("The connection to the client was closed unexpectedly")]
+    Disconnected,
+}
+
+/// Runs the language server until the client asks it to shut down.
+///
+/// `setup_driver` creates a driver for the package containing the given file, with its
+/// dependencies already added.
+pub fn start_server(
+    setup_driver: impl Fn(&Path) -> Result<Driver, String>,
+) -> Result<(), LspError> {
+    let (connection, io_threads) = Connection::stdio();
+    connection.initialize(serde_json::to_value(capabilities())?)?;
+
+    let mut server = Server { connection, setup_driver, packages: HashMap::new() };
+    server.run()?;
+
+    // The connection has to be closed before waiting for the threads using it
+    drop(server);
+    io_threads.join()?;
+    Ok(())
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+            open_close: Some(true),
+            save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+            ..TextDocumentSyncOptions::default()
+        })),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..ServerCapabilities::default()
+    }
+}
+
+/// A checked package, along with the files which currently have diagnostics from it
+struct Package {
+    driver: Driver,
+    files_with_diagnostics: Vec<Url>,
+}
+
+struct Server<F> {
+    connection: Connection,
+    setup_driver: F,
+    /// The checked packages, by the path of their root file
+    packages: HashMap<PathBuf, Package>,
+}
+
+impl<F: Fn(&Path) -> Result<Driver, String>> Server<F> {
+    fn run(&mut self) -> Result<(), LspError> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.handle_request(request);
+                    self.send(Message::Response(response))?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        match request.method.as_str() {
+            GotoDefinition::METHOD => {
+                self.respond::<GotoDefinition>(request, Self::goto_definition)
+            }
+            HoverRequest::METHOD => self.respond::<HoverRequest>(request, Self::hover),
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request: {}", request.method),
+            ),
+        }
+    }
+
+    fn respond<R: lsp_types::request::Request>(
+        &self,
+        request: Request,
+        handler: impl Fn(&Self, R::Params) -> R::Result,
+    ) -> Response {
+        let id = request.id.clone();
+        match request.extract::<R::Params>(R::METHOD) {
+            Ok((id, params)) => Response::new_ok(id, handler(self, params)),
+            Err(error) => Response::new_err(
+                id,
+                ErrorCode::InvalidParams as i32,
+                format!("Invalid parameters for {}: {error:?}", R::METHOD),
+            ),
+        }
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), LspError> {
+        // Malformed notifications are ignored, as there is no way to answer them
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                match notification
+                    .extract::<lsp_types::DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)
+                {
+                    Ok(params) => self.check(&params.text_document.uri),
+                    Err(_) => Ok(()),
+                }
+            }
+            DidSaveTextDocument::METHOD => {
+                match notification
+                    .extract::<lsp_types::DidSaveTextDocumentParams>(DidSaveTextDocument::METHOD)
+                {
+                    Ok(params) => self.check(&params.text_document.uri),
+                    Err(_) => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the package containing the file at `uri`, and publishes its errors and warnings
+    fn check(&mut self, uri: &Url) -> Result<(), LspError> {
+        let path = match uri.to_file_path() {
+            Ok(path) => path,
+            Err(_) => return Ok(()),
+        };
+        let mut driver = match (self.setup_driver)(&path) {
+            Ok(driver) => driver,
+            Err(message) => {
+                return self
+                    .notify::<ShowMessage>(ShowMessageParams { typ: MessageType::ERROR, message })
+            }
+        };
+
+        let diagnostics = driver.collect_diagnostics();
+        let context = driver.context();
+        let root_file_id = context.crate_graph[LOCAL_CRATE].root_file_id;
+        let root = context.file_manager.file_path(root_file_id).to_path_buf();
+
+        let mut diagnostics_by_file: HashMap<Url, Vec<Diagnostic>> = HashMap::new();
+        for diagnostic in diagnostics {
+            // Files without a path on disk, such as the ones of the standard library, are skipped
+            let path = context.file_manager.file_path(diagnostic.file_id);
+            if let Ok(uri) = Url::from_file_path(path) {
+                let source = context.file_manager.fetch_file(diagnostic.file_id).source();
+                let diagnostic = to_lsp_diagnostic(source, &diagnostic.diagnostic);
+                diagnostics_by_file.entry(uri).or_default().push(diagnostic);
+            }
+        }
+
+        // The diagnostics of the previous check are replaced, so the files which no longer have
+        // any must be cleared explicitly.
+        if let Some(previous) = self.packages.remove(&root) {
+            for uri in previous.files_with_diagnostics {
+                diagnostics_by_file.entry(uri).or_default();
+            }
+        }
+
+        let mut files_with_diagnostics = Vec::new();
+        for (uri, diagnostics) in diagnostics_by_file {
+            if !diagnostics.is_empty() {
+                files_with_diagnostics.push(uri.clone());
+            }
+            self.notify::<PublishDiagnostics>(PublishDiagnosticsParams {
+                uri,
+                diagnostics,
+                version: None,
+            })?;
+        }
+
+        self.packages.insert(root, Package { driver, files_with_diagnostics });
+        Ok(())
+    }
+
+    fn goto_definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let (driver, file_id, offset) = self.locate(&params.text_document_position_params)?;
+        let interner = &driver.context().def_interner;
+
+        let reference = interner.find_reference(file_id, offset)?;
+        let location = interner.definition_location(reference.id)?;
+        to_lsp_location(driver, location).map(GotoDefinitionResponse::Scalar)
+    }
+
+    /// Shows the type of the name or expression being hovered
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let (driver, file_id, offset) = self.locate(&params.text_document_position_params)?;
+        let context = driver.context();
+        let interner = &context.def_interner;
+
+        let (contents, span) = match interner.find_reference(file_id, offset) {
+            Some(reference) => {
+                let definition = interner.definition(reference.id);
+                let typ = match &definition.kind {
+                    DefinitionKind::Function(func_id) => interner.function_meta(func_id).typ,
+                    _ => interner.id_type(reference.id),
+                };
+                let mutable = if definition.mutable { "mut " } else { "" };
+                (format!("{mutable}{}: {typ}", definition.name), reference.location.span)
+            }
+            None => {
+                let expr_id = interner.find_expression(file_id, offset)?;
+                (interner.id_type(expr_id).to_string(), interner.expr_span(&expr_id))
+            }
+        };
+
+        let source = context.file_manager.fetch_file(file_id).source();
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```noir\n{contents}\n```"),
+            }),
+            range: Some(position::to_range(source, span)),
+        })
+    }
+
+    /// Finds the checked package containing the file of `params`, returning its driver along
+    /// with the id of the file and the offset of the position within it.
+    fn locate(&self, params: &TextDocumentPositionParams) -> Option<(&Driver, FileId, u32)> {
+        let path = params.text_document.uri.to_file_path().ok()?;
+        self.packages.values().find_map(|package| {
+            let file_manager = &package.driver.context().file_manager;
+            let file_id = file_manager.find_file(&path)?;
+            let source = file_manager.fetch_file(file_id).source();
+            let offset = position::to_offset(source, params.position)?;
+            Some((&package.driver, file_id, offset))
+        })
+    }
+
+    fn notify<N: lsp_types::notification::Notification>(
+        &self,
+        params: N::Params,
+    ) -> Result<(), LspError> {
+        self.send(Message::Notification(Notification::new(N::METHOD.to_string(), params)))
+    }
+
+    fn send(&self, message: Message) -> Result<(), LspError> {
+        self.connection.sender.send(message).map_err(|_| LspError::Disconnected)
+    }
+}
+
+fn to_lsp_diagnostic(source: &str, diagnostic: &CustomDiagnostic) -> Diagnostic {
+    // The primary label is the first one, the others are appended to the message along with
+    // the notes as clients show them separately from the code.
+    let range = diagnostic
+        .secondaries
+        .first()
+        .map_or_else(Range::default, |label| position::to_range(source, label.span));
+
+    let mut message = diagnostic.message.clone();
+    let labels = diagnostic.secondaries.iter().map(|label| &label.message);
+    for line in labels.chain(&diagnostic.notes).filter(|line| !line.is_empty()) {
+        message.push('\n');
+        message.push_str(line);
+    }
+
+    let severity = match diagnostic.kind {
+        DiagnosticKind::Error => DiagnosticSeverity::ERROR,
+        DiagnosticKind::Warning => DiagnosticSeverity::WARNING,
+    };
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        source: Some("nargo".to_string()),
+        message,
+        ..Diagnostic::default()
+    }
+}
+
+fn to_lsp_location(driver: &Driver, location: Location) -> Option<lsp_types::Location> {
+    let file_manager = &driver.context().file_manager;
+    let uri = Url::from_file_path(file_manager.file_path(location.file)).ok()?;
+    let source = file_manager.fetch_file(location.file).source();
+    Some(lsp_types::Location::new(uri, position::to_range(source, location.span)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    use acvm::Language;
+    use lsp_server::{Connection, Message};
+    use lsp_types::notification::{Notification as _, PublishDiagnostics};
+    use lsp_types::{
+        Diagnostic, DiagnosticSeverity, GotoDefinitionParams, GotoDefinitionResponse,
+        HoverContents, HoverParams, Position, PublishDiagnosticsParams, TextDocumentIdentifier,
+        TextDocumentPositionParams, Url,
+    };
+    use noirc_driver::Driver;
+    use noirc_frontend::graph::CrateType;
+
+    use super::Server;
+
+    const PROGRAM: &str = "fn main(x: Field) {
+    let y = double(x);
+    assert(y == x + x);
+}
+
+fn double(x: Field) -> Field {
+    x * 2
+}
+";
+
+    /// Creates a server checking the crate rooted at `root`, along with the client end of its
+    /// connection.
+    fn server(root: PathBuf) -> (Server<impl Fn(&Path) -> Result<Driver, String>>, Connection) {
+        let setup_driver = move |_: &Path| -> Result<Driver, String> {
+            let mut driver = Driver::new(
+                &Language::R1CS,
+                #[allow(deprecated)]
+                Box::new(acvm::default_is_opcode_supported(Language::R1CS)),
+            );
+            driver.create_local_crate(&root, CrateType::Binary);
+            Ok(driver)
+        };
+        let (connection, client) = Connection::memory();
+        (Server { connection, setup_driver, packages: HashMap::new() }, client)
+    }
+
+    fn published_diagnostics(client: &Connection) -> HashMap<Url, Vec<Diagnostic>> {
+        client
+            .receiver
+            .try_iter()
+            .filter_map(|message| match message {
+                Message::Notification(notification)
+                    if notification.method == PublishDiagnostics::METHOD =>
+                {
+                    let params: PublishDiagnosticsParams =
+                        serde_json::from_value(notification.params).unwrap();
+                    Some((params.uri, params.diagnostics))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn position_params(uri: &Url, line: u32, character: u32) -> TextDocumentPositionParams {
+        TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(uri.clone()),
+            Position::new(line, character),
+        )
+    }
+
+    #[test]
+    fn publishes_and_clears_diagnostics() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("main.nr");
+        std::fs::write(&root, "fn main(x: Field) {\n    assert(x == y);\n}\n").unwrap();
+        let uri = Url::from_file_path(&root).unwrap();
+
+        let (mut server, client) = server(root.clone());
+        server.check(&uri).unwrap();
+        let diagnostics = &published_diagnostics(&client)[&uri];
+        assert!(
+            diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.severity == Some(DiagnosticSeverity::ERROR)
+                    && diagnostic.range.start == Position::new(1, 16)),
+            "expected an error on the undeclared variable, got {diagnostics:?}"
+        );
+
+        // Fixing the error clears the diagnostics of the file
+        std::fs::write(&root, PROGRAM).unwrap();
+        server.check(&uri).unwrap();
+        assert_eq!(published_diagnostics(&client)[&uri], Vec::new());
+    }
+
+    #[test]
+    fn finds_definitions() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("main.nr");
+        std::fs::write(&root, PROGRAM).unwrap();
+        let uri = Url::from_file_path(&root).unwrap();
+
+        let (mut server, _client) = server(root);
+        server.check(&uri).unwrap();
+
+        let params = GotoDefinitionParams {
+            text_document_position_params: position_params(&uri, 1, 14),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        match server.goto_definition(params) {
+            Some(GotoDefinitionResponse::Scalar(location)) => {
+                assert_eq!(location.uri, uri);
+                assert_eq!(location.range.start, Position::new(5, 3));
+                assert_eq!(location.range.end, Position::new(5, 9));
+            }
+            other => panic!("expected the definition of `double`, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn shows_types_on_hover() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("main.nr");
+        std::fs::write(&root, PROGRAM).unwrap();
+        let uri = Url::from_file_path(&root).unwrap();
+
+        let (mut server, _client) = server(root);
+        server.check(&uri).unwrap();
+
+        let hover = |line, character| {
+            let params = HoverParams {
+                text_document_position_params: position_params(&uri, line, character),
+                work_done_progress_params: Default::default(),
+            };
+            match server.hover(params)?.contents {
+                HoverContents::Markup(markup) => Some(markup.value),
+                contents => panic!("expected markdown, got {contents:?}"),
+            }
+        };
+        // A variable, then the innermost expression around the `+` operator
+        assert_eq!(hover(2, 11).as_deref(), Some("```noir\ny: Field\n```"));
+        assert_eq!(hover(2, 18).as_deref(), Some("```noir\nField\n```"));
+    }
+
+    #[test]
+    fn ignores_files_which_were_not_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("main.nr");
+        std::fs::write(&root, PROGRAM).unwrap();
+
+        let (mut server, _client) = server(root.clone());
+        server.check(&Url::from_file_path(&root).unwrap()).unwrap();
+
+        let other = Url::from_file_path(dir.path().join("other.nr")).unwrap();
+        assert!(server.locate(&position_params(&other, 1, 14)).is_none());
+    }
+}
@@ -0,0 +1,73 @@
+//! Conversions between the character offsets used by the Noir lexer and the positions used
+//! by the language server protocol, which are made of a line and a UTF-16 column.
+
+use lsp_types::{Position, Range};
+use noirc_errors::Span;
+
+/// Returns the position of the character at `offset` within `source`
+pub(crate) fn to_position(source: &str, offset: u32) -> Position {
+    let mut position = Position::new(0, 0);
+    for c in source.chars().take(offset as usize) {
+        if c == '\n' {
+            position.line += 1;
+            position.character = 0;
+        } else {
+            position.character += c.len_utf16() as u32;
+        }
+    }
+    position
+}
+
+/// Returns the offset of the character at `position` within `source`.
+///
+/// Positions past the end of their line are moved back to the end of the line, while positions
+/// past the last line of `source` have no offset.
+pub(crate) fn to_offset(source: &str, position: Position) -> Option<u32> {
+    let mut line = 0;
+    let mut character = 0;
+    for (c, offset) in source.chars().zip(0..) {
+        if line == position.line && (character >= position.character || c == '\n') {
+            return Some(offset);
+        }
+        if c == '\n' {
+            line += 1;
+            character = 0;
+        } else {
+            character += c.len_utf16() as u32;
+        }
+    }
+    (line == position.line).then(|| source.chars().count() as u32)
+}
+
+/// Returns the range covered by `span` within `source`.
+///
+/// Like in the error reporter, the end of a span is the last character it covers.
+pub(crate) fn to_range(source: &str, span: Span) -> Range {
+    Range::new(to_position(source, span.start()), to_position(source, span.end() + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "fn main() {\n    let é = 1;\n}";
+
+    #[test]
+    fn converts_offsets_to_positions() {
+        assert_eq!(to_position(SOURCE, 0), Position::new(0, 0));
+        assert_eq!(to_position(SOURCE, 11), Position::new(0, 11));
+        assert_eq!(to_position(SOURCE, 12), Position::new(1, 0));
+        assert_eq!(to_position(SOURCE, 21), Position::new(1, 9));
+        assert_eq!(to_position(SOURCE, 27), Position::new(2, 0));
+    }
+
+    #[test]
+    fn converts_positions_to_offsets() {
+        for offset in 0..SOURCE.chars().count() as u32 {
+            assert_eq!(to_offset(SOURCE, to_position(SOURCE, offset)), Some(offset));
+        }
+        assert_eq!(to_offset(SOURCE, Position::new(0, 40)), Some(11));
+        assert_eq!(to_offset(SOURCE, Position::new(2, 1)), Some(28));
+        assert_eq!(to_offset(SOURCE, Position::new(3, 0)), None);
+    }
+}
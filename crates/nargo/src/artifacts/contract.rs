@@ -1,6 +1,6 @@
 use acvm::acir::circuit::Circuit;
 use noirc_abi::Abi;
-use noirc_driver::ContractFunctionType;
+use noirc_driver::{ContractFunctionType, CustomAttribute, SourceMap};
 use serde::{Deserialize, Serialize};
 
 /// `PreprocessedContract` represents a Noir contract which has been preprocessed by a particular backend proving system.
@@ -28,6 +28,8 @@ pub struct PreprocessedContractFunction {
 
     pub function_type: ContractFunctionType,
 
+    pub custom_attributes: Vec<CustomAttribute>,
+
     pub abi: Abi,
 
     #[serde(
@@ -36,6 +38,10 @@ pub struct PreprocessedContractFunction {
     )]
     pub bytecode: Circuit,
 
+    /// The source code locations each opcode of the bytecode is generated from
+    #[serde(default)]
+    pub source_map: SourceMap,
+
     pub proving_key: Vec<u8>,
     pub verification_key: Vec<u8>,
 }
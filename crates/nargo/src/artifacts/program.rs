@@ -1,5 +1,6 @@
 use acvm::acir::circuit::Circuit;
 use noirc_abi::Abi;
+use noirc_driver::{CustomAttribute, SourceMap};
 use serde::{Deserialize, Serialize};
 
 /// `PreprocessedProgram` represents a Noir program which has been preprocessed by a particular backend proving system.
@@ -18,6 +19,14 @@ pub struct PreprocessedProgram {
     )]
     pub bytecode: Circuit,
 
+    /// The source code locations each opcode of the bytecode is generated from
+    #[serde(default)]
+    pub source_map: SourceMap,
+
+    /// The attributes of the main function which have no meaning to the compiler
+    #[serde(default)]
+    pub custom_attributes: Vec<CustomAttribute>,
+
     pub proving_key: Vec<u8>,
     pub verification_key: Vec<u8>,
 }
@@ -1,4 +1,6 @@
-use acvm::OpcodeResolutionError;
+use acvm::{FieldElement, OpcodeResolutionError};
+use noirc_abi::errors::InputParserError;
+use noirc_driver::{Assertion, AssertionTable};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -10,4 +12,47 @@ pub enum NargoError {
     /// ACIR circuit solving error
     #[error(transparent)]
     SolvingError(#[from] OpcodeResolutionError),
+
+    /// The constraint of an opcode of the circuit is not satisfied by the witness
+    #[error("Cannot satisfy the constraint of opcode {opcode_index}")]
+    UnsatisfiedOpcode { opcode_index: usize },
+
+    /// Error while providing the values requested by an oracle
+    #[error(transparent)]
+    OracleError(#[from] OracleResolutionError),
+}
+
+impl NargoError {
+    /// Returns the index of the opcode which failed, if it is known.
+    pub fn opcode_index(&self) -> Option<usize> {
+        match self {
+            NargoError::UnsatisfiedOpcode { opcode_index } => Some(*opcode_index),
+            _ => None,
+        }
+    }
+
+    /// Returns the assertion of the program which failed, if it caused this error.
+    pub fn failed_assertion<'a>(&self, assertions: &'a AssertionTable) -> Option<&'a Assertion> {
+        self.opcode_index().and_then(|opcode_index| assertions.get(&opcode_index))
+    }
+}
+
+/// Errors covering situations where an oracle call made during execution could not be resolved.
+#[derive(Debug, Error)]
+pub enum OracleResolutionError {
+    /// No canned response matches the oracle call.
+    #[error("No response was provided for oracle `{name}` called with inputs {inputs:?}")]
+    MissingResponse { name: String, inputs: Vec<FieldElement> },
+
+    /// The response provided for the oracle call doesn't have the expected number of outputs.
+    #[error("Oracle `{name}` expects {expected} output values but the provided response contains {found}")]
+    OutputCountMismatch { name: String, expected: usize, found: usize },
+
+    /// The file of canned oracle responses is unreadable.
+    #[error("Oracle responses file is badly formed, could not parse.\n\n {0}")]
+    MalformedResponses(String),
+
+    /// A value in the file of canned oracle responses is not a valid field element.
+    #[error(transparent)]
+    InvalidResponseValue(#[from] InputParserError),
 }
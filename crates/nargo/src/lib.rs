@@ -9,7 +9,9 @@
 
 pub mod artifacts;
 mod errors;
+pub mod lockfile;
 pub mod manifest;
 pub mod ops;
+pub mod workspace;
 
-pub use self::errors::NargoError;
+pub use self::errors::{NargoError, OracleResolutionError};
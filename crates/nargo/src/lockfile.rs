@@ -0,0 +1,107 @@
+//! The `Nargo.lock` file records exactly which version of each remote dependency was used,
+//! so that later builds can check they resolve to the same source code.
+use serde::{Deserialize, Serialize};
+
+use crate::manifest::GitDependency;
+
+const LOCKFILE_HEADER: &str =
+    "# This file is automatically generated by nargo.\n# It is not intended for manual editing.\n";
+
+/// The version of the lockfile format written by this version of nargo.
+pub const LOCKFILE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "dependency")]
+    pub dependencies: Vec<LockedDependency>,
+}
+
+/// A git dependency as it was resolved.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LockedDependency {
+    /// The url of the repository
+    pub git: String,
+    /// The tag, branch or commit requested by the manifest, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    /// The directory of the package within the repository
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+    /// The SHA of the commit the dependency resolved to
+    pub commit: String,
+    /// The hash of the contents of the dependency
+    pub checksum: String,
+}
+
+impl LockedDependency {
+    pub fn new(dependency: &GitDependency, commit: String, checksum: String) -> Self {
+        let GitDependency { git, tag, branch, rev, directory } = dependency.clone();
+        LockedDependency { git, tag, branch, rev, directory, commit, checksum }
+    }
+
+    /// Returns whether this is how the given dependency was resolved.
+    pub fn is_resolution_of(&self, dependency: &GitDependency) -> bool {
+        self.git == dependency.git
+            && self.tag == dependency.tag
+            && self.branch == dependency.branch
+            && self.rev == dependency.rev
+            && self.directory == dependency.directory
+    }
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Lockfile { version: LOCKFILE_VERSION, dependencies: Vec::new() }
+    }
+}
+
+impl Lockfile {
+    /// Creates a lockfile holding the given dependencies, in a deterministic order.
+    pub fn new(mut dependencies: Vec<LockedDependency>) -> Self {
+        dependencies.sort();
+        dependencies.dedup();
+        Lockfile { version: LOCKFILE_VERSION, dependencies }
+    }
+
+    pub fn from_toml_str(toml_as_string: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(toml_as_string)
+    }
+
+    pub fn to_toml_string(&self) -> String {
+        let lockfile = toml::to_string(self).expect("a lockfile can always be serialized");
+        format!("{LOCKFILE_HEADER}\n{lockfile}")
+    }
+
+    /// Returns how the given dependency was resolved.
+    pub fn find(&self, dependency: &GitDependency) -> Option<&LockedDependency> {
+        self.dependencies.iter().find(|locked| locked.is_resolution_of(dependency))
+    }
+}
+
+#[test]
+fn roundtrip_lockfile() {
+    let mut dependency = GitDependency {
+        git: "https://github.com/noir-lang/example".to_owned(),
+        tag: Some("v0.1.0".to_owned()),
+        branch: None,
+        rev: None,
+        directory: None,
+    };
+    let lockfile = Lockfile::new(vec![LockedDependency::new(
+        &dependency,
+        "0123456789abcdef0123456789abcdef01234567".to_owned(),
+        "00".to_owned(),
+    )]);
+
+    let parsed = Lockfile::from_toml_str(&lockfile.to_toml_string()).unwrap();
+    assert_eq!(parsed, lockfile);
+    assert!(parsed.find(&dependency).is_some());
+
+    dependency.tag = Some("v0.2.0".to_owned());
+    assert!(parsed.find(&dependency).is_none());
+}
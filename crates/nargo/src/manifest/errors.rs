@@ -23,4 +23,36 @@ pub enum InvalidPackageError {
     /// Package has both a `main.nr` (for binaries) and `lib.nr` (for libraries)
     #[error("package cannot contain both a `lib.nr` and a `main.nr`")]
     ContainsMultipleCrates,
+
+    /// Manifest has both a `[package]` and a `[workspace]` section.
+    #[error("Nargo.toml cannot declare both a package and a workspace")]
+    PackageAndWorkspace,
+
+    /// A package was expected but the manifest declares a workspace.
+    #[error("{} declares a workspace rather than a package", .0.display())]
+    UnexpectedWorkspace(PathBuf),
+
+    /// Two packages of a workspace have the same name.
+    #[error("workspace contains multiple packages named `{0}`")]
+    DuplicatePackageName(String),
+
+    /// A git dependency gives more than one of a tag, a branch and a commit to use.
+    #[error("dependency {0} may only specify one of `tag`, `branch` and `rev`")]
+    AmbiguousGitReference(String),
+
+    /// A git dependency gives an abbreviated commit, which can't be fetched on its own.
+    #[error("dependency {dependency} must give the full SHA of commit {rev} as its `rev`")]
+    AbbreviatedRev { dependency: String, rev: String },
+
+    /// The directory of a git dependency's package may lead outside of its repository.
+    #[error("dependency {dependency} must give a relative path within its repository as its `directory`, not `{directory}`")]
+    InvalidGitDirectory { dependency: String, directory: String },
+
+    /// The manifest doesn't name the package and neither can its directory.
+    #[error("cannot name the package in {}, please give it a name in its Nargo.toml", .0.display())]
+    UnnamedPackage(PathBuf),
+
+    /// No package of the workspace has the requested name.
+    #[error("workspace has no package named `{0}`")]
+    UnknownPackage(String),
 }
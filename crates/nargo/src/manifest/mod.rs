@@ -1,9 +1,32 @@
 use serde::Deserialize;
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Component, Path, PathBuf},
+};
 
 mod errors;
 pub use self::errors::InvalidPackageError;
 
+/// The contents of a `Nargo.toml` file, which describes either a single package
+/// or a workspace of several packages.
+#[derive(Debug, Clone)]
+pub enum Manifest {
+    Package(PackageManifest),
+    Workspace(WorkspaceManifest),
+}
+
+impl Manifest {
+    pub fn from_toml_str(toml_as_string: &str) -> Result<Self, InvalidPackageError> {
+        let manifest = toml::from_str::<toml::Value>(toml_as_string)?;
+        match (manifest.get("package"), manifest.get("workspace")) {
+            (Some(_), Some(_)) => Err(InvalidPackageError::PackageAndWorkspace),
+            (None, Some(_)) => Ok(Manifest::Workspace(manifest.try_into()?)),
+            _ => Ok(Manifest::Package(manifest.try_into()?)),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct PackageManifest {
     pub package: PackageMetadata,
@@ -27,7 +50,9 @@ impl PackageManifest {
 #[allow(dead_code)]
 #[derive(Debug, Deserialize, Clone)]
 pub struct PackageMetadata {
-    // Note: a package name is not needed unless there is a registry
+    // Note: a package name is not needed unless there is a registry,
+    // or to refer to a package of a workspace
+    name: Option<String>,
     authors: Vec<String>,
     // If not compiler version is supplied, the latest is used
     // For now, we state that all packages must be compiled under the same
@@ -37,6 +62,34 @@ pub struct PackageMetadata {
     compiler_version: Option<String>,
     backend: Option<String>,
     license: Option<String>,
+    // Whether git dependencies may only be taken from the vendor directory or the cache
+    #[serde(default)]
+    offline: bool,
+}
+
+impl PackageMetadata {
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+}
+
+/// A manifest declaring a workspace, whose packages are built together.
+#[derive(Debug, Deserialize, Clone)]
+pub struct WorkspaceManifest {
+    pub workspace: WorkspaceConfig,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WorkspaceConfig {
+    /// The root directories of the packages of the workspace, relative to the manifest
+    pub members: Vec<PathBuf>,
+    /// Whether git dependencies may only be taken from the vendor directory or the cache
+    #[serde(default)]
+    pub offline: bool,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -44,10 +97,92 @@ pub struct PackageMetadata {
 /// Enum representing the different types of ways to
 /// supply a source for the dependency
 pub enum Dependency {
-    Github { git: String, tag: String },
+    Github(GitDependency),
     Path { path: String },
 }
 
+/// A dependency on a package in a git repository.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct GitDependency {
+    /// The url of the repository
+    pub git: String,
+    pub tag: Option<String>,
+    pub branch: Option<String>,
+    pub rev: Option<String>,
+    /// The directory of the package within the repository, which defaults to its root
+    pub directory: Option<String>,
+}
+
+/// The version of a git repository a dependency refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GitReference<'a> {
+    Tag(&'a str),
+    Branch(&'a str),
+    /// A commit SHA
+    Rev(&'a str),
+    /// The head of the default branch of the repository
+    DefaultBranch,
+}
+
+impl GitDependency {
+    /// Returns the version of the repository to use, erroring if several are given.
+    pub fn reference(&self) -> Result<GitReference, InvalidPackageError> {
+        match (&self.tag, &self.branch, &self.rev) {
+            (Some(tag), None, None) => Ok(GitReference::Tag(tag)),
+            (None, Some(branch), None) => Ok(GitReference::Branch(branch)),
+            // Only full commit SHAs can be fetched on their own
+            (None, None, Some(rev)) if is_full_sha(rev) => Ok(GitReference::Rev(rev)),
+            (None, None, Some(rev)) => Err(InvalidPackageError::AbbreviatedRev {
+                dependency: self.git.clone(),
+                rev: rev.clone(),
+            }),
+            (None, None, None) => Ok(GitReference::DefaultBranch),
+            _ => Err(InvalidPackageError::AmbiguousGitReference(self.git.clone())),
+        }
+    }
+
+    /// Returns the directory of the package within the repository, erroring if it may lead
+    /// outside of the repository.
+    pub fn directory(&self) -> Result<Option<&str>, InvalidPackageError> {
+        let directory = match &self.directory {
+            Some(directory) => directory,
+            None => return Ok(None),
+        };
+        let components: Vec<_> = Path::new(directory).components().collect();
+        let is_relative = !components.is_empty()
+            && components.iter().all(|component| matches!(component, Component::Normal(_)));
+        if is_relative {
+            Ok(Some(directory))
+        } else {
+            Err(InvalidPackageError::InvalidGitDirectory {
+                dependency: self.git.clone(),
+                directory: directory.clone(),
+            })
+        }
+    }
+}
+
+/// Returns whether `rev` is the full, 40 hexadecimal digits, SHA of a commit.
+fn is_full_sha(rev: &str) -> bool {
+    rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+impl Display for GitDependency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.git)?;
+        match self.reference() {
+            Ok(GitReference::Tag(tag)) => write!(f, " at tag {tag}")?,
+            Ok(GitReference::Branch(branch)) => write!(f, " at branch {branch}")?,
+            Ok(GitReference::Rev(rev)) => write!(f, " at rev {rev}")?,
+            Ok(GitReference::DefaultBranch) | Err(_) => (),
+        }
+        if let Some(directory) = &self.directory {
+            write!(f, " in directory {directory}")?;
+        }
+        Ok(())
+    }
+}
+
 #[test]
 fn parse_standard_toml() {
     let src = r#"
@@ -64,3 +199,90 @@ fn parse_standard_toml() {
 
     assert!(PackageManifest::from_toml_str(src).is_ok());
 }
+
+#[test]
+fn parse_workspace_toml() {
+    let src = r#"
+        [workspace]
+        members = ["circuits/a", "libs/b"]
+    "#;
+
+    let manifest = Manifest::from_toml_str(src).unwrap();
+    assert!(
+        matches!(manifest, Manifest::Workspace(workspace) if workspace.workspace.members.len() == 2)
+    );
+}
+
+#[test]
+fn reject_package_and_workspace_toml() {
+    let src = r#"
+        [package]
+        authors = [""]
+
+        [workspace]
+        members = ["a"]
+
+        [dependencies]
+    "#;
+
+    assert!(matches!(Manifest::from_toml_str(src), Err(InvalidPackageError::PackageAndWorkspace)));
+}
+
+#[test]
+fn parse_git_dependencies() {
+    let src = r#"
+        [package]
+        authors = [""]
+
+        [dependencies]
+        pinned = { git = "https://github.com/noir-lang/monorepo", rev = "0123456789abcdef0123456789abcdef01234567", directory = "libs/pinned" }
+        abbreviated = { git = "https://github.com/noir-lang/monorepo", rev = "0123abc" }
+        latest = { git = "https://github.com/noir-lang/monorepo", branch = "main" }
+        ambiguous = { git = "https://github.com/noir-lang/monorepo", tag = "v1", branch = "main" }
+    "#;
+
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    let git_dependency = |name: &str| match &manifest.dependencies[name] {
+        Dependency::Github(dependency) => dependency.clone(),
+        Dependency::Path { .. } => panic!("expected a git dependency"),
+    };
+
+    let pinned = git_dependency("pinned");
+    assert_eq!(
+        pinned.reference().unwrap(),
+        GitReference::Rev("0123456789abcdef0123456789abcdef01234567")
+    );
+    assert_eq!(pinned.directory.as_deref(), Some("libs/pinned"));
+    assert!(matches!(
+        git_dependency("abbreviated").reference(),
+        Err(InvalidPackageError::AbbreviatedRev { .. })
+    ));
+    assert_eq!(git_dependency("latest").reference().unwrap(), GitReference::Branch("main"));
+    assert!(matches!(
+        git_dependency("ambiguous").reference(),
+        Err(InvalidPackageError::AmbiguousGitReference(_))
+    ));
+}
+
+#[test]
+fn git_dependency_directories_stay_within_the_repository() {
+    let dependency = |directory: Option<&str>| GitDependency {
+        git: "https://github.com/noir-lang/monorepo".to_owned(),
+        tag: None,
+        branch: None,
+        rev: None,
+        directory: directory.map(ToOwned::to_owned),
+    };
+
+    assert_eq!(dependency(None).directory().unwrap(), None);
+    assert_eq!(dependency(Some("libs/pinned")).directory().unwrap(), Some("libs/pinned"));
+    for directory in ["", "/etc", "../sibling", "libs/../../sibling", "./libs"] {
+        assert!(
+            matches!(
+                dependency(Some(directory)).directory(),
+                Err(InvalidPackageError::InvalidGitDirectory { .. })
+            ),
+            "{directory:?} should be rejected"
+        );
+    }
+}
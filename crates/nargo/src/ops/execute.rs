@@ -1,19 +1,120 @@
 use acvm::pwg::{solve, PartialWitnessGeneratorStatus};
-use acvm::PartialWitnessGenerator;
-use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap, pwg::block::Blocks};
+use acvm::{
+    acir::circuit::{Circuit, Opcode},
+    acir::native_types::{Expression, WitnessMap},
+    pwg::block::Blocks,
+};
+use acvm::{FieldElement, OpcodeResolutionError, PartialWitnessGenerator};
 
 use crate::NargoError;
 
+use super::OracleResolver;
+
 pub fn execute_circuit(
     backend: &impl PartialWitnessGenerator,
     circuit: Circuit,
     mut initial_witness: WitnessMap,
+    oracle_resolver: &mut impl OracleResolver,
 ) -> Result<WitnessMap, NargoError> {
     let mut blocks = Blocks::default();
-    let solver_status = solve(backend, &mut initial_witness, &mut blocks, circuit.opcodes)?;
-    if matches!(solver_status, PartialWitnessGeneratorStatus::RequiresOracleData { .. }) {
-        todo!("Add oracle support to nargo execute")
+    let mut opcodes = circuit.opcodes.clone();
+    loop {
+        let solver_status = solve(backend, &mut initial_witness, &mut blocks, opcodes)
+            .map_err(|error| solving_error(error, &circuit, &initial_witness))?;
+        match solver_status {
+            PartialWitnessGeneratorStatus::Solved => break,
+            PartialWitnessGeneratorStatus::RequiresOracleData {
+                required_oracle_data,
+                unsolved_opcodes,
+            } => {
+                // Fill in the outputs of each of the oracles which the solver is waiting on and then
+                // place them in front of the remaining opcodes so that solving can resume.
+                let mut next_opcodes =
+                    Vec::with_capacity(required_oracle_data.len() + unsolved_opcodes.len());
+                for mut oracle_data in required_oracle_data {
+                    oracle_data.output_values = oracle_resolver.resolve_oracle(&oracle_data)?;
+                    next_opcodes.push(Opcode::Oracle(oracle_data));
+                }
+                next_opcodes.extend(unsolved_opcodes);
+                opcodes = next_opcodes;
+            }
+        }
     }
 
     Ok(initial_witness)
 }
+
+/// Points an unsatisfied constraint at the opcode of the circuit which is not satisfied, when
+/// it can be found from the witnesses solved so far. The solver of acvm 0.12 does not report
+/// which opcode it failed on, and may have reordered the opcodes it was given.
+fn solving_error(
+    error: OpcodeResolutionError,
+    circuit: &Circuit,
+    witness_map: &WitnessMap,
+) -> NargoError {
+    if !matches!(error, OpcodeResolutionError::UnsatisfiedConstrain { .. }) {
+        return error.into();
+    }
+    match unsatisfied_opcode(&circuit.opcodes, witness_map) {
+        Some(opcode_index) => NargoError::UnsatisfiedOpcode { opcode_index },
+        None => error.into(),
+    }
+}
+
+/// Returns the index of the first arithmetic opcode whose witnesses are all solved
+/// but which does not hold.
+fn unsatisfied_opcode(opcodes: &[Opcode], witness_map: &WitnessMap) -> Option<usize> {
+    opcodes.iter().position(|opcode| match opcode {
+        Opcode::Arithmetic(expression) => {
+            evaluate(expression, witness_map).map_or(false, |value| !value.is_zero())
+        }
+        _ => false,
+    })
+}
+
+/// Evaluates `expression`, unless one of its witnesses has not been solved.
+fn evaluate(expression: &Expression, witness_map: &WitnessMap) -> Option<FieldElement> {
+    let mut value = expression.q_c;
+    for (coefficient, lhs, rhs) in &expression.mul_terms {
+        value = value + *coefficient * *witness_map.get(lhs)? * *witness_map.get(rhs)?;
+    }
+    for (coefficient, witness) in &expression.linear_combinations {
+        value = value + *coefficient * *witness_map.get(witness)?;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::{
+            circuit::Opcode,
+            native_types::{Expression, Witness, WitnessMap},
+        },
+        FieldElement,
+    };
+
+    use super::unsatisfied_opcode;
+
+    #[test]
+    fn finds_the_first_unsatisfied_opcode() {
+        let constant = |value: u128| Expression::from(FieldElement::from(value));
+        // w1 == 2, w1 * w2 == 6 and w3 == 1
+        let opcodes = vec![
+            Opcode::Arithmetic(&Expression::from(Witness(1)) - &constant(2)),
+            Opcode::Arithmetic(Expression {
+                mul_terms: vec![(FieldElement::one(), Witness(1), Witness(2))],
+                linear_combinations: Vec::new(),
+                q_c: FieldElement::zero() - FieldElement::from(6_u128),
+            }),
+            Opcode::Arithmetic(&Expression::from(Witness(3)) - &constant(1)),
+        ];
+
+        let mut witness_map = WitnessMap::new();
+        witness_map.insert(Witness(1), FieldElement::from(2_u128));
+        assert_eq!(unsatisfied_opcode(&opcodes, &witness_map), None);
+
+        witness_map.insert(Witness(2), FieldElement::from(4_u128));
+        assert_eq!(unsatisfied_opcode(&opcodes, &witness_map), Some(1));
+    }
+}
@@ -1,11 +1,13 @@
 pub use self::codegen_verifier::codegen_verifier;
 pub use self::execute::execute_circuit;
+pub use self::oracles::{MockedOracleResolver, OracleResolver};
 pub use self::preprocess::{preprocess_contract_function, preprocess_program};
 pub use self::prove::prove_execution;
 pub use self::verify::verify_proof;
 
 mod codegen_verifier;
 mod execute;
+mod oracles;
 mod preprocess;
 mod prove;
 mod verify;
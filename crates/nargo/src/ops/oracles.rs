@@ -0,0 +1,164 @@
+use acvm::{acir::circuit::opcodes::OracleData, FieldElement};
+use iter_extended::try_vecmap;
+use noirc_abi::input_parser::{parse_str_to_field, Format};
+use serde::Deserialize;
+
+use crate::errors::OracleResolutionError;
+
+/// Supplies the values requested by the oracle opcodes of a circuit while it is being executed.
+///
+/// Once the solver has computed all of an oracle's inputs it is paused and the resolver is asked
+/// to provide the oracle's outputs, after which execution is resumed.
+pub trait OracleResolver {
+    /// Returns the values to be written to the output witnesses of `oracle`.
+    ///
+    /// `oracle.input_values` is guaranteed to have been populated by the solver.
+    fn resolve_oracle(
+        &mut self,
+        oracle: &OracleData,
+    ) -> Result<Vec<FieldElement>, OracleResolutionError>;
+}
+
+/// A single canned response for an oracle call, as written in an oracle responses file.
+#[derive(Debug, Deserialize, Clone)]
+struct MockedOracleResponseEntry {
+    name: String,
+    /// If provided, the response is only used when the oracle is called with exactly these inputs.
+    inputs: Option<Vec<String>>,
+    outputs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct MockedOracleResponsesFile {
+    #[serde(default)]
+    oracle: Vec<MockedOracleResponseEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MockedOracleResponse {
+    name: String,
+    inputs: Option<Vec<FieldElement>>,
+    outputs: Vec<FieldElement>,
+}
+
+impl MockedOracleResponse {
+    fn matches(&self, oracle: &OracleData) -> bool {
+        self.name == oracle.name
+            && self.inputs.as_ref().map_or(true, |inputs| inputs == &oracle.input_values)
+    }
+}
+
+/// An [`OracleResolver`] which answers oracle calls with canned responses loaded from a file.
+///
+/// Responses are written as a list of `oracle` entries, e.g. in TOML:
+///
+/// ```toml
+/// [[oracle]]
+/// name = "get_price"
+/// inputs = ["1"]
+/// outputs = ["0x05"]
+/// ```
+///
+/// The `inputs` key may be omitted in order to return the same outputs for any call to the oracle.
+/// When several responses match a call, the first one listed is used.
+#[derive(Debug, Default, Clone)]
+pub struct MockedOracleResolver {
+    responses: Vec<MockedOracleResponse>,
+}
+
+impl MockedOracleResolver {
+    /// Parses a set of canned oracle responses from `contents`, which is expected to be written in `format`.
+    pub fn parse(contents: &str, format: Format) -> Result<Self, OracleResolutionError> {
+        let responses_file: MockedOracleResponsesFile = match format {
+            Format::Json => serde_json::from_str(contents)
+                .map_err(|err| OracleResolutionError::MalformedResponses(err.to_string()))?,
+            Format::Toml => toml::from_str(contents)
+                .map_err(|err| OracleResolutionError::MalformedResponses(err.to_string()))?,
+        };
+
+        let responses = try_vecmap(responses_file.oracle, |entry| {
+            let inputs = entry
+                .inputs
+                .map(|inputs| try_vecmap(inputs, |input| parse_str_to_field(&input)))
+                .transpose()?;
+            let outputs = try_vecmap(entry.outputs, |output| parse_str_to_field(&output))?;
+            Ok::<_, OracleResolutionError>(MockedOracleResponse {
+                name: entry.name,
+                inputs,
+                outputs,
+            })
+        })?;
+
+        Ok(MockedOracleResolver { responses })
+    }
+}
+
+impl OracleResolver for MockedOracleResolver {
+    fn resolve_oracle(
+        &mut self,
+        oracle: &OracleData,
+    ) -> Result<Vec<FieldElement>, OracleResolutionError> {
+        let response =
+            self.responses.iter().find(|response| response.matches(oracle)).ok_or_else(|| {
+                OracleResolutionError::MissingResponse {
+                    name: oracle.name.clone(),
+                    inputs: oracle.input_values.clone(),
+                }
+            })?;
+
+        if response.outputs.len() != oracle.outputs.len() {
+            return Err(OracleResolutionError::OutputCountMismatch {
+                name: oracle.name.clone(),
+                expected: oracle.outputs.len(),
+                found: response.outputs.len(),
+            });
+        }
+
+        Ok(response.outputs.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::{circuit::opcodes::OracleData, native_types::Witness},
+        FieldElement,
+    };
+    use noirc_abi::input_parser::Format;
+
+    use super::{MockedOracleResolver, OracleResolver};
+
+    fn oracle_call(name: &str, input_values: Vec<FieldElement>) -> OracleData {
+        OracleData {
+            name: name.to_owned(),
+            inputs: Vec::new(),
+            input_values,
+            outputs: vec![Witness(1)],
+            output_values: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn resolves_responses_by_name_and_inputs() {
+        let src = r#"
+            [[oracle]]
+            name = "get_price"
+            inputs = ["1"]
+            outputs = ["0x05"]
+
+            [[oracle]]
+            name = "get_price"
+            outputs = ["7"]
+        "#;
+        let mut resolver = MockedOracleResolver::parse(src, Format::Toml).unwrap();
+
+        let matching_inputs = oracle_call("get_price", vec![FieldElement::one()]);
+        assert_eq!(resolver.resolve_oracle(&matching_inputs).unwrap(), vec![5u128.into()]);
+
+        let other_inputs = oracle_call("get_price", vec![FieldElement::zero()]);
+        assert_eq!(resolver.resolve_oracle(&other_inputs).unwrap(), vec![7u128.into()]);
+
+        let unknown_oracle = oracle_call("get_volume", Vec::new());
+        assert!(resolver.resolve_oracle(&unknown_oracle).is_err());
+    }
+}
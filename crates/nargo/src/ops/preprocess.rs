@@ -21,6 +21,8 @@ pub fn preprocess_program<B: ProofSystemCompiler>(
         backend: String::from(BACKEND_IDENTIFIER),
         abi: compiled_program.abi,
         bytecode: optimized_bytecode,
+        source_map: compiled_program.source_map,
+        custom_attributes: compiled_program.custom_attributes,
         proving_key,
         verification_key,
     })
@@ -40,9 +42,11 @@ pub fn preprocess_contract_function<B: ProofSystemCompiler>(
     Ok(PreprocessedContractFunction {
         name: func.name,
         function_type: func.function_type,
+        custom_attributes: func.custom_attributes,
         abi: func.abi,
 
         bytecode: optimized_bytecode,
+        source_map: func.source_map,
         proving_key,
         verification_key,
     })
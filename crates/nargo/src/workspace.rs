@@ -1,4 +1,134 @@
-// We will say that a cargo unit must contain either a binary or a library
-// Then we use workspace to allow more than one. In the future, do not allow there to be
-// both a binary and a library.
-// - library will be default
+//! A workspace is a set of packages which are built together, declared by a manifest
+//! with a `[workspace]` section. A package which isn't part of a workspace is treated
+//! as a workspace of its own.
+use std::path::{Path, PathBuf};
+
+use crate::manifest::InvalidPackageError;
+
+/// A package of a workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// The name given to the package in its manifest, or the name of its root directory.
+    pub name: String,
+    /// The directory containing the package's manifest.
+    pub root_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    /// The directory containing the manifest of the workspace,
+    /// or `None` for a package which isn't part of a workspace.
+    pub root_dir: Option<PathBuf>,
+    pub members: Vec<Package>,
+    /// Whether git dependencies may only be taken from the vendor directory or the cache
+    pub offline: bool,
+}
+
+/// Which packages of a workspace a command should run on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageSelection {
+    /// The package with the given name.
+    Named(String),
+    /// Every package of the workspace.
+    All,
+    /// The package containing the given directory, or every package if it is
+    /// outside of all of them.
+    Containing(PathBuf),
+}
+
+impl Workspace {
+    pub fn new(
+        root_dir: Option<PathBuf>,
+        members: Vec<Package>,
+    ) -> Result<Self, InvalidPackageError> {
+        for (index, package) in members.iter().enumerate() {
+            if members[..index].iter().any(|other| other.name == package.name) {
+                return Err(InvalidPackageError::DuplicatePackageName(package.name.clone()));
+            }
+        }
+        Ok(Workspace { root_dir, members, offline: false })
+    }
+
+    /// Returns the directory holding the files shared by the packages of the workspace,
+    /// such as the lockfile.
+    pub fn manifest_dir(&self) -> &Path {
+        // A package which isn't part of a workspace is the only member of its own
+        self.root_dir.as_deref().unwrap_or(&self.members[0].root_dir)
+    }
+
+    /// Returns the packages of the workspace a command should run on.
+    pub fn select(
+        &self,
+        selection: &PackageSelection,
+    ) -> Result<Vec<&Package>, InvalidPackageError> {
+        match selection {
+            PackageSelection::Named(name) => self
+                .members
+                .iter()
+                .find(|package| &package.name == name)
+                .map(|package| vec![package])
+                .ok_or_else(|| InvalidPackageError::UnknownPackage(name.clone())),
+            PackageSelection::All => Ok(self.members.iter().collect()),
+            PackageSelection::Containing(dir) => match self.member_containing(dir) {
+                Some(package) => Ok(vec![package]),
+                None => Ok(self.members.iter().collect()),
+            },
+        }
+    }
+
+    fn member_containing(&self, dir: &Path) -> Option<&Package> {
+        // Members may be nested, in which case the innermost one contains the directory
+        self.members
+            .iter()
+            .filter(|package| dir.starts_with(&package.root_dir))
+            .max_by_key(|package| package.root_dir.components().count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Package, PackageSelection, Workspace};
+    use crate::manifest::InvalidPackageError;
+
+    fn package(name: &str) -> Package {
+        Package { name: name.to_owned(), root_dir: PathBuf::from("/workspace").join(name) }
+    }
+
+    fn workspace() -> Workspace {
+        let members = vec![package("circuit"), package("lib")];
+        Workspace::new(Some(PathBuf::from("/workspace")), members).unwrap()
+    }
+
+    #[test]
+    fn selects_packages() {
+        let workspace = workspace();
+
+        let named = workspace.select(&PackageSelection::Named("lib".to_owned())).unwrap();
+        assert_eq!(named, vec![&package("lib")]);
+
+        let all = workspace.select(&PackageSelection::All).unwrap();
+        assert_eq!(all.len(), 2);
+
+        let inside = PackageSelection::Containing(PathBuf::from("/workspace/circuit/src"));
+        assert_eq!(workspace.select(&inside).unwrap(), vec![&package("circuit")]);
+
+        let at_root = PackageSelection::Containing(PathBuf::from("/workspace"));
+        assert_eq!(workspace.select(&at_root).unwrap().len(), 2);
+
+        assert!(matches!(
+            workspace.select(&PackageSelection::Named("missing".to_owned())),
+            Err(InvalidPackageError::UnknownPackage(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_duplicate_package_names() {
+        let members = vec![package("circuit"), package("circuit")];
+        assert!(matches!(
+            Workspace::new(Some(PathBuf::from("/workspace")), members),
+            Err(InvalidPackageError::DuplicatePackageName(_))
+        ));
+    }
+}
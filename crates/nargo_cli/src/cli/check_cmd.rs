@@ -3,37 +3,36 @@ use acvm::Backend;
 use clap::Args;
 use iter_extended::btree_map;
 use noirc_abi::{AbiParameter, AbiType, MAIN_RETURN_NAME};
-use noirc_driver::CompileOptions;
+use noirc_driver::{CompileOptions, Driver};
 use std::path::{Path, PathBuf};
 
-use super::NargoConfig;
-use super::{compile_cmd::setup_driver, fs::write_to_file};
+use super::fs::write_to_file;
+use super::{print_message, NargoConfig};
 use crate::constants::{PROVER_INPUT_FILE, VERIFIER_INPUT_FILE};
 
 /// Checks the constraint system for errors
 #[derive(Debug, Clone, Args)]
 pub(crate) struct CheckCommand {
     #[clap(flatten)]
-    compile_options: CompileOptions,
+    pub(super) compile_options: CompileOptions,
 }
 
 pub(crate) fn run<B: Backend>(
-    backend: &B,
+    _backend: &B,
     args: CheckCommand,
     config: NargoConfig,
+    driver: &mut Driver,
 ) -> Result<(), CliError<B>> {
-    check_from_path(backend, config.program_dir, &args.compile_options)?;
-    println!("Constraint system successfully built!");
+    check_from_path(driver, config.program_dir, &args.compile_options)?;
+    print_message(args.compile_options.message_format, "Constraint system successfully built!");
     Ok(())
 }
 
 fn check_from_path<B: Backend, P: AsRef<Path>>(
-    backend: &B,
+    driver: &mut Driver,
     program_dir: P,
     compile_options: &CompileOptions,
 ) -> Result<(), CliError<B>> {
-    let mut driver = setup_driver(backend, program_dir.as_ref())?;
-
     driver.check_crate(compile_options).map_err(|_| CliError::CompilationError)?;
 
     // XXX: We can have a --overwrite flag to determine if you want to overwrite the Prover/Verifier.toml files
@@ -84,6 +83,10 @@ fn create_input_toml_template(
                 );
                 toml::Value::Table(default_value_map)
             }
+            AbiType::Tuple { fields } => {
+                let default_value_vec = fields.into_iter().map(default_value).collect();
+                toml::Value::Array(default_value_vec)
+            }
             _ => toml::Value::String("".to_owned()),
         }
     }
@@ -100,15 +103,28 @@ fn create_input_toml_template(
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     use noirc_abi::{AbiParameter, AbiType, AbiVisibility, Sign};
     use noirc_driver::CompileOptions;
 
-    use super::create_input_toml_template;
+    use super::{check_from_path, create_input_toml_template};
+    use crate::{
+        backends::ConcreteBackend, cli::compile_cmd::setup_driver, errors::CliError,
+        git::GitSources,
+    };
 
     const TEST_DATA_DIR: &str = "tests/target_tests_data";
 
+    fn check_package(
+        path: &Path,
+        config: &CompileOptions,
+    ) -> Result<(), CliError<ConcreteBackend>> {
+        let backend = ConcreteBackend::default();
+        let mut driver = setup_driver(&backend, path, &GitSources::default())?;
+        check_from_path(&mut driver, path, config)
+    }
+
     #[test]
     fn valid_toml_template() {
         let typed_param = |name: &str, typ: AbiType| AbiParameter {
@@ -154,16 +170,11 @@ d2 = ["", "", ""]
         let pass_dir =
             PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("{TEST_DATA_DIR}/pass"));
 
-        let backend = crate::backends::ConcreteBackend::default();
         let config = CompileOptions::default();
         let paths = std::fs::read_dir(pass_dir).unwrap();
         for path in paths.flatten() {
             let path = path.path();
-            assert!(
-                super::check_from_path(&backend, path.clone(), &config).is_ok(),
-                "path: {}",
-                path.display()
-            );
+            assert!(check_package(&path, &config).is_ok(), "path: {}", path.display());
         }
     }
 
@@ -173,16 +184,11 @@ d2 = ["", "", ""]
         let fail_dir =
             PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("{TEST_DATA_DIR}/fail"));
 
-        let backend = crate::backends::ConcreteBackend::default();
         let config = CompileOptions::default();
         let paths = std::fs::read_dir(fail_dir).unwrap();
         for path in paths.flatten() {
             let path = path.path();
-            assert!(
-                super::check_from_path(&backend, path.clone(), &config).is_err(),
-                "path: {}",
-                path.display()
-            );
+            assert!(check_package(&path, &config).is_err(), "path: {}", path.display());
         }
     }
 
@@ -191,17 +197,12 @@ d2 = ["", "", ""]
         let pass_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join(format!("{TEST_DATA_DIR}/pass_dev_mode"));
 
-        let backend = crate::backends::ConcreteBackend::default();
         let config = CompileOptions { deny_warnings: false, ..Default::default() };
 
         let paths = std::fs::read_dir(pass_dir).unwrap();
         for path in paths.flatten() {
             let path = path.path();
-            assert!(
-                super::check_from_path(&backend, path.clone(), &config).is_ok(),
-                "path: {}",
-                path.display()
-            );
+            assert!(check_package(&path, &config).is_ok(), "path: {}", path.display());
         }
     }
 }
@@ -7,15 +7,12 @@ use super::fs::{
     program::read_program_from_file,
     write_to_file,
 };
-use super::NargoConfig;
-use crate::{
-    cli::compile_cmd::compile_circuit, constants::CONTRACT_DIR, constants::TARGET_DIR,
-    errors::CliError,
-};
+use super::{print_message, NargoConfig};
+use crate::{constants::CONTRACT_DIR, errors::CliError};
 use acvm::Backend;
 use clap::Args;
 use nargo::ops::{codegen_verifier, preprocess_program};
-use noirc_driver::CompileOptions;
+use noirc_driver::{CompileOptions, Driver};
 
 /// Generates a Solidity verifier smart contract for the program
 #[derive(Debug, Clone, Args)]
@@ -24,18 +21,18 @@ pub(crate) struct CodegenVerifierCommand {
     circuit_name: Option<String>,
 
     #[clap(flatten)]
-    compile_options: CompileOptions,
+    pub(super) compile_options: CompileOptions,
 }
 
 pub(crate) fn run<B: Backend>(
     backend: &B,
     args: CodegenVerifierCommand,
     config: NargoConfig,
+    driver: &mut Driver,
 ) -> Result<(), CliError<B>> {
     // TODO(#1201): Should this be a utility function?
-    let circuit_build_path = args
-        .circuit_name
-        .map(|circuit_name| config.program_dir.join(TARGET_DIR).join(circuit_name));
+    let circuit_build_path =
+        args.circuit_name.map(|circuit_name| config.target_dir.join(circuit_name));
 
     let common_reference_string = read_cached_common_reference_string();
 
@@ -51,8 +48,9 @@ pub(crate) fn run<B: Backend>(
             (common_reference_string, program)
         }
         None => {
-            let program =
-                compile_circuit(backend, config.program_dir.as_ref(), &args.compile_options)?;
+            let program = driver
+                .compile_main(&args.compile_options)
+                .map_err(|_| CliError::CompilationError)?;
             let common_reference_string =
                 update_common_reference_string(backend, &common_reference_string, &program.circuit)
                     .map_err(CliError::CommonReferenceStringError)?;
@@ -73,6 +71,9 @@ pub(crate) fn run<B: Backend>(
     let contract_path = contract_dir.join("plonk_vk").with_extension("sol");
 
     let path = write_to_file(smart_contract_string.as_bytes(), &contract_path);
-    println!("Contract successfully created and located at {path}");
+    print_message(
+        args.compile_options.message_format,
+        format!("Contract successfully created and located at {path}"),
+    );
     Ok(())
 }
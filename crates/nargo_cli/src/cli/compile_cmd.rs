@@ -1,7 +1,7 @@
 use acvm::Backend;
 use iter_extended::try_vecmap;
 use nargo::artifacts::contract::PreprocessedContract;
-use noirc_driver::{CompileOptions, CompiledProgram, Driver};
+use noirc_driver::{CompileOptions, Driver};
 use std::path::Path;
 
 use clap::Args;
@@ -9,7 +9,7 @@ use clap::Args;
 use nargo::ops::{preprocess_contract_function, preprocess_program};
 
 use crate::resolver::DependencyResolutionError;
-use crate::{constants::TARGET_DIR, errors::CliError, resolver::Resolver};
+use crate::{errors::CliError, git::GitSources, resolver::Resolver};
 
 use super::fs::{
     common_reference_string::{
@@ -34,21 +34,21 @@ pub(crate) struct CompileCommand {
     contracts: bool,
 
     #[clap(flatten)]
-    compile_options: CompileOptions,
+    pub(super) compile_options: CompileOptions,
 }
 
 pub(crate) fn run<B: Backend>(
     backend: &B,
     args: CompileCommand,
     config: NargoConfig,
+    driver: &mut Driver,
 ) -> Result<(), CliError<B>> {
-    let circuit_dir = config.program_dir.join(TARGET_DIR);
+    let circuit_dir = config.target_dir;
 
     let mut common_reference_string = read_cached_common_reference_string();
 
     // If contracts is set we're compiling every function in a 'contract' rather than just 'main'.
     if args.contracts {
-        let mut driver = setup_driver(backend, &config.program_dir)?;
         let compiled_contracts = driver
             .compile_contracts(&args.compile_options)
             .map_err(|_| CliError::CompilationError)?;
@@ -85,7 +85,8 @@ pub(crate) fn run<B: Backend>(
             );
         }
     } else {
-        let program = compile_circuit(backend, &config.program_dir, &args.compile_options)?;
+        let program =
+            driver.compile_main(&args.compile_options).map_err(|_| CliError::CompilationError)?;
         common_reference_string =
             update_common_reference_string(backend, &common_reference_string, &program.circuit)
                 .map_err(CliError::CommonReferenceStringError)?;
@@ -103,20 +104,13 @@ pub(crate) fn run<B: Backend>(
 pub(super) fn setup_driver<B: Backend>(
     backend: &B,
     program_dir: &Path,
+    git_sources: &GitSources,
 ) -> Result<Driver, DependencyResolutionError> {
     Resolver::resolve_root_manifest(
         program_dir,
         backend.np_language(),
         // TODO(#1102): Remove need for driver to be aware of backend.
         Box::new(|op| B::default().supports_opcode(op)),
+        git_sources,
     )
 }
-
-pub(crate) fn compile_circuit<B: Backend>(
-    backend: &B,
-    program_dir: &Path,
-    compile_options: &CompileOptions,
-) -> Result<CompiledProgram, CliError<B>> {
-    let mut driver = setup_driver(backend, program_dir)?;
-    driver.compile_main(compile_options).map_err(|_| CliError::CompilationError)
-}
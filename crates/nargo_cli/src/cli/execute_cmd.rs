@@ -3,15 +3,18 @@ use std::path::Path;
 use acvm::acir::{circuit::Circuit, native_types::WitnessMap};
 use acvm::Backend;
 use clap::Args;
+use nargo::ops::OracleResolver;
 use noirc_abi::input_parser::{Format, InputValue};
 use noirc_abi::{Abi, InputMap};
-use noirc_driver::{CompileOptions, CompiledProgram};
+use noirc_driver::{CompileOptions, CompiledProgram, Driver, SourceMap};
 
-use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
-use super::NargoConfig;
+use super::fs::{
+    inputs::read_inputs_from_file, oracles::read_oracle_responses_from_file,
+    witness::save_witness_to_dir,
+};
+use super::{print_message, NargoConfig};
 use crate::{
-    cli::compile_cmd::compile_circuit,
-    constants::{PROVER_INPUT_FILE, TARGET_DIR},
+    constants::{ORACLE_RESPONSES_FILE, PROVER_INPUT_FILE},
     errors::CliError,
 };
 
@@ -22,43 +25,59 @@ pub(crate) struct ExecuteCommand {
     witness_name: Option<String>,
 
     #[clap(flatten)]
-    compile_options: CompileOptions,
+    pub(super) compile_options: CompileOptions,
 }
 
 pub(crate) fn run<B: Backend>(
     backend: &B,
     args: ExecuteCommand,
     config: NargoConfig,
+    driver: &mut Driver,
 ) -> Result<(), CliError<B>> {
     let (return_value, solved_witness) =
-        execute_with_path(backend, &config.program_dir, &args.compile_options)?;
+        execute_with_path(backend, driver, &config.program_dir, &args.compile_options)?;
 
-    println!("Circuit witness successfully solved");
+    let message_format = args.compile_options.message_format;
+    print_message(message_format, "Circuit witness successfully solved");
     if let Some(return_value) = return_value {
-        println!("Circuit output: {return_value:?}");
+        print_message(message_format, format!("Circuit output: {return_value:?}"));
     }
     if let Some(witness_name) = args.witness_name {
-        let witness_dir = config.program_dir.join(TARGET_DIR);
+        let witness_dir = config.target_dir;
 
         let witness_path = save_witness_to_dir(solved_witness, &witness_name, witness_dir)?;
 
-        println!("Witness saved to {}", witness_path.display());
+        print_message(message_format, format!("Witness saved to {}", witness_path.display()));
     }
     Ok(())
 }
 
 fn execute_with_path<B: Backend>(
     backend: &B,
+    driver: &mut Driver,
     program_dir: &Path,
     compile_options: &CompileOptions,
 ) -> Result<(Option<InputValue>, WitnessMap), CliError<B>> {
-    let CompiledProgram { abi, circuit } = compile_circuit(backend, program_dir, compile_options)?;
+    let CompiledProgram { abi, circuit, assertions, source_map, .. } =
+        driver.compile_main(compile_options).map_err(|_| CliError::CompilationError)?;
 
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(program_dir, PROVER_INPUT_FILE, Format::Toml, &abi)?;
 
-    let solved_witness = execute_program(backend, circuit, &abi, &inputs_map)?;
+    // Load any canned responses to the oracle calls made by the circuit
+    let mut oracle_resolver = read_oracle_responses_from_file(program_dir, ORACLE_RESPONSES_FILE)?;
+
+    let solved_witness =
+        execute_program(backend, circuit, &abi, &inputs_map, &source_map, &mut oracle_resolver)
+            .map_err(|error| {
+                if let CliError::NargoError(nargo_error) = &error {
+                    if let Some(assertion) = nargo_error.failed_assertion(&assertions) {
+                        driver.report_failed_assertion(assertion, compile_options);
+                    }
+                }
+                error
+            })?;
 
     let public_abi = abi.public_abi();
     let (_, return_value) = public_abi.decode(&solved_witness)?;
@@ -66,15 +85,39 @@ fn execute_with_path<B: Backend>(
     Ok((return_value, solved_witness))
 }
 
+/// Executes the circuit on the given inputs. When an opcode fails, the call stack it is
+/// generated from is printed using the `source_map`.
 pub(crate) fn execute_program<B: Backend>(
     backend: &B,
     circuit: Circuit,
     abi: &Abi,
     inputs_map: &InputMap,
+    source_map: &SourceMap,
+    oracle_resolver: &mut impl OracleResolver,
 ) -> Result<WitnessMap, CliError<B>> {
     let initial_witness = abi.encode(inputs_map, None)?;
 
-    let solved_witness = nargo::ops::execute_circuit(backend, circuit, initial_witness)?;
+    let solved_witness =
+        nargo::ops::execute_circuit(backend, circuit, initial_witness, oracle_resolver).map_err(
+            |error| {
+                if let Some(opcode_index) = error.opcode_index() {
+                    print_call_stack(opcode_index, source_map);
+                }
+                error
+            },
+        )?;
 
     Ok(solved_witness)
 }
+
+/// Prints the locations the given opcode is generated from, innermost first.
+fn print_call_stack(opcode_index: usize, source_map: &SourceMap) {
+    let call_stack = source_map.call_stack(opcode_index);
+    if call_stack.is_empty() {
+        return;
+    }
+    eprintln!("Opcode {opcode_index} failed at:");
+    for location in call_stack.iter().rev() {
+        eprintln!("    {location}");
+    }
+}
@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+
+use acvm::Backend;
+use clap::Args;
+use noirc_frontend::formatter::format_source;
+
+use super::fs::write_to_file;
+use super::NargoConfig;
+use crate::{constants::SRC_DIR, errors::CliError};
+
+/// Formats the Noir source files of the project
+#[derive(Debug, Clone, Args)]
+pub(crate) struct FormatCommand {
+    /// Only check whether the files are formatted, without modifying them
+    #[arg(long)]
+    check: bool,
+}
+
+pub(crate) fn run<B: Backend>(
+    // Backend is currently unused, but the signature is kept the same as the other commands
+    _backend: &B,
+    args: FormatCommand,
+    config: NargoConfig,
+) -> Result<(), CliError<B>> {
+    let mut source_files = Vec::new();
+    find_source_files(&config.program_dir.join(SRC_DIR), &mut source_files);
+    source_files.sort();
+
+    let mut unformatted_files = Vec::new();
+    for path in source_files {
+        let source = std::fs::read_to_string(&path).map_err(|error| {
+            CliError::Generic(format!("Could not read {}: {error}", path.display()))
+        })?;
+
+        let formatted = format_source(&source).map_err(|_| {
+            CliError::Generic(format!(
+                "Could not format {} as it contains errors, run `nargo check` to see them",
+                path.display()
+            ))
+        })?;
+
+        if formatted == source {
+            continue;
+        }
+        if args.check {
+            unformatted_files.push(path);
+        } else {
+            write_to_file(formatted.as_bytes(), &path);
+        }
+    }
+
+    if unformatted_files.is_empty() {
+        return Ok(());
+    }
+    for path in &unformatted_files {
+        println!("{}", path.display());
+    }
+    Err(CliError::Generic(format!(
+        "{} file(s) are not formatted, run `nargo fmt` to format them",
+        unformatted_files.len()
+    )))
+}
+
+/// Collects the paths of the Noir source files within `directory` and its subdirectories
+fn find_source_files(directory: &Path, source_files: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            find_source_files(&path, source_files);
+        } else if path.extension().map_or(false, |extension| extension == "nr") {
+            source_files.push(path);
+        }
+    }
+}
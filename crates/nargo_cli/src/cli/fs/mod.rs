@@ -8,6 +8,7 @@ use crate::errors::FilesystemError;
 
 pub(super) mod common_reference_string;
 pub(super) mod inputs;
+pub(super) mod oracles;
 pub(super) mod program;
 pub(super) mod proof;
 pub(super) mod witness;
@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use nargo::ops::MockedOracleResolver;
+use noirc_abi::input_parser::Format;
+
+use crate::errors::FilesystemError;
+
+/// Loads the canned oracle responses stored in `file_name` within `path`.
+///
+/// Both TOML and JSON files are accepted, with TOML taking precedence if both exist.
+/// If no responses file exists then a resolver without any responses is returned,
+/// so that executing a circuit which calls an oracle results in an error naming the missing oracle.
+pub(crate) fn read_oracle_responses_from_file<P: AsRef<Path>>(
+    path: P,
+    file_name: &str,
+) -> Result<MockedOracleResolver, FilesystemError> {
+    for format in [Format::Toml, Format::Json] {
+        let file_path = path.as_ref().join(file_name).with_extension(format.ext());
+        if !file_path.exists() {
+            continue;
+        }
+
+        let responses_string = std::fs::read_to_string(&file_path)
+            .map_err(|_| FilesystemError::PathNotValid(file_path))?;
+        return Ok(MockedOracleResolver::parse(&responses_string, format)?);
+    }
+
+    Ok(MockedOracleResolver::default())
+}
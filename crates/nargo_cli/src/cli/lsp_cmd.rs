@@ -0,0 +1,26 @@
+use acvm::Backend;
+use clap::Args;
+
+use super::compile_cmd::setup_driver;
+use super::NargoConfig;
+use crate::{errors::CliError, find_package_root, find_workspace, git::GitSources};
+
+/// Starts the Noir language server, which communicates with editors over stdio
+#[derive(Debug, Clone, Args)]
+pub(crate) struct LspCommand;
+
+pub(crate) fn run<B: Backend>(
+    backend: &B,
+    _args: LspCommand,
+    // The language server is not bound to a single package, so the program directory is unused
+    config: NargoConfig,
+) -> Result<(), CliError<B>> {
+    let offline = config.offline || config.frozen;
+    noir_lsp::start_server(|file_path| {
+        let package_root = find_package_root(file_path).map_err(|error| error.to_string())?;
+        let workspace = find_workspace(&package_root).map_err(|error| error.to_string())?;
+        let git_sources = GitSources::new(&workspace, offline);
+        setup_driver(backend, &package_root, &git_sources).map_err(|error| error.to_string())
+    })?;
+    Ok(())
+}
@@ -1,11 +1,21 @@
+use acvm::Backend;
 use clap::{Args, Parser, Subcommand};
 use const_format::formatcp;
-use noirc_driver::CompileOptions;
+use noirc_driver::{CompileOptions, Driver, MessageFormat};
 use std::path::{Path, PathBuf};
 
 use color_eyre::eyre;
+use nargo::workspace::{Package, PackageSelection, Workspace};
 
-use crate::{constants::PROOFS_DIR, find_package_root};
+use crate::{
+    backends::ConcreteBackend,
+    constants::{PROOFS_DIR, TARGET_DIR},
+    find_workspace,
+    git::GitSources,
+    lockfile::{lock_dependencies, LockMode},
+    normalize,
+    resolver::{DependencyResolutionError, Resolver},
+};
 
 mod fs;
 
@@ -13,10 +23,13 @@ mod check_cmd;
 mod codegen_verifier_cmd;
 mod compile_cmd;
 mod execute_cmd;
+mod fmt_cmd;
 mod gates_cmd;
+mod lsp_cmd;
 mod new_cmd;
 mod prove_cmd;
 mod test_cmd;
+mod vendor_cmd;
 mod verify_cmd;
 
 const GIT_HASH: &str = env!("GIT_COMMIT");
@@ -41,6 +54,30 @@ struct NargoCli {
 pub(crate) struct NargoConfig {
     #[arg(short, long, hide=true, default_value_os_t = std::env::current_dir().unwrap())]
     program_dir: PathBuf,
+
+    /// Run the command on the package of the workspace with this name
+    #[arg(long, global = true, conflicts_with = "workspace")]
+    package: Option<String>,
+
+    /// Run the command on every package of the workspace
+    #[arg(long, global = true)]
+    workspace: bool,
+
+    /// Fail if Nargo.lock would need to be updated
+    #[arg(long, global = true)]
+    locked: bool,
+
+    /// Equivalent to both --locked and --offline
+    #[arg(long, global = true)]
+    frozen: bool,
+
+    /// Only resolve git dependencies from the vendor directory or those already downloaded
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// The directory to write the build artifacts of the package to
+    #[arg(skip)]
+    target_dir: PathBuf,
 }
 
 #[non_exhaustive]
@@ -51,32 +88,149 @@ enum NargoCommand {
     Compile(compile_cmd::CompileCommand),
     New(new_cmd::NewCommand),
     Execute(execute_cmd::ExecuteCommand),
+    Fmt(fmt_cmd::FormatCommand),
     Prove(prove_cmd::ProveCommand),
     Verify(verify_cmd::VerifyCommand),
     Test(test_cmd::TestCommand),
     Gates(gates_cmd::GatesCommand),
+    Lsp(lsp_cmd::LspCommand),
+    Vendor(vendor_cmd::VendorCommand),
 }
 
 pub fn start_cli() -> eyre::Result<()> {
-    let NargoCli { command, mut config } = NargoCli::parse();
+    let NargoCli { command, config } = NargoCli::parse();
+
+    let backend = ConcreteBackend::default();
+
+    match command {
+        NargoCommand::New(args) => return Ok(new_cmd::run(&backend, args, config)?),
+        NargoCommand::Lsp(args) => return Ok(lsp_cmd::run(&backend, args, config)?),
+        _ => (),
+    }
+
+    // Search through parent directories to find the workspace, then run the command
+    // on each of the selected packages.
+    let workspace = find_workspace(&config.program_dir)?;
+    let selection = match (&config.package, config.workspace) {
+        (Some(name), _) => PackageSelection::Named(name.clone()),
+        (None, true) => PackageSelection::All,
+        (None, false) => PackageSelection::Containing(normalize(&config.program_dir)),
+    };
+    let packages = workspace.select(&selection)?;
+
+    // Formatting only reads the package's own source files
+    if let NargoCommand::Fmt(args) = &command {
+        for package in &packages {
+            if packages.len() > 1 {
+                println!("[{}]", package.name);
+            }
+            fmt_cmd::run(&backend, args.clone(), package_config(&config, &workspace, package))?;
+        }
+        return Ok(());
+    }
+
+    let lock_mode =
+        if config.locked || config.frozen { LockMode::Locked } else { LockMode::Update };
+    let git_sources = GitSources::new(&workspace, config.offline || config.frozen);
+    let resolved = lock_dependencies(&workspace, lock_mode, &git_sources)?;
 
-    // Search through parent directories to find package root if necessary.
-    if !matches!(command, NargoCommand::New(_)) {
-        config.program_dir = find_package_root(&config.program_dir)?;
+    // Vendoring applies to the whole workspace rather than to each package
+    if let NargoCommand::Vendor(args) = &command {
+        vendor_cmd::run(&backend, args.clone(), &workspace, &resolved)?;
+        return Ok(());
     }
 
-    let backend = crate::backends::ConcreteBackend::default();
+    // The selected packages share a single driver, so that the dependencies they have
+    // in common are only resolved and compiled once.
+    let mut workspace_driver = Resolver::resolve_packages(
+        &packages,
+        backend.np_language(),
+        // TODO(#1102): Remove need for driver to be aware of backend.
+        Box::new(|op| ConcreteBackend::default().supports_opcode(op)),
+        &git_sources,
+    )?;
+
+    for package in &packages {
+        if packages.len() > 1 {
+            print_message(command.message_format(), format!("[{}]", package.name));
+        }
+        let config = package_config(&config, &workspace, package);
+        let driver = workspace_driver.select(package);
+        run_command(&backend, command.clone(), config, driver)?;
+    }
+
+    Ok(())
+}
 
+impl NargoCommand {
+    /// The format in which the command reports diagnostics
+    fn message_format(&self) -> MessageFormat {
+        let compile_options = match self {
+            NargoCommand::Check(args) => &args.compile_options,
+            NargoCommand::CodegenVerifier(args) => &args.compile_options,
+            NargoCommand::Compile(args) => &args.compile_options,
+            NargoCommand::Execute(args) => &args.compile_options,
+            NargoCommand::Prove(args) => &args.compile_options,
+            NargoCommand::Verify(args) => &args.compile_options,
+            NargoCommand::Test(args) => &args.compile_options,
+            NargoCommand::Gates(args) => &args.compile_options,
+            NargoCommand::New(_)
+            | NargoCommand::Fmt(_)
+            | NargoCommand::Lsp(_)
+            | NargoCommand::Vendor(_) => return MessageFormat::Human,
+        };
+        compile_options.message_format
+    }
+}
+
+/// Prints a line of human readable output. Diagnostics are the only output on stdout when they
+/// are printed as JSON, so the line is printed to stderr instead.
+fn print_message(message_format: MessageFormat, line: impl std::fmt::Display) {
+    match message_format {
+        MessageFormat::Human => println!("{line}"),
+        MessageFormat::Json => eprintln!("{line}"),
+    }
+}
+
+/// Returns the configuration to run a command on `package` with.
+fn package_config(config: &NargoConfig, workspace: &Workspace, package: &Package) -> NargoConfig {
+    NargoConfig {
+        program_dir: package.root_dir.clone(),
+        target_dir: target_dir(workspace, package),
+        ..config.clone()
+    }
+}
+
+/// Returns the directory holding the build artifacts of `package`. The packages of a
+/// workspace share the target directory at its root, each in a directory of its own.
+fn target_dir(workspace: &Workspace, package: &Package) -> PathBuf {
+    match &workspace.root_dir {
+        Some(root_dir) => root_dir.join(TARGET_DIR).join(&package.name),
+        None => package.root_dir.join(TARGET_DIR),
+    }
+}
+
+fn run_command(
+    backend: &ConcreteBackend,
+    command: NargoCommand,
+    config: NargoConfig,
+    driver: &mut Driver,
+) -> eyre::Result<()> {
     match command {
-        NargoCommand::New(args) => new_cmd::run(&backend, args, config),
-        NargoCommand::Check(args) => check_cmd::run(&backend, args, config),
-        NargoCommand::Compile(args) => compile_cmd::run(&backend, args, config),
-        NargoCommand::Execute(args) => execute_cmd::run(&backend, args, config),
-        NargoCommand::Prove(args) => prove_cmd::run(&backend, args, config),
-        NargoCommand::Verify(args) => verify_cmd::run(&backend, args, config),
-        NargoCommand::Test(args) => test_cmd::run(&backend, args, config),
-        NargoCommand::Gates(args) => gates_cmd::run(&backend, args, config),
-        NargoCommand::CodegenVerifier(args) => codegen_verifier_cmd::run(&backend, args, config),
+        NargoCommand::Check(args) => check_cmd::run(backend, args, config, driver),
+        NargoCommand::Compile(args) => compile_cmd::run(backend, args, config, driver),
+        NargoCommand::Execute(args) => execute_cmd::run(backend, args, config, driver),
+        NargoCommand::Prove(args) => prove_cmd::run(backend, args, config, driver),
+        NargoCommand::Verify(args) => verify_cmd::run(backend, args, config, driver),
+        NargoCommand::Test(args) => test_cmd::run(backend, args, config, driver),
+        NargoCommand::Gates(args) => gates_cmd::run(backend, args, config, driver),
+        NargoCommand::CodegenVerifier(args) => {
+            codegen_verifier_cmd::run(backend, args, config, driver)
+        }
+        NargoCommand::New(_) | NargoCommand::Lsp(_) | NargoCommand::Fmt(_) => {
+            unreachable!("commands which don't compile the package are handled by `start_cli`")
+        }
+        NargoCommand::Vendor(_) => unreachable!("vendoring is handled by `start_cli`"),
     }?;
 
     Ok(())
@@ -84,7 +238,7 @@ pub fn start_cli() -> eyre::Result<()> {
 
 // helper function which tests noir programs by trying to generate a proof and verify it
 pub fn prove_and_verify(proof_name: &str, program_dir: &Path, experimental_ssa: bool) -> bool {
-    let backend = crate::backends::ConcreteBackend::default();
+    let backend = ConcreteBackend::default();
 
     let compile_options = CompileOptions {
         show_ssa: false,
@@ -92,11 +246,28 @@ pub fn prove_and_verify(proof_name: &str, program_dir: &Path, experimental_ssa:
         deny_warnings: false,
         show_output: false,
         experimental_ssa,
+        message_format: MessageFormat::Human,
     };
     let proof_dir = program_dir.join(PROOFS_DIR);
 
+    // Programs are resolved offline, so that testing them never downloads dependencies
+    let driver = find_workspace(program_dir).map_err(DependencyResolutionError::from).and_then(
+        |workspace| {
+            let git_sources = GitSources::new(&workspace, true);
+            compile_cmd::setup_driver(&backend, program_dir, &git_sources)
+        },
+    );
+    let mut driver = match driver {
+        Ok(driver) => driver,
+        Err(error) => {
+            println!("{error}");
+            return false;
+        }
+    };
+
     match prove_cmd::prove_with_path(
         &backend,
+        &mut driver,
         Some(proof_name.to_owned()),
         program_dir,
         &proof_dir,
@@ -27,7 +27,7 @@ compiler_version = "{CARGO_PKG_VERSION}"
 [dependencies]"#,
 );
 
-const EXAMPLE: &str = r#"fn main(x : Field, y : pub Field) {
+const EXAMPLE: &str = r#"fn main(x: Field, y: pub Field) {
     assert(x != y);
 }
 
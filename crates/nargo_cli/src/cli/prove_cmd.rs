@@ -5,24 +5,22 @@ use clap::Args;
 use nargo::artifacts::program::PreprocessedProgram;
 use nargo::ops::{preprocess_program, prove_execution, verify_proof};
 use noirc_abi::input_parser::Format;
-use noirc_driver::CompileOptions;
-
-use super::NargoConfig;
-use super::{
-    compile_cmd::compile_circuit,
-    fs::{
-        common_reference_string::{
-            read_cached_common_reference_string, update_common_reference_string,
-            write_cached_common_reference_string,
-        },
-        inputs::{read_inputs_from_file, write_inputs_to_file},
-        program::read_program_from_file,
-        proof::save_proof_to_dir,
+use noirc_driver::{CompileOptions, Driver};
+
+use super::fs::{
+    common_reference_string::{
+        read_cached_common_reference_string, update_common_reference_string,
+        write_cached_common_reference_string,
     },
+    inputs::{read_inputs_from_file, write_inputs_to_file},
+    oracles::read_oracle_responses_from_file,
+    program::read_program_from_file,
+    proof::save_proof_to_dir,
 };
+use super::{print_message, NargoConfig};
 use crate::{
     cli::execute_cmd::execute_program,
-    constants::{PROOFS_DIR, PROVER_INPUT_FILE, TARGET_DIR, VERIFIER_INPUT_FILE},
+    constants::{ORACLE_RESPONSES_FILE, PROOFS_DIR, PROVER_INPUT_FILE, VERIFIER_INPUT_FILE},
     errors::CliError,
 };
 
@@ -40,22 +38,23 @@ pub(crate) struct ProveCommand {
     verify: bool,
 
     #[clap(flatten)]
-    compile_options: CompileOptions,
+    pub(super) compile_options: CompileOptions,
 }
 
 pub(crate) fn run<B: Backend>(
     backend: &B,
     args: ProveCommand,
     config: NargoConfig,
+    driver: &mut Driver,
 ) -> Result<(), CliError<B>> {
     let proof_dir = config.program_dir.join(PROOFS_DIR);
 
-    let circuit_build_path = args
-        .circuit_name
-        .map(|circuit_name| config.program_dir.join(TARGET_DIR).join(circuit_name));
+    let circuit_build_path =
+        args.circuit_name.map(|circuit_name| config.target_dir.join(circuit_name));
 
     prove_with_path(
         backend,
+        driver,
         args.proof_name,
         config.program_dir,
         proof_dir,
@@ -69,6 +68,7 @@ pub(crate) fn run<B: Backend>(
 
 pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
     backend: &B,
+    driver: &mut Driver,
     proof_name: Option<String>,
     program_dir: P,
     proof_dir: P,
@@ -90,7 +90,8 @@ pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
             (common_reference_string, program)
         }
         None => {
-            let program = compile_circuit(backend, program_dir.as_ref(), compile_options)?;
+            let program =
+                driver.compile_main(compile_options).map_err(|_| CliError::CompilationError)?;
             let common_reference_string =
                 update_common_reference_string(backend, &common_reference_string, &program.circuit)
                     .map_err(CliError::CommonReferenceStringError)?;
@@ -102,14 +103,24 @@ pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
 
     write_cached_common_reference_string(&common_reference_string);
 
-    let PreprocessedProgram { abi, bytecode, proving_key, verification_key, .. } =
+    let PreprocessedProgram { abi, bytecode, source_map, proving_key, verification_key, .. } =
         preprocessed_program;
 
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(&program_dir, PROVER_INPUT_FILE, Format::Toml, &abi)?;
 
-    let solved_witness = execute_program(backend, bytecode.clone(), &abi, &inputs_map)?;
+    // Load any canned responses to the oracle calls made by the circuit
+    let mut oracle_resolver = read_oracle_responses_from_file(&program_dir, ORACLE_RESPONSES_FILE)?;
+
+    let solved_witness = execute_program(
+        backend,
+        bytecode.clone(),
+        &abi,
+        &inputs_map,
+        &source_map,
+        &mut oracle_resolver,
+    )?;
 
     // Write public inputs into Verifier.toml
     let public_abi = abi.public_abi();
@@ -147,7 +158,7 @@ pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
     let proof_path = if let Some(proof_name) = proof_name {
         Some(save_proof_to_dir(&proof, &proof_name, proof_dir)?)
     } else {
-        println!("{}", hex::encode(&proof));
+        print_message(compile_options.message_format, hex::encode(&proof));
         None
     };
 
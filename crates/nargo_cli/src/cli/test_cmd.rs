@@ -2,14 +2,14 @@ use std::{io::Write, path::Path};
 
 use acvm::{acir::native_types::WitnessMap, Backend};
 use clap::Args;
-use nargo::ops::execute_circuit;
-use noirc_driver::{CompileOptions, Driver};
-use noirc_frontend::node_interner::FuncId;
+use nargo::ops::{execute_circuit, OracleResolver};
+use noirc_driver::{CompileOptions, Driver, TestCompileError};
+use noirc_frontend::{node_interner::FuncId, token::TestScope};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
-use crate::{cli::compile_cmd::setup_driver, errors::CliError};
+use crate::{constants::ORACLE_RESPONSES_FILE, errors::CliError};
 
-use super::NargoConfig;
+use super::{fs::oracles::read_oracle_responses_from_file, print_message, NargoConfig};
 
 /// Run the tests for this program
 #[derive(Debug, Clone, Args)]
@@ -18,86 +18,246 @@ pub(crate) struct TestCommand {
     test_name: Option<String>,
 
     #[clap(flatten)]
-    compile_options: CompileOptions,
+    pub(super) compile_options: CompileOptions,
 }
 
 pub(crate) fn run<B: Backend>(
     backend: &B,
     args: TestCommand,
     config: NargoConfig,
+    driver: &mut Driver,
 ) -> Result<(), CliError<B>> {
     let test_name: String = args.test_name.unwrap_or_else(|| "".to_owned());
 
-    run_tests(backend, &config.program_dir, &test_name, &args.compile_options)
+    run_tests(backend, driver, &config.program_dir, &test_name, &args.compile_options)
 }
 
 fn run_tests<B: Backend>(
     backend: &B,
+    driver: &mut Driver,
     program_dir: &Path,
     test_name: &str,
     compile_options: &CompileOptions,
 ) -> Result<(), CliError<B>> {
-    let mut driver = setup_driver(backend, program_dir)?;
-
     driver.check_crate(compile_options).map_err(|_| CliError::CompilationError)?;
 
+    // Load any canned responses to the oracle calls made by the tests
+    let mut oracle_resolver = read_oracle_responses_from_file(program_dir, ORACLE_RESPONSES_FILE)?;
+
     let test_functions = driver.get_all_test_functions_in_crate_matching(test_name);
-    println!("Running {} test functions...", test_functions.len());
-    let mut failing = 0;
+    print_message(
+        compile_options.message_format,
+        format!("Running {} test functions...", test_functions.len()),
+    );
+    let mut passed = 0;
+    let mut expected_failures = 0;
+    let mut failures = Vec::new();
 
     let writer = StandardStream::stderr(ColorChoice::Always);
     let mut writer = writer.lock();
 
-    for test_function in test_functions {
+    for (test_function, scope) in test_functions {
         let test_name = driver.function_name(test_function);
-        writeln!(writer, "Testing {test_name}...").expect("Failed to write to stdout");
+        write!(writer, "Testing {test_name}... ").expect("Failed to write to stdout");
         writer.flush().ok();
 
-        match run_test(backend, test_name, test_function, &driver, compile_options) {
-            Ok(_) => {
+        let status =
+            run_test(backend, test_function, &scope, driver, compile_options, &mut oracle_resolver);
+        match status {
+            TestStatus::Pass => {
+                passed += 1;
                 writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).ok();
                 writeln!(writer, "ok").ok();
             }
-            // Assume an error was already printed to stdout
-            Err(_) => failing += 1,
+            TestStatus::ExpectedFailure(message) => {
+                expected_failures += 1;
+                writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).ok();
+                writeln!(writer, "ok, failed as expected: {message}").ok();
+            }
+            TestStatus::Fail(message) => {
+                writer.set_color(ColorSpec::new().set_fg(Some(Color::Red))).ok();
+                writeln!(writer, "failed: {message}").ok();
+                failures.push((test_name, message));
+            }
+            // The compilation error has already been reported
+            TestStatus::CompileError => {
+                writer.set_color(ColorSpec::new().set_fg(Some(Color::Red))).ok();
+                writeln!(writer, "failed to compile").ok();
+                failures.push((test_name, "failed to compile".to_owned()));
+            }
         }
         writer.reset().ok();
     }
 
-    if failing == 0 {
+    writeln!(
+        writer,
+        "\n{} passed ({expected_failures} of them failing as expected), {} failed",
+        passed + expected_failures,
+        failures.len()
+    )
+    .ok();
+
+    if failures.is_empty() {
         writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).unwrap();
         writeln!(writer, "All tests passed").ok();
     } else {
-        let plural = if failing == 1 { "" } else { "s" };
-        return Err(CliError::Generic(format!("{failing} test{plural} failed")));
+        writeln!(writer, "Unexpected results:").ok();
+        for (test_name, message) in &failures {
+            writeln!(writer, "    {test_name}: {message}").ok();
+        }
+        let plural = if failures.len() == 1 { "" } else { "s" };
+        return Err(CliError::Generic(format!("{} test{plural} failed", failures.len())));
     }
 
     writer.reset().ok();
     Ok(())
 }
 
+/// How a test ended compared to how it was expected to
+#[derive(Debug, PartialEq, Eq)]
+enum TestStatus {
+    Pass,
+    /// The test failed, with the given message, as its `should_fail` attribute expects
+    ExpectedFailure(String),
+    /// The test failed when it should have passed, or the other way around
+    Fail(String),
+    CompileError,
+}
+
+/// Why a test failed
+#[derive(Debug)]
+struct TestFailure {
+    /// The description of the failure shown to the user
+    error: String,
+    /// The message of the failing assertion, if the test failed on one which has a message
+    assertion_message: Option<String>,
+}
+
+impl TestFailure {
+    fn new(error: String, assertion_message: Option<String>) -> TestFailure {
+        TestFailure { error, assertion_message }
+    }
+}
+
 fn run_test<B: Backend>(
     backend: &B,
-    test_name: &str,
-    main: FuncId,
+    test_function: FuncId,
+    scope: &TestScope,
     driver: &Driver,
     config: &CompileOptions,
-) -> Result<(), CliError<B>> {
-    let program = driver
-        .compile_no_check(config, main)
-        .map_err(|_| CliError::Generic(format!("Test '{test_name}' failed to compile")))?;
-
-    // Run the backend to ensure the PWG evaluates functions like std::hash::pedersen,
-    // otherwise constraints involving these expressions will not error.
-    match execute_circuit(backend, program.circuit, WitnessMap::new()) {
-        Ok(_) => Ok(()),
-        Err(error) => {
-            let writer = StandardStream::stderr(ColorChoice::Always);
-            let mut writer = writer.lock();
-            writer.set_color(ColorSpec::new().set_fg(Some(Color::Red))).ok();
-            writeln!(writer, "failed").ok();
-            writer.reset().ok();
-            Err(error.into())
+    oracle_resolver: &mut impl OracleResolver,
+) -> TestStatus {
+    let failure = match driver.compile_test(config, test_function) {
+        // Run the backend to ensure the PWG evaluates functions like std::hash::pedersen,
+        // otherwise constraints involving these expressions will not error.
+        Ok(program) => {
+            match execute_circuit(backend, program.circuit, WitnessMap::new(), oracle_resolver) {
+                Ok(_) => None,
+                Err(error) => match error.failed_assertion(&program.assertions) {
+                    Some(assertion) => {
+                        // Only point at the assertion when the failure is unexpected
+                        if *scope == TestScope::None {
+                            driver.report_failed_assertion(assertion, config);
+                        }
+                        let message = assertion.message.clone();
+                        let error = message.clone().unwrap_or_else(|| error.to_string());
+                        Some(TestFailure::new(error, message))
+                    }
+                    None => {
+                        let location = error
+                            .opcode_index()
+                            .and_then(|opcode_index| program.source_map.location(opcode_index));
+                        let error = match location {
+                            Some(location) => format!("{error} at {location}"),
+                            None => error.to_string(),
+                        };
+                        Some(TestFailure::new(error, None))
+                    }
+                },
+            }
         }
+        Err(TestCompileError::FailedConstraint { error, message }) => {
+            Some(TestFailure::new(error, message))
+        }
+        Err(TestCompileError::Reported(_)) => return TestStatus::CompileError,
+    };
+    test_status(scope, failure)
+}
+
+/// Checks how a test failed, if it failed, against its test scope. An expected reason is
+/// looked for in the message of the failing assertion only.
+fn test_status(scope: &TestScope, failure: Option<TestFailure>) -> TestStatus {
+    match (scope, failure) {
+        (TestScope::None, None) => TestStatus::Pass,
+        (TestScope::None, Some(failure)) => TestStatus::Fail(failure.error),
+        (TestScope::ShouldFail { .. }, None) => {
+            TestStatus::Fail("the test was expected to fail, but it passed".to_owned())
+        }
+        (TestScope::ShouldFail { reason: None }, Some(failure)) => {
+            TestStatus::ExpectedFailure(failure.error)
+        }
+        (TestScope::ShouldFail { reason: Some(reason) }, Some(failure)) => {
+            match &failure.assertion_message {
+                Some(message) if message.contains(reason.as_str()) => {
+                    TestStatus::ExpectedFailure(failure.error)
+                }
+                Some(message) => TestStatus::Fail(format!(
+                    "the test failed with \"{message}\", which does not contain the expected \"{reason}\""
+                )),
+                None => TestStatus::Fail(format!(
+                    "the test failed with \"{}\" rather than an assertion with the message \"{reason}\"",
+                    failure.error
+                )),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noirc_frontend::token::TestScope;
+
+    use super::{test_status, TestFailure, TestStatus};
+
+    #[test]
+    fn should_fail_tests_pass_when_they_fail() {
+        let failure = || {
+            let error = "Constraint is always false: not equal".to_owned();
+            Some(TestFailure::new(error, Some("not equal".to_owned())))
+        };
+        let should_fail =
+            |reason: Option<&str>| TestScope::ShouldFail { reason: reason.map(ToOwned::to_owned) };
+
+        assert_eq!(test_status(&TestScope::None, None), TestStatus::Pass);
+        assert!(matches!(test_status(&TestScope::None, failure()), TestStatus::Fail(_)));
+
+        assert!(matches!(test_status(&should_fail(None), None), TestStatus::Fail(_)));
+        assert_eq!(
+            test_status(&should_fail(None), failure()),
+            TestStatus::ExpectedFailure("Constraint is always false: not equal".to_owned())
+        );
+        assert!(matches!(
+            test_status(&should_fail(Some("equal")), failure()),
+            TestStatus::ExpectedFailure(_)
+        ));
+        assert!(matches!(
+            test_status(&should_fail(Some("out of range")), failure()),
+            TestStatus::Fail(_)
+        ));
+    }
+
+    #[test]
+    fn should_fail_reasons_only_match_assertion_messages() {
+        let should_fail = TestScope::ShouldFail { reason: Some("always false".to_owned()) };
+
+        // The reason is part of the description of the error, but not of the assertion message
+        let failure = TestFailure::new(
+            "Constraint is always false: not equal".to_owned(),
+            Some("not equal".to_owned()),
+        );
+        assert!(matches!(test_status(&should_fail, Some(failure)), TestStatus::Fail(_)));
+
+        let failure = TestFailure::new("Constraint is always false".to_owned(), None);
+        assert!(matches!(test_status(&should_fail, Some(failure)), TestStatus::Fail(_)));
     }
 }
@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use acvm::Backend;
+use clap::Args;
+use nargo::workspace::Workspace;
+
+use crate::{
+    constants::{VENDOR_COMMIT_FILE, VENDOR_DIR},
+    errors::CliError,
+    git::folder_name,
+    lockfile::ResolvedDependency,
+    resolver::DependencyResolutionError,
+};
+
+/// Copies the git dependencies of the workspace into its `vendor` directory,
+/// from which they are then resolved without needing the network
+#[derive(Debug, Clone, Args)]
+pub(crate) struct VendorCommand {}
+
+pub(crate) fn run<B: Backend>(
+    // Backend is currently unused, but the signature is kept close to the other commands
+    _backend: &B,
+    _args: VendorCommand,
+    workspace: &Workspace,
+    resolved: &[ResolvedDependency],
+) -> Result<(), CliError<B>> {
+    let vendor_dir = workspace.manifest_dir().join(VENDOR_DIR);
+    // Dependencies may currently be resolved from the vendor directory itself, so the new one
+    // is populated beside it before replacing it.
+    let staging_dir = workspace.manifest_dir().join(format!("{VENDOR_DIR}.tmp"));
+    if staging_dir.exists() {
+        remove_dir(&staging_dir)?;
+    }
+    std::fs::create_dir_all(&staging_dir).map_err(|error| {
+        CliError::Generic(format!("Could not create {}: {error}", staging_dir.display()))
+    })?;
+
+    let mut vendored = 0;
+    for resolution in resolved {
+        let folder_name =
+            folder_name(&resolution.dependency).map_err(DependencyResolutionError::from)?;
+        let destination = staging_dir.join(folder_name);
+        if destination.exists() {
+            continue;
+        }
+        copy_package(&resolution.package_dir, &destination)?;
+        write_file(&destination.join(VENDOR_COMMIT_FILE), &resolution.locked.commit)?;
+        vendored += 1;
+    }
+
+    if vendor_dir.exists() {
+        remove_dir(&vendor_dir)?;
+    }
+    std::fs::rename(&staging_dir, &vendor_dir).map_err(|error| {
+        CliError::Generic(format!("Could not create {}: {error}", vendor_dir.display()))
+    })?;
+
+    println!("Vendored {vendored} dependencies into {}", vendor_dir.display());
+    Ok(())
+}
+
+/// Copies the package in `source` to `destination`, without its git metadata
+/// or the commit recorded if it was itself vendored.
+fn copy_package<B: Backend>(source: &Path, destination: &Path) -> Result<(), CliError<B>> {
+    copy_dir(source, destination, &|path| {
+        path.file_name() == Some(".git".as_ref()) || path == source.join(VENDOR_COMMIT_FILE)
+    })
+}
+
+/// Copies the directory `source` to `destination`. Symbolic links are copied as links rather
+/// than followed, as they may point outside of `source` or to one of its parent directories.
+fn copy_dir<B: Backend>(
+    source: &Path,
+    destination: &Path,
+    is_excluded: &dyn Fn(&Path) -> bool,
+) -> Result<(), CliError<B>> {
+    let copy_error = |error: std::io::Error| {
+        CliError::Generic(format!("Could not copy {}: {error}", source.display()))
+    };
+
+    std::fs::create_dir_all(destination).map_err(copy_error)?;
+    for entry in std::fs::read_dir(source).map_err(copy_error)? {
+        let entry = entry.map_err(copy_error)?;
+        let path = entry.path();
+        if is_excluded(&path) {
+            continue;
+        }
+        let target = destination.join(entry.file_name());
+        let file_type = entry.file_type().map_err(copy_error)?;
+        if file_type.is_symlink() {
+            copy_symlink(&path, &target).map_err(copy_error)?;
+        } else if file_type.is_dir() {
+            copy_dir(&path, &target, is_excluded)?;
+        } else {
+            std::fs::copy(&path, &target).map_err(copy_error)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, destination: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(source)?, destination)
+}
+
+#[cfg(windows)]
+fn copy_symlink(source: &Path, destination: &Path) -> std::io::Result<()> {
+    let target = std::fs::read_link(source)?;
+    if source.is_dir() {
+        std::os::windows::fs::symlink_dir(target, destination)
+    } else {
+        std::os::windows::fs::symlink_file(target, destination)
+    }
+}
+
+fn write_file<B: Backend>(path: &Path, contents: &str) -> Result<(), CliError<B>> {
+    std::fs::write(path, contents)
+        .map_err(|error| CliError::Generic(format!("Could not write {}: {error}", path.display())))
+}
+
+fn remove_dir<B: Backend>(path: &Path) -> Result<(), CliError<B>> {
+    std::fs::remove_dir_all(path)
+        .map_err(|error| CliError::Generic(format!("Could not remove {}: {error}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::copy_package;
+    use crate::{backends::ConcreteBackend, constants::VENDOR_COMMIT_FILE};
+
+    #[cfg(unix)]
+    #[test]
+    fn copies_symlinks_without_following_them() {
+        let source = tempdir::TempDir::new("source").unwrap();
+        std::fs::create_dir_all(source.path().join("src")).unwrap();
+        std::fs::write(source.path().join("src").join("lib.nr"), "fn foo() {}").unwrap();
+        std::fs::write(source.path().join(VENDOR_COMMIT_FILE), "0123456789abcdef").unwrap();
+        // Following this link would copy the package into itself endlessly
+        std::os::unix::fs::symlink("..", source.path().join("src").join("parent")).unwrap();
+
+        let destination = tempdir::TempDir::new("destination").unwrap();
+        let package_dir = destination.path().join("package");
+        copy_package::<ConcreteBackend>(source.path(), &package_dir).unwrap();
+
+        let lib = std::fs::read_to_string(package_dir.join("src").join("lib.nr")).unwrap();
+        assert_eq!(lib, "fn foo() {}");
+        let link = package_dir.join("src").join("parent");
+        assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_link(link).unwrap(), std::path::Path::new(".."));
+        assert!(!package_dir.join(VENDOR_COMMIT_FILE).exists());
+    }
+}
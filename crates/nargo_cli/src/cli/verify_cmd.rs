@@ -1,4 +1,3 @@
-use super::compile_cmd::compile_circuit;
 use super::fs::{
     common_reference_string::{
         read_cached_common_reference_string, update_common_reference_string,
@@ -10,7 +9,7 @@ use super::fs::{
 };
 use super::NargoConfig;
 use crate::{
-    constants::{PROOFS_DIR, PROOF_EXT, TARGET_DIR, VERIFIER_INPUT_FILE},
+    constants::{PROOFS_DIR, PROOF_EXT, VERIFIER_INPUT_FILE},
     errors::CliError,
 };
 
@@ -19,7 +18,7 @@ use clap::Args;
 use nargo::artifacts::program::PreprocessedProgram;
 use nargo::ops::{preprocess_program, verify_proof};
 use noirc_abi::input_parser::Format;
-use noirc_driver::CompileOptions;
+use noirc_driver::{CompileOptions, Driver};
 use std::path::{Path, PathBuf};
 
 /// Given a proof and a program, verify whether the proof is valid
@@ -32,23 +31,24 @@ pub(crate) struct VerifyCommand {
     circuit_name: Option<String>,
 
     #[clap(flatten)]
-    compile_options: CompileOptions,
+    pub(super) compile_options: CompileOptions,
 }
 
 pub(crate) fn run<B: Backend>(
     backend: &B,
     args: VerifyCommand,
     config: NargoConfig,
+    driver: &mut Driver,
 ) -> Result<(), CliError<B>> {
     let proof_path =
         config.program_dir.join(PROOFS_DIR).join(&args.proof).with_extension(PROOF_EXT);
 
-    let circuit_build_path = args
-        .circuit_name
-        .map(|circuit_name| config.program_dir.join(TARGET_DIR).join(circuit_name));
+    let circuit_build_path =
+        args.circuit_name.map(|circuit_name| config.target_dir.join(circuit_name));
 
     verify_with_path(
         backend,
+        driver,
         &config.program_dir,
         proof_path,
         circuit_build_path.as_ref(),
@@ -58,6 +58,7 @@ pub(crate) fn run<B: Backend>(
 
 fn verify_with_path<B: Backend, P: AsRef<Path>>(
     backend: &B,
+    driver: &mut Driver,
     program_dir: P,
     proof_path: PathBuf,
     circuit_build_path: Option<P>,
@@ -77,7 +78,8 @@ fn verify_with_path<B: Backend, P: AsRef<Path>>(
             (common_reference_string, program)
         }
         None => {
-            let program = compile_circuit(backend, program_dir.as_ref(), compile_options)?;
+            let program =
+                driver.compile_main(compile_options).map_err(|_| CliError::CompilationError)?;
             let common_reference_string =
                 update_common_reference_string(backend, &common_reference_string, &program.circuit)
                     .map_err(CliError::CommonReferenceStringError)?;
@@ -7,14 +7,22 @@ pub(crate) const PROOFS_DIR: &str = "proofs";
 pub(crate) const SRC_DIR: &str = "src";
 /// The directory to store circuits' serialized ACIR representations.
 pub(crate) const TARGET_DIR: &str = "target";
+/// The directory to store copies of the git dependencies of a workspace.
+pub(crate) const VENDOR_DIR: &str = "vendor";
 
 // Files
 /// The file from which Nargo pulls prover inputs
 pub(crate) const PROVER_INPUT_FILE: &str = "Prover";
 /// The file from which Nargo pulls verifier inputs
 pub(crate) const VERIFIER_INPUT_FILE: &str = "Verifier";
+/// The file from which Nargo pulls canned responses to oracle calls
+pub(crate) const ORACLE_RESPONSES_FILE: &str = "Oracles";
 /// The package definition file for a Noir project.
 pub(crate) const PKG_FILE: &str = "Nargo.toml";
+/// The file recording how the dependencies of a Noir project were resolved.
+pub(crate) const LOCK_FILE: &str = "Nargo.lock";
+/// The file recording the commit a vendored dependency was copied from.
+pub(crate) const VENDOR_COMMIT_FILE: &str = ".nargo-commit";
 
 // Extensions
 /// The extension for files containing circuit proofs.
@@ -3,7 +3,7 @@ use acvm::{
     SmartContract,
 };
 use hex::FromHexError;
-use nargo::NargoError;
+use nargo::{NargoError, OracleResolutionError};
 use noirc_abi::errors::{AbiError, InputParserError};
 use std::path::PathBuf;
 use thiserror::Error;
@@ -28,6 +28,10 @@ pub(crate) enum FilesystemError {
     /// WitnessMap serialization error
     #[error(transparent)]
     WitnessMapSerialization(#[from] WitnessMapError),
+
+    /// Oracle responses parsing error
+    #[error(transparent)]
+    OracleResponsesError(#[from] OracleResolutionError),
 }
 
 #[derive(Debug, Error)]
@@ -59,6 +63,10 @@ pub(crate) enum CliError<B: Backend> {
     #[error(transparent)]
     NargoError(#[from] NargoError),
 
+    /// Error from the language server
+    #[error(transparent)]
+    LspError(#[from] noir_lsp::LspError),
+
     /// Backend error caused by a function on the SmartContract trait
     #[error(transparent)]
     SmartContractError(<B as SmartContract>::Error), // Unfortunately, Rust won't let us `impl From` over an Associated Type on a generic
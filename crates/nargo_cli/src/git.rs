@@ -1,9 +1,97 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub(crate) fn git_dep_location(base: &url::Url, tag: &str) -> PathBuf {
-    let folder_name = super::resolver::resolve_folder_name(base, tag);
+use nargo::{
+    manifest::{GitDependency, GitReference, InvalidPackageError},
+    workspace::Workspace,
+};
+use thiserror::Error;
 
-    super::nargo_crates().join(folder_name)
+use crate::constants::{VENDOR_COMMIT_FILE, VENDOR_DIR};
+
+/// Errors covering situations where the repository of a git dependency can't be obtained.
+#[derive(Debug, Error)]
+pub(crate) enum GitError {
+    /// The url of the repository can't be parsed.
+    #[error("invalid git url {url}: {source}")]
+    InvalidUrl { url: String, source: url::ParseError },
+
+    /// The url of the repository has no host to name the directory of its clone after.
+    #[error("git url {url} has no host")]
+    MissingHost { url: String },
+
+    /// The dependency doesn't specify a single version of the repository.
+    #[error(transparent)]
+    InvalidReference(#[from] InvalidPackageError),
+
+    /// Git could not fetch the repository.
+    #[error("failed to download dependency {dependency}: {reason}")]
+    CloneFailed { dependency: String, reason: String },
+
+    /// The repository would need to be downloaded while offline.
+    #[error("dependency {dependency} is neither vendored nor cached, and cannot be downloaded while offline")]
+    Offline { dependency: String },
+
+    /// The commit of the repository can't be read.
+    #[error("could not read the commit of {}: {reason}", .dir.display())]
+    UnknownCommit { dir: PathBuf, reason: String },
+}
+
+/// Where the packages of git dependencies are taken from.
+///
+/// A package is looked up in the vendor directory of the workspace first, then in the cache
+/// of downloaded repositories. Failing that, its repository is downloaded unless offline.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GitSources {
+    vendor_dir: Option<PathBuf>,
+    offline: bool,
+}
+
+impl GitSources {
+    pub(crate) fn new(workspace: &Workspace, offline: bool) -> Self {
+        let vendor_dir = workspace.manifest_dir().join(VENDOR_DIR);
+        let vendor_dir = vendor_dir.is_dir().then_some(vendor_dir);
+        GitSources { vendor_dir, offline: offline || workspace.offline }
+    }
+
+    /// Returns the directory of the dependency's package, downloading its repository if needed.
+    pub(crate) fn fetch(&self, dependency: &GitDependency) -> Result<PathBuf, GitError> {
+        if let Some(vendor_dir) = &self.vendor_dir {
+            let vendored = vendor_dir.join(folder_name(dependency)?);
+            if vendored.is_dir() {
+                return Ok(vendored);
+            }
+        }
+        let loc = git_dep_location(dependency)?;
+        if !loc.exists() {
+            if self.offline {
+                return Err(GitError::Offline { dependency: dependency.to_string() });
+            }
+            clone_git_repo(dependency, &loc)?;
+        }
+        git_dep_package_dir(dependency, &loc)
+    }
+}
+
+/// Returns the name of the directory holding the given dependency, relative to the cache
+/// or the vendor directory.
+pub(crate) fn folder_name(dependency: &GitDependency) -> Result<String, GitError> {
+    let base = url::Url::parse(&dependency.git)
+        .map_err(|source| GitError::InvalidUrl { url: dependency.git.clone(), source })?;
+    let reference = dependency.reference()?;
+    super::resolver::resolve_folder_name(&base, &reference, dependency.directory()?)
+}
+
+/// Returns the directory the repository of the given dependency is cloned into.
+fn git_dep_location(dependency: &GitDependency) -> Result<PathBuf, GitError> {
+    Ok(super::nargo_crates().join(folder_name(dependency)?))
+}
+
+/// Returns the directory of the dependency's package within the clone of its repository.
+fn git_dep_package_dir(dependency: &GitDependency, repo_dir: &Path) -> Result<PathBuf, GitError> {
+    match dependency.directory()? {
+        Some(directory) => Ok(repo_dir.join(directory)),
+        None => Ok(repo_dir.to_path_buf()),
+    }
 }
 
 /// XXX: I'd prefer to use a GitHub library however, there
@@ -12,31 +100,104 @@ pub(crate) fn git_dep_location(base: &url::Url, tag: &str) -> PathBuf {
 /// github-rs looks promising, however it seems to require an API token
 ///
 /// One advantage of using "git clone" is that there is effectively no rate limit
-pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
+fn clone_git_repo(dependency: &GitDependency, loc: &Path) -> Result<(), GitError> {
+    let url = dependency.git.as_str();
+    let cloned = match dependency.reference()? {
+        GitReference::Tag(name) | GitReference::Branch(name) => {
+            run_git(&["clone", "--depth", "1", "--branch", name, url], Some(loc))
+        }
+        GitReference::DefaultBranch => run_git(&["clone", "--depth", "1", url], Some(loc)),
+        // A single commit can't be cloned, so it is fetched into an empty repository instead.
+        // This requires its full SHA, which `GitDependency::reference` ensures.
+        GitReference::Rev(rev) => run_git(&["init", "--quiet"], Some(loc))
+            .and_then(|_| run_git_in(loc, &["fetch", "--depth", "1", url, rev]))
+            .and_then(|_| run_git_in(loc, &["checkout", "FETCH_HEAD"])),
+    };
+    cloned.map_err(|reason| {
+        // Don't leave a partial clone behind, as it would be mistaken for a complete one
+        let _ = std::fs::remove_dir_all(loc);
+        GitError::CloneFailed { dependency: dependency.to_string(), reason }
+    })
+}
+
+/// Runs a git command, with `target` as its last argument if any.
+fn run_git(args: &[&str], target: Option<&Path>) -> Result<(), String> {
     use std::process::Command;
 
-    let base = match url::Url::parse(url) {
-        Ok(base) => base,
-        Err(err) => return Err(err.to_string()),
-    };
+    let mut command = Command::new("git");
+    command.arg("-c").arg("advice.detachedHead=false").args(args);
+    if let Some(target) = target {
+        command.arg(target);
+    }
+    let status = command.status().map_err(|err| format!("could not run git: {err}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("`git {}` failed with {status}", args.join(" ")))
+    }
+}
+
+/// Runs a git command in the repository at `repo_dir`.
+fn run_git_in(repo_dir: &Path, args: &[&str]) -> Result<(), String> {
+    let repo_dir = repo_dir.to_string_lossy();
+    let args: Vec<&str> =
+        ["-C", repo_dir.as_ref()].into_iter().chain(args.iter().copied()).collect();
+    run_git(&args, None)
+}
 
-    let loc = git_dep_location(&base, tag);
-    if loc.exists() {
-        return Ok(loc);
-    }
-
-    Command::new("git")
-        .arg("-c")
-        .arg("advice.detachedHead=false")
-        .arg("clone")
-        .arg("--depth")
-        .arg("1")
-        .arg("--branch")
-        .arg(tag)
-        .arg(base.as_str())
-        .arg(&loc)
-        .status()
-        .expect("git clone command failed to start");
-
-    Ok(loc)
+/// Returns the SHA of the commit the package in `package_dir` was taken from.
+///
+/// This is the commit checked out in its repository, or the one recorded when it was vendored.
+pub(crate) fn git_commit(package_dir: &Path) -> Result<String, GitError> {
+    use std::process::Command;
+
+    let vendor_commit_file = package_dir.join(VENDOR_COMMIT_FILE);
+    if vendor_commit_file.is_file() {
+        let commit = std::fs::read_to_string(&vendor_commit_file).map_err(|err| {
+            GitError::UnknownCommit { dir: package_dir.to_path_buf(), reason: err.to_string() }
+        })?;
+        return Ok(commit.trim().to_owned());
+    }
+
+    let output =
+        Command::new("git").arg("-C").arg(package_dir).arg("rev-parse").arg("HEAD").output();
+    match output {
+        Ok(output) if output.status.success() => {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+        }
+        Ok(output) => Err(GitError::UnknownCommit {
+            dir: package_dir.to_path_buf(),
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        }),
+        Err(err) => Err(GitError::UnknownCommit {
+            dir: package_dir.to_path_buf(),
+            reason: format!("could not run git: {err}"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nargo::manifest::GitDependency;
+
+    use super::{clone_git_repo, GitError};
+
+    #[test]
+    fn failed_clones_are_removed() {
+        // Nothing listens on port 1, so the clone fails without needing the network. A commit is
+        // fetched into a repository which is created first, so the clone is left partial.
+        let dependency = GitDependency {
+            git: "https://127.0.0.1:1/x".to_owned(),
+            tag: None,
+            branch: None,
+            rev: Some("0123456789abcdef0123456789abcdef01234567".to_owned()),
+            directory: None,
+        };
+        let cache = tempdir::TempDir::new("cache").unwrap();
+        let loc = cache.path().join("x");
+
+        let result = clone_git_repo(&dependency, &loc);
+        assert!(matches!(result, Err(GitError::CloneFailed { .. })), "got {result:?}");
+        assert!(!loc.exists());
+    }
 }
@@ -18,10 +18,14 @@ pub mod cli;
 mod constants;
 mod errors;
 mod git;
+mod lockfile;
 mod manifest;
 mod resolver;
 
-use nargo::manifest::InvalidPackageError;
+use nargo::{
+    manifest::{InvalidPackageError, Manifest, WorkspaceConfig, WorkspaceManifest},
+    workspace::{Package, Workspace},
+};
 
 fn nargo_crates() -> PathBuf {
     dirs::home_dir().unwrap().join("nargo")
@@ -49,6 +53,80 @@ fn find_package_manifest(current_path: &Path) -> Result<PathBuf, InvalidPackageE
         .ok_or_else(|| InvalidPackageError::MissingManifestFile(current_path.to_path_buf()))
 }
 
+/// Returns the workspace containing `current_path`.
+///
+/// This is the workspace declared by the nearest manifest file if it has a `[workspace]`
+/// section. Otherwise, it is the workspace listing the package of this manifest among its
+/// members, or a workspace of its own if there is none.
+fn find_workspace(current_path: &Path) -> Result<Workspace, InvalidPackageError> {
+    let manifest_path = find_package_manifest(current_path)?;
+    let manifest_dir =
+        manifest_path.parent().expect("infallible: manifest file path can't be root directory");
+
+    let (package, offline) = match manifest::parse_manifest(&manifest_path)? {
+        Manifest::Workspace(workspace) => return workspace_from_manifest(manifest_dir, workspace),
+        Manifest::Package(manifest) => {
+            (package_in(manifest_dir, manifest.package.name())?, manifest.package.offline())
+        }
+    };
+
+    for workspace_dir in manifest_dir.ancestors().skip(1) {
+        let manifest_path = match find_file(workspace_dir, "Nargo", "toml") {
+            Some(manifest_path) => manifest_path,
+            None => continue,
+        };
+        if let Manifest::Workspace(workspace) = manifest::parse_manifest(&manifest_path)? {
+            let workspace = workspace_from_manifest(workspace_dir, workspace)?;
+            if workspace.members.contains(&package) {
+                return Ok(workspace);
+            }
+        }
+    }
+    let mut workspace = Workspace::new(None, vec![package])?;
+    workspace.offline = offline;
+    Ok(workspace)
+}
+
+/// Builds the workspace declared by the manifest in `workspace_dir`.
+fn workspace_from_manifest(
+    workspace_dir: &Path,
+    manifest: WorkspaceManifest,
+) -> Result<Workspace, InvalidPackageError> {
+    let WorkspaceConfig { members, offline } = manifest.workspace;
+    let packages = members
+        .iter()
+        .map(|member| {
+            let package_dir = workspace_dir.join(member);
+            let manifest_path = find_file(&package_dir, "Nargo", "toml")
+                .ok_or_else(|| InvalidPackageError::MissingManifestFile(package_dir.clone()))?;
+            let manifest = manifest::parse(manifest_path)?;
+            package_in(&package_dir, manifest.package.name())
+        })
+        .collect::<Result<_, _>>()?;
+    let mut workspace = Workspace::new(Some(normalize(workspace_dir)), packages)?;
+    workspace.offline = offline;
+    Ok(workspace)
+}
+
+/// Returns the package in `package_dir`, named after its directory if its manifest doesn't name it.
+fn package_in(package_dir: &Path, name: Option<&str>) -> Result<Package, InvalidPackageError> {
+    let root_dir = normalize(package_dir);
+    let name = match name {
+        Some(name) => name.to_owned(),
+        None => root_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(str::to_owned)
+            .ok_or_else(|| InvalidPackageError::UnnamedPackage(root_dir.clone()))?,
+    };
+    Ok(Package { name, root_dir })
+}
+
+/// Resolves `.` and `..` components and symlinks so that the paths of a package compare equal.
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
 fn lib_or_bin(current_path: &Path) -> Result<(PathBuf, CrateType), InvalidPackageError> {
     // A library has a lib.nr and a binary has a main.nr
     // You cannot have both.
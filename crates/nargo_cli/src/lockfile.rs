@@ -0,0 +1,290 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use nargo::{
+    lockfile::{LockedDependency, Lockfile, LOCKFILE_VERSION},
+    manifest::{Dependency, GitDependency},
+    workspace::Workspace,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    constants::{LOCK_FILE, VENDOR_COMMIT_FILE},
+    git::{git_commit, GitSources},
+    resolver::DependencyResolutionError,
+};
+
+/// How dependencies may be resolved with regards to the lockfile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LockMode {
+    /// Update the lockfile with any change.
+    Update,
+    /// Fail if the lockfile would need to be updated.
+    Locked,
+}
+
+/// A git dependency of the workspace along with the package it resolved to.
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedDependency {
+    pub(crate) dependency: GitDependency,
+    /// The directory of the dependency's package
+    pub(crate) package_dir: PathBuf,
+    pub(crate) locked: LockedDependency,
+}
+
+/// Resolves the git dependencies of every package of the workspace, including transitive ones,
+/// and checks each of them against the lockfile at the root of the workspace.
+///
+/// A dependency recorded in the lockfile must resolve to the same contents for its commit.
+/// Dependencies which moved to another commit, such as a branch which was pushed to, are
+/// locked again, as are new dependencies, while those no longer used are removed, unless
+/// the `mode` forbids updating the lockfile.
+pub(crate) fn lock_dependencies(
+    workspace: &Workspace,
+    mode: LockMode,
+    git_sources: &GitSources,
+) -> Result<Vec<ResolvedDependency>, DependencyResolutionError> {
+    let lockfile_path = workspace.manifest_dir().join(LOCK_FILE);
+    let existing_lockfile = read_lockfile(&lockfile_path)?;
+
+    let mut visited = HashSet::new();
+    let mut resolved = Vec::new();
+    for package in &workspace.members {
+        resolve_git_dependencies(&package.root_dir, git_sources, &mut visited, &mut resolved)?;
+    }
+
+    for resolution in &resolved {
+        let locked =
+            existing_lockfile.as_ref().and_then(|lockfile| lockfile.find(&resolution.dependency));
+        if let Some(locked) = locked {
+            check_locked_dependency(&resolution.dependency, locked, &resolution.locked, mode)?;
+        }
+    }
+
+    let lockfile =
+        Lockfile::new(resolved.iter().map(|resolution| resolution.locked.clone()).collect());
+    let is_up_to_date = match &existing_lockfile {
+        Some(existing_lockfile) => existing_lockfile == &lockfile,
+        None => lockfile.dependencies.is_empty(),
+    };
+    if !is_up_to_date {
+        if mode != LockMode::Update {
+            return Err(DependencyResolutionError::LockfileOutdated);
+        }
+        std::fs::write(&lockfile_path, lockfile.to_toml_string()).map_err(|source| {
+            DependencyResolutionError::WriteFailed { path: lockfile_path.clone(), source }
+        })?;
+    }
+    Ok(resolved)
+}
+
+fn read_lockfile(lockfile_path: &Path) -> Result<Option<Lockfile>, DependencyResolutionError> {
+    if !lockfile_path.exists() {
+        return Ok(None);
+    }
+    let toml_as_string = std::fs::read_to_string(lockfile_path).map_err(|source| {
+        DependencyResolutionError::ReadFailed { path: lockfile_path.to_path_buf(), source }
+    })?;
+    let lockfile = Lockfile::from_toml_str(&toml_as_string)?;
+    if lockfile.version != LOCKFILE_VERSION {
+        return Err(DependencyResolutionError::UnsupportedLockfileVersion {
+            found: lockfile.version,
+            supported: LOCKFILE_VERSION,
+        });
+    }
+    Ok(Some(lockfile))
+}
+
+fn check_locked_dependency(
+    dependency: &GitDependency,
+    locked: &LockedDependency,
+    resolution: &LockedDependency,
+    mode: LockMode,
+) -> Result<(), DependencyResolutionError> {
+    if locked.commit != resolution.commit {
+        if mode == LockMode::Update {
+            return Ok(());
+        }
+        return Err(DependencyResolutionError::CommitMismatch {
+            dependency: dependency.to_string(),
+            expected: locked.commit.clone(),
+            found: resolution.commit.clone(),
+        });
+    }
+    if locked.checksum != resolution.checksum {
+        return Err(DependencyResolutionError::ChecksumMismatch {
+            dependency: dependency.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Resolves the git dependencies of the package in `package_dir` and of its own dependencies,
+/// downloading them if needed. Each package is only visited once.
+fn resolve_git_dependencies(
+    package_dir: &Path,
+    git_sources: &GitSources,
+    visited: &mut HashSet<PathBuf>,
+    resolved: &mut Vec<ResolvedDependency>,
+) -> Result<(), DependencyResolutionError> {
+    let manifest_path = super::find_package_manifest(package_dir)?;
+    let manifest = super::manifest::parse(manifest_path)?;
+
+    for dependency in manifest.dependencies.values() {
+        let dependency_dir = match dependency {
+            Dependency::Github(dependency) => {
+                let dependency_dir = git_sources.fetch(dependency)?;
+                if visited.contains(&dependency_dir) {
+                    continue;
+                }
+                let commit = git_commit(&dependency_dir)?;
+                let checksum = checksum(&dependency_dir)?;
+                resolved.push(ResolvedDependency {
+                    dependency: dependency.clone(),
+                    package_dir: dependency_dir.clone(),
+                    locked: LockedDependency::new(dependency, commit, checksum),
+                });
+                dependency_dir
+            }
+            Dependency::Path { path } => super::normalize(&package_dir.join(path)),
+        };
+        if visited.insert(dependency_dir.clone()) {
+            resolve_git_dependencies(&dependency_dir, git_sources, visited, resolved)?;
+        }
+    }
+    Ok(())
+}
+
+/// A file of a dependency, as it is hashed.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum DependencyFile {
+    /// A regular file, hashed by its contents
+    Regular(PathBuf),
+    /// A symbolic link, hashed by its target rather than by what it points to
+    Symlink(PathBuf),
+}
+
+/// Hashes the contents of the dependency in `dependency_dir`, ignoring the git metadata
+/// and the commit recorded when vendoring it.
+fn checksum(dependency_dir: &Path) -> Result<String, DependencyResolutionError> {
+    let mut files = Vec::new();
+    collect_files(dependency_dir, dependency_dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for (relative_path, file) in files {
+        hasher.update(relative_path.as_bytes());
+        let contents = match file {
+            DependencyFile::Regular(path) => {
+                hasher.update([0]);
+                std::fs::read(&path)
+                    .map_err(|source| DependencyResolutionError::ReadFailed { path, source })?
+            }
+            DependencyFile::Symlink(target) => {
+                hasher.update([1]);
+                target.to_string_lossy().into_owned().into_bytes()
+            }
+        };
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(contents);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Collects the files under `dir` along with their path relative to `root`, using `/` as separator.
+///
+/// Symbolic links are not followed, so that a link to one of its parent directories can't
+/// make the collection loop.
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<(String, DependencyFile)>,
+) -> Result<(), DependencyResolutionError> {
+    let read_failed = |path: &Path, source| DependencyResolutionError::ReadFailed {
+        path: path.to_owned(),
+        source,
+    };
+
+    let entries = std::fs::read_dir(dir).map_err(|source| read_failed(dir, source))?;
+    for entry in entries {
+        let entry = entry.map_err(|source| read_failed(dir, source))?;
+        let path = entry.path();
+        if entry.file_name() == ".git" || (dir == root && entry.file_name() == VENDOR_COMMIT_FILE) {
+            continue;
+        }
+        let metadata =
+            std::fs::symlink_metadata(&path).map_err(|source| read_failed(&path, source))?;
+        let file = if metadata.file_type().is_symlink() {
+            let target = std::fs::read_link(&path).map_err(|source| read_failed(&path, source))?;
+            DependencyFile::Symlink(target)
+        } else if metadata.is_dir() {
+            collect_files(root, &path, files)?;
+            continue;
+        } else {
+            DependencyFile::Regular(path.clone())
+        };
+
+        let relative_path = path.strip_prefix(root).expect("path is within the root directory");
+        let relative_path = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.push((relative_path, file));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::checksum;
+    use crate::{constants::VENDOR_COMMIT_FILE, resolver::DependencyResolutionError};
+
+    #[test]
+    fn checksum_depends_on_contents_only() {
+        let first = tempdir::TempDir::new("first").unwrap();
+        let second = tempdir::TempDir::new("second").unwrap();
+        for dir in [first.path(), second.path()] {
+            std::fs::create_dir_all(dir.join("src")).unwrap();
+            std::fs::write(dir.join("src").join("lib.nr"), "fn foo() {}").unwrap();
+            std::fs::write(dir.join("Nargo.toml"), "[package]").unwrap();
+        }
+        std::fs::create_dir_all(second.path().join(".git")).unwrap();
+        std::fs::write(second.path().join(".git").join("HEAD"), "ref").unwrap();
+        std::fs::write(second.path().join(VENDOR_COMMIT_FILE), "0123456789abcdef").unwrap();
+        assert_eq!(checksum(first.path()).unwrap(), checksum(second.path()).unwrap());
+
+        std::fs::write(second.path().join("src").join("lib.nr"), "fn bar() {}").unwrap();
+        assert_ne!(checksum(first.path()).unwrap(), checksum(second.path()).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn checksum_hashes_symlinks_by_their_target() {
+        let dir = tempdir::TempDir::new("dependency").unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("lib.nr"), "fn foo() {}").unwrap();
+        let without_link = checksum(dir.path()).unwrap();
+
+        // A link to a parent directory would make the collection loop if it were followed
+        std::os::unix::fs::symlink("..", dir.path().join("src").join("parent")).unwrap();
+        let with_link = checksum(dir.path()).unwrap();
+        assert_ne!(with_link, without_link);
+
+        std::fs::remove_file(dir.path().join("src").join("parent")).unwrap();
+        std::os::unix::fs::symlink(".", dir.path().join("src").join("parent")).unwrap();
+        assert_ne!(checksum(dir.path()).unwrap(), with_link);
+    }
+
+    #[test]
+    fn checksum_fails_on_unreadable_directories() {
+        let dir = tempdir::TempDir::new("dependency").unwrap();
+        let missing = dir.path().join("missing");
+        assert!(matches!(
+            checksum(&missing),
+            Err(DependencyResolutionError::ReadFailed { path, .. }) if path == missing
+        ));
+    }
+}
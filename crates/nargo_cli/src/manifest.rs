@@ -1,15 +1,27 @@
 use std::path::Path;
 
-use nargo::manifest::{InvalidPackageError, PackageManifest};
+use nargo::manifest::{InvalidPackageError, Manifest, PackageManifest};
 
 /// Parses a Nargo.toml file from it's path
 /// The path to the toml file must be present.
 /// Calling this function without this guarantee is an ICE.
-pub(crate) fn parse<P: AsRef<Path>>(
+pub(crate) fn parse_manifest<P: AsRef<Path>>(
     path_to_toml: P,
-) -> Result<PackageManifest, InvalidPackageError> {
+) -> Result<Manifest, InvalidPackageError> {
     let toml_as_string =
         std::fs::read_to_string(&path_to_toml).expect("ice: path given for toml file is invalid");
 
-    PackageManifest::from_toml_str(&toml_as_string)
+    Manifest::from_toml_str(&toml_as_string)
+}
+
+/// Parses the Nargo.toml file of a package from it's path, erroring if it declares a workspace.
+pub(crate) fn parse<P: AsRef<Path>>(
+    path_to_toml: P,
+) -> Result<PackageManifest, InvalidPackageError> {
+    match parse_manifest(&path_to_toml)? {
+        Manifest::Package(manifest) => Ok(manifest),
+        Manifest::Workspace(_) => {
+            Err(InvalidPackageError::UnexpectedWorkspace(path_to_toml.as_ref().to_path_buf()))
+        }
+    }
 }
@@ -4,32 +4,60 @@ use std::{
 };
 
 use acvm::{acir::circuit::Opcode, Language};
-use nargo::manifest::{Dependency, PackageManifest};
-use noirc_driver::Driver;
+use nargo::manifest::{Dependency, GitReference, PackageManifest};
+use nargo::workspace::Package;
+use noirc_driver::{DependencyError, Driver};
 use noirc_frontend::graph::{CrateId, CrateName, CrateType};
 use thiserror::Error;
 
-use crate::{git::clone_git_repo, InvalidPackageError};
+use crate::{
+    git::{GitError, GitSources},
+    InvalidPackageError,
+};
 
 /// Creates a unique folder name for a GitHub repo
-/// by using it's URL and tag
-pub(crate) fn resolve_folder_name(base: &url::Url, tag: &str) -> String {
-    let mut folder_name = base.domain().unwrap().to_owned();
+/// by using it's URL, the version of the repo and the directory of the package within it
+pub(crate) fn resolve_folder_name(
+    base: &url::Url,
+    reference: &GitReference,
+    directory: Option<&str>,
+) -> Result<String, GitError> {
+    // The host may be a domain or an IP address, but urls such as `file://` ones have none
+    let host = base.host_str().ok_or_else(|| GitError::MissingHost { url: base.to_string() })?;
+    let mut folder_name = host.to_owned();
     folder_name.push_str(base.path());
-    folder_name.push_str(tag);
-    folder_name
+    match reference {
+        GitReference::Tag(tag) => folder_name.push_str(&format!("@tag-{tag}")),
+        GitReference::Branch(branch) => folder_name.push_str(&format!("@branch-{branch}")),
+        GitReference::Rev(rev) => folder_name.push_str(&format!("@rev-{rev}")),
+        GitReference::DefaultBranch => folder_name.push_str("@default"),
+    }
+    if let Some(directory) = directory {
+        // Escape the directory so that the folder name remains a single path component
+        let directory = directory.replace('%', "%25").replace('/', "%2F").replace('\\', "%5C");
+        folder_name.push_str(&format!("@dir-{directory}"));
+    }
+    Ok(folder_name)
 }
 
 /// Errors covering situations where a crate's dependency tree cannot be resolved.
 #[derive(Debug, Error)]
 pub(crate) enum DependencyResolutionError {
     /// Encountered error while downloading git repository.
-    #[error("{0}")]
-    GitError(String),
+    #[error(transparent)]
+    GitError(#[from] GitError),
 
     /// Attempted to depend on a binary crate.
-    #[error("dependency {dep_pkg_name} is a binary package and so it cannot be depended upon.")]
-    BinaryDependency { dep_pkg_name: String },
+    #[error("dependency {dep_pkg_name} is a binary package and so it cannot be depended upon.\ndependency chain: {chain}")]
+    BinaryDependency { dep_pkg_name: String, chain: String },
+
+    /// A package depends on itself, directly or transitively.
+    #[error("dependency cycle detected: {chain}")]
+    CyclicDependency { chain: String },
+
+    /// The crate graph rejected a dependency.
+    #[error(transparent)]
+    CrateGraphError(#[from] DependencyError),
 
     /// Attempted to depend on remote crate which has a local dependency.
     /// We have no guarantees that this local dependency will be available so must error.
@@ -39,6 +67,51 @@ pub(crate) enum DependencyResolutionError {
     /// Dependency is not a valid crate
     #[error(transparent)]
     MalformedDependency(#[from] InvalidPackageError),
+
+    /// Lockfile is unreadable.
+    #[error("Nargo.lock is badly formed, could not parse.\n\n {0}")]
+    MalformedLockfile(#[from] toml::de::Error),
+
+    /// The lockfile was written in a format this version of nargo doesn't know.
+    #[error("Nargo.lock has version {found}, but this version of nargo only supports version {supported}")]
+    UnsupportedLockfileVersion { found: u32, supported: u32 },
+
+    /// A file needed to resolve the dependencies can't be read.
+    #[error("could not read {}: {source}", path.display())]
+    ReadFailed { path: PathBuf, source: std::io::Error },
+
+    /// The lockfile can't be written.
+    #[error("could not write {}: {source}", path.display())]
+    WriteFailed { path: PathBuf, source: std::io::Error },
+
+    /// A git dependency resolved to another commit than the one recorded in the lockfile,
+    /// which may not be updated.
+    #[error("dependency {dependency} resolved to commit {found}, but Nargo.lock expects commit {expected}.\nRun without --locked or --frozen to update Nargo.lock")]
+    CommitMismatch { dependency: String, expected: String, found: String },
+
+    /// The contents of a git dependency differ from those recorded in the lockfile.
+    #[error("the contents of dependency {dependency} do not match the checksum in Nargo.lock")]
+    ChecksumMismatch { dependency: String },
+
+    /// The lockfile doesn't match the dependencies but it may not be updated.
+    #[error("Nargo.lock needs to be updated but --locked or --frozen was passed")]
+    LockfileOutdated,
+}
+
+/// A driver holding the crates of several packages of a workspace.
+pub(crate) struct WorkspaceDriver {
+    driver: Driver,
+    /// The crate of each package, by the root directory of the package
+    package_crates: HashMap<PathBuf, CrateId>,
+}
+
+impl WorkspaceDriver {
+    /// Returns the driver, set up to check and compile the crate of `package`.
+    pub(crate) fn select(&mut self, package: &Package) -> &mut Driver {
+        let crate_id = self.package_crates[&package.root_dir];
+        self.driver.set_local_crate(crate_id);
+        &mut self.driver
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -57,11 +130,17 @@ struct CachedDep {
 /// We need to download those too
 pub(crate) struct Resolver<'a> {
     driver: &'a mut Driver,
+    git_sources: &'a GitSources,
+    /// The crate created for each package directory, so that a package reached through
+    /// several paths of the dependency tree is only compiled once.
+    resolved_crates: HashMap<PathBuf, CrateId>,
+    /// The names and directories of the packages from the root to the one being resolved.
+    chain: Vec<(String, PathBuf)>,
 }
 
 impl<'a> Resolver<'a> {
-    fn with_driver(driver: &mut Driver) -> Resolver {
-        Resolver { driver }
+    fn with_driver(driver: &'a mut Driver, git_sources: &'a GitSources) -> Resolver<'a> {
+        Resolver { driver, git_sources, resolved_crates: HashMap::new(), chain: Vec::new() }
     }
 
     /// Returns the Driver and the backend to use
@@ -72,25 +151,66 @@ impl<'a> Resolver<'a> {
         dir_path: &std::path::Path,
         np_language: Language,
         is_opcode_supported: Box<dyn Fn(&Opcode) -> bool>,
+        git_sources: &GitSources,
     ) -> Result<Driver, DependencyResolutionError> {
-        let mut driver = Driver::new(&np_language, is_opcode_supported);
-        let (entry_path, crate_type) = super::lib_or_bin(dir_path)?;
-
         let manifest_path = super::find_package_manifest(dir_path)?;
         let manifest = super::manifest::parse(&manifest_path)?;
+        let pkg_root = manifest_path.parent().expect("Every manifest path has a parent.");
+        let package = super::package_in(pkg_root, manifest.package.name())?;
 
-        let crate_id = driver.create_local_crate(entry_path, crate_type);
+        // The crate of the only package is the first one, so it is already the local crate
+        let workspace_driver =
+            Resolver::resolve_packages(&[&package], np_language, is_opcode_supported, git_sources)?;
+        Ok(workspace_driver.driver)
+    }
 
-        let mut resolver = Resolver::with_driver(&mut driver);
-        let pkg_root = manifest_path.parent().expect("Every manifest path has a parent.");
-        resolver.resolve_manifest(crate_id, manifest, pkg_root)?;
+    /// Returns a driver holding the crates of `packages` and of all of their dependencies.
+    /// Each package is given a single crate, even when several of the packages depend on it,
+    /// so that it is only resolved and compiled once.
+    pub(crate) fn resolve_packages(
+        packages: &[&Package],
+        np_language: Language,
+        is_opcode_supported: Box<dyn Fn(&Opcode) -> bool>,
+        git_sources: &GitSources,
+    ) -> Result<WorkspaceDriver, DependencyResolutionError> {
+        let mut driver = Driver::new(&np_language, is_opcode_supported);
+        let mut resolver = Resolver::with_driver(&mut driver, git_sources);
+
+        let mut package_crates = HashMap::new();
+        for package in packages {
+            let crate_id = resolver.resolve_package(package)?;
+            package_crates.insert(package.root_dir.clone(), crate_id);
+        }
 
         add_std_lib(&mut driver);
-        Ok(driver)
+        Ok(WorkspaceDriver { driver, package_crates })
+    }
+
+    /// Creates the crate of a package and resolves its dependencies.
+    fn resolve_package(&mut self, package: &Package) -> Result<CrateId, DependencyResolutionError> {
+        // The package may be a dependency of a package resolved before it
+        if let Some(&crate_id) = self.resolved_crates.get(&package.root_dir) {
+            return Ok(crate_id);
+        }
+
+        let (entry_path, crate_type) = super::lib_or_bin(&package.root_dir)?;
+        let manifest_path = super::find_package_manifest(&package.root_dir)?;
+        let manifest = super::manifest::parse(manifest_path)?;
+
+        let crate_id = if self.resolved_crates.is_empty() {
+            self.driver.create_local_crate(entry_path, crate_type)
+        } else {
+            self.driver.create_non_local_crate(entry_path, crate_type)
+        };
+        self.resolved_crates.insert(package.root_dir.clone(), crate_id);
+
+        self.chain.push((package.name.clone(), package.root_dir.clone()));
+        self.resolve_manifest(crate_id, manifest, &package.root_dir)?;
+        self.chain.pop();
+        Ok(crate_id)
     }
 
     // Resolves a config file by recursively resolving the dependencies in the config
-    // Need to solve the case of a project trying to use itself as a dep
     //
     // We do not need to add stdlib, as it's implicitly
     // imported. However, it may be helpful to have the stdlib imported by the
@@ -101,37 +221,55 @@ impl<'a> Resolver<'a> {
         manifest: PackageManifest,
         pkg_root: &Path,
     ) -> Result<(), DependencyResolutionError> {
-        let mut cached_packages: HashMap<PathBuf, (CrateId, CachedDep)> = HashMap::new();
-
-        // First download and add these top level dependencies crates to the Driver
         for (dep_pkg_name, pkg_src) in manifest.dependencies.iter() {
-            let (dir_path, dep_meta) = Resolver::cache_dep(pkg_src, pkg_root)?;
+            let (dir_path, dep_meta) = Resolver::cache_dep(pkg_src, pkg_root, self.git_sources)?;
+            let dir_path = super::normalize(&dir_path);
 
-            let (entry_path, crate_type) = (&dep_meta.entry_path, &dep_meta.crate_type);
+            if self.chain.iter().any(|(_, package_dir)| package_dir == &dir_path) {
+                return Err(DependencyResolutionError::CyclicDependency {
+                    chain: self.format_chain(dep_pkg_name),
+                });
+            }
 
-            if crate_type == &CrateType::Binary {
+            if dep_meta.crate_type == CrateType::Binary {
                 return Err(DependencyResolutionError::BinaryDependency {
                     dep_pkg_name: dep_pkg_name.to_string(),
+                    chain: self.format_chain(dep_pkg_name),
                 });
             }
 
-            let crate_id = self.driver.create_non_local_crate(entry_path, *crate_type);
-            self.driver.add_dep(parent_crate, crate_id, dep_pkg_name);
-
-            cached_packages.insert(dir_path, (crate_id, dep_meta));
-        }
+            // The package was already reached through another path of the dependency tree
+            if let Some(&crate_id) = self.resolved_crates.get(&dir_path) {
+                self.driver.add_dep(parent_crate, crate_id, dep_pkg_name)?;
+                continue;
+            }
 
-        // Resolve all transitive dependencies
-        for (dependency_path, (crate_id, dep_meta)) in cached_packages {
             if dep_meta.remote && dep_meta.manifest.has_local_dependency() {
-                return Err(DependencyResolutionError::RemoteDepWithLocalDep { dependency_path });
+                return Err(DependencyResolutionError::RemoteDepWithLocalDep {
+                    dependency_path: dir_path,
+                });
             }
-            let mut new_res = Resolver::with_driver(self.driver);
-            new_res.resolve_manifest(crate_id, dep_meta.manifest, &dependency_path)?;
+
+            let crate_id =
+                self.driver.create_non_local_crate(&dep_meta.entry_path, dep_meta.crate_type);
+            self.driver.add_dep(parent_crate, crate_id, dep_pkg_name)?;
+            self.resolved_crates.insert(dir_path.clone(), crate_id);
+
+            // Resolve the transitive dependencies
+            self.chain.push((dep_pkg_name.clone(), dir_path.clone()));
+            self.resolve_manifest(crate_id, dep_meta.manifest, &dir_path)?;
+            self.chain.pop();
         }
         Ok(())
     }
 
+    /// Describes the path of dependencies from the root package to `dep_pkg_name`.
+    fn format_chain(&self, dep_pkg_name: &str) -> String {
+        let mut names: Vec<&str> = self.chain.iter().map(|(name, _)| name.as_str()).collect();
+        names.push(dep_pkg_name);
+        names.join(" -> ")
+    }
+
     /// If the dependency is remote, download the dependency
     /// and return the directory path along with the metadata
     /// Needed to fill the CachedDep struct
@@ -141,6 +279,7 @@ impl<'a> Resolver<'a> {
     fn cache_dep(
         dep: &Dependency,
         pkg_root: &Path,
+        git_sources: &GitSources,
     ) -> Result<(PathBuf, CachedDep), DependencyResolutionError> {
         fn retrieve_meta(
             dir_path: &Path,
@@ -153,9 +292,8 @@ impl<'a> Resolver<'a> {
         }
 
         match dep {
-            Dependency::Github { git, tag } => {
-                let dir_path =
-                    clone_git_repo(git, tag).map_err(DependencyResolutionError::GitError)?;
+            Dependency::Github(dependency) => {
+                let dir_path = git_sources.fetch(dependency)?;
                 let meta = retrieve_meta(&dir_path, true)?;
                 Ok((dir_path, meta))
             }
@@ -175,3 +313,24 @@ pub(crate) fn add_std_lib(driver: &mut Driver) {
     let std_crate = driver.create_non_local_crate(path_to_std_lib_file, CrateType::Library);
     driver.propagate_dep(std_crate, &CrateName::new(std_crate_name).unwrap());
 }
+
+#[cfg(test)]
+mod tests {
+    use nargo::manifest::GitReference;
+
+    use super::resolve_folder_name;
+    use crate::git::GitError;
+
+    #[test]
+    fn folder_names_of_mirrors() {
+        let url = url::Url::parse("https://10.0.0.1/noir-lang/example").unwrap();
+        let folder_name = resolve_folder_name(&url, &GitReference::Tag("v0.1.0"), None).unwrap();
+        assert_eq!(folder_name, "10.0.0.1/noir-lang/example@tag-v0.1.0");
+
+        let url = url::Url::parse("file:///mirrors/example").unwrap();
+        assert!(matches!(
+            resolve_folder_name(&url, &GitReference::DefaultBranch, None),
+            Err(GitError::MissingHost { .. })
+        ));
+    }
+}
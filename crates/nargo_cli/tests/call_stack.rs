@@ -0,0 +1,43 @@
+//! This integration test checks that a failing execution reports the call stack of the failing
+//! constraint, innermost location first.
+
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathChild};
+
+#[test]
+fn failing_execution_reports_call_stack() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    test_dir
+        .child("Nargo.toml")
+        .write_str("[package]\nauthors = [\"\"]\n\n[dependencies]")
+        .unwrap();
+    test_dir
+        .child("src")
+        .child("main.nr")
+        .write_str(
+            r#"fn main(x: Field) {
+    check(x);
+}
+
+fn check(x: Field) {
+    assert(x == 1);
+}
+"#,
+        )
+        .unwrap();
+    test_dir.child("Prover.toml").write_str("x = \"2\"").unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("execute");
+
+    let output = cmd.assert().failure().get_output().stderr.clone();
+    let stderr = String::from_utf8(output).unwrap();
+    assert!(stderr.contains("failed at:"), "expected a call stack, got {stderr:?}");
+
+    let assertion = stderr.find("main.nr:6:").expect("the failing assertion is reported");
+    let call = stderr.find("main.nr:2:").expect("the call to `check` is reported");
+    assert!(assertion < call, "expected the innermost location first, got {stderr:?}");
+}
@@ -0,0 +1,77 @@
+//! This integration test resolves the dependency graph of a package.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathChild};
+
+/// Writes a package named `name` in `dir` with the given dependencies and source file.
+fn write_package(dir: &assert_fs::TempDir, name: &str, dependencies: &[&str], entry: (&str, &str)) {
+    let package_dir = dir.child(name);
+    let dependencies: String = dependencies
+        .iter()
+        .map(|dependency| format!("{dependency} = {{ path = \"../{dependency}\" }}\n"))
+        .collect();
+    package_dir
+        .child("Nargo.toml")
+        .write_str(&format!("[package]\nauthors = [\"\"]\n\n[dependencies]\n{dependencies}"))
+        .unwrap();
+    package_dir.child("src").child(entry.0).write_str(entry.1).unwrap();
+}
+
+#[test]
+fn diamond_dependencies_share_a_crate() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+
+    write_package(
+        &test_dir,
+        "d",
+        &[],
+        (
+            "lib.nr",
+            "pub struct Point { pub x: Field }\n\npub fn new(x: Field) -> Point { Point { x } }",
+        ),
+    );
+    write_package(
+        &test_dir,
+        "b",
+        &["d"],
+        ("lib.nr", "use dep::d;\n\npub fn make(x: Field) -> d::Point { d::new(x) }"),
+    );
+    write_package(
+        &test_dir,
+        "c",
+        &["d"],
+        ("lib.nr", "use dep::d;\n\npub fn take(point: d::Point) -> Field { point.x }"),
+    );
+    write_package(
+        &test_dir,
+        "a",
+        &["b", "c"],
+        (
+            "main.nr",
+            "use dep::b;\nuse dep::c;\n\nfn main(x: Field) { assert(c::take(b::make(x)) == x); }",
+        ),
+    );
+
+    // `b` and `c` must agree on the type of `d::Point`
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.child("a").path()).arg("check");
+    cmd.assert().success();
+}
+
+#[test]
+fn dependency_cycles_are_reported() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+
+    write_package(&test_dir, "b", &["c"], ("lib.nr", "pub fn foo() {}"));
+    write_package(&test_dir, "c", &["b"], ("lib.nr", "pub fn bar() {}"));
+    write_package(&test_dir, "a", &["b"], ("main.nr", "fn main(x: Field) { assert(x == x); }"));
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.child("a").path()).arg("check");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("dependency cycle detected: a -> b -> c -> b"));
+}
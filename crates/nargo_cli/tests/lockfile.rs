@@ -0,0 +1,64 @@
+//! This integration test checks how an existing Nargo.lock is handled.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathChild};
+
+const STALE_DEPENDENCY: &str = r#"
+[[dependency]]
+git = "https://github.com/noir-lang/example"
+tag = "v0.1.0"
+commit = "0123456789abcdef0123456789abcdef01234567"
+checksum = "00"
+"#;
+
+fn create_package(lockfile: &str) -> assert_fs::TempDir {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    test_dir
+        .child("Nargo.toml")
+        .write_str("[package]\nauthors = [\"\"]\n\n[dependencies]")
+        .unwrap();
+    test_dir
+        .child("src")
+        .child("main.nr")
+        .write_str("fn main(x: Field) { assert(x == 1); }")
+        .unwrap();
+    test_dir.child("Nargo.lock").write_str(lockfile).unwrap();
+    test_dir
+}
+
+#[test]
+fn locked_fails_on_outdated_lockfile() {
+    // The lockfile records a dependency which the package no longer has
+    let test_dir = create_package(&format!("version = 1\n{STALE_DEPENDENCY}"));
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("check").arg("--locked");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Nargo.lock needs to be updated but --locked or --frozen was passed",
+    ));
+
+    // Without `--locked`, the lockfile is updated instead
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("check");
+    cmd.assert().success();
+
+    let lockfile = std::fs::read_to_string(test_dir.child("Nargo.lock").path()).unwrap();
+    assert!(!lockfile.contains("noir-lang/example"), "got {lockfile:?}");
+}
+
+#[test]
+fn unknown_lockfile_version_is_rejected() {
+    let test_dir = create_package(&format!("version = 2\n{STALE_DEPENDENCY}"));
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("check");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Nargo.lock has version 2, but this version of nargo only supports version 1",
+    ));
+}
@@ -0,0 +1,65 @@
+//! This integration test checks that diagnostics printed as JSON are the only output on stdout.
+
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathChild};
+
+#[test]
+fn missing_main_function_is_reported_as_json() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    test_dir
+        .child("Nargo.toml")
+        .write_str("[package]\nauthors = [\"\"]\n\n[dependencies]")
+        .unwrap();
+    test_dir.child("src").child("main.nr").write_str("fn foo() {}").unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("compile").arg("main").arg("--message-format").arg("json");
+
+    let output = cmd.assert().failure().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 1, "expected a single diagnostic on stdout, got {stdout:?}");
+
+    let diagnostic: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(diagnostic["message"], "cannot compile a program with no main function");
+    assert_eq!(diagnostic["severity"], "error");
+}
+
+#[test]
+fn workspace_headers_are_kept_off_stdout_in_json_mode() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    test_dir.child("Nargo.toml").write_str("[workspace]\nmembers = [\"a\", \"b\"]").unwrap();
+
+    // The misspelled attribute is warned about in each package
+    for package_name in ["a", "b"] {
+        let package_dir = test_dir.child(package_name);
+        package_dir
+            .child("Nargo.toml")
+            .write_str("[package]\nauthors = [\"\"]\n\n[dependencies]")
+            .unwrap();
+        package_dir
+            .child("src")
+            .child("main.nr")
+            .write_str("#[tset]\nfn main(x: Field) {\n    assert(x == 1);\n}")
+            .unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("check").arg("--message-format").arg("json");
+
+    let output = cmd.assert().success().get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "expected a warning for each package on stdout, got {stdout:?}");
+    for line in lines {
+        let diagnostic: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(diagnostic["severity"], "warning");
+    }
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("[a]") && stderr.contains("[b]"), "missing headers in {stderr:?}");
+}
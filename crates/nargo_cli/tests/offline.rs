@@ -0,0 +1,66 @@
+//! This integration test resolves git dependencies without network access.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathAssert, PathChild};
+
+#[test]
+fn offline_resolves_vendored_dependencies() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    std::env::set_current_dir(&test_dir).unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("new").arg("a");
+    cmd.assert().success();
+
+    let package_dir = test_dir.child("a");
+    package_dir
+        .child("Nargo.toml")
+        .write_str(
+            "[package]\nauthors = [\"\"]\n\n[dependencies]\n\
+             dep = { git = \"https://github.com/noir-lang/nargo-offline-test\", tag = \"v0.1.0\" }",
+        )
+        .unwrap();
+    std::env::set_current_dir(&package_dir).unwrap();
+
+    // The dependency isn't downloaded, so it can't be resolved while offline
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("check").arg("--offline");
+    cmd.assert().failure().stderr(predicate::str::contains("cannot be downloaded while offline"));
+
+    // Once vendored, it is resolved from the vendor directory
+    let vendored = package_dir.child("vendor/github.com/noir-lang/nargo-offline-test@tag-v0.1.0");
+    vendored
+        .child("Nargo.toml")
+        .write_str("[package]\nauthors = [\"\"]\n\n[dependencies]")
+        .unwrap();
+    vendored.child("src/lib.nr").write_str("fn foo() {}").unwrap();
+    vendored.child(".nargo-commit").write_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("check").arg("--offline");
+    cmd.assert().success();
+
+    package_dir
+        .child("Nargo.lock")
+        .assert(predicate::str::contains("0123456789abcdef0123456789abcdef01234567"));
+
+    // A dependency which moved to another commit is locked again, unless the lockfile is frozen
+    vendored.child(".nargo-commit").write_str("89abcdef0123456789abcdef0123456789abcdef").unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("check").arg("--frozen");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Run without --locked or --frozen to update Nargo.lock"));
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("check").arg("--offline");
+    cmd.assert().success();
+
+    package_dir
+        .child("Nargo.lock")
+        .assert(predicate::str::contains("89abcdef0123456789abcdef0123456789abcdef"));
+}
@@ -0,0 +1,61 @@
+//! This integration test runs the programs of `test_data_ssa_refactor` through both the legacy
+//! and the experimental SSA pipelines and checks that they compute the same outputs.
+
+use assert_cmd::prelude::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use assert_fs::prelude::{PathChild, PathCopy};
+
+fn execute(program_dir: &Path, experimental_ssa: bool) -> Output {
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(program_dir);
+    cmd.arg("execute");
+    if experimental_ssa {
+        cmd.arg("--experimental-ssa");
+    }
+    cmd.output().unwrap()
+}
+
+#[test]
+fn pipelines_compute_the_same_outputs() {
+    let test_data_dir =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("test_data_ssa_refactor");
+    let config = fs::read_to_string(test_data_dir.join("config.toml")).unwrap();
+    let config: BTreeMap<String, Vec<String>> = toml::from_str(&config).unwrap();
+
+    // Copy the test cases into a temp dir so we don't leave artifacts around.
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    test_dir.copy_from(&test_data_dir, &["**"]).unwrap();
+
+    let test_names = fs::read_dir(&test_data_dir)
+        .unwrap()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().into_string().unwrap());
+
+    for test_name in test_names {
+        if config["exclude"].contains(&test_name) {
+            continue;
+        }
+        let program_dir = test_dir.child(&test_name);
+
+        let legacy = execute(&program_dir, false);
+        let experimental = execute(&program_dir, true);
+
+        let should_fail = config["fail"].contains(&test_name);
+        assert_eq!(legacy.status.success(), !should_fail, "legacy pipeline on {test_name}");
+        assert_eq!(
+            experimental.status.success(),
+            !should_fail,
+            "experimental pipeline on {test_name}"
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&legacy.stdout),
+            String::from_utf8_lossy(&experimental.stdout),
+            "the pipelines disagree on the output of {test_name}"
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! This integration test checks that the legacy SSA pass expands recursive calls up to its
+//! recursion limit of 100 nested calls, and reports an error rather than overflowing the stack
+//! beyond it.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathChild};
+
+fn execute_with_depth(depth: u32) -> assert_cmd::assert::Assert {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    test_dir
+        .child("Nargo.toml")
+        .write_str("[package]\nauthors = [\"\"]\n\n[dependencies]")
+        .unwrap();
+    // `count(n)` expands `n + 1` nested calls to itself
+    test_dir
+        .child("src")
+        .child("main.nr")
+        .write_str(&format!(
+            r#"fn main(x: Field) {{
+    assert(count({}) == x);
+}}
+
+fn count(n: u32) -> Field {{
+    if n == 0 {{
+        0
+    }} else {{
+        1 + count(n - 1)
+    }}
+}}
+"#,
+            depth - 1
+        ))
+        .unwrap();
+    test_dir.child("Prover.toml").write_str(&format!("x = \"{}\"", depth - 1)).unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("execute");
+    cmd.assert()
+}
+
+#[test]
+fn expands_recursion_up_to_the_limit() {
+    execute_with_depth(100).success();
+}
+
+#[test]
+fn reports_recursion_beyond_the_limit() {
+    execute_with_depth(101)
+        .failure()
+        .stderr(predicate::str::contains("Could not determine the depth of the recursive calls"));
+}
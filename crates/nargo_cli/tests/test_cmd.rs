@@ -0,0 +1,103 @@
+//! This integration test checks that `nargo test` reports tests marked `#[test(should_fail)]`
+//! as passing when they fail, and as failing when they pass.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathChild};
+
+fn create_package(source: &str) -> assert_fs::TempDir {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    test_dir
+        .child("Nargo.toml")
+        .write_str("[package]\nauthors = [\"\"]\n\n[dependencies]")
+        .unwrap();
+    test_dir.child("src").child("main.nr").write_str(source).unwrap();
+    test_dir
+}
+
+#[test]
+fn should_fail_test_passes_when_it_fails() {
+    let test_dir = create_package(
+        r#"fn add(x: Field, y: Field) -> Field {
+    x + y
+}
+
+#[test(should_fail)]
+fn test_wrong_sum() {
+    assert(add(1, 1) == 3);
+}
+
+#[test(should_fail_with = "not equal")]
+fn test_wrong_sum_with_reason() {
+    assert(add(1, 2) == 4, "not equal");
+}
+"#,
+    );
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("test");
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("2 passed (2 of them failing as expected), 0 failed"));
+}
+
+#[test]
+fn should_fail_test_fails_when_it_passes() {
+    let test_dir = create_package(
+        r#"fn add(x: Field, y: Field) -> Field {
+    x + y
+}
+
+#[test(should_fail)]
+fn test_right_sum() {
+    assert(add(1, 1) == 2);
+}
+"#,
+    );
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("test");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "test_right_sum: the test was expected to fail, but it passed",
+        ))
+        .stderr(predicate::str::contains("1 test failed"));
+}
+
+#[test]
+fn should_fail_with_checks_assertions_which_fail_during_execution() {
+    // The hash is only computed by the backend, so the assertions can't be checked at compile time
+    let test_dir = create_package(
+        r#"#[test(should_fail_with = "wrong hash")]
+fn test_expected_message() {
+    let hash = std::hash::pedersen([1]);
+    assert(hash[0] == 0, "wrong hash");
+}
+
+#[test(should_fail_with = "Cannot satisfy")]
+fn test_unexpected_message() {
+    let hash = std::hash::pedersen([1]);
+    assert(hash[0] == 0, "wrong hash");
+}
+"#,
+    );
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("test");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("ok, failed as expected: wrong hash"))
+        .stderr(predicate::str::contains(
+            "test_unexpected_message: the test failed with \"wrong hash\", which does not contain the expected \"Cannot satisfy\"",
+        ))
+        .stderr(predicate::str::contains("1 passed (1 of them failing as expected), 1 failed"));
+}
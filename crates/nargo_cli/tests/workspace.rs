@@ -0,0 +1,115 @@
+//! This integration test runs commands across the packages of a workspace.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathAssert, PathChild};
+
+#[test]
+fn workspace_example() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    std::env::set_current_dir(&test_dir).unwrap();
+
+    for package_name in ["a", "b"] {
+        let mut cmd = Command::cargo_bin("nargo").unwrap();
+        cmd.arg("new").arg(package_name);
+        cmd.assert().success();
+    }
+    test_dir.child("Nargo.toml").write_str("[workspace]\nmembers = [\"a\", \"b\"]").unwrap();
+
+    // `nargo check` at the root of the workspace checks every package
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("check");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[a]").and(predicate::str::contains("[b]")));
+
+    test_dir.child("a").child("Prover.toml").assert(predicate::path::is_file());
+    test_dir.child("b").child("Prover.toml").assert(predicate::path::is_file());
+
+    // `nargo compile c --package b` only compiles `b`, into the target directory of the workspace
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("compile").arg("c").arg("--package").arg("b");
+    cmd.assert().success();
+
+    test_dir.child("target").child("b").child("c.json").assert(predicate::path::is_file());
+    test_dir.child("target").child("a").assert(predicate::path::missing());
+
+    // Within a package, commands run on that package unless `--workspace` is given
+    std::env::set_current_dir(test_dir.child("a")).unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("compile").arg("c");
+    cmd.assert().success();
+    test_dir.child("target").child("a").child("c.json").assert(predicate::path::is_file());
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("check").arg("--workspace");
+    cmd.assert().success().stdout(predicate::str::contains("[b]"));
+
+    // Unknown packages are rejected
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("check").arg("--package").arg("c");
+    cmd.assert().failure().stderr(predicate::str::contains("workspace has no package named `c`"));
+}
+
+#[test]
+fn shared_dependencies_are_compiled_once() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    test_dir.child("Nargo.toml").write_str("[workspace]\nmembers = [\"a\", \"b\"]").unwrap();
+
+    // The misspelled attribute of the library is warned about each time it is compiled
+    let lib_dir = test_dir.child("lib");
+    lib_dir.child("Nargo.toml").write_str("[package]\nauthors = [\"\"]\n\n[dependencies]").unwrap();
+    lib_dir
+        .child("src")
+        .child("lib.nr")
+        .write_str("#[tset]\npub fn double(x: Field) -> Field {\n    x * 2\n}")
+        .unwrap();
+
+    for package_name in ["a", "b"] {
+        let package_dir = test_dir.child(package_name);
+        package_dir
+            .child("Nargo.toml")
+            .write_str("[package]\nauthors = [\"\"]\n\n[dependencies]\nlib = { path = \"../lib\" }")
+            .unwrap();
+        package_dir
+            .child("src")
+            .child("main.nr")
+            .write_str("use dep::lib;\n\nfn main(x: Field) {\n    assert(lib::double(x) == 2);\n}")
+            .unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("check");
+
+    let output = cmd.assert().success().get_output().stderr.clone();
+    let stderr = String::from_utf8(output).unwrap();
+    assert_eq!(stderr.matches("Unknown attribute 'tset'").count(), 1, "got {stderr:?}");
+}
+
+#[test]
+fn impls_on_primitive_types_are_rejected_in_every_member() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    test_dir.child("Nargo.toml").write_str("[workspace]\nmembers = [\"a\", \"b\"]").unwrap();
+
+    // Only the second member, which isn't the first crate of the driver, has the impl
+    for (package_name, source) in [
+        ("a", "fn main(x: Field) {\n    assert(x == 1);\n}"),
+        ("b", "impl Field {}\n\nfn main(x: Field) {\n    assert(x == 1);\n}"),
+    ] {
+        let package_dir = test_dir.child(package_name);
+        package_dir
+            .child("Nargo.toml")
+            .write_str("[package]\nauthors = [\"\"]\n\n[dependencies]")
+            .unwrap();
+        package_dir.child("src").child("main.nr").write_str(source).unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("check");
+    cmd.assert().failure().stderr(predicate::str::contains("Non-struct type used in impl"));
+}
@@ -67,6 +67,8 @@ enum JsonTypes {
     ArrayString(Vec<String>),
     // Array of booleans
     ArrayBool(Vec<bool>),
+    // Array of mixed JsonTypes, e.g. the elements of a tuple
+    Array(Vec<JsonTypes>),
     // Struct of JsonTypes
     Table(BTreeMap<String, JsonTypes>),
 }
@@ -78,16 +80,27 @@ impl From<InputValue> for JsonTypes {
                 let f_str = format!("0x{}", f.to_hex());
                 JsonTypes::String(f_str)
             }
-            InputValue::Vec(v) => {
-                let array = v.iter().map(|i| format!("0x{}", i.to_hex())).collect();
-                JsonTypes::ArrayString(array)
-            }
+            InputValue::Vec(elements) => JsonTypes::Array(vecmap(elements, JsonTypes::from)),
             InputValue::String(s) => JsonTypes::String(s),
             InputValue::Struct(map) => {
                 let map_with_json_types =
                     btree_map(map, |(key, value)| (key, JsonTypes::from(value)));
                 JsonTypes::Table(map_with_json_types)
             }
+            InputValue::Tuple(elements) => JsonTypes::Array(vecmap(elements, JsonTypes::from)),
+        }
+    }
+}
+
+impl JsonTypes {
+    /// Returns the elements of an array, regardless of which of the array variants it was parsed as.
+    fn into_array_elements(self) -> Option<Vec<JsonTypes>> {
+        match self {
+            JsonTypes::ArrayNum(arr_num) => Some(vecmap(arr_num, JsonTypes::Integer)),
+            JsonTypes::ArrayString(arr_str) => Some(vecmap(arr_str, JsonTypes::String)),
+            JsonTypes::ArrayBool(arr_bool) => Some(vecmap(arr_bool, JsonTypes::Bool)),
+            JsonTypes::Array(array) => Some(array),
+            _ => None,
         }
     }
 }
@@ -98,6 +111,41 @@ impl InputValue {
         param_type: &AbiType,
         arg_name: &str,
     ) -> Result<InputValue, InputParserError> {
+        match param_type {
+            // Array elements may themselves be arrays, structs or tuples so each is parsed recursively.
+            AbiType::Array { typ, .. } => {
+                let elements = value
+                    .into_array_elements()
+                    .ok_or_else(|| InputParserError::AbiTypeMismatch(param_type.clone()))?;
+
+                let array_elements =
+                    try_vecmap(elements.into_iter().enumerate(), |(index, element)| {
+                        let element_id = format!("{arg_name}[{index}]");
+                        InputValue::try_from_json(element, typ, &element_id)
+                    })?;
+
+                return Ok(InputValue::Vec(array_elements));
+            }
+            // The elements of a tuple may be of different types so each is parsed against the type of its field.
+            AbiType::Tuple { fields } => {
+                let elements = value
+                    .into_array_elements()
+                    .filter(|elements| elements.len() == fields.len())
+                    .ok_or_else(|| InputParserError::AbiTypeMismatch(param_type.clone()))?;
+
+                let tuple_elements = try_vecmap(
+                    elements.into_iter().zip(fields).enumerate(),
+                    |(index, (element, field_type))| {
+                        let element_id = format!("{arg_name}.{index}");
+                        InputValue::try_from_json(element, field_type, &element_id)
+                    },
+                )?;
+
+                return Ok(InputValue::Tuple(tuple_elements));
+            }
+            _ => (),
+        }
+
         let input_value = match value {
             JsonTypes::String(string) => match param_type {
                 AbiType::String { .. } => InputValue::String(string),
@@ -105,7 +153,7 @@ impl InputValue {
                     InputValue::Field(parse_str_to_field(&string)?)
                 }
 
-                AbiType::Array { .. } | AbiType::Struct { .. } => {
+                AbiType::Array { .. } | AbiType::Struct { .. } | AbiType::Tuple { .. } => {
                     return Err(InputParserError::AbiTypeMismatch(param_type.clone()))
                 }
             },
@@ -115,21 +163,11 @@ impl InputValue {
                 InputValue::Field(new_value)
             }
             JsonTypes::Bool(boolean) => InputValue::Field(boolean.into()),
-            JsonTypes::ArrayNum(arr_num) => {
-                let array_elements =
-                    vecmap(arr_num, |elem_num| FieldElement::from(i128::from(elem_num)));
-
-                InputValue::Vec(array_elements)
-            }
-            JsonTypes::ArrayString(arr_str) => {
-                let array_elements = try_vecmap(arr_str, |elem_str| parse_str_to_field(&elem_str))?;
-
-                InputValue::Vec(array_elements)
-            }
-            JsonTypes::ArrayBool(arr_bool) => {
-                let array_elements = vecmap(arr_bool, FieldElement::from);
-
-                InputValue::Vec(array_elements)
+            JsonTypes::ArrayNum(_)
+            | JsonTypes::ArrayString(_)
+            | JsonTypes::ArrayBool(_)
+            | JsonTypes::Array(_) => {
+                return Err(InputParserError::AbiTypeMismatch(param_type.clone()))
             }
 
             JsonTypes::Table(table) => match param_type {
@@ -14,9 +14,10 @@ use crate::{Abi, AbiType};
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub enum InputValue {
     Field(FieldElement),
-    Vec(Vec<FieldElement>),
+    Vec(Vec<InputValue>),
     String(String),
     Struct(BTreeMap<String, InputValue>),
+    Tuple(Vec<InputValue>),
 }
 
 impl InputValue {
@@ -32,14 +33,12 @@ impl InputValue {
                 field_element.is_one() || field_element.is_zero()
             }
 
-            (InputValue::Vec(field_elements), AbiType::Array { length, typ, .. }) => {
-                if field_elements.len() != *length as usize {
+            (InputValue::Vec(elements), AbiType::Array { length, typ, .. }) => {
+                if elements.len() != *length as usize {
                     return false;
                 }
                 // Check that all of the array's elements' values match the ABI as well.
-                field_elements
-                    .iter()
-                    .all(|field_element| Self::Field(*field_element).matches_abi(typ))
+                elements.iter().all(|element| element.matches_abi(typ))
             }
 
             (InputValue::String(string), AbiType::String { length }) => {
@@ -63,6 +62,18 @@ impl InputValue {
                 })
             }
 
+            (InputValue::Tuple(elements), AbiType::Tuple { fields }) => {
+                if elements.len() != fields.len() {
+                    return false;
+                }
+
+                // Check that all of the tuple's elements' values match the ABI as well.
+                elements
+                    .iter()
+                    .zip(fields)
+                    .all(|(element, field_type)| element.matches_abi(field_type))
+            }
+
             // All other InputValue-AbiType combinations are fundamentally incompatible.
             _ => false,
         }
@@ -150,6 +161,29 @@ mod serialization_tests {
                     },
                     visibility: AbiVisibility::Private,
                 },
+                AbiParameter {
+                    name: "baz".into(),
+                    typ: AbiType::Tuple {
+                        fields: vec![
+                            AbiType::Field,
+                            AbiType::Struct { fields: vec![("field1".into(), AbiType::Boolean)] },
+                        ],
+                    },
+                    visibility: AbiVisibility::Private,
+                },
+                AbiParameter {
+                    name: "qux".into(),
+                    typ: AbiType::Array {
+                        length: 2,
+                        typ: Box::new(AbiType::Struct {
+                            fields: vec![(
+                                "path".into(),
+                                AbiType::Array { length: 2, typ: Box::new(AbiType::Field) },
+                            )],
+                        }),
+                    },
+                    visibility: AbiVisibility::Private,
+                },
             ],
             return_type: Some(AbiType::String { length: 5 }),
             // These two fields are unused when serializing/deserializing to file.
@@ -163,9 +197,44 @@ mod serialization_tests {
                 "bar".into(),
                 InputValue::Struct(BTreeMap::from([
                     ("field1".into(), InputValue::Field(255u128.into())),
-                    ("field2".into(), InputValue::Vec(vec![true.into(), false.into()])),
+                    (
+                        "field2".into(),
+                        InputValue::Vec(vec![
+                            InputValue::Field(true.into()),
+                            InputValue::Field(false.into()),
+                        ]),
+                    ),
                 ])),
             ),
+            (
+                "baz".into(),
+                InputValue::Tuple(vec![
+                    InputValue::Field(FieldElement::from(42u128)),
+                    InputValue::Struct(BTreeMap::from([(
+                        "field1".into(),
+                        InputValue::Field(false.into()),
+                    )])),
+                ]),
+            ),
+            (
+                "qux".into(),
+                InputValue::Vec(vec![
+                    InputValue::Struct(BTreeMap::from([(
+                        "path".into(),
+                        InputValue::Vec(vec![
+                            InputValue::Field(1u128.into()),
+                            InputValue::Field(2u128.into()),
+                        ]),
+                    )])),
+                    InputValue::Struct(BTreeMap::from([(
+                        "path".into(),
+                        InputValue::Vec(vec![
+                            InputValue::Field(3u128.into()),
+                            InputValue::Field(4u128.into()),
+                        ]),
+                    )])),
+                ]),
+            ),
             (MAIN_RETURN_NAME.into(), InputValue::String("hello".to_owned())),
         ]);
 
@@ -179,7 +248,8 @@ mod serialization_tests {
     }
 }
 
-fn parse_str_to_field(value: &str) -> Result<FieldElement, InputParserError> {
+/// Parses a field element from either a hexadecimal string prefixed with `0x` or a decimal integer string.
+pub fn parse_str_to_field(value: &str) -> Result<FieldElement, InputParserError> {
     if value.starts_with("0x") {
         FieldElement::from_hex(value).ok_or_else(|| InputParserError::ParseHexStr(value.to_owned()))
     } else {
@@ -64,6 +64,8 @@ enum TomlTypes {
     ArrayString(Vec<String>),
     // Array of booleans
     ArrayBool(Vec<bool>),
+    // Array of mixed TomlTypes, e.g. the elements of a tuple
+    Array(Vec<TomlTypes>),
     // Struct of TomlTypes
     Table(BTreeMap<String, TomlTypes>),
 }
@@ -75,16 +77,27 @@ impl From<InputValue> for TomlTypes {
                 let f_str = format!("0x{}", f.to_hex());
                 TomlTypes::String(f_str)
             }
-            InputValue::Vec(v) => {
-                let array = v.iter().map(|i| format!("0x{}", i.to_hex())).collect();
-                TomlTypes::ArrayString(array)
-            }
+            InputValue::Vec(elements) => TomlTypes::Array(vecmap(elements, TomlTypes::from)),
             InputValue::String(s) => TomlTypes::String(s),
             InputValue::Struct(map) => {
                 let map_with_toml_types =
                     btree_map(map, |(key, value)| (key, TomlTypes::from(value)));
                 TomlTypes::Table(map_with_toml_types)
             }
+            InputValue::Tuple(elements) => TomlTypes::Array(vecmap(elements, TomlTypes::from)),
+        }
+    }
+}
+
+impl TomlTypes {
+    /// Returns the elements of an array, regardless of which of the array variants it was parsed as.
+    fn into_array_elements(self) -> Option<Vec<TomlTypes>> {
+        match self {
+            TomlTypes::ArrayNum(arr_num) => Some(vecmap(arr_num, TomlTypes::Integer)),
+            TomlTypes::ArrayString(arr_str) => Some(vecmap(arr_str, TomlTypes::String)),
+            TomlTypes::ArrayBool(arr_bool) => Some(vecmap(arr_bool, TomlTypes::Bool)),
+            TomlTypes::Array(array) => Some(array),
+            _ => None,
         }
     }
 }
@@ -95,6 +108,41 @@ impl InputValue {
         param_type: &AbiType,
         arg_name: &str,
     ) -> Result<InputValue, InputParserError> {
+        match param_type {
+            // Array elements may themselves be arrays, structs or tuples so each is parsed recursively.
+            AbiType::Array { typ, .. } => {
+                let elements = value
+                    .into_array_elements()
+                    .ok_or_else(|| InputParserError::AbiTypeMismatch(param_type.clone()))?;
+
+                let array_elements =
+                    try_vecmap(elements.into_iter().enumerate(), |(index, element)| {
+                        let element_id = format!("{arg_name}[{index}]");
+                        InputValue::try_from_toml(element, typ, &element_id)
+                    })?;
+
+                return Ok(InputValue::Vec(array_elements));
+            }
+            // The elements of a tuple may be of different types so each is parsed against the type of its field.
+            AbiType::Tuple { fields } => {
+                let elements = value
+                    .into_array_elements()
+                    .filter(|elements| elements.len() == fields.len())
+                    .ok_or_else(|| InputParserError::AbiTypeMismatch(param_type.clone()))?;
+
+                let tuple_elements = try_vecmap(
+                    elements.into_iter().zip(fields).enumerate(),
+                    |(index, (element, field_type))| {
+                        let element_id = format!("{arg_name}.{index}");
+                        InputValue::try_from_toml(element, field_type, &element_id)
+                    },
+                )?;
+
+                return Ok(InputValue::Tuple(tuple_elements));
+            }
+            _ => (),
+        }
+
         let input_value = match value {
             TomlTypes::String(string) => match param_type {
                 AbiType::String { .. } => InputValue::String(string),
@@ -102,7 +150,7 @@ impl InputValue {
                     InputValue::Field(parse_str_to_field(&string)?)
                 }
 
-                AbiType::Array { .. } | AbiType::Struct { .. } => {
+                AbiType::Array { .. } | AbiType::Struct { .. } | AbiType::Tuple { .. } => {
                     return Err(InputParserError::AbiTypeMismatch(param_type.clone()))
                 }
             },
@@ -112,21 +160,11 @@ impl InputValue {
                 InputValue::Field(new_value)
             }
             TomlTypes::Bool(boolean) => InputValue::Field(boolean.into()),
-            TomlTypes::ArrayNum(arr_num) => {
-                let array_elements =
-                    vecmap(arr_num, |elem_num| FieldElement::from(i128::from(elem_num)));
-
-                InputValue::Vec(array_elements)
-            }
-            TomlTypes::ArrayString(arr_str) => {
-                let array_elements = try_vecmap(arr_str, |elem_str| parse_str_to_field(&elem_str))?;
-
-                InputValue::Vec(array_elements)
-            }
-            TomlTypes::ArrayBool(arr_bool) => {
-                let array_elements = vecmap(arr_bool, FieldElement::from);
-
-                InputValue::Vec(array_elements)
+            TomlTypes::ArrayNum(_)
+            | TomlTypes::ArrayString(_)
+            | TomlTypes::ArrayBool(_)
+            | TomlTypes::Array(_) => {
+                return Err(InputParserError::AbiTypeMismatch(param_type.clone()))
             }
 
             TomlTypes::Table(table) => match param_type {
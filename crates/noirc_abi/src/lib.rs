@@ -64,6 +64,9 @@ pub enum AbiType {
     String {
         length: u64,
     },
+    Tuple {
+        fields: Vec<AbiType>,
+    },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -123,6 +126,7 @@ impl AbiType {
             AbiType::Array { length, typ: _ } => *length as usize,
             AbiType::Struct { fields, .. } => fields.len(),
             AbiType::String { length } => *length as usize,
+            AbiType::Tuple { fields } => fields.len(),
         }
     }
 
@@ -135,6 +139,9 @@ impl AbiType {
                 fields.iter().fold(0, |acc, (_, field_type)| acc + field_type.field_count())
             }
             AbiType::String { length } => *length as u32,
+            AbiType::Tuple { fields } => {
+                fields.iter().fold(0, |acc, field_type| acc + field_type.field_count())
+            }
         }
     }
 }
@@ -304,7 +311,14 @@ impl Abi {
         let mut encoded_value = Vec::new();
         match value {
             InputValue::Field(elem) => encoded_value.push(elem),
-            InputValue::Vec(vec_elem) => encoded_value.extend(vec_elem),
+            InputValue::Vec(elements) => match abi_type {
+                AbiType::Array { typ, .. } => {
+                    for element in elements {
+                        encoded_value.extend(Self::encode_value(element, typ)?);
+                    }
+                }
+                _ => unreachable!("value should have already been checked to match abi type"),
+            },
             InputValue::String(string) => {
                 let str_as_fields =
                     string.bytes().map(|byte| FieldElement::from_be_bytes_reduce(&[byte]));
@@ -318,6 +332,14 @@ impl Abi {
                 }
                 _ => unreachable!("value should have already been checked to match abi type"),
             },
+            InputValue::Tuple(elements) => match abi_type {
+                AbiType::Tuple { fields } => {
+                    for (element, typ) in elements.into_iter().zip(fields) {
+                        encoded_value.extend(Self::encode_value(element, typ)?);
+                    }
+                }
+                _ => unreachable!("value should have already been checked to match abi type"),
+            },
         }
         Ok(encoded_value)
     }
@@ -383,11 +405,14 @@ impl Abi {
 
                 InputValue::Field(field_element)
             }
-            AbiType::Array { length, .. } => {
-                let field_elements: Vec<FieldElement> =
-                    field_iterator.take(*length as usize).collect();
+            AbiType::Array { length, typ } => {
+                let length = *length as usize;
+                let mut array_elements = Vec::with_capacity(length);
+                for _ in 0..length {
+                    array_elements.push(Self::decode_value(field_iterator, typ)?);
+                }
 
-                InputValue::Vec(field_elements)
+                InputValue::Vec(array_elements)
             }
             AbiType::String { length } => {
                 let field_elements: Vec<FieldElement> =
@@ -406,6 +431,13 @@ impl Abi {
 
                 InputValue::Struct(struct_map)
             }
+            AbiType::Tuple { fields } => {
+                let tuple_elements = try_vecmap(fields, |field_type| {
+                    Self::decode_value(field_iterator, field_type)
+                })?;
+
+                InputValue::Tuple(tuple_elements)
+            }
         };
 
         Ok(value)
@@ -458,7 +490,13 @@ mod test {
 
         // Note we omit return value from inputs
         let inputs: InputMap = BTreeMap::from([
-            ("thing1".to_string(), InputValue::Vec(vec![FieldElement::one(), FieldElement::one()])),
+            (
+                "thing1".to_string(),
+                InputValue::Vec(vec![
+                    InputValue::Field(FieldElement::one()),
+                    InputValue::Field(FieldElement::one()),
+                ]),
+            ),
             ("thing2".to_string(), InputValue::Field(FieldElement::zero())),
         ]);
 
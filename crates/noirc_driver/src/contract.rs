@@ -1,6 +1,8 @@
 use crate::program::{deserialize_circuit, serialize_circuit};
+use crate::SourceMap;
 use acvm::acir::circuit::Circuit;
 use noirc_abi::Abi;
+use noirc_frontend::token::CustomAttribute;
 use serde::{Deserialize, Serialize};
 
 /// Describes the types of smart contract functions that are allowed.
@@ -41,10 +43,18 @@ pub struct ContractFunction {
 
     pub function_type: ContractFunctionType,
 
+    /// The attributes of the function which have no meaning to the compiler,
+    /// kept for tooling such as deployment scripts to read.
+    pub custom_attributes: Vec<CustomAttribute>,
+
     pub abi: Abi,
 
     #[serde(serialize_with = "serialize_circuit", deserialize_with = "deserialize_circuit")]
     pub bytecode: Circuit,
+
+    /// The source code locations each opcode of the bytecode is generated from
+    #[serde(default)]
+    pub source_map: SourceMap,
 }
 
 impl ContractFunctionType {
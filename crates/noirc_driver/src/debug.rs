@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use fm::FileManager;
+use noirc_errors::Location;
+use noirc_evaluator::OpcodeLocations;
+use serde::{Deserialize, Serialize};
+
+/// A location in the source code of a program. Unlike a `Location`, it doesn't refer to
+/// the files loaded by the driver so it remains meaningful once the program is serialized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: PathBuf,
+    /// The offset, in characters, of the start of the span within the file
+    pub start: u32,
+    /// The offset, in characters, of the end of the span within the file
+    pub end: u32,
+    /// The line of the start of the span, starting at 1
+    pub line: usize,
+    /// The column of the start of the span, starting at 1
+    pub column: usize,
+}
+
+impl SourceLocation {
+    fn new(location: Location, file_manager: &FileManager) -> SourceLocation {
+        let source = file_manager.fetch_file(location.file).source();
+        let start = location.span.start();
+        let (line, column) = line_and_column(source, start as usize);
+        SourceLocation {
+            file: file_manager.file_path(location.file).to_path_buf(),
+            start,
+            end: location.span.end(),
+            line,
+            column,
+        }
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file.display(), self.line, self.column)
+    }
+}
+
+/// Maps the index of each opcode of a circuit to the call stack it is generated from.
+///
+/// Each call stack starts at the outermost call and ends at the expression which generated
+/// the opcode. Opcodes which can't be traced back to the source program have no entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceMap {
+    pub locations: BTreeMap<usize, Vec<SourceLocation>>,
+}
+
+impl SourceMap {
+    pub(crate) fn new(locations: &OpcodeLocations, file_manager: &FileManager) -> SourceMap {
+        let locations = locations
+            .iter()
+            .map(|(opcode_index, call_stack)| {
+                let call_stack = call_stack
+                    .iter()
+                    .map(|location| SourceLocation::new(*location, file_manager))
+                    .collect();
+                (*opcode_index, call_stack)
+            })
+            .collect();
+        SourceMap { locations }
+    }
+
+    /// Returns the call stack the given opcode is generated from, outermost call first.
+    pub fn call_stack(&self, opcode_index: usize) -> &[SourceLocation] {
+        self.locations.get(&opcode_index).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the location of the expression the given opcode is generated from.
+    pub fn location(&self, opcode_index: usize) -> Option<&SourceLocation> {
+        self.call_stack(opcode_index).last()
+    }
+}
+
+/// Returns the line and column, both starting at 1, of the character at `offset` in `source`.
+fn line_and_column(source: &str, offset: usize) -> (usize, usize) {
+    let (mut line, mut column) = (1, 1);
+    for c in source.chars().take(offset) {
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use super::{line_and_column, SourceLocation, SourceMap};
+
+    #[test]
+    fn finds_lines_and_columns() {
+        let source = "fn main() {\n    assert(é == 1);\n}";
+        assert_eq!(line_and_column(source, 0), (1, 1));
+        assert_eq!(line_and_column(source, 16), (2, 5));
+        // Offsets count characters rather than bytes, like the spans of the frontend
+        assert_eq!(line_and_column(source, 24), (2, 13));
+        assert_eq!(line_and_column(source, 28), (2, 17));
+    }
+
+    #[test]
+    fn source_map_round_trips_through_json() {
+        let location = |line, column| SourceLocation {
+            file: PathBuf::from("src/main.nr"),
+            start: 0,
+            end: 1,
+            line,
+            column,
+        };
+        let call_stack = vec![location(2, 5), location(6, 12)];
+        let source_map = SourceMap { locations: BTreeMap::from([(3, call_stack)]) };
+
+        let json = serde_json::to_string(&source_map).unwrap();
+        let deserialized: SourceMap = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, source_map);
+
+        assert_eq!(deserialized.location(3), Some(&location(6, 12)));
+        assert!(deserialized.call_stack(4).is_empty());
+    }
+}
@@ -3,30 +3,82 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
-use acvm::acir::circuit::Opcode;
+use acvm::acir::circuit::{Circuit, Opcode};
 use acvm::Language;
-use clap::Args;
-use fm::FileType;
+use clap::{Args, ValueEnum};
+use fm::{FileId, FileType};
 use iter_extended::try_vecmap;
 use noirc_abi::FunctionSignature;
-use noirc_errors::{reporter, ReportedError};
-use noirc_evaluator::{create_circuit, ssa_refactor::experimental_create_circuit};
+use noirc_errors::{reporter, CustomDiagnostic, FileDiagnostic, ReportedError};
+use noirc_evaluator::{
+    create_circuit, ssa_refactor::experimental_create_circuit, RuntimeError, RuntimeErrorKind,
+};
 use noirc_frontend::graph::{CrateId, CrateName, CrateType, LOCAL_CRATE};
 use noirc_frontend::hir::def_map::{Contract, CrateDefMap};
 use noirc_frontend::hir::Context;
 use noirc_frontend::monomorphization::monomorphize;
 use noirc_frontend::node_interner::FuncId;
+use noirc_frontend::token::TestScope;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
 mod contract;
+mod debug;
 mod program;
 
 pub use contract::{CompiledContract, ContractFunction, ContractFunctionType};
+pub use debug::{SourceLocation, SourceMap};
+pub use noirc_evaluator::{Assertion, AssertionTable};
+pub use noirc_frontend::token::CustomAttribute;
 pub use program::CompiledProgram;
 
+/// The reason a test function could not be compiled
+pub enum TestCompileError {
+    /// One of the test's constraints never holds. The test is expected to fail,
+    /// so this is not reported as an error.
+    FailedConstraint {
+        /// The description of the error
+        error: String,
+        /// The message of the failing assertion, if it has one
+        message: Option<String>,
+    },
+    /// Any other error, which has already been reported
+    Reported(Vec<FileDiagnostic>),
+}
+
+/// The reason a dependency could not be added to the crate graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyError {
+    /// The dependency's name contains a character which isn't allowed in crate names.
+    InvalidCrateName(String),
+    /// The dependency is a binary crate, which cannot be depended upon.
+    BinaryDependency(String),
+    /// The dependency depends on the crate depending on it, directly or transitively.
+    CyclicDependency(String),
+}
+
+impl std::fmt::Display for DependencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DependencyError::InvalidCrateName(name) => {
+                write!(f, "crate name {name} contains blacklisted characters, please remove")
+            }
+            DependencyError::BinaryDependency(name) => {
+                write!(f, "crates cannot depend on binaries. {name} is a binary crate")
+            }
+            DependencyError::CyclicDependency(name) => {
+                write!(f, "adding dependency {name} would create a dependency cycle")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DependencyError {}
+
 pub struct Driver {
     context: Context,
+    /// The crate which is checked and compiled
+    local_crate: CrateId,
     language: Language,
     is_opcode_supported: Box<dyn Fn(&Opcode) -> bool>,
 }
@@ -52,6 +104,21 @@ pub struct CompileOptions {
     /// Compile and optimize using the new experimental SSA pass
     #[arg(long)]
     pub experimental_ssa: bool,
+
+    /// The format in which errors and warnings are printed
+    #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+    #[serde(default)]
+    pub message_format: MessageFormat,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageFormat {
+    /// Human readable diagnostics, showing the code they refer to
+    #[default]
+    Human,
+    /// One JSON object per diagnostic, printed to stdout
+    Json,
 }
 
 impl Default for CompileOptions {
@@ -62,13 +129,19 @@ impl Default for CompileOptions {
             deny_warnings: false,
             show_output: true,
             experimental_ssa: false,
+            message_format: MessageFormat::Human,
         }
     }
 }
 
 impl Driver {
     pub fn new(language: &Language, is_opcode_supported: Box<dyn Fn(&Opcode) -> bool>) -> Self {
-        Driver { context: Context::default(), language: language.clone(), is_opcode_supported }
+        Driver {
+            context: Context::default(),
+            local_crate: LOCAL_CRATE,
+            language: language.clone(),
+            is_opcode_supported,
+        }
     }
 
     // This is here for backwards compatibility
@@ -77,7 +150,7 @@ impl Driver {
         root_file: PathBuf,
         language: &Language,
         is_opcode_supported: Box<dyn Fn(&Opcode) -> bool>,
-    ) -> Result<CompiledProgram, ReportedError> {
+    ) -> Result<CompiledProgram, Vec<FileDiagnostic>> {
         let mut driver = Driver::new(language, is_opcode_supported);
         driver.create_local_crate(root_file, CrateType::Binary);
         driver.compile_main(&CompileOptions::default())
@@ -88,17 +161,16 @@ impl Driver {
     /// This is used for tests.
     pub fn file_compiles(&mut self) -> bool {
         let mut errs = vec![];
-        CrateDefMap::collect_defs(LOCAL_CRATE, &mut self.context, &mut errs);
+        CrateDefMap::collect_defs(self.local_crate, &mut self.context, &mut errs);
         reporter::report_all(&self.context.file_manager, &errs, false);
         errs.is_empty()
     }
 
     /// Adds the File with the local crate root to the file system
     /// and adds the local crate to the graph
-    /// XXX: This may pose a problem with workspaces, where you can change the local crate and where
-    /// we have multiple binaries in one workspace
-    /// A Fix would be for the driver instance to store the local crate id.
-    // Granted that this is the only place which relies on the local crate being first
+    ///
+    /// The local crate is always the first crate of the graph. Drivers holding the crates
+    /// of several packages select the one to compile with `set_local_crate`.
     pub fn create_local_crate<P: AsRef<Path>>(
         &mut self,
         root_file: P,
@@ -114,6 +186,12 @@ impl Driver {
         LOCAL_CRATE
     }
 
+    /// Selects the crate to check and compile. Crates which were already checked, such as
+    /// the dependencies shared with a crate compiled before, are not checked again.
+    pub fn set_local_crate(&mut self, crate_id: CrateId) {
+        self.local_crate = crate_id;
+    }
+
     /// Creates a Non Local Crate. A Non Local Crate is any crate which is the not the crate that
     /// the compiler is compiling.
     pub fn create_non_local_crate<P: AsRef<Path>>(
@@ -133,19 +211,24 @@ impl Driver {
     }
 
     /// Adds a edge in the crate graph for two crates
-    pub fn add_dep(&mut self, this_crate: CrateId, depends_on: CrateId, crate_name: &str) {
-        let crate_name = CrateName::new(crate_name)
-            .expect("crate name contains blacklisted characters, please remove");
+    pub fn add_dep(
+        &mut self,
+        this_crate: CrateId,
+        depends_on: CrateId,
+        crate_name: &str,
+    ) -> Result<(), DependencyError> {
+        let name = CrateName::new(crate_name)
+            .map_err(|name| DependencyError::InvalidCrateName(name.to_owned()))?;
 
         // Cannot depend on a binary
         if self.context.crate_graph.crate_type(depends_on) == CrateType::Binary {
-            panic!("crates cannot depend on binaries. {crate_name:?} is a binary crate")
+            return Err(DependencyError::BinaryDependency(crate_name.to_owned()));
         }
 
         self.context
             .crate_graph
-            .add_dep(this_crate, crate_name, depends_on)
-            .expect("cyclic dependency triggered");
+            .add_dep(this_crate, name, depends_on)
+            .map_err(|_| DependencyError::CyclicDependency(crate_name.to_owned()))
     }
 
     /// Propagates a given dependency to every other crate.
@@ -166,17 +249,60 @@ impl Driver {
     }
 
     /// Run the lexing, parsing, name resolution, and type checking passes,
-    /// returning Err(FrontendError) and printing any errors that were found.
-    pub fn check_crate(&mut self, options: &CompileOptions) -> Result<(), ReportedError> {
+    /// printing any errors and warnings that were found in the format given by `options`.
+    ///
+    /// Returns the warnings if the crate has no errors, otherwise returns all of the
+    /// diagnostics that were found.
+    pub fn check_crate(
+        &mut self,
+        options: &CompileOptions,
+    ) -> Result<Vec<FileDiagnostic>, Vec<FileDiagnostic>> {
+        let errs = self.collect_diagnostics();
+        let error_count = errs
+            .iter()
+            .map(|error| self.report(&error.diagnostic, Some(error.file_id), options) as u32)
+            .sum();
+        match reporter::finish_report(error_count) {
+            Ok(()) => Ok(errs),
+            Err(ReportedError) => Err(errs),
+        }
+    }
+
+    /// Report a diagnostic in the format given by `options`, returning true if it is an error
+    fn report(
+        &self,
+        diagnostic: &CustomDiagnostic,
+        file: Option<FileId>,
+        options: &CompileOptions,
+    ) -> bool {
+        let files = &self.context.file_manager;
+        match options.message_format {
+            MessageFormat::Human => {
+                reporter::report(files, diagnostic, file, options.deny_warnings)
+            }
+            MessageFormat::Json => {
+                reporter::report_json(files, diagnostic, file, options.deny_warnings)
+            }
+        }
+    }
+
+    /// Run the lexing, parsing, name resolution, and type checking passes,
+    /// returning the errors and warnings that were found instead of printing them.
+    ///
+    /// This is meant for tools such as the language server, which then inspect the crate
+    /// through `Driver::context`.
+    pub fn collect_diagnostics(&mut self) -> Vec<FileDiagnostic> {
         let mut errs = vec![];
-        CrateDefMap::collect_defs(LOCAL_CRATE, &mut self.context, &mut errs);
-        let error_count =
-            reporter::report_all(&self.context.file_manager, &errs, options.deny_warnings);
-        reporter::finish_report(error_count)
+        CrateDefMap::collect_defs(self.local_crate, &mut self.context, &mut errs);
+        errs
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.context
     }
 
     pub fn compute_function_signature(&self) -> Option<FunctionSignature> {
-        let local_crate = self.context.def_map(LOCAL_CRATE).unwrap();
+        let local_crate = self.context.def_map(self.local_crate).unwrap();
 
         let main_function = local_crate.main_function()?;
 
@@ -186,22 +312,19 @@ impl Driver {
     }
 
     /// Run the frontend to check the crate for errors then compile the main function if there were none
+    ///
+    /// On failure, the diagnostics which were reported are returned.
     pub fn compile_main(
         &mut self,
         options: &CompileOptions,
-    ) -> Result<CompiledProgram, ReportedError> {
+    ) -> Result<CompiledProgram, Vec<FileDiagnostic>> {
         self.check_crate(options)?;
-        let main = match self.main_function() {
-            Ok(m) => m,
-            Err(e) => {
-                println!("cannot compile a program with no main function");
-                return Err(e);
-            }
-        };
+        let main = self
+            .main_function()
+            .map_err(|diagnostic| self.report_error(diagnostic, None, options))?;
         let compiled_program = self.compile_no_check(options, main)?;
         if options.print_acir {
-            println!("Compiled ACIR for main:");
-            println!("{}", compiled_program.circuit);
+            print_acir(options, "Compiled ACIR for main:", &compiled_program.circuit);
         }
         Ok(compiled_program)
     }
@@ -210,7 +333,7 @@ impl Driver {
     pub fn compile_contracts(
         &mut self,
         options: &CompileOptions,
-    ) -> Result<Vec<CompiledContract>, ReportedError> {
+    ) -> Result<Vec<CompiledContract>, Vec<FileDiagnostic>> {
         self.check_crate(options)?;
         let contracts = self.get_all_contracts();
         let compiled_contracts =
@@ -218,11 +341,11 @@ impl Driver {
         if options.print_acir {
             for compiled_contract in &compiled_contracts {
                 for contract_function in &compiled_contract.functions {
-                    println!(
+                    let header = format!(
                         "Compiled ACIR for {}::{}:",
                         compiled_contract.name, contract_function.name
                     );
-                    println!("{}", contract_function.bytecode);
+                    print_acir(options, &header, &contract_function.bytecode);
                 }
             }
         }
@@ -234,7 +357,7 @@ impl Driver {
         &self,
         contract: Contract,
         options: &CompileOptions,
-    ) -> Result<CompiledContract, ReportedError> {
+    ) -> Result<CompiledContract, Vec<FileDiagnostic>> {
         let functions = try_vecmap(&contract.functions, |function_id| {
             let name = self.function_name(*function_id).to_owned();
             let function = self.compile_no_check(options, *function_id)?;
@@ -248,8 +371,10 @@ impl Driver {
             Ok(ContractFunction {
                 name,
                 function_type,
+                custom_attributes: function.custom_attributes,
                 abi: function.abi,
                 bytecode: function.circuit,
+                source_map: function.source_map,
             })
         })?;
 
@@ -258,41 +383,75 @@ impl Driver {
 
     /// Returns the FuncId of the 'main' function.
     /// - Expects check_crate to be called beforehand
-    /// - Panics if no main function is found
-    pub fn main_function(&self) -> Result<FuncId, ReportedError> {
+    /// - Returns the diagnostic to report if there is no main function
+    pub fn main_function(&self) -> Result<FuncId, CustomDiagnostic> {
         // Find the local crate, one should always be present
-        let local_crate = self.context.def_map(LOCAL_CRATE).unwrap();
+        let local_crate = self.context.def_map(self.local_crate).unwrap();
 
         // Check the crate type
         // We don't panic here to allow users to `evaluate` libraries which will do nothing
-        if self.context.crate_graph[LOCAL_CRATE].crate_type != CrateType::Binary {
-            println!("cannot compile crate into a program as the local crate is not a binary. For libraries, please use the check command");
-            return Err(ReportedError);
+        if self.context.crate_graph[self.local_crate].crate_type != CrateType::Binary {
+            return Err(CustomDiagnostic::from_message("cannot compile crate into a program as the local crate is not a binary. For libraries, please use the check command"));
         };
 
         // All Binaries should have a main function
-        local_crate.main_function().ok_or(ReportedError)
+        local_crate.main_function().ok_or_else(|| {
+            CustomDiagnostic::from_message("cannot compile a program with no main function")
+        })
     }
 
     /// Compile the current crate. Assumes self.check_crate is called beforehand!
-    #[allow(deprecated)]
     pub fn compile_no_check(
         &self,
         options: &CompileOptions,
         main_function: FuncId,
-    ) -> Result<CompiledProgram, ReportedError> {
+    ) -> Result<CompiledProgram, Vec<FileDiagnostic>> {
+        self.compile_circuit(options, main_function)
+            .map_err(|err| self.report_runtime_error(err, options))
+    }
+
+    /// Compiles a test function. A test which is expected to fail may do so during
+    /// compilation, when one of its constraints is known to never hold. This failure is
+    /// returned for the test runner to check rather than reported as an error.
+    pub fn compile_test(
+        &self,
+        options: &CompileOptions,
+        test_function: FuncId,
+    ) -> Result<CompiledProgram, TestCompileError> {
+        let should_fail = matches!(
+            self.context.def_interner.function_meta(&test_function).test_scope(),
+            Some(TestScope::ShouldFail { .. })
+        );
+
+        self.compile_circuit(options, test_function).map_err(|err| match &err.kind {
+            RuntimeErrorKind::FailedConstraint { message } if should_fail => {
+                TestCompileError::FailedConstraint {
+                    error: err.kind.to_string(),
+                    message: message.clone(),
+                }
+            }
+            _ => TestCompileError::Reported(self.report_runtime_error(err, options)),
+        })
+    }
+
+    #[allow(deprecated)]
+    fn compile_circuit(
+        &self,
+        options: &CompileOptions,
+        main_function: FuncId,
+    ) -> Result<CompiledProgram, RuntimeError> {
         let program = monomorphize(main_function, &self.context.def_interner);
 
         let np_language = self.language.clone();
 
-        let circuit_abi = if options.experimental_ssa {
+        let (circuit, abi, debug_info) = if options.experimental_ssa {
             experimental_create_circuit(
                 program,
                 np_language,
                 &self.is_opcode_supported,
                 options.show_ssa,
                 options.show_output,
-            )
+            )?
         } else {
             create_circuit(
                 program,
@@ -300,40 +459,75 @@ impl Driver {
                 &self.is_opcode_supported,
                 options.show_ssa,
                 options.show_output,
-            )
+            )?
         };
+        let source_map = SourceMap::new(&debug_info.locations, &self.context.file_manager);
+        let custom_attributes = self.context.def_interner.custom_attributes(&main_function);
+        Ok(CompiledProgram {
+            circuit,
+            abi,
+            assertions: debug_info.assertions,
+            source_map,
+            custom_attributes,
+        })
+    }
 
-        match circuit_abi {
-            Ok((circuit, abi)) => Ok(CompiledProgram { circuit, abi }),
-            Err(err) => {
-                // The FileId here will be the file id of the file with the main file
-                // Errors will be shown at the call site without a stacktrace
-                let file = err.location.map(|loc| loc.file);
-                let files = &self.context.file_manager;
-                let error = reporter::report(files, &err.into(), file, options.deny_warnings);
-                reporter::finish_report(error as u32)?;
-                Err(ReportedError)
-            }
-        }
+    /// Reports an assertion of a compiled program which failed during its execution.
+    pub fn report_failed_assertion(&self, assertion: &Assertion, options: &CompileOptions) {
+        let message = match &assertion.message {
+            Some(message) => format!("Failed assertion: {message}"),
+            None => "Failed assertion".to_owned(),
+        };
+        let diagnostic =
+            CustomDiagnostic::simple_error(message, String::new(), assertion.location.span);
+        self.report(&diagnostic, Some(assertion.location.file), options);
+    }
+
+    fn report_runtime_error(
+        &self,
+        err: RuntimeError,
+        options: &CompileOptions,
+    ) -> Vec<FileDiagnostic> {
+        // Errors will be shown at the call site without a stacktrace
+        let file = err.location.map(|loc| loc.file);
+        self.report_error(err.into(), file, options)
+    }
+
+    /// Reports an error which stops the compilation, returning it as the diagnostics of the
+    /// compilation. Errors without a file are attributed to the root file of the crate.
+    fn report_error(
+        &self,
+        diagnostic: CustomDiagnostic,
+        file: Option<FileId>,
+        options: &CompileOptions,
+    ) -> Vec<FileDiagnostic> {
+        let error = self.report(&diagnostic, file, options);
+        reporter::finish_report(error as u32).ok();
+
+        let file = file.unwrap_or(self.context.crate_graph[self.local_crate].root_file_id);
+        vec![diagnostic.in_file(file)]
     }
 
     /// Returns a list of all functions in the current crate marked with #[test]
     /// whose names contain the given pattern string. An empty pattern string
     /// will return all functions marked with #[test].
-    pub fn get_all_test_functions_in_crate_matching(&self, pattern: &str) -> Vec<FuncId> {
+    pub fn get_all_test_functions_in_crate_matching(
+        &self,
+        pattern: &str,
+    ) -> Vec<(FuncId, TestScope)> {
         let interner = &self.context.def_interner;
         self.context
-            .def_map(LOCAL_CRATE)
+            .def_map(self.local_crate)
             .expect("The local crate should be analyzed already")
             .get_all_test_functions(interner)
-            .filter_map(|id| interner.function_name(&id).contains(pattern).then_some(id))
+            .filter(|(id, _)| interner.function_name(id).contains(pattern))
             .collect()
     }
 
     /// Return a Vec of all `contract` declarations in the source code and the functions they contain
     pub fn get_all_contracts(&self) -> Vec<Contract> {
         self.context
-            .def_map(LOCAL_CRATE)
+            .def_map(self.local_crate)
             .expect("The local crate should be analyzed already")
             .get_all_contracts()
     }
@@ -343,6 +537,15 @@ impl Driver {
     }
 }
 
+/// Prints the ACIR of a compiled circuit. Diagnostics are the only output on stdout when they are
+/// printed as JSON, so the circuit is printed to stderr instead.
+fn print_acir(options: &CompileOptions, header: &str, circuit: &Circuit) {
+    match options.message_format {
+        MessageFormat::Human => println!("{header}\n{circuit}"),
+        MessageFormat::Json => eprintln!("{header}\n{circuit}"),
+    }
+}
+
 impl Default for Driver {
     fn default() -> Self {
         #[allow(deprecated)]
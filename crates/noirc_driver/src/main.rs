@@ -20,8 +20,8 @@ fn main() {
     let crate_id2 = driver.create_non_local_crate(EXTERNAL_DIR, CrateType::Library);
 
     // Add dependencies as package
-    driver.add_dep(LOCAL_CRATE, crate_id1, "coo4");
-    driver.add_dep(LOCAL_CRATE, crate_id2, "coo3");
+    driver.add_dep(LOCAL_CRATE, crate_id1, "coo4").expect("coo4 is a library");
+    driver.add_dep(LOCAL_CRATE, crate_id2, "coo3").expect("coo3 is a library");
 
     driver.compile_main(&CompileOptions::default()).ok();
 }
@@ -1,4 +1,7 @@
 use acvm::acir::circuit::Circuit;
+use noirc_evaluator::AssertionTable;
+
+use crate::{CustomAttribute, SourceMap};
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -7,6 +10,17 @@ pub struct CompiledProgram {
     #[serde(serialize_with = "serialize_circuit", deserialize_with = "deserialize_circuit")]
     pub circuit: Circuit,
     pub abi: noirc_abi::Abi,
+    /// The `assert` statements of the program, indexed by the opcode enforcing each of them.
+    /// Their locations are only meaningful to the driver which compiled the program.
+    #[serde(skip)]
+    pub assertions: AssertionTable,
+    /// The source code locations each opcode of the circuit is generated from
+    #[serde(default)]
+    pub source_map: SourceMap,
+    /// The attributes of the main function which have no meaning to the compiler,
+    /// kept for tooling to read.
+    #[serde(default)]
+    pub custom_attributes: Vec<CustomAttribute>,
 }
 
 pub(crate) fn serialize_circuit<S>(circuit: &Circuit, s: S) -> Result<S::Ok, S::Error>
@@ -6,7 +6,7 @@
 mod position;
 pub mod reporter;
 pub use position::{Location, Position, Span, Spanned};
-pub use reporter::{CustomDiagnostic, DiagnosticKind};
+pub use reporter::{CustomDiagnostic, CustomLabel, DiagnosticKind};
 use serde::{Deserialize, Serialize};
 
 /// Returned when the Reporter finishes after reporting errors
@@ -4,17 +4,20 @@ use codespan_reporting::term;
 use codespan_reporting::term::termcolor::{
     Color, ColorChoice, ColorSpec, StandardStream, WriteColor,
 };
+use serde::Serialize;
 use std::io::Write;
+use std::path::Path;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct CustomDiagnostic {
-    message: String,
-    secondaries: Vec<CustomLabel>,
-    notes: Vec<String>,
-    kind: DiagnosticKind,
+    pub message: String,
+    pub secondaries: Vec<CustomLabel>,
+    pub notes: Vec<String>,
+    pub kind: DiagnosticKind,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DiagnosticKind {
     Error,
     Warning,
@@ -68,7 +71,12 @@ impl CustomDiagnostic {
         self.secondaries.push(CustomLabel::new(message, span));
     }
 
-    fn is_error(&self) -> bool {
+    /// Adds a label in a file other than the one the diagnostic is reported in
+    pub fn add_secondary_in_file(&mut self, message: String, span: Span, file: fm::FileId) {
+        self.secondaries.push(CustomLabel { message, span, file: Some(file) });
+    }
+
+    pub fn is_error(&self) -> bool {
         matches!(self.kind, DiagnosticKind::Error)
     }
 }
@@ -90,14 +98,16 @@ impl std::fmt::Display for CustomDiagnostic {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct CustomLabel {
-    message: String,
-    span: Span,
+pub struct CustomLabel {
+    pub message: String,
+    pub span: Span,
+    /// The file of the span, if it differs from the file of the diagnostic
+    pub file: Option<fm::FileId>,
 }
 
 impl CustomLabel {
     fn new(message: String, span: Span) -> CustomLabel {
-        CustomLabel { message, span }
+        CustomLabel { message, span, file: None }
     }
 }
 
@@ -140,22 +150,142 @@ fn convert_diagnostic(
         _ => Diagnostic::error(),
     };
 
-    let secondary_labels = if let Some(file_id) = file {
-        cd.secondaries
-            .iter()
-            .map(|sl| {
-                let start_span = sl.span.start() as usize;
-                let end_span = sl.span.end() as usize + 1;
-                Label::secondary(file_id.as_usize(), start_span..end_span).with_message(&sl.message)
-            })
-            .collect()
-    } else {
-        vec![]
-    };
+    let secondary_labels = cd
+        .secondaries
+        .iter()
+        .filter_map(|sl| {
+            let file_id = sl.file.or(file)?;
+            let start_span = sl.span.start() as usize;
+            let end_span = sl.span.end() as usize + 1;
+            let label = Label::secondary(file_id.as_usize(), start_span..end_span);
+            Some(label.with_message(&sl.message))
+        })
+        .collect();
 
     diagnostic.with_message(&cd.message).with_labels(secondary_labels).with_notes(cd.notes.clone())
 }
 
+/// Report the given diagnostic as JSON, and return true if it was an error
+pub fn report_json(
+    files: &fm::FileManager,
+    custom_diagnostic: &CustomDiagnostic,
+    file: Option<fm::FileId>,
+    deny_warnings: bool,
+) -> bool {
+    let diagnostic = convert_json_diagnostic(files, custom_diagnostic, file, deny_warnings);
+    let json = serde_json::to_string(&diagnostic).expect("diagnostics are serializable to JSON");
+
+    let stdout = std::io::stdout();
+    writeln!(&mut stdout.lock(), "{json}").unwrap();
+
+    deny_warnings || custom_diagnostic.is_error()
+}
+
+/// A diagnostic in the format printed by `report_json`
+#[derive(Debug, Serialize)]
+struct JsonDiagnostic<'a> {
+    message: &'a str,
+    severity: DiagnosticKind,
+    file: Option<&'a Path>,
+    labels: Vec<JsonLabel<'a>>,
+    notes: &'a [String],
+}
+
+#[derive(Debug, Serialize)]
+struct JsonLabel<'a> {
+    message: &'a str,
+    span: JsonSpan,
+    /// Only given for labels in a file other than the diagnostic's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<&'a Path>,
+}
+
+/// The location of a label, where lines and columns start at 1 and columns count characters.
+/// Ends are exclusive.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct JsonSpan {
+    byte_start: usize,
+    byte_end: usize,
+    line_start: usize,
+    column_start: usize,
+    line_end: usize,
+    column_end: usize,
+}
+
+fn convert_json_diagnostic<'a>(
+    files: &'a fm::FileManager,
+    cd: &'a CustomDiagnostic,
+    file: Option<fm::FileId>,
+    deny_warnings: bool,
+) -> JsonDiagnostic<'a> {
+    let severity = if deny_warnings { DiagnosticKind::Error } else { cd.kind };
+
+    // Like in `convert_diagnostic`, labels can only be shown when their file is known
+    let labels = cd
+        .secondaries
+        .iter()
+        .filter_map(|sl| {
+            let file_id = sl.file.or(file)?;
+            let source = files.fetch_file(file_id).source();
+            let span = convert_span(source, sl.span);
+            let other_file = sl.file.filter(|&label_file| Some(label_file) != file);
+            let label_file = other_file.map(|label_file| files.file_path(label_file));
+            Some(JsonLabel { message: &sl.message, span, file: label_file })
+        })
+        .collect();
+
+    JsonDiagnostic {
+        message: &cd.message,
+        severity,
+        file: file.map(|file_id| files.file_path(file_id)),
+        labels,
+        notes: &cd.notes,
+    }
+}
+
+/// Spans are made of character indices, where the end is the last character covered
+fn convert_span(source: &str, span: Span) -> JsonSpan {
+    let (start, end) = (span.start() as usize, span.end() as usize + 1);
+    let mut json_span = JsonSpan {
+        byte_start: source.len(),
+        byte_end: source.len(),
+        line_start: 1,
+        column_start: 1,
+        line_end: 1,
+        column_end: 1,
+    };
+
+    let (mut line, mut column) = (1, 1);
+    for (index, (byte, c)) in source.char_indices().enumerate() {
+        if index == start {
+            json_span.byte_start = byte;
+            json_span.line_start = line;
+            json_span.column_start = column;
+        }
+        if index == end {
+            json_span.byte_end = byte;
+            json_span.line_end = line;
+            json_span.column_end = column;
+            return json_span;
+        }
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+
+    // The span ends at the end of the source
+    if start >= source.chars().count() {
+        json_span.line_start = line;
+        json_span.column_start = column;
+    }
+    json_span.line_end = line;
+    json_span.column_end = column;
+    json_span
+}
+
 pub fn finish_report(error_count: u32) -> Result<(), ReportedError> {
     if error_count != 0 {
         let writer = StandardStream::stderr(ColorChoice::Always);
@@ -170,3 +300,39 @@ pub fn finish_report(error_count: u32) -> Result<(), ReportedError> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_spans_to_bytes_and_lines() {
+        let source = "let é = 1;\nlet b = é;";
+
+        // `1` on the first line, after a two bytes character
+        assert_eq!(
+            convert_span(source, Span::new(8..8)),
+            JsonSpan {
+                byte_start: 9,
+                byte_end: 10,
+                line_start: 1,
+                column_start: 9,
+                line_end: 1,
+                column_end: 10,
+            }
+        );
+
+        // `é;` at the end of the second line
+        assert_eq!(
+            convert_span(source, Span::new(19..20)),
+            JsonSpan {
+                byte_start: 20,
+                byte_end: 23,
+                line_start: 2,
+                column_start: 9,
+                line_end: 2,
+                column_end: 11,
+            }
+        );
+    }
+}
@@ -0,0 +1,16 @@
+//! Bookkeeping of the `assert` statements which end up in a circuit, so that a failure
+//! during execution can be traced back to the assertion which caused it.
+use std::collections::BTreeMap;
+
+use noirc_errors::Location;
+
+/// An `assert` statement from the source program.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Assertion {
+    /// The message given to the assertion, e.g. `assert(x == y, "message")`
+    pub message: Option<String>,
+    pub location: Location,
+}
+
+/// Maps the index of the ACIR opcode which enforces an assertion onto that assertion.
+pub type AssertionTable = BTreeMap<usize, Assertion>;
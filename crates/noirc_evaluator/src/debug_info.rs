@@ -0,0 +1,23 @@
+//! Tracking of the source code each ACIR opcode of a circuit is generated from.
+use std::collections::BTreeMap;
+
+use noirc_errors::Location;
+
+use crate::AssertionTable;
+
+/// The locations of the function calls an instruction was inlined through, outermost first,
+/// followed by the location of the expression the instruction was generated from.
+pub type CallStack = Vec<Location>;
+
+/// Maps the index of each ACIR opcode onto the call stack it was generated from.
+pub type OpcodeLocations = BTreeMap<usize, CallStack>;
+
+/// The debugging information gathered while compiling a program into a circuit.
+#[derive(Debug, Clone, Default)]
+pub struct DebugInfo {
+    /// Where each opcode comes from. Opcodes which can't be traced back to the source
+    /// program, such as those range constraining the inputs, are left out.
+    pub locations: OpcodeLocations,
+    /// The `assert` statements of the program, indexed by the opcode enforcing each of them
+    pub assertions: AssertionTable,
+}
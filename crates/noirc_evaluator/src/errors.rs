@@ -63,6 +63,11 @@ pub enum RuntimeErrorKind {
 
     #[error("Unsupported operation error")]
     UnsupportedOp { op: String, first_type: String, second_type: String },
+
+    /// A constraint which is known to fail whatever the inputs of the program,
+    /// along with the message of the failing assertion if it has one
+    #[error("Constraint is always false{}", .message.as_ref().map(|message| format!(": {message}")).unwrap_or_default())]
+    FailedConstraint { message: Option<String> },
 }
 
 impl RuntimeErrorKind {
@@ -99,6 +104,13 @@ impl From<RuntimeError> for Diagnostic {
                 )
             }
             RuntimeErrorKind::Spanless(message) => Diagnostic::from_message(message),
+            RuntimeErrorKind::FailedConstraint { .. } => {
+                let message = error.kind.to_string();
+                match error.location {
+                    Some(_) => Diagnostic::simple_error(String::new(), message, span),
+                    None => Diagnostic::from_message(&message),
+                }
+            }
             RuntimeErrorKind::Unimplemented(message) => Diagnostic::from_message(message),
             RuntimeErrorKind::FunctionNonMainContext { func_name } => Diagnostic::simple_error(
                 "cannot call function outside of main".to_owned(),
@@ -3,6 +3,8 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
+mod assertions;
+mod debug_info;
 mod errors;
 mod ssa;
 
@@ -16,7 +18,9 @@ use acvm::{
     compiler::optimizers::simplify::CircuitSimplifier,
     Language,
 };
-use errors::{RuntimeError, RuntimeErrorKind};
+pub use assertions::{Assertion, AssertionTable};
+pub use debug_info::{CallStack, DebugInfo, OpcodeLocations};
+pub use errors::{RuntimeError, RuntimeErrorKind};
 use iter_extended::vecmap;
 use noirc_abi::{Abi, AbiType, AbiVisibility};
 use noirc_frontend::monomorphization::ast::*;
@@ -56,6 +60,9 @@ pub struct Evaluator {
     return_is_distinct: bool,
 
     opcodes: Vec<AcirOpcode>,
+
+    // Where each opcode comes from, along with the `assert` statements of the program
+    debug_info: DebugInfo,
 }
 
 /// Compiles the Program into ACIR and applies optimizations to the arithmetic gates
@@ -69,7 +76,7 @@ pub fn create_circuit(
     is_opcode_supported: &impl Fn(&AcirOpcode) -> bool,
     enable_logging: bool,
     show_output: bool,
-) -> Result<(Circuit, Abi), RuntimeError> {
+) -> Result<(Circuit, Abi, DebugInfo), RuntimeError> {
     let mut evaluator = Evaluator::default();
 
     // First evaluate the main function
@@ -81,26 +88,97 @@ pub fn create_circuit(
         public_parameters,
         return_values,
         opcodes,
+        debug_info,
         ..
     } = evaluator;
-    let simplifier = CircuitSimplifier::new(current_witness_index);
-    let optimized_circuit = acvm::compiler::compile(
-        Circuit {
-            current_witness_index,
-            opcodes,
-            public_parameters: PublicInputs(public_parameters),
-            return_values: PublicInputs(return_values.iter().copied().collect()),
-        },
-        np_language,
-        is_opcode_supported,
-        &simplifier,
-    )
-    .map_err(|_| RuntimeErrorKind::Spanless(String::from("produced an acvm compile error")))?;
+    let circuit = Circuit {
+        current_witness_index,
+        opcodes,
+        public_parameters: PublicInputs(public_parameters),
+        return_values: PublicInputs(return_values.iter().copied().collect()),
+    };
+    let (optimized_circuit, debug_info) =
+        optimize_circuit(circuit, debug_info, np_language, is_opcode_supported)?;
 
     let (parameters, return_type) = program.main_function_signature;
     let abi = Abi { parameters, param_witnesses, return_type, return_witnesses: return_values };
 
-    Ok((optimized_circuit, abi))
+    Ok((optimized_circuit, abi, debug_info))
+}
+
+/// Applies the backend specific optimizations to the circuit, keeping its debugging
+/// information in line with the optimized opcodes.
+///
+/// An opcode may be replaced by several during optimization, and the compiler of acvm 0.12
+/// does not report which opcode each optimized opcode comes from. The circuit is therefore
+/// optimized in chunks of opcodes generated from the same call stack, with the opcode enforcing
+/// an assertion in a chunk of its own. The opcodes of an optimized chunk then come from that
+/// call stack, and the last of them enforces the chunk's assertion. An assertion is dropped if
+/// its opcode is optimized away.
+fn optimize_circuit(
+    circuit: Circuit,
+    debug_info: DebugInfo,
+    np_language: Language,
+    is_opcode_supported: &impl Fn(&AcirOpcode) -> bool,
+) -> Result<(Circuit, DebugInfo), RuntimeError> {
+    let Circuit { mut current_witness_index, opcodes, public_parameters, return_values } = circuit;
+    let DebugInfo { mut locations, mut assertions } = debug_info;
+    let simplifier = CircuitSimplifier::new(current_witness_index);
+
+    let opcode_count = opcodes.len();
+    let mut chunks = Vec::new();
+    let mut chunk = Vec::new();
+    for (opcode_index, opcode) in opcodes.into_iter().enumerate() {
+        chunk.push(opcode);
+        let ends_chunk = opcode_index + 1 == opcode_count
+            || assertions.contains_key(&opcode_index)
+            || assertions.contains_key(&(opcode_index + 1))
+            || locations.get(&opcode_index) != locations.get(&(opcode_index + 1));
+        if ends_chunk {
+            chunks.push((std::mem::take(&mut chunk), opcode_index));
+        }
+    }
+
+    let mut optimized_opcodes = Vec::new();
+    let mut optimized_debug_info = DebugInfo::default();
+    for (chunk, last_opcode_index) in chunks {
+        // The public inputs are only carried through by the compiler, so the chunks go without
+        let optimized_chunk = acvm::compiler::compile(
+            Circuit {
+                current_witness_index,
+                opcodes: chunk,
+                public_parameters: PublicInputs(BTreeSet::new()),
+                return_values: PublicInputs(BTreeSet::new()),
+            },
+            np_language.clone(),
+            is_opcode_supported,
+            &simplifier,
+        )
+        .map_err(|_| RuntimeErrorKind::Spanless(String::from("produced an acvm compile error")))?;
+
+        current_witness_index = optimized_chunk.current_witness_index;
+        let chunk_start = optimized_opcodes.len();
+        optimized_opcodes.extend(optimized_chunk.opcodes);
+        if optimized_opcodes.len() == chunk_start {
+            continue;
+        }
+        if let Some(call_stack) = locations.remove(&last_opcode_index) {
+            for opcode_index in chunk_start..optimized_opcodes.len() {
+                optimized_debug_info.locations.insert(opcode_index, call_stack.clone());
+            }
+        }
+        if let Some(assertion) = assertions.remove(&last_opcode_index) {
+            optimized_debug_info.assertions.insert(optimized_opcodes.len() - 1, assertion);
+        }
+    }
+
+    let optimized_circuit = Circuit {
+        current_witness_index,
+        opcodes: optimized_opcodes,
+        public_parameters,
+        return_values,
+    };
+    Ok((optimized_circuit, optimized_debug_info))
 }
 
 impl Evaluator {
@@ -145,6 +223,21 @@ impl Evaluator {
         self.opcodes.push(gate);
     }
 
+    // Records that the last opcode added enforces `assertion`
+    fn record_assertion(&mut self, assertion: Assertion) {
+        self.debug_info.assertions.insert(self.opcodes.len() - 1, assertion);
+    }
+
+    // Records that the opcodes added since `first_opcode` are generated from `call_stack`
+    fn record_call_stack(&mut self, first_opcode: usize, call_stack: &CallStack) {
+        if call_stack.is_empty() {
+            return;
+        }
+        for opcode_index in first_opcode..self.opcodes.len() {
+            self.debug_info.locations.insert(opcode_index, call_stack.clone());
+        }
+    }
+
     /// Compiles the AST into the intermediate format by evaluating the main function
     pub fn evaluate_main_alt(
         &mut self,
@@ -154,8 +247,9 @@ impl Evaluator {
     ) -> Result<(), RuntimeError> {
         self.return_is_distinct =
             program.return_distinctness == noirc_abi::AbiDistinctness::Distinct;
+
         let mut ir_gen = IrGenerator::new(program);
-        self.parse_abi_alt(&mut ir_gen);
+        self.parse_abi_alt(&mut ir_gen)?;
 
         // Now call the main function
         ir_gen.ssa_gen_main()?;
@@ -200,13 +294,13 @@ impl Evaluator {
             AbiType::Array { length, typ } => {
                 let witnesses = self.generate_array_witnesses(length, typ)?;
 
-                ir_gen.abi_array(name, Some(def), typ.as_ref(), *length, &witnesses);
+                ir_gen.abi_array_value(name, Some(def), typ.as_ref(), *length, &witnesses)?;
                 witnesses
             }
             AbiType::Integer { sign: _, width } => {
                 let witness = self.add_witness_to_cs();
                 ssa::acir_gen::range_constraint(witness, *width, self)?;
-                let obj_type = ir_gen.get_object_type_from_abi(param_type); // Fetch signedness of the integer
+                let obj_type = ir_gen.get_object_type_from_abi(param_type)?; // Fetch signedness of the integer
                 ir_gen.create_new_variable(name.to_owned(), Some(def), obj_type, Some(witness));
 
                 vec![witness]
@@ -219,61 +313,17 @@ impl Evaluator {
 
                 vec![witness]
             }
-            AbiType::Struct { fields } => {
-                let new_fields = vecmap(fields, |(inner_name, value)| {
-                    let new_name = format!("{name}.{inner_name}");
-                    (new_name, value.clone())
-                });
-
-                let mut struct_witnesses: BTreeMap<String, Vec<Witness>> = BTreeMap::new();
-                self.generate_struct_witnesses(&mut struct_witnesses, &new_fields)?;
-
-                ir_gen.abi_struct(name, Some(def), fields, &struct_witnesses);
-
-                // This is a dirty hack and should be removed in future.
-                //
-                // `struct_witnesses` is a flat map where structs are represented by multiple entries
-                // i.e. a struct `foo` with fields `bar` and `baz` is stored under the keys
-                // `foo.bar` and `foo.baz` each holding the witnesses for fields `bar` and `baz` respectively.
-                //
-                // We've then lost the information on ordering of these fields. To reconstruct this we iterate
-                // over `fields` recursively to calculate the proper ordering of this `BTreeMap`s keys.
-                //
-                // Ideally we wouldn't lose this information in the first place.
-                fn get_field_ordering(prefix: String, fields: &[(String, AbiType)]) -> Vec<String> {
-                    fields
-                        .iter()
-                        .flat_map(|(field_name, field_type)| {
-                            let flattened_name = format!("{prefix}.{field_name}");
-                            if let AbiType::Struct { fields } = field_type {
-                                get_field_ordering(flattened_name, fields)
-                            } else {
-                                vec![flattened_name]
-                            }
-                        })
-                        .collect()
-                }
-                let field_ordering = get_field_ordering(name.to_owned(), fields);
-
-                // We concatenate the witness vectors in the order of the struct's fields.
-                // This ensures that struct fields are mapped to the correct witness indices during ABI encoding.
-                field_ordering
-                    .iter()
-                    .flat_map(|field_name| {
-                        struct_witnesses.remove(field_name).unwrap_or_else(|| {
-                            unreachable!(
-                                "Expected a field named '{field_name}' in the struct pattern"
-                            )
-                        })
-                    })
-                    .collect()
-            }
+            AbiType::Struct { fields } => self.struct_param_to_var(name, def, fields, ir_gen)?,
             AbiType::String { length } => {
                 let typ = AbiType::Integer { sign: noirc_abi::Sign::Unsigned, width: 8 };
                 let witnesses = self.generate_array_witnesses(length, &typ)?;
-                ir_gen.abi_array(name, Some(def), &typ, *length, &witnesses);
+                ir_gen.abi_array(name, Some(def), &typ, *length, &witnesses)?;
                 witnesses
             }
+            AbiType::Tuple { fields } => {
+                let fields = tuple_fields_as_struct_fields(fields);
+                self.struct_param_to_var(name, def, &fields, ir_gen)?
+            }
         };
 
         if param_visibility == &AbiVisibility::Public {
@@ -284,6 +334,65 @@ impl Evaluator {
         Ok(())
     }
 
+    fn struct_param_to_var(
+        &mut self,
+        name: &str,
+        def: Definition,
+        fields: &[(String, AbiType)],
+        ir_gen: &mut IrGenerator,
+    ) -> Result<Vec<Witness>, RuntimeErrorKind> {
+        let new_fields = vecmap(fields, |(inner_name, value)| {
+            let new_name = format!("{name}.{inner_name}");
+            (new_name, value.clone())
+        });
+
+        let mut struct_witnesses: BTreeMap<String, Vec<Witness>> = BTreeMap::new();
+        self.generate_struct_witnesses(&mut struct_witnesses, &new_fields)?;
+
+        ir_gen.abi_struct(name, Some(def), fields, &struct_witnesses)?;
+
+        // This is a dirty hack and should be removed in future.
+        //
+        // `struct_witnesses` is a flat map where structs are represented by multiple entries
+        // i.e. a struct `foo` with fields `bar` and `baz` is stored under the keys
+        // `foo.bar` and `foo.baz` each holding the witnesses for fields `bar` and `baz` respectively.
+        //
+        // We've then lost the information on ordering of these fields. To reconstruct this we iterate
+        // over `fields` recursively to calculate the proper ordering of this `BTreeMap`s keys.
+        //
+        // Ideally we wouldn't lose this information in the first place.
+        fn get_field_ordering(prefix: String, fields: &[(String, AbiType)]) -> Vec<String> {
+            fields
+                .iter()
+                .flat_map(|(field_name, field_type)| {
+                    let flattened_name = format!("{prefix}.{field_name}");
+                    match field_type {
+                        AbiType::Struct { fields } => get_field_ordering(flattened_name, fields),
+                        AbiType::Tuple { fields } => get_field_ordering(
+                            flattened_name,
+                            &tuple_fields_as_struct_fields(fields),
+                        ),
+                        _ => vec![flattened_name],
+                    }
+                })
+                .collect()
+        }
+        let field_ordering = get_field_ordering(name.to_owned(), fields);
+
+        // We concatenate the witness vectors in the order of the struct's fields.
+        // This ensures that struct fields are mapped to the correct witness indices during ABI encoding.
+        let witnesses = field_ordering
+            .iter()
+            .flat_map(|field_name| {
+                struct_witnesses.remove(field_name).unwrap_or_else(|| {
+                    unreachable!("Expected a field named '{field_name}' in the struct pattern")
+                })
+            })
+            .collect();
+
+        Ok(witnesses)
+    }
+
     fn generate_struct_witnesses(
         &mut self,
         struct_witnesses: &mut BTreeMap<String, Vec<Witness>>,
@@ -321,6 +430,13 @@ impl Evaluator {
                     let internal_str_witnesses = self.generate_array_witnesses(length, &typ)?;
                     struct_witnesses.insert(name.clone(), internal_str_witnesses);
                 }
+                AbiType::Tuple { fields } => {
+                    let new_fields =
+                        vecmap(tuple_fields_as_struct_fields(fields), |(field_name, typ)| {
+                            (format!("{name}.{field_name}"), typ)
+                        });
+                    self.generate_struct_witnesses(struct_witnesses, &new_fields)?;
+                }
             }
         }
         Ok(())
@@ -332,25 +448,55 @@ impl Evaluator {
         typ: &AbiType,
     ) -> Result<Vec<Witness>, RuntimeErrorKind> {
         let mut witnesses = Vec::new();
-        let element_width = match typ {
-            AbiType::Integer { width, .. } => Some(*width),
-            _ => None,
-        };
         for _ in 0..*length {
-            let witness = self.add_witness_to_cs();
-            witnesses.push(witness);
-            if let Some(ww) = element_width {
-                ssa::acir_gen::range_constraint(witness, ww, self)?;
-            }
+            self.generate_element_witnesses(typ, &mut witnesses)?;
         }
         Ok(witnesses)
     }
 
+    // Generates the witnesses of a single array element, one for each field of structs and tuples
+    fn generate_element_witnesses(
+        &mut self,
+        typ: &AbiType,
+        witnesses: &mut Vec<Witness>,
+    ) -> Result<(), RuntimeErrorKind> {
+        match typ {
+            AbiType::Field => witnesses.push(self.add_witness_to_cs()),
+            AbiType::Integer { width, .. } => {
+                let witness = self.add_witness_to_cs();
+                witnesses.push(witness);
+                ssa::acir_gen::range_constraint(witness, *width, self)?;
+            }
+            AbiType::Boolean => {
+                let witness = self.add_witness_to_cs();
+                witnesses.push(witness);
+                ssa::acir_gen::range_constraint(witness, 1, self)?;
+            }
+            AbiType::Struct { fields } => {
+                for (_, field_typ) in fields {
+                    self.generate_element_witnesses(field_typ, witnesses)?;
+                }
+            }
+            AbiType::Tuple { fields } => {
+                for field_typ in fields {
+                    self.generate_element_witnesses(field_typ, witnesses)?;
+                }
+            }
+            AbiType::Array { .. } | AbiType::String { .. } => {
+                return Err(RuntimeErrorKind::Unimplemented(
+                    "nested arrays and arrays of strings are not supported as inputs to `main`"
+                        .to_owned(),
+                ))
+            }
+        }
+        Ok(())
+    }
+
     /// The ABI is the intermediate representation between Noir and types like Toml
     /// Noted in the noirc_abi, it is possible to convert Toml -> NoirTypes
     /// However, this intermediate representation is useful as it allows us to have
     /// intermediate Types which the core type system does not know about like Strings.
-    fn parse_abi_alt(&mut self, ir_gen: &mut IrGenerator) {
+    fn parse_abi_alt(&mut self, ir_gen: &mut IrGenerator) -> Result<(), RuntimeErrorKind> {
         let main = ir_gen.program.main_mut();
         let main_params = std::mem::take(&mut main.parameters);
         let abi_params = std::mem::take(&mut ir_gen.program.main_function_signature.0);
@@ -360,12 +506,100 @@ impl Evaluator {
         for ((param_id, _, param_name, _), abi_param) in main_params.iter().zip(abi_params) {
             assert_eq!(param_name, &abi_param.name);
             let def = Definition::Local(*param_id);
-            self.param_to_var(param_name, def, &abi_param.typ, &abi_param.visibility, ir_gen)
-                .unwrap();
+            self.param_to_var(param_name, def, &abi_param.typ, &abi_param.visibility, ir_gen)?;
         }
 
         // Store the number of witnesses used to represent the types
         // in the ABI
         self.num_witnesses_abi_len = self.current_witness_index as usize;
+        Ok(())
+    }
+}
+
+/// The legacy SSA pass represents both structs and tuples as `Value::Tuple`s, so a tuple in the ABI is
+/// laid out identically to a struct whose fields are named after their index in the tuple.
+pub(crate) fn tuple_fields_as_struct_fields(fields: &[AbiType]) -> Vec<(String, AbiType)> {
+    vecmap(fields.iter().enumerate(), |(index, typ)| (index.to_string(), typ.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::circuit::{opcodes::Opcode as AcirOpcode, Circuit, PublicInputs},
+        acir::native_types::{Expression, Witness},
+        compiler::optimizers::simplify::CircuitSimplifier,
+        FieldElement, Language,
+    };
+    use noirc_errors::{Location, Span};
+    use std::collections::BTreeSet;
+
+    use super::{optimize_circuit, Assertion, DebugInfo};
+
+    const LANGUAGE: Language = Language::PLONKCSat { width: 3 };
+
+    /// Constrains the sum of the given witnesses to be zero
+    fn sum_is_zero(witnesses: &[u32]) -> AcirOpcode {
+        AcirOpcode::Arithmetic(Expression {
+            mul_terms: Vec::new(),
+            linear_combinations: witnesses
+                .iter()
+                .map(|witness| (FieldElement::one(), Witness(*witness)))
+                .collect(),
+            q_c: FieldElement::zero(),
+        })
+    }
+
+    fn circuit(opcodes: Vec<AcirOpcode>) -> Circuit {
+        Circuit {
+            current_witness_index: 6,
+            opcodes,
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        }
+    }
+
+    fn location(start: u32) -> Location {
+        Location::new(Span::new(start..start + 1), fm::FileId::dummy())
+    }
+
+    #[test]
+    fn optimizing_in_chunks_keeps_the_opcode_count() {
+        // The opcodes wider than the language are split during optimization
+        let opcodes = vec![
+            sum_is_zero(&[1, 2]),
+            sum_is_zero(&[1, 2, 3, 4, 5]),
+            sum_is_zero(&[2, 3]),
+            sum_is_zero(&[3, 4, 5, 6]),
+            sum_is_zero(&[4, 5]),
+        ];
+        let mut debug_info = DebugInfo::default();
+        for (opcode_index, start) in [(0, 0), (1, 0), (2, 10), (3, 20), (4, 20)] {
+            debug_info.locations.insert(opcode_index, vec![location(start)]);
+        }
+        let assertion = Assertion { message: Some("not zero".to_owned()), location: location(20) };
+        debug_info.assertions.insert(3, assertion.clone());
+
+        let simplifier = CircuitSimplifier::new(6);
+        let whole = acvm::compiler::compile(
+            circuit(opcodes.clone()),
+            LANGUAGE,
+            |_: &AcirOpcode| true,
+            &simplifier,
+        )
+        .unwrap();
+        let (chunked, debug_info) =
+            optimize_circuit(circuit(opcodes), debug_info, LANGUAGE, &|_: &AcirOpcode| true)
+                .unwrap();
+        assert_eq!(chunked.opcodes.len(), whole.opcodes.len());
+
+        // Every optimized opcode keeps its location, and the assertion its last opcode
+        let opcode_indices: Vec<_> = debug_info.locations.keys().copied().collect();
+        assert_eq!(opcode_indices, (0..chunked.opcodes.len()).collect::<Vec<_>>());
+        let (assertion_index, optimized_assertion) =
+            debug_info.assertions.iter().next().expect("the assertion should be kept");
+        assert_eq!(optimized_assertion, &assertion);
+        assert_eq!(debug_info.locations[assertion_index], vec![location(20)]);
+        assert_eq!(debug_info.locations[&(assertion_index + 1)], vec![location(20)]);
+        assert_eq!(assertion_index + 2, chunked.opcodes.len());
     }
 }
@@ -1,4 +1,3 @@
-use crate::Evaluator;
 use crate::{
     errors::RuntimeError,
     ssa::{
@@ -8,6 +7,7 @@ use crate::{
         node::{Instruction, Operation},
     },
 };
+use crate::{Assertion, Evaluator};
 use acvm::acir::native_types::{Expression, Witness};
 
 mod operations;
@@ -41,7 +41,9 @@ impl Acir {
         while let Some(block) = current_block {
             for iter in &block.instructions {
                 let ins = ctx.instruction(*iter);
+                let first_opcode = evaluator.opcodes.len();
                 self.acir_gen_instruction(ins, evaluator, ctx, show_output)?;
+                evaluator.record_call_stack(first_opcode, &ins.call_stack);
             }
             //TODO we should rather follow the jumps
             current_block = block.left.map(|block_id| &ctx[block_id]);
@@ -69,8 +71,13 @@ impl Acir {
             Operation::Binary(binary) => {
                 binary::evaluate(binary, ins.res_type, self, evaluator, ctx)
             }
-            Operation::Constrain(value, ..) => {
-                constrain::evaluate(value, var_cache, evaluator, ctx)
+            Operation::Constrain(value, location, message) => {
+                let output = constrain::evaluate(value, var_cache, evaluator, ctx);
+                if let Some(location) = location {
+                    let assertion = Assertion { message: message.clone(), location: *location };
+                    evaluator.record_assertion(assertion);
+                }
+                output
             }
             Operation::Not(value) => not::evaluate(value, ins.res_type, var_cache, evaluator, ctx),
             Operation::Cast(value) => {
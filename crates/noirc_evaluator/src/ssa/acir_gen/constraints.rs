@@ -487,18 +487,180 @@ pub(crate) fn evaluate_cmp(
     evaluator: &mut Evaluator,
 ) -> Expression {
     if signed {
-        //TODO use range_constraints instead of bit decomposition, like in the unsigned case
-        let mut sub_expr = subtract(lhs, FieldElement::one(), rhs);
-        let two_pow = BigUint::one() << (bit_size + 1);
-        sub_expr.q_c += FieldElement::from_be_bytes_reduce(&two_pow.to_bytes_be());
-        let bits = to_radix_base(&sub_expr, 2, bit_size + 2, Endian::Little, evaluator);
-        bits[(bit_size - 1) as usize].into()
+        // Flipping the sign bit of both operands maps the two's complement range [-2^(n-1), 2^(n-1))
+        // onto [0, 2^n) while preserving the order, so that we can use an unsigned comparison.
+        let lhs_offset = evaluate_sign_flip(lhs, bit_size, evaluator);
+        let rhs_offset = evaluate_sign_flip(rhs, bit_size, evaluator);
+        evaluate_cmp(&lhs_offset, &rhs_offset, bit_size, false, evaluator)
     } else {
         let is_greater = bound_check(lhs, rhs, bit_size, evaluator);
         subtract(&Expression::one(), FieldElement::one(), &is_greater.into())
     }
 }
 
+// Returns the sign bit of lhs, a bit_size-bits signed integer in two's complement, i.e. 1 if lhs is negative
+pub(crate) fn evaluate_sign(
+    lhs: &Expression,
+    bit_size: u32,
+    evaluator: &mut Evaluator,
+) -> Expression {
+    if bit_size == 1 {
+        return lhs.clone();
+    }
+    // lhs = low + 2^(n-1)*sign
+    let low = evaluate_truncate(lhs, bit_size - 1, bit_size, evaluator);
+    let sign_shift = two_pow(bit_size - 1);
+    &subtract(lhs, FieldElement::one(), &low) * sign_shift.inverse()
+}
+
+// Returns lhs + 2^(n-1) mod 2^n, for lhs a bit_size-bits integer, by flipping its most significant bit
+fn evaluate_sign_flip(lhs: &Expression, bit_size: u32, evaluator: &mut Evaluator) -> Expression {
+    let sign = evaluate_sign(lhs, bit_size, evaluator);
+    let mut result = subtract(lhs, two_pow(bit_size), &sign);
+    result.q_c += two_pow(bit_size - 1);
+    result
+}
+
+// Returns 2^n - x if negate is 1, and x if negate is 0
+// This is used to switch between the two's complement representation of a signed integer and its absolute value
+fn evaluate_conditional_negate(
+    x: &Expression,
+    negate: &Expression,
+    bit_size: u32,
+    evaluator: &mut Evaluator,
+) -> Expression {
+    // x + negate*(2^n - 2*x)
+    let negated =
+        subtract(&Expression::from_field(two_pow(bit_size)), FieldElement::from(2_i128), x);
+    let correction = mul_with_witness(evaluator, negate, &negated);
+    add(x, FieldElement::one(), &correction)
+}
+
+// Returns the arithmetic right shift of lhs, a bit_size-bits signed integer in two's complement, by a constant shift.
+// The vacated most significant bits are filled with the sign bit of lhs, i.e. the result is rounded towards -infinity.
+pub(crate) fn evaluate_signed_shr(
+    lhs: &Expression,
+    shift: u32,
+    bit_size: u32,
+    evaluator: &mut Evaluator,
+) -> Expression {
+    if shift == 0 {
+        return lhs.clone();
+    }
+    let sign = evaluate_sign(lhs, bit_size, evaluator);
+    if shift >= bit_size {
+        // all the bits are set to the sign bit: the result is either 0 or -1
+        return &sign * (two_pow(bit_size) - FieldElement::one());
+    }
+    //logical shift: (lhs - lhs mod 2^shift)/2^shift
+    let low = evaluate_truncate(lhs, shift, bit_size, evaluator);
+    let logical = &subtract(lhs, FieldElement::one(), &low) * two_pow(shift).inverse();
+    //set the 'shift' most significant bits to the sign bit
+    add(&logical, two_pow(bit_size) - two_pow(bit_size - shift), &sign)
+}
+
+// Returns 2^y if y < bit_size and 0 otherwise, along with the boolean y < bit_size, for y an exponent_bits-bits unsigned integer.
+fn evaluate_pow2_in_range(
+    y: &Expression,
+    exponent_bits: u32,
+    bit_size: u32,
+    evaluator: &mut Evaluator,
+) -> (Expression, Expression) {
+    let y_bits = to_radix_base(y, 2, exponent_bits, Endian::Little, evaluator);
+    //2^y = \prod 2^(b_i*2^i), where the bits b_i with 2^i >= bit_size can only be set when y >= bit_size
+    let mut pow = Expression::one();
+    for (i, bit) in y_bits.iter().enumerate() {
+        if i >= 32 || 1_u32 << i >= bit_size {
+            break;
+        }
+        let factor = add(&Expression::one(), two_pow(1 << i) - FieldElement::one(), &(*bit).into());
+        pow = mul_with_witness(evaluator, &pow, &factor);
+    }
+    if exponent_bits < 32 && 1_u32 << exponent_bits <= bit_size {
+        // y is always smaller than bit_size
+        return (pow, Expression::one());
+    }
+    let max_bits = u32::max(exponent_bits, bit_size_u32(bit_size));
+    let bit_size_expr = Expression::from_field(FieldElement::from(bit_size as i128));
+    let in_range = evaluate_cmp(y, &bit_size_expr, max_bits, false, evaluator);
+    (mul_with_witness(evaluator, &pow, &in_range), in_range)
+}
+
+// Returns lhs << rhs, for lhs a bit_size-bits integer and rhs an rhs_bits-bits unsigned integer only known at runtime.
+// The result is truncated to bit_size bits, so shifting by bit_size or more bits gives 0.
+pub(crate) fn evaluate_shl(
+    lhs: &Expression,
+    rhs: &Expression,
+    rhs_bits: u32,
+    bit_size: u32,
+    evaluator: &mut Evaluator,
+) -> Expression {
+    let (pow, _) = evaluate_pow2_in_range(rhs, rhs_bits, bit_size, evaluator);
+    //lhs < 2^n and pow <= 2^(n-1), so lhs*pow requires at most 2n-1 bits
+    let max_bits = u32::max(2 * bit_size - 1, bit_size + 1);
+    if max_bits < FieldElement::max_num_bits() {
+        let shifted = mul_with_witness(evaluator, lhs, &pow);
+        return evaluate_truncate(&shifted, bit_size, max_bits, evaluator);
+    }
+
+    //lhs*pow could overflow the field, so lhs is split into halves lhs = lo + 2^h*hi, and
+    //lhs << rhs = lo*pow + 2^h*(hi*pow mod 2^(n-h)) mod 2^n, where both products fit into the field
+    let half = bit_size / 2;
+    let hi_max_bits = 2 * bit_size - half - 1;
+    assert!(
+        hi_max_bits < FieldElement::max_num_bits(),
+        "left shifts of {bit_size}-bits integers by runtime values would overflow the field"
+    );
+    let lo = evaluate_truncate(lhs, half, bit_size, evaluator);
+    let hi = &subtract(lhs, FieldElement::one(), &lo) * two_pow(half).inverse();
+    let hi_shifted = mul_with_witness(evaluator, &hi, &pow);
+    let hi_shifted = evaluate_truncate(&hi_shifted, bit_size - half, hi_max_bits, evaluator);
+    let lo_shifted = mul_with_witness(evaluator, &lo, &pow);
+    //lo*pow < 2^(h+n-1) and 2^h*hi_shifted < 2^n, so their sum requires at most n+h bits
+    let shifted = add(&lo_shifted, two_pow(half), &hi_shifted);
+    evaluate_truncate(&shifted, bit_size, bit_size + half, evaluator)
+}
+
+// Returns lhs >> rhs, for lhs a bit_size-bits integer and rhs an rhs_bits-bits unsigned integer only known at runtime.
+// Right shifts of signed integers are arithmetic shifts, i.e. they fill the vacated bits with the sign bit of lhs.
+pub(crate) fn evaluate_shr(
+    lhs: &Expression,
+    rhs: &Expression,
+    rhs_bits: u32,
+    bit_size: u32,
+    signed: bool,
+    predicate: &Expression,
+    evaluator: &mut Evaluator,
+) -> Expression {
+    let (pow, in_range) = evaluate_pow2_in_range(rhs, rhs_bits, bit_size, evaluator);
+    //we divide by 2^n when the shift is out of range, so that the logical shift gives 0
+    let out_of_range = subtract(&Expression::one(), FieldElement::one(), &in_range);
+    let divisor = add(&pow, two_pow(bit_size), &out_of_range);
+    if !signed {
+        let (q_witness, _) = evaluate_udiv(lhs, &divisor, bit_size + 1, predicate, evaluator);
+        return q_witness.into();
+    }
+    //for negative lhs, lhs >> rhs = !((!lhs) >> rhs), where !x = 2^n - 1 - x is the bitwise not of x
+    let sign = evaluate_sign(lhs, bit_size, evaluator);
+    let lhs_not = evaluate_conditional_not(lhs, &sign, bit_size, evaluator);
+    let (q_witness, _) = evaluate_udiv(&lhs_not, &divisor, bit_size + 1, predicate, evaluator);
+    evaluate_conditional_not(&q_witness.into(), &sign, bit_size, evaluator)
+}
+
+// Returns 2^n - 1 - x, i.e. the bitwise not of x, if flip is 1, and x if flip is 0
+fn evaluate_conditional_not(
+    x: &Expression,
+    flip: &Expression,
+    bit_size: u32,
+    evaluator: &mut Evaluator,
+) -> Expression {
+    // x + flip*(2^n - 1 - 2*x)
+    let mut flipped = Expression::from_field(two_pow(bit_size) - FieldElement::one());
+    flipped = subtract(&flipped, FieldElement::from(2_i128), x);
+    let correction = mul_with_witness(evaluator, flip, &flipped);
+    add(x, FieldElement::one(), &correction)
+}
+
 //truncate lhs (a number whose value requires max_bits) into a rhs-bits number: i.e it returns b such that lhs mod 2^rhs is b
 pub(crate) fn evaluate_truncate(
     lhs: &Expression,
@@ -704,13 +866,36 @@ pub(crate) fn arrays_eq_predicate(
     sum
 }
 
-// TODO: An issue should be created for this
+// Signed division of lhs by rhs, two bit_size-bits signed integers in two's complement.
+// Returns the quotient, rounded towards zero, and the remainder, which has the sign of lhs.
+// n.b. the results are reduced modulo 2^n only up to a multiple of 2^n, i.e. they require bit_size+1 bits.
 pub(crate) fn evaluate_sdiv(
-    _lhs: &Expression,
-    _rhs: &Expression,
-    _evaluator: &mut Evaluator,
+    lhs: &Expression,
+    rhs: &Expression,
+    bit_size: u32,
+    predicate: &Expression,
+    evaluator: &mut Evaluator,
 ) -> (Expression, Expression) {
-    todo!();
+    let lhs_sign = evaluate_sign(lhs, bit_size, evaluator);
+    let rhs_sign = evaluate_sign(rhs, bit_size, evaluator);
+    // n.b. the absolute value of -2^(n-1) is 2^(n-1), which still fits into bit_size bits
+    let lhs_abs = evaluate_conditional_negate(lhs, &lhs_sign, bit_size, evaluator);
+    let rhs_abs = evaluate_conditional_negate(rhs, &rhs_sign, bit_size, evaluator);
+
+    //|lhs| = |rhs|*q + r
+    let (q_witness, r_witness) = evaluate_udiv(&lhs_abs, &rhs_abs, bit_size, predicate, evaluator);
+
+    //the quotient is negative iff the signs of the operands differ: q_sign = lhs_sign xor rhs_sign
+    let signs_product = mul_with_witness(evaluator, &lhs_sign, &rhs_sign);
+    let mut q_sign = add(&lhs_sign, FieldElement::one(), &rhs_sign);
+    q_sign = subtract(&q_sign, FieldElement::from(2_i128), &signs_product);
+    let q = evaluate_conditional_negate(&q_witness.into(), &q_sign, bit_size, evaluator);
+    let r = evaluate_conditional_negate(&r_witness.into(), &lhs_sign, bit_size, evaluator);
+    (q, r)
+}
+
+fn two_pow(bit_size: u32) -> FieldElement {
+    FieldElement::from(2_i128).pow(&FieldElement::from(bit_size as i128))
 }
 
 const fn num_bits<T>() -> usize {
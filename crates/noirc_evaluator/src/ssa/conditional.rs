@@ -484,7 +484,7 @@ impl DecisionTree {
                 Some(stack.block),
             ));
             stack.push(cond);
-            let unreachable = Operation::Constrain(cond, None);
+            let unreachable = Operation::Constrain(cond, None, None);
             let ins2 = ctx.add_instruction(Instruction::new(
                 unreachable,
                 ObjectType::NotAnObject,
@@ -712,7 +712,7 @@ impl DecisionTree {
                     }
                     stack.push(ins_id);
                 }
-                Operation::Constrain(expr, loc) => {
+                Operation::Constrain(expr, loc, message) => {
                     if ctx.under_assumption(ass_value) {
                         let operation = Operation::Cond {
                             condition: ass_value,
@@ -729,7 +729,7 @@ impl DecisionTree {
                         ));
                         stack.push(cond);
                         let ins2 = ctx.instruction_mut(ins_id);
-                        ins2.operation = Operation::Constrain(cond, *loc);
+                        ins2.operation = Operation::Constrain(cond, *loc, message.clone());
                         if ctx.is_zero(*expr) {
                             stack.push(ins_id);
                             return Ok(false);
@@ -43,9 +43,8 @@ pub(crate) struct SsaContext {
     dummy_store: HashMap<ArrayId, NodeId>,
     dummy_load: HashMap<ArrayId, NodeId>,
 
-    //debug information
-    #[allow(dead_code)]
-    locations: HashMap<NodeId, Location>,
+    //debug information: the location of the expression instructions are being generated from
+    pub(crate) current_location: Option<Location>,
 }
 
 impl Default for SsaContext {
@@ -63,7 +62,7 @@ impl Default for SsaContext {
             call_graph: Vec::new(),
             dummy_store: HashMap::new(),
             dummy_load: HashMap::new(),
-            locations: HashMap::new(),
+            current_location: None,
             constants: HashMap::new(),
         };
         block::create_first_block(&mut pc);
@@ -299,7 +298,10 @@ impl SsaContext {
     /// Add an instruction to self.nodes and sets its id.
     /// This function does NOT push the instruction to the current block.
     /// See push_instruction for that.
-    pub(crate) fn add_instruction(&mut self, instruction: node::Instruction) -> NodeId {
+    pub(crate) fn add_instruction(&mut self, mut instruction: node::Instruction) -> NodeId {
+        if instruction.call_stack.is_empty() {
+            instruction.call_stack.extend(self.current_location);
+        }
         let obj = NodeObject::Instr(instruction);
         let id = NodeId(self.nodes.insert(obj));
         match &mut self[id] {
@@ -1251,7 +1253,7 @@ impl SsaContext {
                 };
                 bin.predicate = Some(cond);
             }
-            Operation::Constrain(cond, _) => {
+            Operation::Constrain(cond, ..) => {
                 let operation =
                     Operation::Cond { condition: pred, val_true: *cond, val_false: self.one() };
                 let c_ins = self.add_instruction(Instruction::new(
@@ -117,6 +117,7 @@ pub(super) fn unroll_std_block(
                 });
                 let mut new_ins =
                     node::Instruction::new(new_op, i.res_type, Some(unroll_ctx.unroll_into));
+                new_ins.call_stack = i.call_stack.clone();
                 match i.operation {
                     Operation::Binary(node::Binary { operator: BinaryOp::Assign, .. }) => {
                         unreachable!("unsupported instruction type when unrolling: assign");
@@ -1,4 +1,4 @@
-use crate::errors::RuntimeError;
+use crate::errors::{RuntimeError, RuntimeErrorKind};
 use crate::ssa::{
     block::BlockId,
     conditional::{AssumptionId, DecisionTree, TreeBuilder},
@@ -6,12 +6,20 @@ use crate::ssa::{
     mem::ArrayId,
     node::{Node, NodeId, ObjectType, Opcode, Operation},
     ssa_gen::IrGenerator,
+    value::Value,
     {block, builtin, node, ssa_form},
 };
 use iter_extended::try_vecmap;
-use noirc_frontend::monomorphization::ast::{Call, Definition, FuncId, LocalId, Type};
+use noirc_frontend::monomorphization::ast::{
+    Call, Definition, Expression, FuncId, Ident, LocalId, Type,
+};
 use std::collections::{HashMap, VecDeque};
 
+/// The maximum number of nested calls to recursive functions which are expanded during
+/// SSA generation. Each expansion generates the body of the function through several nested
+/// native calls, so this is kept low enough for the compiler not to overflow its stack.
+const RECURSION_LIMIT: u32 = 100;
+
 #[derive(Clone, Debug, PartialEq, Eq, Copy)]
 pub(crate) struct FuncIndex(pub(crate) usize);
 
@@ -227,6 +235,14 @@ impl IrGenerator {
 
     //generates an instruction for calling the function
     pub(super) fn call(&mut self, call: &Call) -> Result<Vec<NodeId>, RuntimeError> {
+        if let Expression::Ident(Ident { definition: Definition::Function(id), .. }) =
+            call.func.as_ref()
+        {
+            if self.recursive_functions.contains(id) {
+                return self.expand_recursive_call(*id, call);
+            }
+        }
+
         let func = self.ssa_gen_expression(&call.func)?.unwrap_id();
         let arguments = self.ssa_gen_expression_list(&call.arguments);
 
@@ -303,6 +319,54 @@ impl IrGenerator {
         result_ids
     }
 
+    /// Generates the body of a recursive function in place of a call to it, since every call
+    /// is eventually inlined and the call graph of recursive functions has no leaf.
+    ///
+    /// Constant arguments are bound directly to the parameters, so that conditions on them are
+    /// evaluated at compile time. The recursion ends once the recursive calls are only reachable
+    /// through branches which are known to be not taken.
+    fn expand_recursive_call(
+        &mut self,
+        func_id: FuncId,
+        call: &Call,
+    ) -> Result<Vec<NodeId>, RuntimeError> {
+        let function = &self.program[func_id];
+        if self.recursion_level >= RECURSION_LIMIT {
+            let message = format!(
+                "Could not determine the depth of the recursive calls to `{}` at compile time. Recursion is only supported when the arguments deciding when it ends are known at compile time",
+                function.name
+            );
+            return Err(RuntimeError::new(
+                RuntimeErrorKind::UnstructuredError { message },
+                Some(call.location),
+            ));
+        }
+        let parameters = function.parameters.clone();
+        let body = function.body.clone();
+
+        let arguments = try_vecmap(&call.arguments, |argument| self.ssa_gen_expression(argument))?;
+
+        // The parameters of each expansion shadow those of the enclosing ones
+        let caller_values = self.variable_values.clone();
+        for ((id, mutable, name, _), argument) in parameters.into_iter().zip(arguments) {
+            match argument {
+                Value::Node(node_id)
+                    if !mutable && self.context.get_as_constant(node_id).is_some() =>
+                {
+                    self.variable_values.insert(Definition::Local(id), Value::Node(node_id));
+                }
+                argument => self.bind_id(id, argument, &name)?,
+            }
+        }
+
+        self.recursion_level += 1;
+        let result = self.ssa_gen_expression(&body);
+        self.recursion_level -= 1;
+        self.variable_values = caller_values;
+
+        Ok(result?.to_node_ids())
+    }
+
     fn create_call_results(
         &mut self,
         call: &Call,
@@ -379,13 +443,18 @@ fn is_leaf(call_graph: &[Vec<u8>], i: FuncIndex) -> bool {
     true
 }
 
-fn get_new_leaf(ctx: &SsaContext, processed: &[FuncIndex]) -> (FuncIndex, FuncId) {
+fn get_new_leaf(
+    ctx: &SsaContext,
+    processed: &[FuncIndex],
+) -> Result<(FuncIndex, FuncId), RuntimeError> {
     for f in ctx.functions.values() {
         if !processed.contains(&(f.idx)) && is_leaf(&ctx.call_graph, f.idx) {
-            return (f.idx, f.id);
+            return Ok((f.idx, f.id));
         }
     }
-    unimplemented!("Recursive function call is not supported");
+    // Every remaining function calls another remaining function, so they form a cycle
+    let message = "Recursive function call is not supported".to_string();
+    Err(RuntimeErrorKind::UnstructuredError { message }.into())
 }
 
 //inline all functions of the call graph such that every inlining operates with a fully flattened function
@@ -394,7 +463,7 @@ pub(super) fn inline_all(ctx: &mut SsaContext) -> Result<(), RuntimeError> {
     let l = ctx.call_graph.len();
     let mut processed = Vec::new();
     while processed.len() < l {
-        let i = get_new_leaf(ctx, &processed);
+        let i = get_new_leaf(ctx, &processed)?;
         if !processed.is_empty() {
             super::optimizations::full_cse(ctx, ctx.functions[&i.1].entry_block, false)?;
         }
@@ -260,11 +260,13 @@ fn inline_in_block(
     let block_func = &ctx[block_id];
     let next_block = block_func.left;
     let block_func_instructions = &block_func.instructions.clone();
-    let predicate = if let Operation::Call { predicate, .. } = &ctx.instruction(call_id).operation {
+    let call = ctx.instruction(call_id);
+    let predicate = if let Operation::Call { predicate, .. } = &call.operation {
         *predicate
     } else {
         unreachable!("invalid call id");
     };
+    let call_stack = call.call_stack.clone();
     let mut short_circuit = false;
 
     *nested_call = false;
@@ -275,6 +277,8 @@ fn inline_in_block(
             }
             let mut array_id = None;
             let mut clone = ins.clone();
+            // The inlined instructions come from the call
+            clone.call_stack = call_stack.iter().chain(&ins.call_stack).copied().collect();
 
             if let node::ObjectType::ArrayPointer(id) = ins.res_type {
                 //We collect data here for potential mapping using the array_map below.
@@ -317,6 +321,7 @@ fn inline_in_block(
                         Some(stack_frame.block),
                     );
                     new_ins.id = clone.id;
+                    new_ins.call_stack = clone.call_stack.clone();
                     push_instruction(ctx, new_ins, stack_frame, inline_map);
                 }
                 Operation::Store { array_id, index, value, predicate, location } => {
@@ -333,6 +338,7 @@ fn inline_in_block(
                         Some(stack_frame.block),
                     );
                     new_ins.id = clone.id;
+                    new_ins.call_stack = clone.call_stack.clone();
                     push_instruction(ctx, new_ins, stack_frame, inline_map);
                 }
                 Operation::Phi { .. } => {
@@ -404,6 +410,7 @@ fn new_cloned_instruction(original: Instruction, block: BlockId) -> Instruction
     let mut clone = Instruction::new(original.operation, original.res_type, Some(block));
     // Take the original's ID, it will be used to map it as a replacement in push_instruction later
     clone.id = original.id;
+    clone.call_stack = original.call_stack;
     clone
 }
 
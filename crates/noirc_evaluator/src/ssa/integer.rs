@@ -312,10 +312,10 @@ fn block_overflow(
                     });
                 }
             }
-            Operation::Binary(node::Binary { operator: BinaryOp::Shr(loc), lhs, rhs, .. }) => {
-                if !ins.res_type.is_unsigned_integer() {
-                    todo!("Right shift is only implemented for unsigned integers");
-                }
+            // Right shifts of signed integers are arithmetic shifts, which are handled during ACIR generation
+            Operation::Binary(node::Binary { operator: BinaryOp::Shr(loc), lhs, rhs, .. })
+                if ins.res_type.is_unsigned_integer() =>
+            {
                 if let Some(r_const) = ctx.get_as_constant(rhs) {
                     let r_type = ctx[rhs].get_type();
                     if r_const.to_u128() > r_type.bits() as u128 {
@@ -515,9 +515,8 @@ fn get_binary_max_value(
     let rhs_max = &max_map[&binary.rhs];
 
     match &binary.operator {
-        BinaryOp::Add => lhs_max + rhs_max,
-        BinaryOp::SafeAdd => todo!(),
-        BinaryOp::Sub { .. } => {
+        BinaryOp::Add | BinaryOp::SafeAdd => lhs_max + rhs_max,
+        BinaryOp::Sub { .. } | BinaryOp::SafeSub { .. } => {
             let r_mod = BigUint::one() << res_type.bits();
             let mut k = rhs_max / &r_mod;
             if rhs_max % &r_mod != BigUint::zero() {
@@ -526,13 +525,11 @@ fn get_binary_max_value(
             assert!(&k * &r_mod >= *rhs_max);
             lhs_max + k * r_mod
         }
-        BinaryOp::SafeSub { .. } => todo!(),
-        BinaryOp::Mul => lhs_max * rhs_max,
-        BinaryOp::SafeMul => todo!(),
+        BinaryOp::Mul | BinaryOp::SafeMul => lhs_max * rhs_max,
         BinaryOp::Udiv(_) => lhs_max.clone(),
-        BinaryOp::Sdiv(_) => todo!(),
         BinaryOp::Urem(_) => rhs_max - BigUint::one(),
-        BinaryOp::Srem(_) => todo!(),
+        //the result of a signed division is negated from its absolute value, which gives 2^n when it is zero
+        BinaryOp::Sdiv(_) | BinaryOp::Srem(_) => res_type.max_size() + BigUint::one(),
         BinaryOp::Div(_) => FieldElement::modulus() - BigUint::one(),
         BinaryOp::Eq => BigUint::one(),
         BinaryOp::Ne => BigUint::one(),
@@ -551,16 +548,9 @@ fn get_binary_max_value(
                 - BigUint::one()
         }
         BinaryOp::Assign => rhs_max.clone(),
-        BinaryOp::Shl => BigUint::min(
-            BigUint::from(2_u32).pow((lhs_max.bits() + 1) as u32) - BigUint::one(),
-            res_type.max_size(),
-        ),
-        BinaryOp::Shr(_) => {
-            if lhs_max.bits() >= 1 {
-                BigUint::from(2_u32).pow((lhs_max.bits() - 1) as u32) - BigUint::one()
-            } else {
-                BigUint::zero()
-            }
-        }
+        // Unsigned shifts by a constant are replaced by a multiplication or a division, so the shift amount is unknown here
+        BinaryOp::Shl => res_type.max_size(),
+        BinaryOp::Shr(_) if !res_type.is_unsigned_integer() => res_type.max_size(),
+        BinaryOp::Shr(_) => lhs_max.clone(),
     }
 }
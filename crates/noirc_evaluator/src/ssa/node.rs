@@ -1,5 +1,6 @@
 use crate::errors::{RuntimeError, RuntimeErrorKind};
 use crate::ssa::{block::BlockId, builtin, conditional, context::SsaContext, mem::ArrayId};
+use crate::CallStack;
 use acvm::{acir::native_types::Witness, FieldElement};
 use iter_extended::vecmap;
 use noirc_errors::Location;
@@ -263,9 +264,10 @@ impl ObjectType {
                 unreachable!()
             }
             ObjectType::Numeric(NumericType::NativeField) => f,
-            // TODO: document why this is a TODO and create an issue
-            ObjectType::Numeric(NumericType::Signed(_)) => todo!(),
-            ObjectType::Function | ObjectType::Numeric(NumericType::Unsigned(_)) => {
+            // Signed integers are represented in two's complement, so they are reduced the same way as unsigned integers
+            ObjectType::Function
+            | ObjectType::Numeric(NumericType::Signed(_))
+            | ObjectType::Numeric(NumericType::Unsigned(_)) => {
                 // TODO: document where this 128 comes from
                 assert!(self.bits() < 128);
                 FieldElement::from(f.to_u128() % (1_u128 << self.bits()))
@@ -282,6 +284,7 @@ pub(crate) struct Instruction {
     pub(crate) parent_block: BlockId,
     pub(crate) res_name: String,
     pub(crate) mark: Mark,
+    pub(crate) call_stack: CallStack,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -366,6 +369,7 @@ impl Instruction {
             res_name: String::new(),
             parent_block: p_block,
             mark: Mark::None,
+            call_stack: CallStack::new(),
         }
     }
 
@@ -431,25 +435,16 @@ impl Instruction {
                     return Ok(NodeEval::Const(FieldElement::from((!l) & max), self.res_type));
                 }
             }
-            Operation::Constrain(value, location) => {
+            Operation::Constrain(value, location, message) => {
                 if let Some(obj) = eval_fn(ctx, *value)?.into_const_value() {
                     if obj.is_one() {
                         // Delete the constrain, it is always true
                         return Ok(NodeEval::VarOrInstruction(NodeId::dummy()));
                     } else if obj.is_zero() {
-                        if let Some(location) = *location {
-                            return Err(RuntimeError::new(
-                                RuntimeErrorKind::UnstructuredError {
-                                    message: "Constraint is always false".into(),
-                                },
-                                Some(location),
-                            ));
-                        } else {
-                            return Err(RuntimeErrorKind::Spanless(
-                                "Constraint is always false".into(),
-                            )
-                            .into());
-                        }
+                        return Err(RuntimeError::new(
+                            RuntimeErrorKind::FailedConstraint { message: message.clone() },
+                            *location,
+                        ));
                     }
                 }
             }
@@ -521,7 +516,7 @@ impl Instruction {
             Operation::Call { location, .. } => Some(*location),
             Operation::Load { location, .. }
             | Operation::Store { location, .. }
-            | Operation::Constrain(_, location) => *location,
+            | Operation::Constrain(_, location, _) => *location,
             Operation::Cast(_)
             | Operation::Truncate { .. }
             | Operation::Not(_)
@@ -551,7 +546,7 @@ pub(crate) enum Operation {
     }, //truncate
 
     Not(NodeId), //(!) Bitwise Not
-    Constrain(NodeId, Option<Location>),
+    Constrain(NodeId, Option<Location>, Option<String>),
 
     //control flow
     Jne(NodeId, BlockId), //jump on not equal
@@ -936,10 +931,7 @@ impl Binary {
                 } else if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
                     let a = field_to_signed(lhs, res_type.bits());
                     let b = field_to_signed(rhs, res_type.bits());
-                    return Ok(NodeEval::Const(
-                        signed_to_field(a - b + (a / b), res_type.bits())?,
-                        res_type,
-                    ));
+                    return Ok(NodeEval::Const(signed_to_field(a % b, res_type.bits())?, res_type));
                 }
             }
             BinaryOp::Ult => {
@@ -972,8 +964,22 @@ impl Binary {
                     ));
                 }
             }
-            BinaryOp::Slt => (),
-            BinaryOp::Sle => (),
+            BinaryOp::Slt => {
+                if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+                    let a = field_to_signed(lhs, l_type.bits());
+                    let b = field_to_signed(rhs, l_type.bits());
+                    return Ok(NodeEval::Const(FieldElement::from(a < b), ObjectType::boolean()));
+                }
+            }
+            BinaryOp::Sle => {
+                if self.lhs == self.rhs {
+                    return Ok(NodeEval::Const(FieldElement::one(), ObjectType::boolean()));
+                } else if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+                    let a = field_to_signed(lhs, l_type.bits());
+                    let b = field_to_signed(rhs, l_type.bits());
+                    return Ok(NodeEval::Const(FieldElement::from(a <= b), ObjectType::boolean()));
+                }
+            }
             BinaryOp::Lt => {
                 if r_is_zero {
                     return Ok(NodeEval::Const(FieldElement::zero(), ObjectType::boolean()));
@@ -1080,6 +1086,15 @@ impl Binary {
                     return Ok(l_eval);
                 }
                 if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+                    if let ObjectType::Numeric(NumericType::Signed(bit_size)) = res_type {
+                        //arithmetic shift, which rounds towards -infinity
+                        let a = field_to_signed(lhs, bit_size);
+                        let shift = u32::min(rhs.to_u128().try_into().unwrap_or(u32::MAX), 127);
+                        return Ok(NodeEval::Const(
+                            signed_to_field(a >> shift, bit_size)?,
+                            res_type,
+                        ));
+                    }
                     return Ok(wrapping(lhs, rhs, res_type, u128::shr, field_op_not_allowed));
                 }
             }
@@ -1202,7 +1217,7 @@ impl Operation {
                 Truncate { value: f(*value), bit_size: *bit_size, max_bit_size: *max_bit_size }
             }
             Not(id) => Not(f(*id)),
-            Constrain(id, loc) => Constrain(f(*id), *loc),
+            Constrain(id, loc, message) => Constrain(f(*id), *loc, message.clone()),
             Jne(id, block) => Jne(f(*id), *block),
             Jeq(id, block) => Jeq(f(*id), *block),
             Jmp(block) => Jmp(*block),
@@ -1378,27 +1393,25 @@ impl BinaryOp {
 }
 // TODO: We should create a constant and explain where the 127 and 126 constants
 // TODO are from
+// Interprets the n lowest bits of f as a signed integer in two's complement
 fn field_to_signed(f: FieldElement, n: u32) -> i128 {
     assert!(n < 127);
-    let a = f.to_u128();
-    let pow_2 = 2_u128.pow(n);
+    let a = f.to_u128() % 2_u128.pow(n);
+    let pow_2 = 2_u128.pow(n - 1);
     if a < pow_2 {
         a as i128
     } else {
-        (a - 2 * pow_2) as i128
+        a as i128 - 2_i128.pow(n)
     }
 }
 
+// Returns the two's complement representation of a as a n-bits signed integer, wrapping around in case of overflow
 fn signed_to_field(a: i128, n: u32) -> Result<FieldElement, RuntimeError> {
     if n >= 126 {
         return Err(RuntimeErrorKind::UnstructuredError {
-            message: "ICE: cannot convert signed {n} bit size into field".to_string(),
+            message: format!("ICE: cannot convert signed {n} bit size into field"),
         })?;
     }
-    if a >= 0 {
-        Ok(FieldElement::from(a))
-    } else {
-        let b = (a + 2_i128.pow(n + 1)) as u128;
-        Ok(FieldElement::from(b))
-    }
+    let b = a.rem_euclid(2_i128.pow(n)) as u128;
+    Ok(FieldElement::from(b))
 }
@@ -507,7 +507,7 @@ fn cse_block_with_anchor(
                         new_list.push(*ins_id);
                     }
                 }
-                Operation::Constrain(condition, location) => {
+                Operation::Constrain(condition, location, message) => {
                     if let Some(similar) = anchor.find_similar_instruction(&operator) {
                         assert_ne!(similar, ins.id);
                         *modified = true;
@@ -515,7 +515,8 @@ fn cse_block_with_anchor(
                             .try_get_mut_instruction(similar)
                             .expect("Similar instructions are instructions");
                         if location.is_some() && similar_ins.get_location().is_none() {
-                            similar_ins.operation = Operation::Constrain(*condition, *location);
+                            similar_ins.operation =
+                                Operation::Constrain(*condition, *location, message.clone());
                         }
                         new_mark = Mark::ReplaceWith(similar);
                     } else {
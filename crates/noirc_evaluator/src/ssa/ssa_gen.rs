@@ -11,18 +11,21 @@ use crate::{
     },
 };
 use acvm::{acir::native_types::Witness, FieldElement};
-use iter_extended::vecmap;
+use iter_extended::{try_vecmap, vecmap};
 use noirc_errors::Location;
 use noirc_frontend::{
-    monomorphization::ast::{
-        ArrayLiteral, Definition, Expression, For, Ident, If, LValue, Let, Literal, LocalId,
-        Program, Type,
+    monomorphization::{
+        ast::{
+            ArrayLiteral, Definition, Expression, For, FuncId, Ident, If, LValue, Let, Literal,
+            LocalId, Program, Type,
+        },
+        call_graph::CallGraph,
     },
     BinaryOpKind, UnaryOp,
 };
 use num_bigint::BigUint;
 use num_traits::Zero;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 pub(crate) struct IrGenerator {
     pub(crate) context: SsaContext,
@@ -30,18 +33,28 @@ pub(crate) struct IrGenerator {
 
     /// The current value of a variable. Used for flattening structs
     /// into multiple variables/values
-    variable_values: HashMap<Definition, Value>,
+    pub(crate) variable_values: HashMap<Definition, Value>,
 
     pub(crate) program: Program,
+
+    /// The functions which are part of a cycle in the call graph.
+    /// Calls to these are expanded in place, see `expand_recursive_call`.
+    pub(crate) recursive_functions: BTreeSet<FuncId>,
+    /// The number of nested expansions of recursive calls currently being generated.
+    pub(crate) recursion_level: u32,
 }
 
 impl IrGenerator {
     pub(crate) fn new(program: Program) -> IrGenerator {
+        let recursive_functions =
+            CallGraph::new(&program).recursive_calls().iter().map(|call| call.callee).collect();
         IrGenerator {
             context: SsaContext::default(),
             variable_values: HashMap::new(),
             function_context: None,
             program,
+            recursive_functions,
+            recursion_level: 0,
         }
     }
 
@@ -65,6 +78,8 @@ impl IrGenerator {
     /// This is why it requires a mutable SsaContext
     pub(crate) fn get_current_value(&mut self, value: &Value) -> Value {
         match value {
+            // Constants may be bound to the parameters of recursive functions, see `expand_recursive_call`
+            Value::Node(id) if self.context.get_as_constant(*id).is_some() => Value::Node(*id),
             Value::Node(id) => Value::Node(ssa_form::get_current_value(&mut self.context, *id)),
             Value::Tuple(fields) => {
                 Value::Tuple(vecmap(fields, |value| self.get_current_value(value)))
@@ -72,23 +87,23 @@ impl IrGenerator {
         }
     }
 
-    pub(crate) fn get_object_type_from_abi(&self, el_type: &noirc_abi::AbiType) -> ObjectType {
+    pub(crate) fn get_object_type_from_abi(
+        &self,
+        el_type: &noirc_abi::AbiType,
+    ) -> Result<ObjectType, RuntimeErrorKind> {
         match el_type {
-            noirc_abi::AbiType::Field => ObjectType::native_field(),
+            noirc_abi::AbiType::Field => Ok(ObjectType::native_field()),
             noirc_abi::AbiType::Integer { sign, width, .. } => match sign {
-                noirc_abi::Sign::Unsigned => ObjectType::unsigned_integer(*width),
-                noirc_abi::Sign::Signed => ObjectType::signed_integer(*width),
+                noirc_abi::Sign::Unsigned => Ok(ObjectType::unsigned_integer(*width)),
+                noirc_abi::Sign::Signed => Ok(ObjectType::signed_integer(*width)),
             },
-            noirc_abi::AbiType::Boolean => ObjectType::boolean(),
-            noirc_abi::AbiType::Array { .. } => {
-                unreachable!("array of arrays are not supported for now")
-            }
-            noirc_abi::AbiType::Struct { .. } => {
-                unreachable!("array of structs are not supported for now")
-            }
-            noirc_abi::AbiType::String { .. } => {
-                unreachable!("array of strings are not supported for now")
-            }
+            noirc_abi::AbiType::Boolean => Ok(ObjectType::boolean()),
+            noirc_abi::AbiType::Array { .. }
+            | noirc_abi::AbiType::Struct { .. }
+            | noirc_abi::AbiType::String { .. }
+            | noirc_abi::AbiType::Tuple { .. } => Err(RuntimeErrorKind::Unimplemented(format!(
+                "arrays with elements of type {el_type:?} are not supported"
+            ))),
         }
     }
 
@@ -99,8 +114,8 @@ impl IrGenerator {
         el_type: &noirc_abi::AbiType,
         len: u64,
         witness: &[Witness],
-    ) -> NodeId {
-        let element_type = self.get_object_type_from_abi(el_type);
+    ) -> Result<NodeId, RuntimeErrorKind> {
+        let element_type = self.get_object_type_from_abi(el_type)?;
         let (v_id, array_idx) = self.new_array(name, element_type, len as u32, ident_def);
         let values = vecmap(witness.iter().enumerate(), |(i, w)| {
             let mut var = Variable::new(
@@ -117,7 +132,87 @@ impl IrGenerator {
         let block = self.context.get_current_block_mut();
         block.instructions.extend_from_slice(&stack_frame.stack);
         block.update_variable(v_id, v_id);
-        v_id
+        Ok(v_id)
+    }
+
+    /// Creates the value of an array input to `main`, whose elements may be structs or tuples.
+    pub(crate) fn abi_array_value(
+        &mut self,
+        name: &str,
+        ident_def: Option<Definition>,
+        el_type: &noirc_abi::AbiType,
+        len: u64,
+        witness: &[Witness],
+    ) -> Result<Value, RuntimeErrorKind> {
+        match el_type {
+            noirc_abi::AbiType::Struct { fields } => {
+                self.abi_struct_array(name, ident_def, fields, len, witness)
+            }
+            noirc_abi::AbiType::Tuple { fields } => {
+                let fields = crate::tuple_fields_as_struct_fields(fields);
+                self.abi_struct_array(name, ident_def, &fields, len, witness)
+            }
+            _ => Ok(Value::Node(self.abi_array(name, ident_def, el_type, len, witness)?)),
+        }
+    }
+
+    /// Arrays of structs are represented as a struct holding one array for each field of the
+    /// elements, while the ABI lays out their witnesses one element after the other.
+    fn abi_struct_array(
+        &mut self,
+        name: &str,
+        ident_def: Option<Definition>,
+        fields: &[(String, noirc_abi::AbiType)],
+        len: u64,
+        witness: &[Witness],
+    ) -> Result<Value, RuntimeErrorKind> {
+        let element_size: u32 = fields.iter().map(|(_, typ)| typ.field_count()).sum();
+        // Arrays of empty structs have no witnesses, `chunks` requires a non-zero size
+        let elements: Vec<_> = witness.chunks(element_size.max(1) as usize).collect();
+        let values = self.abi_struct_array_fields(name, fields, len, &elements, &mut 0)?;
+        Ok(self.insert_new_struct(ident_def, values))
+    }
+
+    fn abi_struct_array_fields(
+        &mut self,
+        name: &str,
+        fields: &[(String, noirc_abi::AbiType)],
+        len: u64,
+        elements: &[&[Witness]],
+        field_index: &mut usize,
+    ) -> Result<Vec<Value>, RuntimeErrorKind> {
+        try_vecmap(fields, |(field_name, field_typ)| {
+            let new_name = format!("{name}.{field_name}");
+            match field_typ {
+                noirc_abi::AbiType::Struct { fields } => {
+                    let values = self.abi_struct_array_fields(
+                        &new_name,
+                        fields,
+                        len,
+                        elements,
+                        field_index,
+                    )?;
+                    Ok(Value::Tuple(values))
+                }
+                noirc_abi::AbiType::Tuple { fields } => {
+                    let fields = crate::tuple_fields_as_struct_fields(fields);
+                    let values = self.abi_struct_array_fields(
+                        &new_name,
+                        &fields,
+                        len,
+                        elements,
+                        field_index,
+                    )?;
+                    Ok(Value::Tuple(values))
+                }
+                _ => {
+                    let field_witnesses = vecmap(elements, |element| element[*field_index]);
+                    *field_index += 1;
+                    let v_id = self.abi_array(&new_name, None, field_typ, len, &field_witnesses)?;
+                    Ok(Value::Node(v_id))
+                }
+            }
+        })
     }
 
     pub(crate) fn abi_struct(
@@ -126,38 +221,41 @@ impl IrGenerator {
         ident_def: Option<Definition>,
         fields: &[(String, noirc_abi::AbiType)],
         witnesses: &BTreeMap<String, Vec<Witness>>,
-    ) -> Value {
-        let values = vecmap(fields, |(name, field_typ)| {
+    ) -> Result<Value, RuntimeErrorKind> {
+        let values = try_vecmap(fields, |(name, field_typ)| {
             let new_name = format!("{struct_name}.{name}");
             match field_typ {
                 noirc_abi::AbiType::Array { length, typ } => {
-                    let v_id = self.abi_array(&new_name, None, typ, *length, &witnesses[&new_name]);
-                    Value::Node(v_id)
+                    self.abi_array_value(&new_name, None, typ, *length, &witnesses[&new_name])
                 }
                 noirc_abi::AbiType::Struct { fields, .. } => {
                     let new_name = format!("{struct_name}.{name}");
                     self.abi_struct(&new_name, None, fields, witnesses)
                 }
+                noirc_abi::AbiType::Tuple { fields } => {
+                    let fields = crate::tuple_fields_as_struct_fields(fields);
+                    self.abi_struct(&new_name, None, &fields, witnesses)
+                }
                 noirc_abi::AbiType::String { length } => {
                     let typ =
                         noirc_abi::AbiType::Integer { sign: noirc_abi::Sign::Unsigned, width: 8 };
                     let v_id =
-                        self.abi_array(&new_name, None, &typ, *length, &witnesses[&new_name]);
-                    Value::Node(v_id)
+                        self.abi_array(&new_name, None, &typ, *length, &witnesses[&new_name])?;
+                    Ok(Value::Node(v_id))
                 }
                 _ => {
-                    let obj_type = self.get_object_type_from_abi(field_typ);
+                    let obj_type = self.get_object_type_from_abi(field_typ)?;
                     let v_id = self.create_new_variable(
                         new_name.clone(),
                         None,
                         obj_type,
                         Some(witnesses[&new_name][0]),
                     );
-                    Value::Node(v_id)
+                    Ok(Value::Node(v_id))
                 }
             }
-        });
-        self.insert_new_struct(ident_def, values)
+        })?;
+        Ok(self.insert_new_struct(ident_def, values))
     }
 
     fn ssa_gen_identifier(&mut self, ident: &Ident) -> Result<Value, RuntimeError> {
@@ -347,9 +445,10 @@ impl IrGenerator {
         &mut self,
         expr: &Expression,
         location: noirc_errors::Location,
+        message: Option<String>,
     ) -> Result<Value, RuntimeError> {
         let cond = self.ssa_gen_expression(expr)?.unwrap_id();
-        let operation = Operation::Constrain(cond, Some(location));
+        let operation = Operation::Constrain(cond, Some(location), message);
         self.context.new_instruction(operation, ObjectType::NotAnObject)?;
         Ok(Value::dummy())
     }
@@ -492,7 +591,26 @@ impl IrGenerator {
         Ok(Value::dummy())
     }
 
+    /// Generates the SSA of an expression. Its instructions are attributed to the innermost
+    /// expression with a location.
     pub(crate) fn ssa_gen_expression(&mut self, expr: &Expression) -> Result<Value, RuntimeError> {
+        let location = match expr {
+            Expression::Binary(binary) => Some(binary.location),
+            Expression::Call(call) => Some(call.location),
+            Expression::Index(index) => Some(index.location),
+            Expression::Constrain(_, location, _) => Some(*location),
+            _ => None,
+        };
+        let previous_location = self.context.current_location;
+        if location.is_some() {
+            self.context.current_location = location;
+        }
+        let value = self.ssa_gen_expression_kind(expr);
+        self.context.current_location = previous_location;
+        value
+    }
+
+    fn ssa_gen_expression_kind(&mut self, expr: &Expression) -> Result<Value, RuntimeError> {
         match expr {
             Expression::Ident(ident) => self.ssa_gen_identifier(ident),
             Expression::Binary(binary) => {
@@ -560,8 +678,8 @@ impl IrGenerator {
                 Ok(tuple.into_field_member(*field))
             }
             Expression::Let(let_expr) => self.ssa_gen_let(let_expr),
-            Expression::Constrain(expr, location) => {
-                self.ssa_gen_constrain(expr.as_ref(), *location)
+            Expression::Constrain(expr, location, message) => {
+                self.ssa_gen_constrain(expr.as_ref(), *location, message.clone())
             }
             Expression::Assign(assign) => {
                 self.ssa_gen_assign(&assign.lvalue, assign.expression.as_ref())
@@ -7,16 +7,21 @@
 //! This module heavily borrows from Cranelift
 #![allow(dead_code)]
 
-use crate::errors::RuntimeError;
+use crate::{
+    errors::{RuntimeError, RuntimeErrorKind},
+    DebugInfo,
+};
 use acvm::{
     acir::circuit::{Circuit, Opcode as AcirOpcode, PublicInputs},
     Language,
 };
 use noirc_abi::Abi;
 
-use noirc_frontend::monomorphization::ast::Program;
+use iter_extended::vecmap;
+use noirc_errors::Location;
+use noirc_frontend::monomorphization::{ast::Program, call_graph::CallGraph};
 
-use self::{abi_gen::gen_abi, acir_gen::GeneratedAcir, ssa_gen::Ssa};
+use self::{abi_gen::gen_abi, acir_gen::GeneratedAcir, opt::RecursionLimitExceeded, ssa_gen::Ssa};
 
 mod abi_gen;
 mod acir_gen;
@@ -28,11 +33,19 @@ pub mod ssa_gen;
 /// Optimize the given program by converting it into SSA
 /// form and performing optimizations there. When finished,
 /// convert the final SSA into ACIR and return it.
-pub(crate) fn optimize_into_acir(program: Program) -> GeneratedAcir {
+pub(crate) fn optimize_into_acir(
+    program: Program,
+    show_output: bool,
+) -> Result<GeneratedAcir, RuntimeError> {
     let func_signature = program.main_function_signature.clone();
+    let recursive_calls = vecmap(CallGraph::new(&program).recursive_calls(), |call| {
+        (program[call.callee].name.clone(), call.location)
+    });
+
     ssa_gen::generate_ssa(program)
         .print("Initial SSA:")
         .inline_functions()
+        .map_err(|err| recursion_limit_error(err, &recursive_calls))?
         .print("After Inlining:")
         .unroll_loops()
         .print("After Unrolling:")
@@ -42,7 +55,25 @@ pub(crate) fn optimize_into_acir(program: Program) -> GeneratedAcir {
         .print("After Flattening:")
         .mem2reg()
         .print("After Mem2Reg:")
-        .into_acir(func_signature)
+        .into_acir(func_signature, show_output)
+        .map_err(|err| RuntimeErrorKind::UnstructuredError { message: err.message() }.into())
+}
+
+/// Converts a failure to inline a recursive function into an error pointing at one of its
+/// recursive calls.
+fn recursion_limit_error(
+    err: RecursionLimitExceeded,
+    recursive_calls: &[(String, Location)],
+) -> RuntimeError {
+    let location = recursive_calls
+        .iter()
+        .find(|(callee, _)| *callee == err.function_name)
+        .map(|(_, location)| *location);
+    let message = format!(
+        "Could not determine the depth of the recursive calls to `{}` at compile time. Recursion is only supported when the arguments deciding when it ends are known at compile time",
+        err.function_name
+    );
+    RuntimeError::new(RuntimeErrorKind::UnstructuredError { message }, location)
 }
 
 /// Compiles the Program into ACIR and applies optimizations to the arithmetic gates
@@ -53,11 +84,17 @@ pub fn experimental_create_circuit(
     _np_language: Language,
     _is_opcode_supported: &impl Fn(&AcirOpcode) -> bool,
     _enable_logging: bool,
-    _show_output: bool,
-) -> Result<(Circuit, Abi), RuntimeError> {
+    show_output: bool,
+) -> Result<(Circuit, Abi, DebugInfo), RuntimeError> {
     let func_sig = program.main_function_signature.clone();
-    let GeneratedAcir { current_witness_index, opcodes, return_witnesses } =
-        optimize_into_acir(program);
+    let GeneratedAcir {
+        current_witness_index,
+        opcodes,
+        return_witnesses,
+        assertions,
+        locations,
+        ..
+    } = optimize_into_acir(program, show_output)?;
 
     let abi = gen_abi(func_sig, return_witnesses.clone());
     let public_abi = abi.clone().public_abi();
@@ -66,7 +103,7 @@ pub fn experimental_create_circuit(
         PublicInputs(public_abi.param_witnesses.values().flatten().copied().collect());
     let return_values = PublicInputs(return_witnesses.into_iter().collect());
     let circuit = Circuit { current_witness_index, opcodes, public_parameters, return_values };
-    Ok((circuit, abi))
+    Ok((circuit, abi, DebugInfo { locations, assertions }))
 }
 
 impl Ssa {
@@ -2,19 +2,44 @@ use std::collections::BTreeMap;
 
 use acvm::acir::native_types::Witness;
 use iter_extended::{btree_map, vecmap};
-use noirc_abi::{Abi, AbiParameter, FunctionSignature};
+use noirc_abi::{Abi, AbiParameter, AbiType, FunctionSignature};
 
-/// Traverses the parameters to the program to infer the lengths of any arrays that occur.
+/// Traverses the parameters to the program to collect the types of any arrays that occur.
 ///
 /// This is needed for the acir_gen pass, because while the SSA representation of the program
 /// knows the positions at which any arrays occur in the parameters to main, it does not know the
-/// lengths of said arrays.
+/// lengths of said arrays nor the types of their elements.
 ///
-/// This function returns the lengths ordered such as to correspond to the ordering used by the
-/// SSA representation. This allows the lengths to be consumed as array params are encountered in
-/// the SSA.
-pub(crate) fn collate_array_lengths(_abi_params: &[AbiParameter]) -> Vec<usize> {
-    Vec::new()
+/// This function returns the array types ordered such as to correspond to the ordering used by
+/// the SSA representation. This allows the types to be consumed as array params are encountered
+/// in the SSA.
+pub(crate) fn collate_array_types(abi_params: &[AbiParameter]) -> Vec<AbiType> {
+    let mut array_types = Vec::new();
+    for param in abi_params {
+        collect_array_types(&param.typ, &mut array_types);
+    }
+    array_types
+}
+
+/// Appends each array type occurring within `typ` to `array_types`.
+///
+/// Structs and tuples are flattened into a separate SSA parameter per field so we recurse into
+/// their fields, whereas arrays are a single SSA parameter regardless of their element type.
+fn collect_array_types(typ: &AbiType, array_types: &mut Vec<AbiType>) {
+    match typ {
+        AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean => (),
+        AbiType::Array { .. } | AbiType::String { .. } => array_types.push(typ.clone()),
+        AbiType::Struct { fields } => {
+            for (_, field_type) in fields {
+                collect_array_types(field_type, array_types);
+            }
+        }
+        AbiType::Tuple { fields } => {
+            for field_type in fields {
+                collect_array_types(field_type, array_types);
+            }
+        }
+    }
 }
 
 /// Arranges a function signature and a generated circuit's return witnesses into a
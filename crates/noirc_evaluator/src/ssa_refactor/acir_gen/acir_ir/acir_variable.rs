@@ -1,8 +1,15 @@
-use super::generated_acir::GeneratedAcir;
+use super::{errors::AcirGenError, generated_acir::GeneratedAcir};
+use crate::{Assertion, CallStack};
 use acvm::{
-    acir::native_types::{Expression, Witness},
+    acir::{
+        circuit::{directives::LogInfo, opcodes::FunctionInput},
+        native_types::{Expression, Witness},
+        BlackBoxFunc,
+    },
     FieldElement,
 };
+use iter_extended::{try_vecmap, vecmap};
+use num_bigint::BigUint;
 use std::{collections::HashMap, hash::Hash};
 
 #[derive(Debug, Default)]
@@ -73,7 +80,7 @@ impl AcirContext {
 
     /// Adds a new Variable to context whose value will
     /// be constrained to be the inverse of `var`.
-    pub(crate) fn inv_var(&mut self, var: AcirVar) -> AcirVar {
+    pub(crate) fn inv_var(&mut self, var: AcirVar) -> Result<AcirVar, AcirGenError> {
         let var_data = &self.data[&var];
         let inverted_witness = match var_data {
             AcirVarData::Witness(witness) => {
@@ -83,25 +90,37 @@ impl AcirContext {
             AcirVarData::Expr(expr) => self.acir_ir.directive_inverse(expr),
             AcirVarData::Const(constant) => {
                 // Note that this will return a 0 if the inverse is not available
-                return self.add_data(AcirVarData::Const(constant.inverse()));
+                return Ok(self.add_data(AcirVarData::Const(constant.inverse())));
             }
         };
         let inverted_var = self.add_data(AcirVarData::Witness(inverted_witness));
 
         let should_be_one = self.mul_var(inverted_var, var);
-        self.assert_eq_one(should_be_one);
+        self.assert_eq_one(should_be_one)?;
 
-        inverted_var
+        Ok(inverted_var)
     }
 
     /// Constrains the lhs to be equal to the constant value `1`
-    pub(crate) fn assert_eq_one(&mut self, var: AcirVar) {
+    pub(crate) fn assert_eq_one(&mut self, var: AcirVar) -> Result<(), AcirGenError> {
         let one_var = self.add_constant(FieldElement::one());
-        self.assert_eq_var(var, one_var);
+        self.assert_eq_var(var, one_var)
+    }
+
+    /// Sets the call stack the opcodes added from now on are generated from.
+    pub(crate) fn set_call_stack(&mut self, call_stack: CallStack) {
+        self.acir_ir.call_stack = call_stack;
+    }
+
+    /// Records that the last constraint added enforces `assertion`.
+    pub(crate) fn record_assertion(&mut self, assertion: Assertion) {
+        self.acir_ir.record_assertion(assertion);
     }
 
     /// Constrains the `lhs` and `rhs` to be equal.
-    pub(crate) fn assert_eq_var(&mut self, lhs: AcirVar, rhs: AcirVar) {
+    ///
+    /// Returns an error if both are constants which differ, as the program could never be satisfied.
+    pub(crate) fn assert_eq_var(&mut self, lhs: AcirVar, rhs: AcirVar) -> Result<(), AcirGenError> {
         // TODO: could use sub_var and then assert_eq_zero
         let lhs_data = &self.data[&lhs];
         let rhs_data = &self.data[&rhs];
@@ -126,21 +145,23 @@ impl AcirContext {
                 .acir_ir
                 .assert_is_zero(&Expression::from(*lhs_witness) - &Expression::from(*rhs_witness)),
             (AcirVarData::Const(lhs_constant), AcirVarData::Const(rhs_constant)) => {
-                // TODO: for constants, we add it as a gate.
-                // TODO: Assuming users will never want to create unsatisfiable programs
-                // TODO: We could return an error here instead
-                self.acir_ir.assert_is_zero(Expression::from(FieldElement::from(
-                    lhs_constant == rhs_constant,
-                )));
+                // The equality is checked at compile time, so no opcode is needed
+                if lhs_constant != rhs_constant {
+                    return Err(AcirGenError::BadConstantEquality {
+                        lhs: *lhs_constant,
+                        rhs: *rhs_constant,
+                    });
+                }
             }
         };
+        Ok(())
     }
 
     /// Adds a new Variable to context whose value will
     /// be constrained to be the division of `lhs` and `rhs`
-    pub(crate) fn div_var(&mut self, lhs: AcirVar, rhs: AcirVar) -> AcirVar {
-        let inv_rhs = self.inv_var(rhs);
-        self.mul_var(lhs, inv_rhs)
+    pub(crate) fn div_var(&mut self, lhs: AcirVar, rhs: AcirVar) -> Result<AcirVar, AcirGenError> {
+        let inv_rhs = self.inv_var(rhs)?;
+        Ok(self.mul_var(lhs, inv_rhs))
     }
 
     /// Adds a new Variable to context whose value will
@@ -227,19 +248,522 @@ impl AcirContext {
         }
     }
 
+    /// Returns a Variable which is constrained to be `(lhs + rhs) mod 2^{bit_size}`, where both
+    /// operands are integers of `bit_size` bits.
+    pub(crate) fn wrapping_add_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        let sum = self.add_var(lhs, rhs);
+        self.truncate_var(sum, bit_size, bit_size + 1)
+    }
+
+    /// Returns a Variable which is constrained to be `(lhs - rhs) mod 2^{bit_size}`, where both
+    /// operands are integers of `bit_size` bits.
+    pub(crate) fn wrapping_sub_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        // Adding 2^{bit_size} keeps the difference positive, so that it doesn't wrap around the field
+        let modulus = self.add_constant(two_pow(bit_size));
+        let shifted_lhs = self.add_var(lhs, modulus);
+        let difference = self.sub_var(shifted_lhs, rhs);
+        self.truncate_var(difference, bit_size, bit_size + 1)
+    }
+
+    /// Returns a Variable which is constrained to be `(lhs * rhs) mod 2^{bit_size}`, where both
+    /// operands are integers of `bit_size` bits.
+    pub(crate) fn wrapping_mul_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        if 2 * bit_size < FieldElement::max_num_bits() {
+            let product = self.mul_var(lhs, rhs);
+            return self.truncate_var(product, bit_size, 2 * bit_size);
+        }
+
+        // The product could overflow the field, so lhs is split into halves lhs = lo + 2^h * hi.
+        // Then lhs * rhs == lo * rhs + 2^h * (hi * rhs mod 2^{bit_size - h}) mod 2^{bit_size},
+        // where neither product overflows the field.
+        let half = bit_size / 2;
+        let hi_max_bit_size = 2 * bit_size - half;
+        if hi_max_bit_size >= FieldElement::max_num_bits() {
+            return Err(AcirGenError::Unimplemented(format!(
+                "multiplication of {bit_size}-bits integers"
+            )));
+        }
+        let lo = self.truncate_var(lhs, half, bit_size)?;
+        let lhs_minus_lo = self.sub_var(lhs, lo);
+        let inverse_two_pow_half = self.add_constant(two_pow(half).inverse());
+        let hi = self.mul_var(lhs_minus_lo, inverse_two_pow_half);
+
+        let hi_product = self.mul_var(hi, rhs);
+        let hi_product = self.truncate_var(hi_product, bit_size - half, hi_max_bit_size)?;
+        let two_pow_half = self.add_constant(two_pow(half));
+        let hi_product = self.mul_var(hi_product, two_pow_half);
+        let lo_product = self.mul_var(lo, rhs);
+        // lo * rhs < 2^{h + bit_size} and the shifted hi product is below 2^{bit_size}
+        let sum = self.add_var(lo_product, hi_product);
+        self.truncate_var(sum, bit_size, bit_size + half + 1)
+    }
+
     /// Converts the `AcirVar` to a `Witness` if it hasn't been already, and appends it to the
     /// `GeneratedAcir`'s return witnesses.
     pub(crate) fn return_var(&mut self, acir_var: AcirVar) {
-        let acir_var_data = self.data.get(&acir_var).expect("ICE: return of undeclared AcirVar");
         // TODO: Add caching to prevent expressions from being needlessly duplicated
-        let witness = match acir_var_data {
-            AcirVarData::Const(constant) => {
-                self.acir_ir.expression_to_witness(&Expression::from(*constant))
+        let witness = self.var_to_witness(acir_var);
+        self.acir_ir.push_return_witness(witness);
+    }
+
+    /// Returns a Variable which is constrained to be `1` if `lhs == rhs` and `0` otherwise.
+    pub(crate) fn eq_var(&mut self, lhs: AcirVar, rhs: AcirVar) -> AcirVar {
+        if lhs == rhs {
+            return self.add_constant(FieldElement::one());
+        }
+        if let (Some(lhs_const), Some(rhs_const)) = (self.constant(lhs), self.constant(rhs)) {
+            return self.add_constant(FieldElement::from(lhs_const == rhs_const));
+        }
+
+        let lhs_expr = self.var_to_expression(lhs);
+        let rhs_expr = self.var_to_expression(rhs);
+        let is_equal = self.acir_ir.is_equal(&lhs_expr, &rhs_expr);
+        self.add_data(AcirVarData::Witness(is_equal))
+    }
+
+    /// Returns a Variable which is constrained to be `1` if `lhs < rhs` and `0` otherwise,
+    /// where both operands are integers of `bit_size` bits.
+    ///
+    /// Signed integers are expected to be in two's complement form.
+    pub(crate) fn less_than_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+        signed: bool,
+    ) -> Result<AcirVar, AcirGenError> {
+        let (lhs, rhs) = if signed {
+            // Flipping the sign bit of both operands maps the signed ordering onto the
+            // unsigned ordering, i.e. -2^{bit_size - 1} becomes 0 and 2^{bit_size - 1} - 1
+            // becomes 2^{bit_size} - 1.
+            let offset = self.add_constant(two_pow(bit_size - 1));
+            let lhs_offset = self.add_var(lhs, offset);
+            let rhs_offset = self.add_var(rhs, offset);
+            (
+                self.truncate_var(lhs_offset, bit_size, bit_size + 1)?,
+                self.truncate_var(rhs_offset, bit_size, bit_size + 1)?,
+            )
+        } else {
+            (lhs, rhs)
+        };
+
+        let lhs_expr = self.var_to_expression(lhs);
+        let rhs_expr = self.var_to_expression(rhs);
+        let more_than_eq = self.acir_ir.more_than_eq_comparison(&lhs_expr, &rhs_expr, bit_size)?;
+        let more_than_eq = self.add_data(AcirVarData::Witness(more_than_eq));
+
+        let one = self.add_constant(FieldElement::one());
+        Ok(self.sub_var(one, more_than_eq))
+    }
+
+    /// Returns the quotient and remainder of the euclidean division of `lhs` by `rhs`,
+    /// where both operands are unsigned integers of `bit_size` bits.
+    pub(crate) fn euclidean_division_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+    ) -> Result<(AcirVar, AcirVar), AcirGenError> {
+        if let Some(rhs_const) = self.constant(rhs) {
+            if rhs_const.is_zero() {
+                return Err(AcirGenError::DivisionByZero);
+            }
+            if let Some(lhs_const) = self.constant(lhs) {
+                if let (Some(lhs_const), Some(rhs_const)) =
+                    (lhs_const.try_into_u128(), rhs_const.try_into_u128())
+                {
+                    let quotient = self.add_constant(FieldElement::from(lhs_const / rhs_const));
+                    let remainder = self.add_constant(FieldElement::from(lhs_const % rhs_const));
+                    return Ok((quotient, remainder));
+                }
+            }
+        }
+
+        let lhs_expr = self.var_to_expression(lhs);
+        let rhs_expr = self.var_to_expression(rhs);
+        let (quotient, remainder) =
+            self.acir_ir.euclidean_division(&lhs_expr, &rhs_expr, bit_size)?;
+
+        Ok((
+            self.add_data(AcirVarData::Witness(quotient)),
+            self.add_data(AcirVarData::Witness(remainder)),
+        ))
+    }
+
+    /// Returns the quotient, rounded towards zero, and the remainder, which has the sign of `lhs`,
+    /// of the division of `lhs` by `rhs`, where both operands are signed integers of `bit_size`
+    /// bits in two's complement form.
+    pub(crate) fn signed_division_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+    ) -> Result<(AcirVar, AcirVar), AcirGenError> {
+        let lhs_sign = self.sign_var(lhs, bit_size)?;
+        let rhs_sign = self.sign_var(rhs, bit_size)?;
+        // The absolute value of -2^{bit_size - 1} is 2^{bit_size - 1}, which still fits into
+        // `bit_size` bits when interpreted as an unsigned integer
+        let lhs_abs = self.conditional_negate_var(lhs, lhs_sign, bit_size)?;
+        let rhs_abs = self.conditional_negate_var(rhs, rhs_sign, bit_size)?;
+        let (quotient, remainder) = self.euclidean_division_var(lhs_abs, rhs_abs, bit_size)?;
+
+        // The quotient is negative if the signs of the operands differ
+        let quotient_sign = self.xor_var(lhs_sign, rhs_sign, 1)?;
+        let quotient = self.conditional_negate_var(quotient, quotient_sign, bit_size)?;
+        let remainder = self.conditional_negate_var(remainder, lhs_sign, bit_size)?;
+        Ok((quotient, remainder))
+    }
+
+    /// Returns a Variable which is constrained to be `1` if `var` is negative and `0` otherwise,
+    /// where `var` is a signed integer of `bit_size` bits in two's complement form.
+    fn sign_var(&mut self, var: AcirVar, bit_size: u32) -> Result<AcirVar, AcirGenError> {
+        // The sign is the most significant bit
+        let low_bits = self.truncate_var(var, bit_size - 1, bit_size)?;
+        let high_bit = self.sub_var(var, low_bits);
+        let inverse_two_pow = self.add_constant(two_pow(bit_size - 1).inverse());
+        Ok(self.mul_var(high_bit, inverse_two_pow))
+    }
+
+    /// Returns a Variable which is constrained to be `-var mod 2^{bit_size}` if `negate` is `1`
+    /// and `var` if it is `0`.
+    fn conditional_negate_var(
+        &mut self,
+        var: AcirVar,
+        negate: AcirVar,
+        bit_size: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        // var + negate * (2^{bit_size} - 2 * var), which is 2^{bit_size} when negating 0
+        let modulus = self.add_constant(two_pow(bit_size));
+        let two = self.add_constant(FieldElement::from(2_u128));
+        let double_var = self.mul_var(var, two);
+        let negated = self.sub_var(modulus, double_var);
+        let correction = self.mul_var(negate, negated);
+        let result = self.add_var(var, correction);
+        self.truncate_var(result, bit_size, bit_size + 1)
+    }
+
+    /// Returns a Variable which is constrained to be `2^{bit_size} - 1 - var` if `flip` is `1`
+    /// and `var` if it is `0`, i.e. the bitwise not of `var` when `flip` is set.
+    fn conditional_not_var(&mut self, var: AcirVar, flip: AcirVar, bit_size: u32) -> AcirVar {
+        // var + flip * (2^{bit_size} - 1 - 2 * var)
+        let max = self.add_constant(two_pow(bit_size) - FieldElement::one());
+        let two = self.add_constant(FieldElement::from(2_u128));
+        let double_var = self.mul_var(var, two);
+        let flipped = self.sub_var(max, double_var);
+        let correction = self.mul_var(flip, flipped);
+        self.add_var(var, correction)
+    }
+
+    /// Returns a Variable which is constrained to be the bitwise not of `var`,
+    /// where `var` is an integer of `bit_size` bits.
+    pub(crate) fn not_var(&mut self, var: AcirVar, bit_size: u32) -> AcirVar {
+        // Since `var` fits into `bit_size` bits, flipping all of its bits is the same as
+        // subtracting it from 2^{bit_size} - 1
+        let max = self.add_constant(two_pow(bit_size) - FieldElement::one());
+        self.sub_var(max, var)
+    }
+
+    /// Returns a Variable which is constrained to be the bitwise and of `lhs` and `rhs`,
+    /// where both operands are integers of `bit_size` bits.
+    pub(crate) fn and_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        if bit_size == 1 {
+            return Ok(self.mul_var(lhs, rhs));
+        }
+        self.bitwise_black_box(BlackBoxFunc::AND, lhs, rhs, bit_size)
+    }
+
+    /// Returns a Variable which is constrained to be the bitwise xor of `lhs` and `rhs`,
+    /// where both operands are integers of `bit_size` bits.
+    pub(crate) fn xor_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        if bit_size == 1 {
+            // lhs + rhs - 2 * lhs * rhs
+            let sum = self.add_var(lhs, rhs);
+            let product = self.mul_var(lhs, rhs);
+            let two = self.add_constant(FieldElement::from(2_u128));
+            let double_product = self.mul_var(product, two);
+            return Ok(self.sub_var(sum, double_product));
+        }
+        self.bitwise_black_box(BlackBoxFunc::XOR, lhs, rhs, bit_size)
+    }
+
+    /// Returns a Variable which is constrained to be the bitwise or of `lhs` and `rhs`,
+    /// where both operands are integers of `bit_size` bits.
+    pub(crate) fn or_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        if bit_size == 1 {
+            // lhs + rhs - lhs * rhs
+            let sum = self.add_var(lhs, rhs);
+            let product = self.mul_var(lhs, rhs);
+            return Ok(self.sub_var(sum, product));
+        }
+        // There is no black box function for or, so we use De Morgan's law:
+        // a | b == !(!a & !b)
+        let bit_size = even_bit_size(bit_size);
+        let not_lhs = self.not_var(lhs, bit_size);
+        let not_rhs = self.not_var(rhs, bit_size);
+        let and = self.bitwise_black_box(BlackBoxFunc::AND, not_lhs, not_rhs, bit_size)?;
+        Ok(self.not_var(and, bit_size))
+    }
+
+    /// Calls the `AND` or `XOR` black box function on `lhs` and `rhs`.
+    fn bitwise_black_box(
+        &mut self,
+        func: BlackBoxFunc,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        // Backends only support bitwise operations over an even number of bits
+        let num_bits = even_bit_size(bit_size);
+        let inputs = vec![vec![(lhs, num_bits)], vec![(rhs, num_bits)]];
+        let outputs = self.black_box_function(func, inputs)?;
+        Ok(outputs[0])
+    }
+
+    /// Returns a Variable which is constrained to be `(lhs << rhs) mod 2^{bit_size}`, where `lhs`
+    /// is an integer of `bit_size` bits and `rhs` is an unsigned integer of `rhs_bit_size` bits.
+    ///
+    /// Shifting by `bit_size` or more bits gives `0`.
+    pub(crate) fn shift_left_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+        rhs_bit_size: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        let (two_pow_rhs, _) = self.two_pow_in_range_var(rhs, rhs_bit_size, bit_size)?;
+        self.wrapping_mul_var(lhs, two_pow_rhs, bit_size)
+    }
+
+    /// Returns a Variable which is constrained to be `lhs >> rhs`, where `lhs` is an integer
+    /// of `bit_size` bits and `rhs` is an unsigned integer of `rhs_bit_size` bits.
+    ///
+    /// Right shifts of signed integers are arithmetic shifts, which fill the vacated bits with
+    /// the sign bit of `lhs`.
+    pub(crate) fn shift_right_var(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        bit_size: u32,
+        rhs_bit_size: u32,
+        signed: bool,
+    ) -> Result<AcirVar, AcirGenError> {
+        let (two_pow_rhs, in_range) = self.two_pow_in_range_var(rhs, rhs_bit_size, bit_size)?;
+        // Dividing by 2^{bit_size} when shifting by `bit_size` or more bits gives 0
+        let one = self.add_constant(FieldElement::one());
+        let out_of_range = self.sub_var(one, in_range);
+        let modulus = self.add_constant(two_pow(bit_size));
+        let out_of_range_divisor = self.mul_var(out_of_range, modulus);
+        let divisor = self.add_var(two_pow_rhs, out_of_range_divisor);
+
+        if !signed {
+            let (quotient, _) = self.euclidean_division_var(lhs, divisor, bit_size + 1)?;
+            return Ok(quotient);
+        }
+
+        // For a negative lhs, lhs >> rhs == !((!lhs) >> rhs)
+        let sign = self.sign_var(lhs, bit_size)?;
+        let lhs_not = self.conditional_not_var(lhs, sign, bit_size);
+        let (quotient, _) = self.euclidean_division_var(lhs_not, divisor, bit_size + 1)?;
+        Ok(self.conditional_not_var(quotient, sign, bit_size))
+    }
+
+    /// Returns a Variable which is constrained to be `2^exponent` if `exponent < max_exponent`
+    /// and `0` otherwise, along with a Variable which is constrained to be `1` if
+    /// `exponent < max_exponent` and `0` otherwise.
+    ///
+    /// `exponent` is an unsigned integer of `bit_size` bits.
+    fn two_pow_in_range_var(
+        &mut self,
+        exponent: AcirVar,
+        bit_size: u32,
+        max_exponent: u32,
+    ) -> Result<(AcirVar, AcirVar), AcirGenError> {
+        if let Some(exponent) = self.constant(exponent) {
+            let in_range = exponent.try_into_u128().map_or(false, |e| e < max_exponent as u128);
+            let power = if in_range {
+                FieldElement::from(2_u128).pow(&exponent)
+            } else {
+                FieldElement::zero()
+            };
+            let in_range = self.add_constant(FieldElement::from(in_range));
+            return Ok((self.add_constant(power), in_range));
+        }
+
+        // 2^exponent == product of 2^{2^i} over each set bit `i` of the exponent.
+        // Bits with 2^i >= max_exponent can only be set when the exponent is out of range.
+        let bits = self.radix_decompose_var(exponent, 2, bit_size)?;
+        let mut result = self.add_constant(FieldElement::one());
+        let one = self.add_constant(FieldElement::one());
+        for (i, bit) in bits.into_iter().enumerate() {
+            if i >= 32 || 1_u32 << i >= max_exponent {
+                break;
+            }
+            // factor = 1 + bit * (2^{2^i} - 1)
+            let power = two_pow(1_u32 << i);
+            let power_minus_one = self.add_constant(power - FieldElement::one());
+            let scaled_bit = self.mul_var(bit, power_minus_one);
+            let factor = self.add_var(one, scaled_bit);
+            result = self.mul_var(result, factor);
+        }
+
+        if bit_size < 32 && 1_u32 << bit_size <= max_exponent {
+            // The exponent is always in range
+            return Ok((result, one));
+        }
+        let max_exponent_bits = u32::BITS - max_exponent.leading_zeros();
+        let max_exponent = self.add_constant(FieldElement::from(max_exponent as u128));
+        let in_range = self.less_than_var(
+            exponent,
+            max_exponent,
+            u32::max(bit_size, max_exponent_bits),
+            false,
+        )?;
+        Ok((self.mul_var(result, in_range), in_range))
+    }
+
+    /// Returns a Variable which is constrained to be `var mod 2^{bit_size}`, where `var` is known
+    /// to be at most `max_bit_size` bits.
+    pub(crate) fn truncate_var(
+        &mut self,
+        var: AcirVar,
+        bit_size: u32,
+        max_bit_size: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        if max_bit_size <= bit_size {
+            return Ok(var);
+        }
+        if let Some(constant) = self.constant(var) {
+            let value = BigUint::from_bytes_be(&constant.to_be_bytes())
+                % (BigUint::from(1_u32) << bit_size);
+            return Ok(self.add_constant(FieldElement::from_be_bytes_reduce(&value.to_bytes_be())));
+        }
+
+        let expr = self.var_to_expression(var);
+        let truncated = self.acir_ir.truncate(&expr, bit_size, max_bit_size)?;
+        Ok(self.add_data(AcirVarData::Witness(truncated)))
+    }
+
+    /// Constrains `var` to be an integer of `bit_size` bits.
+    pub(crate) fn range_constrain_var(
+        &mut self,
+        var: AcirVar,
+        bit_size: u32,
+    ) -> Result<(), AcirGenError> {
+        let witness = self.var_to_witness(var);
+        self.acir_ir.range_constraint(witness, bit_size)
+    }
+
+    /// Decomposes `var` into `limb_count` limbs of the given `radix`, returning Variables
+    /// for the limbs in little endian order.
+    pub(crate) fn radix_decompose_var(
+        &mut self,
+        var: AcirVar,
+        radix: u32,
+        limb_count: u32,
+    ) -> Result<Vec<AcirVar>, AcirGenError> {
+        let expr = self.var_to_expression(var);
+        let limbs = self.acir_ir.radix_le_decompose(&expr, radix, limb_count)?;
+        Ok(vecmap(limbs, |limb| self.add_data(AcirVarData::Witness(limb))))
+    }
+
+    /// Returns Variables which are constrained to be `inputs` sorted in increasing order,
+    /// where each input is an integer of `bit_size` bits.
+    pub(crate) fn sort_vars(
+        &mut self,
+        inputs: &[AcirVar],
+        bit_size: u32,
+    ) -> Result<Vec<AcirVar>, AcirGenError> {
+        let input_exprs = vecmap(inputs, |input| self.var_to_expression(*input));
+        let outputs = self.acir_ir.sort(&input_exprs, bit_size)?;
+        Ok(vecmap(outputs, |output| self.add_data(AcirVarData::Witness(output))))
+    }
+
+    /// Calls the black box function `func` on `inputs`, returning a Variable for each of
+    /// its outputs.
+    ///
+    /// There is one entry in `inputs` per argument to the function, holding each of the
+    /// Variables which make up the argument along with their bit sizes.
+    pub(crate) fn black_box_function(
+        &mut self,
+        func: BlackBoxFunc,
+        inputs: Vec<Vec<(AcirVar, u32)>>,
+    ) -> Result<Vec<AcirVar>, AcirGenError> {
+        let inputs = vecmap(inputs, |argument| {
+            vecmap(argument, |(var, num_bits)| FunctionInput {
+                witness: self.var_to_witness(var),
+                num_bits,
+            })
+        });
+        let outputs = self.acir_ir.call_black_box(func, inputs)?;
+        Ok(vecmap(outputs, |output| self.add_data(AcirVarData::Witness(output))))
+    }
+
+    /// Prints `vars` when the program is executed.
+    ///
+    /// If every Variable is a constant, the output is formatted at compile time.
+    pub(crate) fn print(&mut self, vars: &[AcirVar]) {
+        let constants = try_vecmap(vars, |var| self.constant(*var).ok_or(()));
+        let output = match constants {
+            Ok(constants) if constants.len() == 1 => {
+                LogInfo::FinalizedOutput(format_field_string(constants[0]))
+            }
+            Ok(constants) => {
+                let fields = vecmap(constants, format_field_string);
+                LogInfo::FinalizedOutput(format!("[{}]", fields.join(", ")))
             }
-            AcirVarData::Expr(expr) => self.acir_ir.expression_to_witness(expr),
-            AcirVarData::Witness(witness) => *witness,
+            Err(()) => LogInfo::WitnessOutput(vecmap(vars, |var| self.var_to_witness(*var))),
         };
-        self.acir_ir.push_return_witness(witness);
+        self.acir_ir.directive_log(output);
+    }
+
+    /// Returns the constant value of `var`, if it is known at compile time.
+    pub(crate) fn constant(&self, var: AcirVar) -> Option<FieldElement> {
+        self.data[&var].as_constant()
+    }
+
+    /// Returns the `Expression` which `var` represents.
+    fn var_to_expression(&self, var: AcirVar) -> Expression {
+        match &self.data[&var] {
+            AcirVarData::Witness(witness) => Expression::from(*witness),
+            AcirVarData::Expr(expr) => expr.clone(),
+            AcirVarData::Const(constant) => Expression::from(*constant),
+        }
+    }
+
+    /// Returns a `Witness` which is constrained to be equal to `var`.
+    fn var_to_witness(&mut self, var: AcirVar) -> Witness {
+        let expr = self.var_to_expression(var);
+        self.acir_ir.get_or_create_witness(&expr)
     }
 
     /// Terminates the context and takes the resulting `GeneratedAcir`
@@ -305,3 +829,23 @@ impl AcirVarData {
 /// A Reference to an `AcirVarData`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct AcirVar(usize);
+
+/// Returns `2^exponent` as a `FieldElement`.
+fn two_pow(exponent: u32) -> FieldElement {
+    FieldElement::from(2_u128).pow(&FieldElement::from(exponent as u128))
+}
+
+/// Rounds `bit_size` up to the nearest even number of bits.
+fn even_bit_size(bit_size: u32) -> u32 {
+    bit_size + bit_size % 2
+}
+
+/// Formats `field` as a hex string, trimming any leading zeroes.
+/// A singular '0' will be prepended as well if the trimmed string has an odd length.
+fn format_field_string(field: FieldElement) -> String {
+    let mut trimmed_field = field.to_hex().trim_start_matches('0').to_owned();
+    if trimmed_field.len() % 2 != 0 {
+        trimmed_field = "0".to_owned() + &trimmed_field;
+    };
+    "0x".to_owned() + &trimmed_field
+}
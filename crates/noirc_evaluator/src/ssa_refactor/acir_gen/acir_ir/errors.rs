@@ -1,7 +1,12 @@
+use acvm::FieldElement;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub(crate) enum AcirGenError {
     InvalidRangeConstraint { num_bits: u32 },
     IndexOutOfBounds { index: usize, array_size: usize },
+    DivisionByZero,
+    BadConstantEquality { lhs: FieldElement, rhs: FieldElement },
+    Unimplemented(String),
 }
 
 impl AcirGenError {
@@ -15,6 +20,11 @@ impl AcirGenError {
             AcirGenError::IndexOutOfBounds { index, array_size } => {
                 format!("Index out of bounds, array has size {array_size}, but index was {index}")
             }
+            AcirGenError::DivisionByZero => "Attempted to divide by the constant zero".to_owned(),
+            AcirGenError::BadConstantEquality { lhs, rhs } => {
+                format!("Assertion is always false: {lhs} can never be equal to {rhs}")
+            }
+            AcirGenError::Unimplemented(message) => format!("Unimplemented: {message}"),
         }
     }
 }
@@ -1,14 +1,21 @@
 //! `GeneratedAcir` is constructed as part of the `acir_gen` pass to accumulate all of the ACIR
 //! program as it is being converted from SSA form.
 use super::errors::AcirGenError;
+use crate::{Assertion, AssertionTable, CallStack, OpcodeLocations};
 use acvm::acir::{
-    circuit::opcodes::{BlackBoxFuncCall, FunctionInput, Opcode as AcirOpcode},
+    circuit::{
+        directives::{LogInfo, QuotientDirective},
+        opcodes::{BlackBoxFuncCall, FunctionInput, Opcode as AcirOpcode},
+    },
     native_types::Witness,
+    BlackBoxFunc,
 };
 use acvm::{
     acir::{circuit::directives::Directive, native_types::Expression},
     FieldElement,
 };
+use iter_extended::vecmap;
+use num_bigint::BigUint;
 
 #[derive(Debug, Default)]
 /// The output of the Acir-gen pass
@@ -26,6 +33,15 @@ pub(crate) struct GeneratedAcir {
     /// Note: This may contain repeated indices, which is necessary for later mapping into the
     /// abi's return type.
     pub(crate) return_witnesses: Vec<Witness>,
+
+    /// The `assert` statements of the program, indexed by the opcode enforcing each of them
+    pub(crate) assertions: AssertionTable,
+
+    /// The call stack each opcode is generated from, indexed by opcode
+    pub(crate) locations: OpcodeLocations,
+
+    /// The call stack of the instruction currently being converted into opcodes
+    pub(crate) call_stack: CallStack,
 }
 
 impl GeneratedAcir {
@@ -37,6 +53,15 @@ impl GeneratedAcir {
     /// Adds a new opcode into ACIR.
     fn push_opcode(&mut self, opcode: AcirOpcode) {
         self.opcodes.push(opcode);
+        if !self.call_stack.is_empty() {
+            self.locations.insert(self.opcodes.len() - 1, self.call_stack.clone());
+        }
+    }
+
+    /// Records that the last opcode added enforces `assertion`.
+    pub(crate) fn record_assertion(&mut self, assertion: Assertion) {
+        let opcode_index = self.opcodes.len() - 1;
+        self.assertions.insert(opcode_index, assertion);
     }
 
     /// Updates the witness index counter and returns
@@ -74,6 +99,15 @@ impl GeneratedAcir {
         fresh_witness
     }
 
+    /// Returns the `Witness` which `expression` consists of, if `expression` is a single witness.
+    /// Otherwise the expression is converted into a new `Witness`.
+    pub(crate) fn get_or_create_witness(&mut self, expression: &Expression) -> Witness {
+        match expression.to_witness() {
+            Some(witness) => witness,
+            None => self.expression_to_witness(expression),
+        }
+    }
+
     /// Adds a witness index to the program's return witnesses.
     pub(crate) fn push_return_witness(&mut self, witness: Witness) {
         self.return_witnesses.push(witness);
@@ -94,10 +128,7 @@ impl GeneratedAcir {
         // the inputs be Witness, so we need this potential extra
         // reduction constraint.
         // Note: changing this in ACIR would allow us to remove it
-        let witness = match expr.to_witness() {
-            Some(witness) => witness,
-            None => self.expression_to_witness(expr),
-        };
+        let witness = self.get_or_create_witness(expr);
 
         // Create the witness for the result
         let inverted_witness = self.next_witness_index();
@@ -110,6 +141,43 @@ impl GeneratedAcir {
         inverted_witness
     }
 
+    /// Adds a quotient directive.
+    ///
+    /// This directive will compute the quotient and remainder of the euclidean
+    /// division of `lhs` by `rhs` without applying constraints and return the
+    /// `Witness`es which may or may not hold the quotient and remainder, in that order.
+    ///
+    /// Safety: It is the callers responsibility to ensure that the
+    /// resulting `Witness`es are constrained to be the quotient and remainder.
+    fn directive_quotient(&mut self, lhs: Expression, rhs: Expression) -> (Witness, Witness) {
+        let quotient = self.next_witness_index();
+        let remainder = self.next_witness_index();
+
+        self.push_opcode(AcirOpcode::Directive(Directive::Quotient(QuotientDirective {
+            a: lhs,
+            b: rhs,
+            q: quotient,
+            r: remainder,
+            predicate: None,
+        })));
+
+        (quotient, remainder)
+    }
+
+    /// Adds a directive which decomposes `expr` into `limbs.len()` limbs of the given `radix`,
+    /// the least significant limb being written to the first witness of `limbs`.
+    ///
+    /// Safety: It is the callers responsibility to ensure that the
+    /// resulting `Witness`es are constrained to be the decomposition of `expr`.
+    fn directive_to_le_radix(&mut self, expr: Expression, radix: u32, limbs: Vec<Witness>) {
+        self.push_opcode(AcirOpcode::Directive(Directive::ToLeRadix { a: expr, b: limbs, radix }));
+    }
+
+    /// Adds a log directive, which will print the given output when the program is executed.
+    pub(crate) fn directive_log(&mut self, output: LogInfo) {
+        self.push_opcode(AcirOpcode::Directive(Directive::Log(output)));
+    }
+
     /// Asserts `expr` to be zero.
     ///
     /// If `expr` is not zero, then the constraint system will
@@ -118,6 +186,57 @@ impl GeneratedAcir {
         self.push_opcode(AcirOpcode::Arithmetic(expr));
     }
 
+    /// Returns an expression equal to `lhs * rhs`.
+    ///
+    /// Operands which are not constant are first converted into witnesses so that the
+    /// resulting expression is at most of degree 2.
+    pub(crate) fn mul_with_witness(&mut self, lhs: &Expression, rhs: &Expression) -> Expression {
+        if let Some(lhs_constant) = lhs.to_const() {
+            return rhs * lhs_constant;
+        }
+        if let Some(rhs_constant) = rhs.to_const() {
+            return lhs * rhs_constant;
+        }
+
+        let lhs_witness = self.get_or_create_witness(lhs);
+        let rhs_witness = self.get_or_create_witness(rhs);
+
+        let mut product = Expression::default();
+        product.push_multiplication_term(FieldElement::one(), lhs_witness, rhs_witness);
+        product
+    }
+
+    /// Returns a `Witness` which is constrained to be `1` if `lhs == rhs` and `0` otherwise.
+    pub(crate) fn is_equal(&mut self, lhs: &Expression, rhs: &Expression) -> Witness {
+        let difference = self.get_or_create_witness(&(lhs - rhs));
+
+        // `inverse` is the inverse of `difference`, or zero if `difference` is zero.
+        let inverse = self.next_witness_index();
+        self.push_opcode(AcirOpcode::Directive(Directive::Invert {
+            x: difference,
+            result: inverse,
+        }));
+
+        // is_equal = 1 - difference * inverse
+        //
+        // If `difference` is zero then `is_equal` is forced to be `1`.
+        let is_equal = self.next_witness_index();
+        let mut constraint = Expression::default();
+        constraint.push_multiplication_term(FieldElement::one(), difference, inverse);
+        constraint.push_addition_term(FieldElement::one(), is_equal);
+        constraint.q_c = -FieldElement::one();
+        self.assert_is_zero(constraint);
+
+        // difference * is_equal = 0
+        //
+        // If `difference` is not zero then `is_equal` is forced to be `0`.
+        let mut constraint = Expression::default();
+        constraint.push_multiplication_term(FieldElement::one(), difference, is_equal);
+        self.assert_is_zero(constraint);
+
+        is_equal
+    }
+
     /// Adds a constraint which ensure thats `witness` is an
     /// integer within the range [0, 2^{num_bits} - 1]
     pub(crate) fn range_constraint(
@@ -131,11 +250,369 @@ impl GeneratedAcir {
             });
         };
 
-        let constraint = AcirOpcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
-            input: FunctionInput { witness, num_bits },
-        });
-        self.push_opcode(constraint);
+        if num_bits == 1 {
+            // Add a bool gate: witness * witness - witness == 0
+            let mut bool_constraint = Expression::default();
+            bool_constraint.push_multiplication_term(FieldElement::one(), witness, witness);
+            bool_constraint.push_addition_term(-FieldElement::one(), witness);
+            self.assert_is_zero(bool_constraint);
+        } else if num_bits % 2 == 1 {
+            // Backends do not support range constraints over an odd number of bits so we split
+            // the witness into its most significant bit and the remaining even number of bits.
+            let exp = two_pow(num_bits - 1);
+            let (msb, remainder) = self.directive_quotient(witness.into(), Expression::from(exp));
+
+            self.range_constraint(remainder, num_bits - 1)?;
+            self.range_constraint(msb, 1)?;
+
+            // witness == remainder + 2^{num_bits - 1} * msb
+            let recomposed = &Expression::from(remainder) + &(&Expression::from(msb) * exp);
+            self.assert_is_zero(&recomposed - &Expression::from(witness));
+        } else {
+            let constraint = AcirOpcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                input: FunctionInput { witness, num_bits },
+            });
+            self.push_opcode(constraint);
+        }
 
         Ok(())
     }
+
+    /// Adds the constraints for the euclidean division of `lhs` by `rhs`, where both
+    /// operands are integers of at most `max_bit_size` bits.
+    ///
+    /// Returns the quotient and remainder of the division, in that order.
+    pub(crate) fn euclidean_division(
+        &mut self,
+        lhs: &Expression,
+        rhs: &Expression,
+        max_bit_size: u32,
+    ) -> Result<(Witness, Witness), AcirGenError> {
+        let (quotient, remainder) = self.directive_quotient(lhs.clone(), rhs.clone());
+
+        // remainder < rhs, which also ensures that rhs is not zero.
+        self.range_constraint(remainder, max_bit_size)?;
+        let remainder_plus_one = &Expression::from(remainder) + &Expression::one();
+        self.bound_constraint(&remainder_plus_one, rhs, max_bit_size)?;
+
+        // quotient <= lhs < 2^{max_bit_size}
+        self.range_constraint(quotient, max_bit_size)?;
+
+        // lhs == rhs * quotient + remainder
+        let rhs_times_quotient = self.mul_with_witness(rhs, &quotient.into());
+        let recomposed = &rhs_times_quotient + &Expression::from(remainder);
+        self.assert_is_zero(&recomposed - lhs);
+
+        Ok((quotient, remainder))
+    }
+
+    /// Adds the constraints which ensure that `lhs <= rhs`, where `rhs - lhs` is known to
+    /// be at most `max_bit_size` bits whenever the assertion holds.
+    ///
+    /// If `lhs > rhs` then `rhs - lhs` wraps around the field modulus and cannot fit into
+    /// `max_bit_size` bits, causing the range constraint to fail.
+    fn bound_constraint(
+        &mut self,
+        lhs: &Expression,
+        rhs: &Expression,
+        max_bit_size: u32,
+    ) -> Result<(), AcirGenError> {
+        let difference = self.get_or_create_witness(&(rhs - lhs));
+        self.range_constraint(difference, max_bit_size)
+    }
+
+    /// Returns a `Witness` which is constrained to be `1` if `lhs >= rhs` and `0` otherwise,
+    /// where both operands are integers of at most `max_bit_size` bits.
+    pub(crate) fn more_than_eq_comparison(
+        &mut self,
+        lhs: &Expression,
+        rhs: &Expression,
+        max_bit_size: u32,
+    ) -> Result<Witness, AcirGenError> {
+        // We need 2^{max_bit_size + 1} < p for the comparison to be sound.
+        if max_bit_size + 1 >= FieldElement::max_num_bits() {
+            return Err(AcirGenError::Unimplemented(format!(
+                "comparison of integers of {max_bit_size} bits"
+            )));
+        }
+
+        // 2^{max_bit_size} + lhs - rhs = quotient * 2^{max_bit_size} + remainder
+        //
+        // As both operands are less than 2^{max_bit_size}, the left hand side lies in the range
+        // [0, 2^{max_bit_size + 1}) and so the quotient is a single bit which is set if and only
+        // if lhs >= rhs.
+        let exp = two_pow(max_bit_size);
+        let comparison = &(lhs - rhs) + &Expression::from(exp);
+
+        let (quotient, remainder) =
+            self.directive_quotient(comparison.clone(), Expression::from(exp));
+
+        let recomposed = &(&Expression::from(quotient) * exp) + &Expression::from(remainder);
+        self.assert_is_zero(&comparison - &recomposed);
+
+        self.range_constraint(remainder, max_bit_size)?;
+        self.range_constraint(quotient, 1)?;
+
+        Ok(quotient)
+    }
+
+    /// Returns a `Witness` constrained to be `lhs mod 2^{bit_size}`, where `lhs` is known
+    /// to be at most `max_bit_size` bits.
+    pub(crate) fn truncate(
+        &mut self,
+        lhs: &Expression,
+        bit_size: u32,
+        max_bit_size: u32,
+    ) -> Result<Witness, AcirGenError> {
+        assert!(max_bit_size > bit_size, "max_bit_size = {max_bit_size}, bit_size = {bit_size}");
+
+        let exp = two_pow(bit_size);
+        let (quotient, remainder) = self.directive_quotient(lhs.clone(), Expression::from(exp));
+
+        self.range_constraint(remainder, bit_size)?;
+        self.range_constraint(quotient, max_bit_size - bit_size)?;
+
+        // lhs == remainder + 2^{bit_size} * quotient
+        let recomposed = &Expression::from(remainder) + &(&Expression::from(quotient) * exp);
+        self.assert_is_zero(&recomposed - lhs);
+
+        Ok(remainder)
+    }
+
+    /// Decomposes `input` into `limb_count` limbs of the given `radix`, returning the limbs
+    /// in little endian order.
+    pub(crate) fn radix_le_decompose(
+        &mut self,
+        input: &Expression,
+        radix: u32,
+        limb_count: u32,
+    ) -> Result<Vec<Witness>, AcirGenError> {
+        let radix_big = BigUint::from(radix);
+        let max_value = radix_big.pow(limb_count) - BigUint::from(1u32);
+
+        if max_value < FieldElement::modulus() {
+            let limbs = vecmap(0..limb_count, |_| self.next_witness_index());
+            self.directive_to_le_radix(input.clone(), radix, limbs.clone());
+
+            let mut recomposed = Expression::default();
+            let mut radix_pow = FieldElement::one();
+            for limb in &limbs {
+                self.constrain_limb(*limb, radix)?;
+                recomposed = &recomposed + &(&Expression::from(*limb) * radix_pow);
+                radix_pow = radix_pow * FieldElement::from(radix as u128);
+            }
+            self.assert_is_zero(input - &recomposed);
+
+            Ok(limbs)
+        } else {
+            // The decomposition into `limb_count` limbs would not be unique, as values may
+            // overflow the field modulus. We instead extract the least significant limb and then
+            // decompose the remaining value into `limb_count - 1` limbs.
+            let radix_field = FieldElement::from(radix as u128);
+            let (quotient, remainder) =
+                self.directive_quotient(input.clone(), Expression::from(radix_field));
+
+            self.constrain_limb(remainder, radix)?;
+            let quotient_bound = FieldElement::modulus() / radix_big - BigUint::from(1u32);
+            self.range_constraint(quotient, quotient_bound.bits() as u32)?;
+
+            // input == quotient * radix + remainder
+            let recomposed =
+                &(&Expression::from(quotient) * radix_field) + &Expression::from(remainder);
+            self.assert_is_zero(input - &recomposed);
+
+            let mut limbs = vec![remainder];
+            limbs.extend(self.radix_le_decompose(&quotient.into(), radix, limb_count - 1)?);
+            Ok(limbs)
+        }
+    }
+
+    /// Constrains `limb` to be less than `radix`.
+    fn constrain_limb(&mut self, limb: Witness, radix: u32) -> Result<(), AcirGenError> {
+        if radix.is_power_of_two() {
+            self.range_constraint(limb, radix.trailing_zeros())
+        } else {
+            let bit_size = u32::BITS - radix.leading_zeros();
+            self.range_constraint(limb, bit_size)?;
+            let limb_plus_one = &Expression::from(limb) + &Expression::one();
+            self.bound_constraint(
+                &limb_plus_one,
+                &Expression::from(FieldElement::from(radix as u128)),
+                bit_size,
+            )
+        }
+    }
+
+    /// Returns `inputs` sorted in increasing order, where each input is an integer
+    /// of at most `bit_size` bits.
+    pub(crate) fn sort(
+        &mut self,
+        inputs: &[Expression],
+        bit_size: u32,
+    ) -> Result<Vec<Witness>, AcirGenError> {
+        let outputs = vecmap(inputs, |_| self.next_witness_index());
+        let output_expressions = vecmap(&outputs, |output| Expression::from(*output));
+
+        // The outputs must be sorted...
+        for window in output_expressions.windows(2) {
+            self.bound_constraint(&window[0], &window[1], bit_size)?;
+        }
+
+        // ... and be a permutation of the inputs.
+        let (control_bits, permuted) = self.permutation_layer(inputs);
+        for (permuted, output) in permuted.iter().zip(&output_expressions) {
+            self.assert_is_zero(permuted - output);
+        }
+
+        let inputs = vecmap(inputs, |input| vec![input.clone()]);
+        self.push_opcode(AcirOpcode::Directive(Directive::PermutationSort {
+            inputs,
+            tuple: 1,
+            bits: control_bits,
+            sort_by: vec![0],
+        }));
+
+        Ok(outputs)
+    }
+
+    /// Generates the gates of a sorting network which permutes `in_expr`.
+    ///
+    /// Returns the witnesses which control the switches of the network, along with the
+    /// expressions corresponding to the outputs of the network.
+    fn permutation_layer(&mut self, in_expr: &[Expression]) -> (Vec<Witness>, Vec<Expression>) {
+        let n = in_expr.len();
+        if n <= 1 {
+            return (Vec::new(), in_expr.to_vec());
+        }
+        let n1 = n / 2;
+
+        // Witnesses for the input switches
+        let mut conf = vecmap(0..n1, |_| self.next_witness_index());
+
+        // Compute the expressions after the input switches.
+        // If the inputs are a1, a2 and the switch value is c, then we compute the expressions
+        // b1 = a1 + q and b2 = a2 - q where q = c * (a2 - a1)
+        let mut in_sub1 = Vec::new();
+        let mut in_sub2 = Vec::new();
+        for i in 0..n1 {
+            let difference = &in_expr[2 * i + 1] - &in_expr[2 * i];
+            let intermediate = self.mul_with_witness(&conf[i].into(), &difference);
+            in_sub1.push(&intermediate + &in_expr[2 * i]);
+            in_sub2.push(&in_expr[2 * i + 1] - &intermediate);
+        }
+        if n % 2 == 1 {
+            in_sub2.push(in_expr[n - 1].clone());
+        }
+
+        // Compute the results of the sub networks
+        let (w1, b1) = self.permutation_layer(&in_sub1);
+        let (w2, b2) = self.permutation_layer(&in_sub2);
+
+        // Apply the output switches
+        let mut out_expr = Vec::new();
+        for i in 0..(n - 1) / 2 {
+            let c = self.next_witness_index();
+            conf.push(c);
+            let difference = &b2[i] - &b1[i];
+            let intermediate = self.mul_with_witness(&c.into(), &difference);
+            out_expr.push(&intermediate + &b1[i]);
+            out_expr.push(&b2[i] - &intermediate);
+        }
+        if n % 2 == 0 {
+            out_expr.push(b1[b1.len() - 1].clone());
+        }
+        out_expr.push(b2[b2.len() - 1].clone());
+
+        conf.extend(w1);
+        conf.extend(w2);
+        (conf, out_expr)
+    }
+
+    /// Calls the black box function `func` with the given inputs, returning the
+    /// witnesses which hold its outputs.
+    ///
+    /// There is one entry in `inputs` per argument to the function. Arguments which are
+    /// not arrays are expected to consist of a single `FunctionInput`.
+    pub(crate) fn call_black_box(
+        &mut self,
+        func: BlackBoxFunc,
+        inputs: Vec<Vec<FunctionInput>>,
+    ) -> Result<Vec<Witness>, AcirGenError> {
+        let output_count = match func {
+            BlackBoxFunc::SHA256 | BlackBoxFunc::Blake2s | BlackBoxFunc::Keccak256 => 32,
+            BlackBoxFunc::Pedersen | BlackBoxFunc::FixedBaseScalarMul => 2,
+            BlackBoxFunc::SchnorrVerify
+            | BlackBoxFunc::EcdsaSecp256k1
+            | BlackBoxFunc::ComputeMerkleRoot
+            | BlackBoxFunc::HashToField128Security
+            | BlackBoxFunc::AND
+            | BlackBoxFunc::XOR => 1,
+            _ => {
+                return Err(AcirGenError::Unimplemented(format!(
+                    "calls to the black box function {func}"
+                )))
+            }
+        };
+        let outputs = vecmap(0..output_count, |_| self.next_witness_index());
+
+        // Arguments which are not arrays are passed as a single `FunctionInput`.
+        let single = |index: usize| inputs[index][0];
+
+        let func_call = match func {
+            BlackBoxFunc::AND => {
+                BlackBoxFuncCall::AND { lhs: single(0), rhs: single(1), output: outputs[0] }
+            }
+            BlackBoxFunc::XOR => {
+                BlackBoxFuncCall::XOR { lhs: single(0), rhs: single(1), output: outputs[0] }
+            }
+            BlackBoxFunc::SHA256 => {
+                BlackBoxFuncCall::SHA256 { inputs: inputs[0].clone(), outputs: outputs.clone() }
+            }
+            BlackBoxFunc::Blake2s => {
+                BlackBoxFuncCall::Blake2s { inputs: inputs[0].clone(), outputs: outputs.clone() }
+            }
+            BlackBoxFunc::Keccak256 => {
+                BlackBoxFuncCall::Keccak256 { inputs: inputs[0].clone(), outputs: outputs.clone() }
+            }
+            BlackBoxFunc::Pedersen => {
+                BlackBoxFuncCall::Pedersen { inputs: inputs[0].clone(), outputs: outputs.clone() }
+            }
+            BlackBoxFunc::FixedBaseScalarMul => {
+                BlackBoxFuncCall::FixedBaseScalarMul { input: single(0), outputs: outputs.clone() }
+            }
+            BlackBoxFunc::SchnorrVerify => BlackBoxFuncCall::SchnorrVerify {
+                public_key_x: single(0),
+                public_key_y: single(1),
+                signature: inputs[2].clone(),
+                message: inputs[3].clone(),
+                output: outputs[0],
+            },
+            BlackBoxFunc::EcdsaSecp256k1 => BlackBoxFuncCall::EcdsaSecp256k1 {
+                public_key_x: inputs[0].clone(),
+                public_key_y: inputs[1].clone(),
+                signature: inputs[2].clone(),
+                hashed_message: inputs[3].clone(),
+                output: outputs[0],
+            },
+            BlackBoxFunc::ComputeMerkleRoot => BlackBoxFuncCall::ComputeMerkleRoot {
+                leaf: single(0),
+                index: single(1),
+                hash_path: inputs[2].clone(),
+                output: outputs[0],
+            },
+            BlackBoxFunc::HashToField128Security => BlackBoxFuncCall::HashToField128Security {
+                inputs: inputs[0].clone(),
+                output: outputs[0],
+            },
+            _ => unreachable!("ICE: output count of {func} should have been rejected above"),
+        };
+        self.push_opcode(AcirOpcode::BlackBoxFuncCall(func_call));
+
+        Ok(outputs)
+    }
+}
+
+/// Returns `2^exponent` as a `FieldElement`.
+fn two_pow(exponent: u32) -> FieldElement {
+    FieldElement::from(2_u128).pow(&FieldElement::from(exponent as u128))
 }
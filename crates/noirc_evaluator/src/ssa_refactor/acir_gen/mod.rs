@@ -4,18 +4,23 @@ use std::collections::HashMap;
 
 use self::acir_ir::acir_variable::{AcirContext, AcirVar};
 use super::{
-    abi_gen::collate_array_lengths,
+    abi_gen::collate_array_types,
     ir::{
         dfg::DataFlowGraph,
-        instruction::{Binary, BinaryOp, Instruction, InstructionId, TerminatorInstruction},
+        instruction::{
+            Binary, BinaryOp, Endian, Instruction, InstructionId, Intrinsic, TerminatorInstruction,
+        },
         map::Id,
-        types::Type,
+        types::{NumericType, Type},
         value::{Value, ValueId},
     },
     ssa_gen::Ssa,
 };
-use noirc_abi::FunctionSignature;
+use acvm::{acir::BlackBoxFunc, FieldElement};
+use iter_extended::vecmap;
+use noirc_abi::{AbiType, FunctionSignature, Sign};
 
+use acir_ir::errors::AcirGenError;
 pub(crate) use acir_ir::generated_acir::GeneratedAcir;
 
 mod acir_ir;
@@ -24,28 +29,62 @@ mod acir_ir;
 /// May be similar to the Evaluator struct in the current SSA IR.
 #[derive(Default)]
 struct Context {
-    /// Maps SSA values to `AcirVar`.
+    /// Maps SSA values to `AcirValue`.
     ///
     /// This is needed so that we only create a single
-    /// AcirVar per SSA value. Before creating an `AcirVar`
-    /// for an SSA value, we check this map. If an `AcirVar`
-    /// already exists for this Value, we return the `AcirVar`.
-    ssa_value_to_acir_var: HashMap<Id<Value>, AcirVar>,
+    /// AcirValue per SSA value. Before creating an `AcirValue`
+    /// for an SSA value, we check this map. If an `AcirValue`
+    /// already exists for this Value, we return the `AcirValue`.
+    ssa_values: HashMap<Id<Value>, AcirValue>,
     ///
     acir_context: AcirContext,
+
+    /// The contents of each array in memory, indexed by `ArrayId`.
+    ///
+    /// Arrays are flattened such that each element occupies a single slot. An array of
+    /// structs is then stored the same as an array holding each field of each struct.
+    arrays: Vec<Vec<AcirValue>>,
+
+    /// Whether calls to `println` should be compiled into log directives.
+    show_output: bool,
+}
+
+/// The ACIR representation of an SSA value.
+#[derive(Debug, Copy, Clone)]
+enum AcirValue {
+    /// A numeric value along with its type.
+    Var(AcirVar, NumericType),
+
+    /// A reference to the element at `offset` within `array`.
+    ///
+    /// References are created by allocations and by reference parameters, in which case
+    /// they point to the start of the array. They can then be offset by add instructions.
+    Reference { array: ArrayId, offset: AcirVar },
 }
 
+/// An index into the arrays held by the `Context`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct ArrayId(usize);
+
 impl Ssa {
-    pub(crate) fn into_acir(self, main_function_signature: FunctionSignature) -> GeneratedAcir {
-        let _param_array_lengths = collate_array_lengths(&main_function_signature.0);
-        let context = Context::default();
-        context.convert_ssa(self)
+    pub(crate) fn into_acir(
+        self,
+        main_function_signature: FunctionSignature,
+        show_output: bool,
+    ) -> Result<GeneratedAcir, AcirGenError> {
+        let param_array_types = collate_array_types(&main_function_signature.0);
+        let context = Context { show_output, ..Context::default() };
+        context.convert_ssa(self, &param_array_types)
     }
 }
 
 impl Context {
     /// Converts SSA into ACIR
-    fn convert_ssa(mut self, ssa: Ssa) -> GeneratedAcir {
+    fn convert_ssa(
+        mut self,
+        ssa: Ssa,
+        param_array_types: &[AbiType],
+    ) -> Result<GeneratedAcir, AcirGenError> {
         assert_eq!(
             ssa.functions.len(),
             1,
@@ -55,52 +94,185 @@ impl Context {
         let dfg = &main_func.dfg;
         let entry_block = &dfg[main_func.entry_block()];
 
+        let mut param_array_types = param_array_types.iter();
         for param_id in entry_block.parameters() {
-            self.convert_ssa_block_param(*param_id, dfg);
+            self.convert_ssa_block_param(*param_id, dfg, &mut param_array_types)?;
         }
 
         for instruction_id in entry_block.instructions() {
-            self.convert_ssa_instruction(*instruction_id, dfg);
+            self.acir_context.set_call_stack(dfg.get_call_stack(*instruction_id));
+            self.convert_ssa_instruction(*instruction_id, dfg)?;
         }
+        self.acir_context.set_call_stack(Vec::new());
 
         self.convert_ssa_return(entry_block.terminator().unwrap(), dfg);
 
-        self.acir_context.finish()
+        Ok(self.acir_context.finish())
     }
 
-    /// Adds and binds an AcirVar for each numeric block parameter
-    fn convert_ssa_block_param(&mut self, param_id: ValueId, dfg: &DataFlowGraph) {
+    /// Adds and binds an AcirValue for each block parameter
+    fn convert_ssa_block_param<'a>(
+        &mut self,
+        param_id: ValueId,
+        dfg: &DataFlowGraph,
+        param_array_types: &mut impl Iterator<Item = &'a AbiType>,
+    ) -> Result<(), AcirGenError> {
         let value = dfg[param_id];
         let param_type = match value {
             Value::Param { typ, .. } => typ,
             _ => unreachable!("ICE: Only Param type values should appear in block parameters"),
         };
-        match param_type {
-            Type::Numeric(..) => {
-                let acir_var = self.acir_context.add_variable();
-                self.ssa_value_to_acir_var.insert(param_id, acir_var);
-            }
+        let acir_value = match param_type {
+            Type::Numeric(numeric_type) => self.add_numeric_param(numeric_type)?,
             Type::Reference => {
-                todo!("Create an abstract array holding an AcirVar for each element of the reference and bind its ArrayId to the reference.");
+                let array_type = param_array_types
+                    .next()
+                    .expect("ICE: expected an array in the abi for each reference parameter");
+                let array = self.add_array_param(array_type)?;
+                self.reference_to_start_of(array)
             }
             _ => {
                 unreachable!("ICE: Params to the program should only contains numerics and arrays")
             }
+        };
+        self.ssa_values.insert(param_id, acir_value);
+        Ok(())
+    }
+
+    /// Adds an AcirVar for a numeric parameter to the program, constraining it to fit
+    /// within its type.
+    fn add_numeric_param(&mut self, numeric_type: NumericType) -> Result<AcirValue, AcirGenError> {
+        let acir_var = self.acir_context.add_variable();
+        if let Some(bit_size) = integer_bit_size(numeric_type) {
+            self.acir_context.range_constrain_var(acir_var, bit_size)?;
+        }
+        Ok(AcirValue::Var(acir_var, numeric_type))
+    }
+
+    /// Creates an array holding an AcirVar for each element of an array parameter to the program.
+    fn add_array_param(&mut self, array_type: &AbiType) -> Result<ArrayId, AcirGenError> {
+        let mut elements = Vec::new();
+        match array_type {
+            AbiType::Array { length, typ } => {
+                for _ in 0..*length {
+                    self.add_array_param_elements(typ, &mut elements)?;
+                }
+            }
+            AbiType::String { length } => {
+                let character_type = AbiType::Integer { sign: Sign::Unsigned, width: 8 };
+                for _ in 0..*length {
+                    self.add_array_param_elements(&character_type, &mut elements)?;
+                }
+            }
+            _ => unreachable!("ICE: expected an array type, found {array_type:?}"),
         }
+        Ok(self.allocate_array(elements))
+    }
+
+    /// Appends the elements which make up a single value of type `typ` within an array parameter.
+    fn add_array_param_elements(
+        &mut self,
+        typ: &AbiType,
+        elements: &mut Vec<AcirValue>,
+    ) -> Result<(), AcirGenError> {
+        match typ {
+            AbiType::Field => elements.push(self.add_numeric_param(NumericType::NativeField)?),
+            AbiType::Integer { sign: Sign::Unsigned, width } => {
+                let numeric_type = NumericType::Unsigned { bit_size: *width };
+                elements.push(self.add_numeric_param(numeric_type)?);
+            }
+            AbiType::Integer { sign: Sign::Signed, width } => {
+                let numeric_type = NumericType::Signed { bit_size: *width };
+                elements.push(self.add_numeric_param(numeric_type)?);
+            }
+            AbiType::Boolean => {
+                let numeric_type = NumericType::Unsigned { bit_size: 1 };
+                elements.push(self.add_numeric_param(numeric_type)?);
+            }
+            AbiType::Array { .. } | AbiType::String { .. } => {
+                // Nested arrays are stored as a reference to a separate array
+                let array = self.add_array_param(typ)?;
+                elements.push(self.reference_to_start_of(array));
+            }
+            AbiType::Struct { fields } => {
+                for (_, field_type) in fields {
+                    self.add_array_param_elements(field_type, elements)?;
+                }
+            }
+            AbiType::Tuple { fields } => {
+                for field_type in fields {
+                    self.add_array_param_elements(field_type, elements)?;
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Converts an SSA instruction into its ACIR representation
-    fn convert_ssa_instruction(&mut self, instruction_id: InstructionId, dfg: &DataFlowGraph) {
+    fn convert_ssa_instruction(
+        &mut self,
+        instruction_id: InstructionId,
+        dfg: &DataFlowGraph,
+    ) -> Result<(), AcirGenError> {
         let instruction = &dfg[instruction_id];
+        let result_ids = dfg.instruction_results(instruction_id);
         match instruction {
             Instruction::Binary(binary) => {
-                let result_acir_var = self.convert_ssa_binary(binary, dfg);
-                let result_ids = dfg.instruction_results(instruction_id);
+                let result_acir_value = self.convert_ssa_binary(binary, dfg)?;
                 assert_eq!(result_ids.len(), 1, "Binary ops have a single result");
-                self.ssa_value_to_acir_var.insert(result_ids[0], result_acir_var);
+                self.ssa_values.insert(result_ids[0], result_acir_value);
+            }
+            Instruction::Cast(value_id, typ) => {
+                let result_acir_value = self.convert_ssa_cast(*value_id, *typ, dfg)?;
+                self.ssa_values.insert(result_ids[0], result_acir_value);
+            }
+            Instruction::Not(value_id) => {
+                let (acir_var, numeric_type) = self.convert_numeric_value(*value_id, dfg);
+                let bit_size = expect_integer(numeric_type, "bitwise not")?;
+                let result_acir_var = self.acir_context.not_var(acir_var, bit_size);
+                self.ssa_values
+                    .insert(result_ids[0], AcirValue::Var(result_acir_var, numeric_type));
+            }
+            Instruction::Truncate { value, bit_size, max_bit_size } => {
+                let (acir_var, numeric_type) = self.convert_numeric_value(*value, dfg);
+                let result_acir_var =
+                    self.acir_context.truncate_var(acir_var, *bit_size, *max_bit_size)?;
+                self.ssa_values
+                    .insert(result_ids[0], AcirValue::Var(result_acir_var, numeric_type));
+            }
+            Instruction::Constrain(value_id, assertion) => {
+                let (acir_var, _) = self.convert_numeric_value(*value_id, dfg);
+                self.acir_context.assert_eq_one(acir_var)?;
+                if let Some(assertion) = assertion {
+                    self.acir_context.record_assertion(assertion.clone());
+                }
+            }
+            Instruction::Call { func, arguments } => {
+                let result_acir_values =
+                    self.convert_ssa_call(*func, arguments, result_ids, dfg)?;
+                assert_eq!(result_ids.len(), result_acir_values.len());
+                for (result_id, result_acir_value) in result_ids.iter().zip(result_acir_values) {
+                    self.ssa_values.insert(*result_id, result_acir_value);
+                }
+            }
+            Instruction::Allocate { size } => {
+                let zero = self.acir_context.add_constant(FieldElement::zero());
+                let elements = vec![AcirValue::Var(zero, NumericType::NativeField); *size as usize];
+                let array = self.allocate_array(elements);
+                let reference = self.reference_to_start_of(array);
+                self.ssa_values.insert(result_ids[0], reference);
+            }
+            Instruction::Load { address } => {
+                let result_type = dfg.type_of_value(result_ids[0]);
+                let result_acir_value = self.convert_ssa_load(*address, result_type, dfg)?;
+                self.ssa_values.insert(result_ids[0], result_acir_value);
+            }
+            Instruction::Store { address, value } => {
+                let value = self.convert_ssa_value(*value, dfg);
+                self.convert_ssa_store(*address, value, dfg)?;
             }
-            _ => todo!(),
         }
+        Ok(())
     }
 
     /// Converts an SSA terminator's return values into their ACIR representations
@@ -110,18 +282,21 @@ impl Context {
             _ => unreachable!("ICE: Program must have a singular return"),
         };
 
-        let is_return_unit_type = return_values.len() == 1 && dfg.type_of_value(return_values[0]) == Type::Unit;
+        let is_return_unit_type =
+            return_values.len() == 1 && dfg.type_of_value(return_values[0]) == Type::Unit;
         if is_return_unit_type {
             return;
         }
 
         for value_id in return_values {
-            let acir_var = self.convert_ssa_value(*value_id, dfg);
-            self.acir_context.return_var(acir_var);
+            let acir_value = self.convert_ssa_value(*value_id, dfg);
+            for (acir_var, _) in self.flatten_value(acir_value) {
+                self.acir_context.return_var(acir_var);
+            }
         }
     }
 
-    /// Gets the cached `AcirVar` that was converted from the corresponding `ValueId`. If it does
+    /// Gets the cached `AcirValue` that was converted from the corresponding `ValueId`. If it does
     /// not already exist in the cache, a conversion is attempted and cached for simple values
     /// that require no further context such as numeric types - values requiring more context
     /// should have already been cached elsewhere.
@@ -130,34 +305,464 @@ impl Context {
     /// parameters. This is because block parameters are converted before anything else, and
     /// because instructions results are converted when the corresponding instruction is
     /// encountered. (An instruction result cannot be referenced before the instruction occurs.)
-    fn convert_ssa_value(&mut self, value_id: ValueId, dfg: &DataFlowGraph) -> AcirVar {
+    fn convert_ssa_value(&mut self, value_id: ValueId, dfg: &DataFlowGraph) -> AcirValue {
         let value = &dfg[value_id];
-        if let Some(acir_var) = self.ssa_value_to_acir_var.get(&value_id) {
-            return *acir_var;
+        if let Some(acir_value) = self.ssa_values.get(&value_id) {
+            return *acir_value;
         }
-        let acir_var = match value {
-            Value::NumericConstant { constant, .. } => {
+        let acir_value = match value {
+            Value::NumericConstant { constant, typ } => {
                 let field_element = &dfg[*constant].value();
-                self.acir_context.add_constant(*field_element)
+                let acir_var = self.acir_context.add_constant(*field_element);
+                AcirValue::Var(acir_var, numeric_type_of(*typ))
+            }
+            Value::Intrinsic(..) => {
+                unreachable!("ICE: Intrinsics should only be referenced by call instructions")
             }
-            Value::Intrinsic(..) => todo!(),
             Value::Function(..) => unreachable!("ICE: All functions should have been inlined"),
             Value::Instruction { .. } | Value::Param { .. } => {
                 unreachable!("ICE: Should have been in cache")
             }
         };
-        self.ssa_value_to_acir_var.insert(value_id, acir_var);
-        acir_var
+        self.ssa_values.insert(value_id, acir_value);
+        acir_value
     }
 
-    /// Processes a binary operation and converts the result into an `AcirVar`
-    fn convert_ssa_binary(&mut self, binary: &Binary, dfg: &DataFlowGraph) -> AcirVar {
-        let lhs = self.convert_ssa_value(binary.lhs, dfg);
-        let rhs = self.convert_ssa_value(binary.rhs, dfg);
-        match binary.operator {
-            BinaryOp::Add => self.acir_context.add_var(lhs, rhs),
-            BinaryOp::Sub => self.acir_context.sub_var(lhs, rhs),
-            _ => todo!(),
+    /// Converts an SSA value which is expected to be numeric, returning its `AcirVar` along
+    /// with its type.
+    fn convert_numeric_value(
+        &mut self,
+        value_id: ValueId,
+        dfg: &DataFlowGraph,
+    ) -> (AcirVar, NumericType) {
+        match self.convert_ssa_value(value_id, dfg) {
+            AcirValue::Var(acir_var, numeric_type) => (acir_var, numeric_type),
+            AcirValue::Reference { .. } => {
+                unreachable!("ICE: expected a numeric value but found a reference")
+            }
         }
     }
+
+    /// Processes a binary operation and converts the result into an `AcirValue`
+    fn convert_ssa_binary(
+        &mut self,
+        binary: &Binary,
+        dfg: &DataFlowGraph,
+    ) -> Result<AcirValue, AcirGenError> {
+        let (lhs, numeric_type) = match self.convert_ssa_value(binary.lhs, dfg) {
+            AcirValue::Var(acir_var, numeric_type) => (acir_var, numeric_type),
+            AcirValue::Reference { array, offset } => {
+                // Adding to a reference offsets it further into the array it points to
+                assert_eq!(binary.operator, BinaryOp::Add, "ICE: references may only be offset");
+                let (rhs, _) = self.convert_numeric_value(binary.rhs, dfg);
+                let offset = self.acir_context.add_var(offset, rhs);
+                return Ok(AcirValue::Reference { array, offset });
+            }
+        };
+        let (rhs, rhs_type) = self.convert_numeric_value(binary.rhs, dfg);
+
+        let result =
+            match binary.operator {
+                // Integer arithmetic wraps around the bit size of the type
+                BinaryOp::Add => match integer_bit_size(numeric_type) {
+                    Some(bit_size) => self.acir_context.wrapping_add_var(lhs, rhs, bit_size)?,
+                    None => self.acir_context.add_var(lhs, rhs),
+                },
+                BinaryOp::Sub => match integer_bit_size(numeric_type) {
+                    Some(bit_size) => self.acir_context.wrapping_sub_var(lhs, rhs, bit_size)?,
+                    None => self.acir_context.sub_var(lhs, rhs),
+                },
+                BinaryOp::Mul => match integer_bit_size(numeric_type) {
+                    Some(bit_size) => self.acir_context.wrapping_mul_var(lhs, rhs, bit_size)?,
+                    None => self.acir_context.mul_var(lhs, rhs),
+                },
+                BinaryOp::Div => match numeric_type {
+                    NumericType::NativeField => self.acir_context.div_var(lhs, rhs)?,
+                    NumericType::Unsigned { bit_size } => {
+                        self.acir_context.euclidean_division_var(lhs, rhs, bit_size)?.0
+                    }
+                    NumericType::Signed { bit_size } => {
+                        self.acir_context.signed_division_var(lhs, rhs, bit_size)?.0
+                    }
+                },
+                BinaryOp::Mod => match numeric_type {
+                    NumericType::Unsigned { bit_size } => {
+                        self.acir_context.euclidean_division_var(lhs, rhs, bit_size)?.1
+                    }
+                    NumericType::Signed { bit_size } => {
+                        self.acir_context.signed_division_var(lhs, rhs, bit_size)?.1
+                    }
+                    NumericType::NativeField => {
+                        return Err(AcirGenError::Unimplemented(format!(
+                            "modulo of values of type {numeric_type}"
+                        )))
+                    }
+                },
+                BinaryOp::Eq => self.acir_context.eq_var(lhs, rhs),
+                BinaryOp::Lt => match numeric_type {
+                    NumericType::Unsigned { bit_size } => {
+                        self.acir_context.less_than_var(lhs, rhs, bit_size, false)?
+                    }
+                    NumericType::Signed { bit_size } => {
+                        self.acir_context.less_than_var(lhs, rhs, bit_size, true)?
+                    }
+                    NumericType::NativeField => return Err(AcirGenError::Unimplemented(
+                        "comparison of field elements, try to cast the operands to an integer type"
+                            .to_owned(),
+                    )),
+                },
+                BinaryOp::And => {
+                    let bit_size = expect_integer(numeric_type, "bitwise and")?;
+                    self.acir_context.and_var(lhs, rhs, bit_size)?
+                }
+                BinaryOp::Or => {
+                    let bit_size = expect_integer(numeric_type, "bitwise or")?;
+                    self.acir_context.or_var(lhs, rhs, bit_size)?
+                }
+                BinaryOp::Xor => {
+                    let bit_size = expect_integer(numeric_type, "bitwise xor")?;
+                    self.acir_context.xor_var(lhs, rhs, bit_size)?
+                }
+                BinaryOp::Shl => {
+                    let bit_size = expect_integer(numeric_type, "shifts")?;
+                    let rhs_bit_size = expect_integer(rhs_type, "shifts")?;
+                    self.acir_context.shift_left_var(lhs, rhs, bit_size, rhs_bit_size)?
+                }
+                BinaryOp::Shr => {
+                    let bit_size = expect_integer(numeric_type, "shifts")?;
+                    let rhs_bit_size = expect_integer(rhs_type, "shifts")?;
+                    let signed = matches!(numeric_type, NumericType::Signed { .. });
+                    self.acir_context.shift_right_var(lhs, rhs, bit_size, rhs_bit_size, signed)?
+                }
+            };
+
+        let result_type = match binary.operator {
+            BinaryOp::Eq | BinaryOp::Lt => NumericType::Unsigned { bit_size: 1 },
+            _ => numeric_type,
+        };
+        Ok(AcirValue::Var(result, result_type))
+    }
+
+    /// Converts a cast of `value_id` to the type `typ`.
+    ///
+    /// Casting to a smaller integer type truncates the value to the bit size of the new type.
+    fn convert_ssa_cast(
+        &mut self,
+        value_id: ValueId,
+        typ: Type,
+        dfg: &DataFlowGraph,
+    ) -> Result<AcirValue, AcirGenError> {
+        let (acir_var, value_type) = self.convert_numeric_value(value_id, dfg);
+        let target_type = match typ {
+            Type::Numeric(numeric_type) => numeric_type,
+            _ => unreachable!("ICE: values may only be cast to numeric types"),
+        };
+
+        let result = match integer_bit_size(target_type) {
+            None => acir_var,
+            Some(bit_size) => {
+                let max_bit_size =
+                    integer_bit_size(value_type).unwrap_or_else(FieldElement::max_num_bits);
+                self.acir_context.truncate_var(acir_var, bit_size, max_bit_size)?
+            }
+        };
+        Ok(AcirValue::Var(result, target_type))
+    }
+
+    /// Converts a call to an intrinsic function, returning the values of its results.
+    fn convert_ssa_call(
+        &mut self,
+        func: ValueId,
+        arguments: &[ValueId],
+        result_ids: &[ValueId],
+        dfg: &DataFlowGraph,
+    ) -> Result<Vec<AcirValue>, AcirGenError> {
+        let intrinsic = match dfg[func] {
+            Value::Intrinsic(intrinsic) => intrinsic,
+            Value::Function(..) => unreachable!("ICE: All functions should have been inlined"),
+            _ => unreachable!("ICE: Only functions and intrinsics may be called"),
+        };
+
+        match intrinsic {
+            Intrinsic::ToBits(endian) => {
+                let (acir_var, _) = self.convert_numeric_value(arguments[0], dfg);
+                let bit_size = self.convert_constant_argument(arguments[1], dfg)?;
+                let bits = self.acir_context.radix_decompose_var(acir_var, 2, bit_size)?;
+                let bit_type = NumericType::Unsigned { bit_size: 1 };
+                Ok(vec![self.make_array(bits, bit_type, endian)])
+            }
+            Intrinsic::ToRadix(endian) => {
+                let (acir_var, _) = self.convert_numeric_value(arguments[0], dfg);
+                let radix = self.convert_constant_argument(arguments[1], dfg)?;
+                let limb_count = self.convert_constant_argument(arguments[2], dfg)?;
+                let limbs = self.acir_context.radix_decompose_var(acir_var, radix, limb_count)?;
+                let limb_type = NumericType::Unsigned { bit_size: 8 };
+                Ok(vec![self.make_array(limbs, limb_type, endian)])
+            }
+            Intrinsic::Sort => {
+                let array = self.convert_ssa_value(arguments[0], dfg);
+                let elements = self.flatten_value(array);
+                let element_type =
+                    elements.first().map_or(NumericType::NativeField, |(_, typ)| *typ);
+                let bit_size = expect_integer(element_type, "sorting")?;
+                let acir_vars = vecmap(elements, |(acir_var, _)| acir_var);
+                let sorted = self.acir_context.sort_vars(&acir_vars, bit_size)?;
+                Ok(vec![self.make_array(sorted, element_type, Endian::Little)])
+            }
+            Intrinsic::Println => {
+                if self.show_output {
+                    let mut acir_vars = Vec::new();
+                    for argument in arguments {
+                        let acir_value = self.convert_ssa_value(*argument, dfg);
+                        acir_vars
+                            .extend(self.flatten_value(acir_value).into_iter().map(|(var, _)| var));
+                    }
+                    self.acir_context.print(&acir_vars);
+                }
+                Ok(Vec::new())
+            }
+            Intrinsic::BlackBox(func) => {
+                let inputs = vecmap(arguments, |argument| {
+                    let acir_value = self.convert_ssa_value(*argument, dfg);
+                    vecmap(self.flatten_value(acir_value), |(acir_var, numeric_type)| {
+                        let num_bits = integer_bit_size(numeric_type)
+                            .unwrap_or_else(FieldElement::max_num_bits);
+                        (acir_var, num_bits)
+                    })
+                });
+                let outputs = self.acir_context.black_box_function(func, inputs)?;
+
+                match dfg.type_of_value(result_ids[0]) {
+                    Type::Numeric(numeric_type) => {
+                        Ok(vec![AcirValue::Var(outputs[0], numeric_type)])
+                    }
+                    Type::Reference => {
+                        let output_type = match func {
+                            BlackBoxFunc::SHA256
+                            | BlackBoxFunc::Blake2s
+                            | BlackBoxFunc::Keccak256 => NumericType::Unsigned { bit_size: 8 },
+                            _ => NumericType::NativeField,
+                        };
+                        Ok(vec![self.make_array(outputs, output_type, Endian::Little)])
+                    }
+                    typ => unreachable!("ICE: unexpected return type {typ} for {func}"),
+                }
+            }
+        }
+    }
+
+    /// Converts an argument to an intrinsic which is required to be a constant `u32`.
+    fn convert_constant_argument(
+        &mut self,
+        value_id: ValueId,
+        dfg: &DataFlowGraph,
+    ) -> Result<u32, AcirGenError> {
+        let (acir_var, _) = self.convert_numeric_value(value_id, dfg);
+        self.acir_context
+            .constant(acir_var)
+            .and_then(|constant| constant.try_into_u128())
+            .and_then(|constant| u32::try_from(constant).ok())
+            .ok_or_else(|| {
+                AcirGenError::Unimplemented(
+                    "intrinsic arguments which are only known at runtime".to_owned(),
+                )
+            })
+    }
+
+    /// Loads the value of type `result_type` which `address` refers to.
+    ///
+    /// If the offset of `address` is only known at runtime, the loaded value is computed
+    /// as the sum of each element of the array multiplied by whether the offset refers to it.
+    fn convert_ssa_load(
+        &mut self,
+        address: ValueId,
+        result_type: Type,
+        dfg: &DataFlowGraph,
+    ) -> Result<AcirValue, AcirGenError> {
+        let (array, offset) = self.convert_reference(address, dfg);
+
+        if let Some(index) = self.acir_context.constant(offset) {
+            let index = self.constant_index(array, index)?;
+            return Ok(self.arrays[array.0][index]);
+        }
+
+        let numeric_type = match result_type {
+            Type::Numeric(numeric_type) => numeric_type,
+            _ => {
+                return Err(AcirGenError::Unimplemented(
+                    "arrays of arrays indexed by values which are only known at runtime".to_owned(),
+                ))
+            }
+        };
+
+        let predicates = self.index_predicates(array, offset)?;
+        let mut result = self.acir_context.add_constant(FieldElement::zero());
+        for (predicate, element) in predicates.into_iter().zip(self.arrays[array.0].clone()) {
+            let (element, _) = expect_var(element)?;
+            let selected = self.acir_context.mul_var(predicate, element);
+            result = self.acir_context.add_var(result, selected);
+        }
+        Ok(AcirValue::Var(result, numeric_type))
+    }
+
+    /// Stores `value` into the array element which `address` refers to.
+    ///
+    /// If the offset of `address` is only known at runtime, every element of the array is
+    /// replaced with either `value` or its previous value depending on whether the offset
+    /// refers to it.
+    fn convert_ssa_store(
+        &mut self,
+        address: ValueId,
+        value: AcirValue,
+        dfg: &DataFlowGraph,
+    ) -> Result<(), AcirGenError> {
+        let (array, offset) = self.convert_reference(address, dfg);
+
+        if let Some(index) = self.acir_context.constant(offset) {
+            let index = self.constant_index(array, index)?;
+            self.arrays[array.0][index] = value;
+            return Ok(());
+        }
+
+        let (value, numeric_type) = expect_var(value)?;
+        let predicates = self.index_predicates(array, offset)?;
+        for (index, predicate) in predicates.into_iter().enumerate() {
+            let (old_value, _) = expect_var(self.arrays[array.0][index])?;
+            // new_value = old_value + predicate * (value - old_value)
+            let difference = self.acir_context.sub_var(value, old_value);
+            let selected_difference = self.acir_context.mul_var(predicate, difference);
+            let new_value = self.acir_context.add_var(old_value, selected_difference);
+            self.arrays[array.0][index] = AcirValue::Var(new_value, numeric_type);
+        }
+        Ok(())
+    }
+
+    /// Converts an SSA value which is expected to be a reference, returning the array it
+    /// refers to along with its offset into the array.
+    fn convert_reference(&mut self, value_id: ValueId, dfg: &DataFlowGraph) -> (ArrayId, AcirVar) {
+        match self.convert_ssa_value(value_id, dfg) {
+            AcirValue::Reference { array, offset } => (array, offset),
+            AcirValue::Var(..) => {
+                unreachable!("ICE: expected a reference but found a numeric value")
+            }
+        }
+    }
+
+    /// Checks that the constant `index` is within the bounds of `array`, returning it as a `usize`.
+    fn constant_index(&self, array: ArrayId, index: FieldElement) -> Result<usize, AcirGenError> {
+        let array_size = self.arrays[array.0].len();
+        let index = index.try_into_u128().map_or(usize::MAX, |index| index as usize);
+        if index >= array_size {
+            return Err(AcirGenError::IndexOutOfBounds { index, array_size });
+        }
+        Ok(index)
+    }
+
+    /// Returns an `AcirVar` for each element of `array` which is constrained to be `1` if
+    /// `index` refers to that element and `0` otherwise.
+    ///
+    /// This also constrains `index` to be within the bounds of `array`, as exactly one of
+    /// the returned predicates must then be set.
+    fn index_predicates(
+        &mut self,
+        array: ArrayId,
+        index: AcirVar,
+    ) -> Result<Vec<AcirVar>, AcirGenError> {
+        let array_size = self.arrays[array.0].len();
+        let predicates = vecmap(0..array_size, |element_index| {
+            let element_index =
+                self.acir_context.add_constant(FieldElement::from(element_index as u128));
+            self.acir_context.eq_var(index, element_index)
+        });
+
+        let mut predicate_sum = self.acir_context.add_constant(FieldElement::zero());
+        for predicate in &predicates {
+            predicate_sum = self.acir_context.add_var(predicate_sum, *predicate);
+        }
+        self.acir_context.assert_eq_one(predicate_sum)?;
+
+        Ok(predicates)
+    }
+
+    /// Adds a new array holding `elements` to memory.
+    fn allocate_array(&mut self, elements: Vec<AcirValue>) -> ArrayId {
+        let array = ArrayId(self.arrays.len());
+        self.arrays.push(elements);
+        array
+    }
+
+    /// Returns a reference to the first element of `array`.
+    fn reference_to_start_of(&mut self, array: ArrayId) -> AcirValue {
+        let offset = self.acir_context.add_constant(FieldElement::zero());
+        AcirValue::Reference { array, offset }
+    }
+
+    /// Adds a new array holding `acir_vars` to memory and returns a reference to it.
+    ///
+    /// `acir_vars` are expected to be in little endian order and are reversed if the
+    /// array should be in big endian order.
+    fn make_array(
+        &mut self,
+        acir_vars: Vec<AcirVar>,
+        numeric_type: NumericType,
+        endian: Endian,
+    ) -> AcirValue {
+        let mut elements = vecmap(acir_vars, |acir_var| AcirValue::Var(acir_var, numeric_type));
+        if endian == Endian::Big {
+            elements.reverse();
+        }
+        let array = self.allocate_array(elements);
+        self.reference_to_start_of(array)
+    }
+
+    /// Returns each numeric value making up `acir_value`.
+    ///
+    /// References are flattened into the elements of the array they refer to, recursively.
+    fn flatten_value(&self, acir_value: AcirValue) -> Vec<(AcirVar, NumericType)> {
+        match acir_value {
+            AcirValue::Var(acir_var, numeric_type) => vec![(acir_var, numeric_type)],
+            AcirValue::Reference { array, .. } => self.arrays[array.0]
+                .iter()
+                .flat_map(|element| self.flatten_value(*element))
+                .collect(),
+        }
+    }
+}
+
+/// Returns the numeric type used to represent values of type `typ`.
+///
+/// Unit values are represented as field elements.
+fn numeric_type_of(typ: Type) -> NumericType {
+    match typ {
+        Type::Numeric(numeric_type) => numeric_type,
+        Type::Unit => NumericType::NativeField,
+        _ => unreachable!("ICE: expected a numeric type but found {typ}"),
+    }
+}
+
+/// Returns the bit size of `numeric_type` if it is an integer type.
+fn integer_bit_size(numeric_type: NumericType) -> Option<u32> {
+    match numeric_type {
+        NumericType::Signed { bit_size } | NumericType::Unsigned { bit_size } => Some(bit_size),
+        NumericType::NativeField => None,
+    }
+}
+
+/// Returns the bit size of `numeric_type`, or an error if `operation` is applied to field elements.
+fn expect_integer(numeric_type: NumericType, operation: &str) -> Result<u32, AcirGenError> {
+    integer_bit_size(numeric_type).ok_or_else(|| {
+        AcirGenError::Unimplemented(format!("{operation} on values of type {numeric_type}"))
+    })
+}
+
+/// Returns the numeric value held by `acir_value`, or an error if it is a reference.
+///
+/// References cannot be selected between using values which are only known at runtime.
+fn expect_var(acir_value: AcirValue) -> Result<(AcirVar, NumericType), AcirGenError> {
+    match acir_value {
+        AcirValue::Var(acir_var, numeric_type) => Ok((acir_var, numeric_type)),
+        AcirValue::Reference { .. } => Err(AcirGenError::Unimplemented(
+            "arrays of arrays indexed by values which are only known at runtime".to_owned(),
+        )),
+    }
 }
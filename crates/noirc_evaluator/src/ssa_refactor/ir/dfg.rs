@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::ssa_refactor::ir::instruction::SimplifyResult;
+use crate::{ssa_refactor::ir::instruction::SimplifyResult, CallStack};
 
 use super::{
     basic_block::{BasicBlock, BasicBlockId},
@@ -61,6 +61,10 @@ pub(crate) struct DataFlowGraph {
 
     /// All blocks in a function
     blocks: DenseMap<BasicBlock>,
+
+    /// The call stack each instruction is generated from, for those which can be traced back
+    /// to the source program
+    locations: HashMap<InstructionId, CallStack>,
 }
 
 impl DataFlowGraph {
@@ -123,12 +127,14 @@ impl DataFlowGraph {
         id
     }
 
-    /// Inserts a new instruction at the end of the given block and returns its results
+    /// Inserts a new instruction at the end of the given block and returns its results.
+    /// The instruction is recorded as generated from `call_stack`.
     pub(crate) fn insert_instruction_and_results(
         &mut self,
         instruction: Instruction,
         block: BasicBlockId,
         ctrl_typevars: Option<Vec<Type>>,
+        call_stack: CallStack,
     ) -> InsertInstructionResult {
         use InsertInstructionResult::*;
         match instruction.simplify(self) {
@@ -137,11 +143,20 @@ impl DataFlowGraph {
             SimplifyResult::None => {
                 let id = self.make_instruction(instruction, ctrl_typevars);
                 self.blocks[block].insert_instruction(id);
+                if !call_stack.is_empty() {
+                    self.locations.insert(id, call_stack);
+                }
                 InsertInstructionResult::Results(self.instruction_results(id))
             }
         }
     }
 
+    /// Returns the call stack the given instruction is generated from, which is empty if it
+    /// can't be traced back to the source program.
+    pub(crate) fn get_call_stack(&self, instruction: InstructionId) -> CallStack {
+        self.locations.get(&instruction).cloned().unwrap_or_default()
+    }
+
     /// Insert a value into the dfg's storage and return an id to reference it.
     /// Until the value is used in an instruction it is unreachable.
     pub(crate) fn make_value(&mut self, value: Value) -> ValueId {
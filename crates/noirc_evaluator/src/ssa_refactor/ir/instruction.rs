@@ -1,11 +1,13 @@
 use acvm::{acir::BlackBoxFunc, FieldElement};
 use iter_extended::vecmap;
 
+use crate::Assertion;
+
 use super::{
     basic_block::BasicBlockId,
     dfg::DataFlowGraph,
     map::Id,
-    types::Type,
+    types::{NumericType, Type},
     value::{Value, ValueId},
 };
 
@@ -86,8 +88,9 @@ pub(crate) enum Instruction {
     /// Truncates `value` to `bit_size`
     Truncate { value: ValueId, bit_size: u32, max_bit_size: u32 },
 
-    /// Constrains a value to be equal to true
-    Constrain(ValueId),
+    /// Constrains a value to be equal to true.
+    /// Constraints coming from an `assert` in the source program also hold that assertion.
+    Constrain(ValueId, Option<Assertion>),
 
     /// Performs a function call with a list of its arguments.
     Call { func: ValueId, arguments: Vec<ValueId> },
@@ -122,7 +125,7 @@ impl Instruction {
             Instruction::Not(value) | Instruction::Truncate { value, .. } => {
                 InstructionResultType::Operand(*value)
             }
-            Instruction::Constrain(_) | Instruction::Store { .. } => InstructionResultType::None,
+            Instruction::Constrain(..) | Instruction::Store { .. } => InstructionResultType::None,
             Instruction::Load { .. } | Instruction::Call { .. } => InstructionResultType::Unknown,
         }
     }
@@ -150,7 +153,9 @@ impl Instruction {
                 bit_size: *bit_size,
                 max_bit_size: *max_bit_size,
             },
-            Instruction::Constrain(value) => Instruction::Constrain(f(*value)),
+            Instruction::Constrain(value, assertion) => {
+                Instruction::Constrain(f(*value), assertion.clone())
+            }
             Instruction::Call { func, arguments } => Instruction::Call {
                 func: f(*func),
                 arguments: vecmap(arguments.iter().copied(), f),
@@ -194,7 +199,7 @@ impl Instruction {
                     _ => None,
                 }
             }
-            Instruction::Constrain(value) => {
+            Instruction::Constrain(value, _) => {
                 if let Some(constant) = dfg.get_numeric_constant(*value) {
                     if constant.is_one() {
                         return Remove;
@@ -427,6 +432,28 @@ impl Binary {
         rhs: FieldElement,
         operand_type: Type,
     ) -> Option<Id<Value>> {
+        let value = match operand_type {
+            Type::Numeric(NumericType::Unsigned { bit_size }) => {
+                self.eval_constant_unsigned_operations(lhs, rhs, bit_size)?
+            }
+            // Signed operations are left to ACIR generation, which evaluates them on
+            // two's complement values
+            Type::Numeric(NumericType::Signed { .. }) => match self.operator {
+                BinaryOp::Eq => (lhs == rhs).into(),
+                _ => return None,
+            },
+            _ => self.eval_constant_field_operations(lhs, rhs)?,
+        };
+        // TODO: Keep original type of constant
+        Some(dfg.make_constant(value, operand_type))
+    }
+
+    /// Evaluate the two constants as field elements with the operation specified by self.operator.
+    fn eval_constant_field_operations(
+        &self,
+        lhs: FieldElement,
+        rhs: FieldElement,
+    ) -> Option<FieldElement> {
         let value = match self.operator {
             BinaryOp::Add => lhs + rhs,
             BinaryOp::Sub => lhs - rhs,
@@ -443,8 +470,43 @@ impl Binary {
             BinaryOp::Shl => self.eval_constant_u128_operations(lhs, rhs)?,
             BinaryOp::Shr => self.eval_constant_u128_operations(lhs, rhs)?,
         };
-        // TODO: Keep original type of constant
-        Some(dfg.make_constant(value, operand_type))
+        Some(value)
+    }
+
+    /// Evaluate the two constants as unsigned integers of `bit_size` bits with the operation
+    /// specified by self.operator, wrapping the result around the bit size.
+    fn eval_constant_unsigned_operations(
+        &self,
+        lhs: FieldElement,
+        rhs: FieldElement,
+        bit_size: u32,
+    ) -> Option<FieldElement> {
+        if bit_size > 128 {
+            return None;
+        }
+        let lhs = lhs.try_into_u128()?;
+        let rhs = rhs.try_into_u128()?;
+        let value = match self.operator {
+            BinaryOp::Add => lhs.wrapping_add(rhs),
+            BinaryOp::Sub => lhs.wrapping_sub(rhs),
+            BinaryOp::Mul => lhs.wrapping_mul(rhs),
+            // Division by zero is reported when generating ACIR
+            BinaryOp::Div => lhs.checked_div(rhs)?,
+            BinaryOp::Mod => lhs.checked_rem(rhs)?,
+            BinaryOp::Eq => (lhs == rhs).into(),
+            BinaryOp::Lt => (lhs < rhs).into(),
+            BinaryOp::And => lhs & rhs,
+            BinaryOp::Or => lhs | rhs,
+            BinaryOp::Xor => lhs ^ rhs,
+            BinaryOp::Shl => {
+                u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shl(rhs)).unwrap_or(0)
+            }
+            BinaryOp::Shr => {
+                u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shr(rhs)).unwrap_or(0)
+            }
+        };
+        let value = if bit_size == 128 { value } else { value & ((1_u128 << bit_size) - 1) };
+        Some(value.into())
     }
 
     /// Try to evaluate the given operands as u128s for operators that are only valid on u128s,
@@ -293,7 +293,10 @@ impl<'f> Context<'f> {
     /// Note that this does not modify self.values.
     fn insert_instruction(&mut self, instruction: Instruction) -> ValueId {
         let block = self.function.entry_block();
-        self.function.dfg.insert_instruction_and_results(instruction, block, None).first()
+        self.function
+            .dfg
+            .insert_instruction_and_results(instruction, block, None, Vec::new())
+            .first()
     }
 
     /// Merge two values a and b from separate basic blocks to a single value. This
@@ -306,16 +309,19 @@ impl<'f> Context<'f> {
     ) -> ValueId {
         let block = self.function.entry_block();
         let mul = Instruction::binary(BinaryOp::Mul, condition, then_value);
-        let then_value = self.function.dfg.insert_instruction_and_results(mul, block, None).first();
+        let then_value =
+            self.function.dfg.insert_instruction_and_results(mul, block, None, Vec::new()).first();
 
         let not = Instruction::Not(condition);
-        let not = self.function.dfg.insert_instruction_and_results(not, block, None).first();
+        let not =
+            self.function.dfg.insert_instruction_and_results(not, block, None, Vec::new()).first();
 
         let mul = Instruction::binary(BinaryOp::Mul, not, else_value);
-        let else_value = self.function.dfg.insert_instruction_and_results(mul, block, None).first();
+        let else_value =
+            self.function.dfg.insert_instruction_and_results(mul, block, None, Vec::new()).first();
 
         let add = Instruction::binary(BinaryOp::Add, then_value, else_value);
-        self.function.dfg.insert_instruction_and_results(add, block, None).first()
+        self.function.dfg.insert_instruction_and_results(add, block, None, Vec::new()).first()
     }
 
     /// Inline one branch of a jmpif instruction.
@@ -427,8 +433,13 @@ impl<'f> Context<'f> {
             .then(|| vecmap(&results, |result| self.function.dfg.type_of_value(*result)));
 
         let block = self.function.entry_block();
-        let new_results =
-            self.function.dfg.insert_instruction_and_results(instruction, block, ctrl_typevars);
+        let call_stack = self.function.dfg.get_call_stack(id);
+        let new_results = self.function.dfg.insert_instruction_and_results(
+            instruction,
+            block,
+            ctrl_typevars,
+            call_stack,
+        );
         Self::insert_new_instruction_results(&mut self.values, &results, new_results);
     }
 
@@ -437,7 +448,7 @@ impl<'f> Context<'f> {
     fn handle_instruction_side_effects(&mut self, instruction: Instruction) -> Instruction {
         if let Some((_, condition)) = self.conditions.last().copied() {
             match instruction {
-                Instruction::Constrain(value) => {
+                Instruction::Constrain(value, assertion) => {
                     let mul = self.insert_instruction(Instruction::binary(
                         BinaryOp::Mul,
                         value,
@@ -445,7 +456,7 @@ impl<'f> Context<'f> {
                     ));
                     let eq =
                         self.insert_instruction(Instruction::binary(BinaryOp::Eq, mul, condition));
-                    Instruction::Constrain(eq)
+                    Instruction::Constrain(eq, assertion)
                 }
                 // TODO: Need to log any stores found
                 other => other,
@@ -561,7 +572,7 @@ mod test {
         builder.terminate_with_jmpif(v0, b1, b2);
 
         builder.switch_to_block(b1);
-        builder.insert_constrain(v1);
+        builder.insert_constrain(v1, None);
         builder.terminate_with_jmp(b2, vec![]);
 
         builder.switch_to_block(b2);
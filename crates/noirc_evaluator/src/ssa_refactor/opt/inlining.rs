@@ -16,17 +16,30 @@ use crate::ssa_refactor::{
     },
     ssa_builder::FunctionBuilder,
     ssa_gen::Ssa,
+    CallStack,
 };
 
 /// An arbitrary limit to the maximum number of recursive call
 /// frames at any point in time.
 const RECURSION_LIMIT: u32 = 1000;
 
+/// The error returned when inlining a function would exceed the RECURSION_LIMIT.
+///
+/// This happens when a recursive function is called with arguments which are not known at
+/// compile time, such that we cannot tell which of its calls are the last ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RecursionLimitExceeded {
+    /// The name of the function which was being inlined when the limit was reached.
+    pub(crate) function_name: String,
+}
+
 impl Ssa {
     /// Inline all functions within the IR.
     ///
     /// In the case of recursive functions, this will attempt
     /// to recursively inline until the RECURSION_LIMIT is reached.
+    /// Recursive calls are only inlined as long as they are reachable, so recursion ends once the
+    /// conditions guarding it are known to be false. Exceeding the limit results in an error.
     ///
     /// Functions are recursively inlined into main until either we finish
     /// inlining all functions or we encounter a function whose function id is not known.
@@ -35,7 +48,7 @@ impl Ssa {
     /// changes. This is because if the function's id later becomes known by a later
     /// pass, we would need to re-run all of inlining anyway to inline it, so we might
     /// as well save the work for later instead of performing it twice.
-    pub(crate) fn inline_functions(self) -> Ssa {
+    pub(crate) fn inline_functions(self) -> Result<Ssa, RecursionLimitExceeded> {
         InlineContext::new(&self).inline_all(self)
     }
 }
@@ -53,6 +66,10 @@ struct InlineContext {
     /// inlining we can remove all other functions from the resulting Ssa struct and keep only
     /// the function that was inlined into.
     failed_to_inline_a_call: bool,
+
+    /// Set to the function which was being inlined when the RECURSION_LIMIT was reached.
+    /// Once this is set no more calls are inlined, and the remaining calls are kept as they are.
+    recursion_limit_exceeded: Option<FunctionId>,
 }
 
 /// The per-function inlining context contains information that is only valid for one function.
@@ -82,6 +99,10 @@ struct PerFunctionContext<'function> {
 
     /// True if we're currently working on the main function.
     inlining_main: bool,
+
+    /// The call stack of the call being inlined. It is prepended to the call stack of each
+    /// instruction inlined from the source function.
+    call_stack: CallStack,
 }
 
 impl InlineContext {
@@ -93,11 +114,16 @@ impl InlineContext {
     fn new(ssa: &Ssa) -> InlineContext {
         let main_name = ssa.main().name().to_owned();
         let builder = FunctionBuilder::new(main_name, ssa.next_id.next());
-        Self { builder, recursion_level: 0, failed_to_inline_a_call: false }
+        Self {
+            builder,
+            recursion_level: 0,
+            failed_to_inline_a_call: false,
+            recursion_limit_exceeded: None,
+        }
     }
 
     /// Start inlining the main function and all functions reachable from it.
-    fn inline_all(mut self, ssa: Ssa) -> Ssa {
+    fn inline_all(mut self, ssa: Ssa) -> Result<Ssa, RecursionLimitExceeded> {
         let main = ssa.main();
         let mut context = PerFunctionContext::new(&mut self, main);
         context.inlining_main = true;
@@ -126,17 +152,13 @@ impl InlineContext {
         ssa: &Ssa,
         id: FunctionId,
         arguments: &[ValueId],
+        call_stack: CallStack,
     ) -> Vec<ValueId> {
         self.recursion_level += 1;
 
-        if self.recursion_level > RECURSION_LIMIT {
-            panic!(
-                "Attempted to recur more than {RECURSION_LIMIT} times during function inlining."
-            );
-        }
-
         let source_function = &ssa.functions[&id];
         let mut context = PerFunctionContext::new(self, source_function);
+        context.call_stack = call_stack;
 
         let parameters = source_function.parameters();
         assert_eq!(parameters.len(), arguments.len());
@@ -145,12 +167,32 @@ impl InlineContext {
         let current_block = context.context.builder.current_block();
         context.blocks.insert(source_function.entry_block(), current_block);
 
-        context.inline_blocks(ssa)
+        let return_values = context.inline_blocks(ssa);
+        self.recursion_level -= 1;
+        return_values
+    }
+
+    /// Returns true if a call to `function` can be inlined at the current recursion level.
+    /// If not, this remembers that the RECURSION_LIMIT was exceeded by `function`.
+    fn can_inline(&mut self, function: FunctionId) -> bool {
+        if self.recursion_limit_exceeded.is_some() {
+            return false;
+        }
+        if self.recursion_level >= RECURSION_LIMIT {
+            self.recursion_limit_exceeded = Some(function);
+            return false;
+        }
+        true
     }
 
     /// Finish inlining and return the new Ssa struct with the inlined version of main.
     /// If any functions failed to inline, they are not removed from the final Ssa struct.
-    fn finish(self, mut ssa: Ssa) -> Ssa {
+    fn finish(self, mut ssa: Ssa) -> Result<Ssa, RecursionLimitExceeded> {
+        if let Some(function) = self.recursion_limit_exceeded {
+            let function_name = ssa.functions[&function].name().to_owned();
+            return Err(RecursionLimitExceeded { function_name });
+        }
+
         let mut new_ssa = self.builder.finish();
         assert_eq!(new_ssa.functions.len(), 1);
 
@@ -162,9 +204,9 @@ impl InlineContext {
             let new_main = new_ssa.functions.pop_first().unwrap().1;
             ssa.main_id = new_main.id();
             ssa.functions.insert(new_main.id(), new_main);
-            ssa
+            Ok(ssa)
         } else {
-            new_ssa
+            Ok(new_ssa)
         }
     }
 }
@@ -182,6 +224,7 @@ impl<'function> PerFunctionContext<'function> {
             instructions: HashMap::new(),
             values: HashMap::new(),
             inlining_main: false,
+            call_stack: CallStack::new(),
         }
     }
 
@@ -325,8 +368,10 @@ impl<'function> PerFunctionContext<'function> {
         for id in block.instructions() {
             match &self.source_function.dfg[*id] {
                 Instruction::Call { func, arguments } => match self.get_function(*func) {
-                    Some(function) => self.inline_function(ssa, *id, function, arguments),
-                    None => self.push_instruction(*id),
+                    Some(function) if self.context.can_inline(function) => {
+                        self.inline_function(ssa, *id, function, arguments);
+                    }
+                    _ => self.push_instruction(*id),
                 },
                 _ => self.push_instruction(*id),
             }
@@ -343,7 +388,8 @@ impl<'function> PerFunctionContext<'function> {
     ) {
         let old_results = self.source_function.dfg.instruction_results(call_id);
         let arguments = vecmap(arguments, |arg| self.translate_value(*arg));
-        let new_results = self.context.inline_function(ssa, function, &arguments);
+        let call_stack = self.translate_call_stack(call_id);
+        let new_results = self.context.inline_function(ssa, function, &arguments, call_stack);
         let new_results = InsertInstructionResult::Results(&new_results);
         Self::insert_new_instruction_results(&mut self.values, old_results, new_results);
     }
@@ -358,10 +404,20 @@ impl<'function> PerFunctionContext<'function> {
             .requires_ctrl_typevars()
             .then(|| vecmap(results, |result| self.source_function.dfg.type_of_value(*result)));
 
+        let call_stack = self.translate_call_stack(id);
+        self.context.builder.set_call_stack(call_stack);
         let new_results = self.context.builder.insert_instruction(instruction, ctrl_typevars);
         Self::insert_new_instruction_results(&mut self.values, results, new_results);
     }
 
+    /// Returns the call stack of the given instruction from the source_function, prefixed with
+    /// the call stack of the call being inlined.
+    fn translate_call_stack(&self, id: InstructionId) -> CallStack {
+        let mut call_stack = self.call_stack.clone();
+        call_stack.extend(self.source_function.dfg.get_call_stack(id));
+        call_stack
+    }
+
     /// Modify the values HashMap to remember the mapping between an instruction result's previous
     /// ValueId (from the source_function) and its new ValueId in the destination function.
     fn insert_new_instruction_results(
@@ -475,7 +531,7 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 2);
 
-        let inlined = ssa.inline_functions();
+        let inlined = ssa.inline_functions().unwrap();
         assert_eq!(inlined.functions.len(), 1);
     }
 
@@ -541,7 +597,7 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 4);
 
-        let inlined = ssa.inline_functions();
+        let inlined = ssa.inline_functions().unwrap();
         assert_eq!(inlined.functions.len(), 1);
     }
 
@@ -605,7 +661,7 @@ mod test {
         //   b1():
         //     return Field 120
         // }
-        let inlined = ssa.inline_functions();
+        let inlined = ssa.inline_functions().unwrap();
         assert_eq!(inlined.functions.len(), 1);
 
         let main = inlined.main();
@@ -624,4 +680,57 @@ mod test {
             other => unreachable!("Unexpected terminator {other:?}"),
         }
     }
+
+    #[test]
+    fn recursion_with_unknown_depth() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call countdown(v0)
+        //     return v1
+        // }
+        // fn countdown f1 {
+        //   b0(v0: Field):
+        //     v1 = eq v0, Field 0
+        //     jmpif v1, then: b1, else: b2
+        //   b1():
+        //     return Field 0
+        //   b2():
+        //     v2 = sub v0, Field 1
+        //     v3 = call countdown(v2)
+        //     return v3
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id);
+
+        let countdown_id = Id::test_new(1);
+        let countdown = builder.import_function(countdown_id);
+
+        let v0 = builder.add_parameter(Type::field());
+        let results = builder.insert_call(countdown, vec![v0], vec![Type::field()]).to_vec();
+        builder.terminate_with_return(results);
+
+        builder.new_function("countdown".into(), countdown_id);
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+
+        let zero = builder.field_constant(0u128);
+        let one = builder.field_constant(1u128);
+
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.insert_binary(v0, BinaryOp::Eq, zero);
+        builder.terminate_with_jmpif(v1, b1, b2);
+
+        builder.switch_to_block(b1);
+        builder.terminate_with_return(vec![zero]);
+
+        builder.switch_to_block(b2);
+        let countdown = builder.import_function(countdown_id);
+        let v2 = builder.insert_binary(v0, BinaryOp::Sub, one);
+        let v3 = builder.insert_call(countdown, vec![v2], vec![Type::field()]).to_vec();
+        builder.terminate_with_return(v3);
+
+        // The depth of the recursion depends on the parameter of main, so it can't be inlined.
+        let error = builder.finish().inline_functions().unwrap_err();
+        assert_eq!(error.function_name, "countdown");
+    }
 }
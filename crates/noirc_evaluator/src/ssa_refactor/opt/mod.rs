@@ -8,3 +8,5 @@ mod inlining;
 mod mem2reg;
 mod simplify_cfg;
 mod unrolling;
+
+pub(crate) use inlining::RecursionLimitExceeded;
@@ -1,11 +1,14 @@
 use acvm::FieldElement;
 
-use crate::ssa_refactor::ir::{
-    basic_block::BasicBlockId,
-    function::{Function, FunctionId},
-    instruction::{Binary, BinaryOp, Instruction, TerminatorInstruction},
-    types::Type,
-    value::{Value, ValueId},
+use crate::{
+    ssa_refactor::ir::{
+        basic_block::BasicBlockId,
+        function::{Function, FunctionId},
+        instruction::{Binary, BinaryOp, Instruction, TerminatorInstruction},
+        types::Type,
+        value::{Value, ValueId},
+    },
+    Assertion, CallStack,
 };
 
 use super::{
@@ -28,6 +31,8 @@ pub(crate) struct FunctionBuilder {
     pub(super) current_function: Function,
     current_block: BasicBlockId,
     finished_functions: Vec<Function>,
+    /// The call stack new instructions are generated from
+    call_stack: CallStack,
 }
 
 impl FunctionBuilder {
@@ -39,7 +44,12 @@ impl FunctionBuilder {
         let new_function = Function::new(function_name, function_id);
         let current_block = new_function.entry_block();
 
-        Self { current_function: new_function, current_block, finished_functions: Vec::new() }
+        Self {
+            current_function: new_function,
+            current_block,
+            finished_functions: Vec::new(),
+            call_stack: CallStack::new(),
+        }
     }
 
     /// Finish the current function and create a new function.
@@ -114,9 +124,16 @@ impl FunctionBuilder {
             instruction,
             self.current_block,
             ctrl_typevars,
+            self.call_stack.clone(),
         )
     }
 
+    /// Sets the call stack the instructions inserted from now on are generated from.
+    /// Returns the previous call stack.
+    pub(crate) fn set_call_stack(&mut self, call_stack: CallStack) -> CallStack {
+        std::mem::replace(&mut self.call_stack, call_stack)
+    }
+
     /// Switch to inserting instructions in the given block.
     /// Expects the given block to be within the same function. If you want to insert
     /// instructions into a new function, call new_function instead.
@@ -185,8 +202,9 @@ impl FunctionBuilder {
     }
 
     /// Insert a constrain instruction at the end of the current block.
-    pub(crate) fn insert_constrain(&mut self, boolean: ValueId) {
-        self.insert_instruction(Instruction::Constrain(boolean), None);
+    /// The assertion is given when the constraint comes from an `assert` in the source program.
+    pub(crate) fn insert_constrain(&mut self, boolean: ValueId, assertion: Option<Assertion>) {
+        self.insert_instruction(Instruction::Constrain(boolean, assertion), None);
     }
 
     /// Insert a call instruction a the end of the current block and return
@@ -15,6 +15,7 @@ use self::{
 };
 
 use super::ir::{instruction::BinaryOp, types::Type, value::ValueId};
+use crate::Assertion;
 
 /// Generates SSA for the given monomorphized program.
 ///
@@ -53,7 +54,28 @@ impl<'a> FunctionContext<'a> {
         self.builder.terminate_with_return(results);
     }
 
+    /// Codegen an expression. Its instructions are attributed to the innermost expression
+    /// with a location.
     fn codegen_expression(&mut self, expr: &Expression) -> Values {
+        let location = match expr {
+            Expression::Binary(binary) => Some(binary.location),
+            Expression::Index(index) => Some(index.location),
+            Expression::Call(call) => Some(call.location),
+            Expression::Constrain(_, location, _) => Some(*location),
+            _ => None,
+        };
+        match location {
+            Some(location) => {
+                let previous_call_stack = self.builder.set_call_stack(vec![location]);
+                let values = self.codegen_expression_kind(expr);
+                self.builder.set_call_stack(previous_call_stack);
+                values
+            }
+            None => self.codegen_expression_kind(expr),
+        }
+    }
+
+    fn codegen_expression_kind(&mut self, expr: &Expression) -> Values {
         match expr {
             Expression::Ident(ident) => self.codegen_ident(ident),
             Expression::Literal(literal) => self.codegen_literal(literal),
@@ -70,8 +92,8 @@ impl<'a> FunctionContext<'a> {
             }
             Expression::Call(call) => self.codegen_call(call),
             Expression::Let(let_expr) => self.codegen_let(let_expr),
-            Expression::Constrain(constrain, location) => {
-                self.codegen_constrain(constrain, *location)
+            Expression::Constrain(constrain, location, message) => {
+                self.codegen_constrain(constrain, *location, message.clone())
             }
             Expression::Assign(assign) => self.codegen_assign(assign),
             Expression::Semi(semi) => self.codegen_semi(semi),
@@ -371,9 +393,14 @@ impl<'a> FunctionContext<'a> {
         self.unit_value()
     }
 
-    fn codegen_constrain(&mut self, expr: &Expression, _location: Location) -> Values {
+    fn codegen_constrain(
+        &mut self,
+        expr: &Expression,
+        location: Location,
+        message: Option<String>,
+    ) -> Values {
         let boolean = self.codegen_non_tuple_expression(expr);
-        self.builder.insert_constrain(boolean);
+        self.builder.insert_constrain(boolean, Some(Assertion { message, location }));
         self.unit_value()
     }
 
@@ -1,7 +1,10 @@
 use std::fmt::Display;
 
 use crate::token::{Attribute, Token};
-use crate::{Ident, Path, Pattern, Recoverable, Statement, UnresolvedType};
+use crate::{
+    Ident, ItemVisibility, Path, Pattern, Recoverable, Statement, UnresolvedTraitConstraint,
+    UnresolvedType,
+};
 use acvm::FieldElement;
 use iter_extended::vecmap;
 use noirc_errors::{Span, Spanned};
@@ -19,10 +22,12 @@ pub enum ExpressionKind {
     Cast(Box<CastExpression>),
     Infix(Box<InfixExpression>),
     For(Box<ForExpression>),
+    While(Box<WhileExpression>),
     If(Box<IfExpression>),
     Variable(Path),
     Tuple(Vec<Expression>),
     Lambda(Box<Lambda>),
+    Match(Box<MatchExpression>),
     Error,
 }
 
@@ -172,6 +177,15 @@ pub struct ForExpression {
     pub block: Expression,
 }
 
+/// A `while` loop. Loops must be unrolled, so each is bounded by
+/// the maximum number of iterations it may run for.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct WhileExpression {
+    pub condition: Expression,
+    pub body: Expression,
+    pub max_iterations: u64,
+}
+
 pub type BinaryOp = Spanned<BinaryOpKind>;
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Copy, Clone)]
@@ -305,6 +319,43 @@ pub struct IfExpression {
     pub alternative: Option<Expression>,
 }
 
+/// A `match` expression. Each rule pairs a pattern with the expression
+/// evaluated when the matched value is the first to fit that pattern.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MatchExpression {
+    pub expression: Expression,
+    pub rules: Vec<(MatchPattern, Expression)>,
+}
+
+/// The patterns allowed in the rules of a `match` expression. Unlike the patterns of a
+/// `let` statement, these may be refutable.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MatchPattern {
+    /// `_`, which matches any value
+    Wildcard(Span),
+    /// Binds the matched value to a variable. If the name refers to an enum
+    /// variant without fields instead, this matches only that variant.
+    Identifier(Ident),
+    Bool(bool, Span),
+    Integer(FieldElement, Span),
+    Tuple(Vec<MatchPattern>, Span),
+    /// An enum variant along with patterns for each of its fields, e.g. `Option::Some(x)`
+    Variant(Path, Vec<MatchPattern>, Span),
+}
+
+impl MatchPattern {
+    pub fn span(&self) -> Span {
+        match self {
+            MatchPattern::Identifier(name) => name.span(),
+            MatchPattern::Wildcard(span)
+            | MatchPattern::Bool(_, span)
+            | MatchPattern::Integer(_, span)
+            | MatchPattern::Tuple(_, span)
+            | MatchPattern::Variant(_, _, span) => *span,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Lambda {
     pub parameters: Vec<(Pattern, UnresolvedType)>,
@@ -316,8 +367,10 @@ pub struct Lambda {
 pub struct FunctionDefinition {
     pub name: Ident,
 
-    // XXX: Currently we only have one attribute defined. If more attributes are needed per function, we can make this a vector and make attribute definition more expressive
-    pub attribute: Option<Attribute>,
+    /// The visibility of this function outside of its module. Methods are always public.
+    pub visibility: ItemVisibility,
+
+    pub attributes: Vec<Attribute>,
 
     /// True if this function was defined with the 'open' keyword
     pub is_open: bool,
@@ -326,6 +379,10 @@ pub struct FunctionDefinition {
     pub is_unconstrained: bool,
 
     pub generics: UnresolvedGenerics,
+
+    /// The trait bounds on the generics of this function, such as `T: Hash`
+    pub trait_constraints: Vec<UnresolvedTraitConstraint>,
+
     pub parameters: Vec<(Pattern, UnresolvedType, noirc_abi::AbiVisibility)>,
     pub body: BlockExpression,
     pub span: Span,
@@ -420,6 +477,7 @@ impl Display for ExpressionKind {
             Cast(cast) => cast.fmt(f),
             Infix(infix) => infix.fmt(f),
             For(for_loop) => for_loop.fmt(f),
+            While(while_loop) => while_loop.fmt(f),
             If(if_expr) => if_expr.fmt(f),
             Variable(path) => path.fmt(f),
             Constructor(constructor) => constructor.fmt(f),
@@ -429,6 +487,7 @@ impl Display for ExpressionKind {
                 write!(f, "({})", elements.join(", "))
             }
             Lambda(lambda) => lambda.fmt(f),
+            Match(match_expr) => match_expr.fmt(f),
             Error => write!(f, "Error"),
         }
     }
@@ -559,6 +618,16 @@ impl Display for ForExpression {
     }
 }
 
+impl Display for WhileExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "#[max_iterations({})] while {} {}",
+            self.max_iterations, self.condition, self.body
+        )
+    }
+}
+
 impl Display for IfExpression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "if {} {}", self.condition, self.consequence)?;
@@ -569,6 +638,36 @@ impl Display for IfExpression {
     }
 }
 
+impl Display for MatchExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "match {} {{", self.expression)?;
+        for (pattern, branch) in &self.rules {
+            writeln!(f, "    {pattern} => {branch},")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Display for MatchPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchPattern::Wildcard(_) => write!(f, "_"),
+            MatchPattern::Identifier(name) => name.fmt(f),
+            MatchPattern::Bool(value, _) => write!(f, "{value}"),
+            MatchPattern::Integer(value, _) => write!(f, "{}", value.to_u128()),
+            MatchPattern::Tuple(fields, _) => {
+                let fields = vecmap(fields, ToString::to_string);
+                write!(f, "({})", fields.join(", "))
+            }
+            MatchPattern::Variant(path, fields, _) if fields.is_empty() => path.fmt(f),
+            MatchPattern::Variant(path, fields, _) => {
+                let fields = vecmap(fields, ToString::to_string);
+                write!(f, "{path}({})", fields.join(", "))
+            }
+        }
+    }
+}
+
 impl Display for Lambda {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let parameters = vecmap(&self.parameters, |(name, r#type)| format!("{name}: {type}"));
@@ -579,7 +678,7 @@ impl Display for Lambda {
 
 impl Display for FunctionDefinition {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(attribute) = &self.attribute {
+        for attribute in &self.attributes {
             writeln!(f, "{attribute}")?;
         }
 
@@ -589,7 +688,8 @@ impl Display for FunctionDefinition {
 
         write!(
             f,
-            "fn {}({}) -> {} {}",
+            "{}fn {}({}) -> {} {}",
+            self.visibility,
             self.name,
             parameters.join(", "),
             self.return_type,
@@ -48,8 +48,8 @@ impl NoirFunction {
     pub fn parameters(&self) -> &Vec<(Pattern, UnresolvedType, noirc_abi::AbiVisibility)> {
         &self.def.parameters
     }
-    pub fn attribute(&self) -> Option<&Attribute> {
-        self.def.attribute.as_ref()
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.def.attributes
     }
     pub fn def(&self) -> &FunctionDefinition {
         &self.def
@@ -66,19 +66,22 @@ impl NoirFunction {
             FunctionKind::LowLevel => {}
             _ => return None,
         }
-        assert!(self.attribute().unwrap().is_foreign());
+        assert!(self.attributes().iter().any(Attribute::is_foreign));
         Some(&self.def)
     }
 }
 
 impl From<FunctionDefinition> for NoirFunction {
     fn from(fd: FunctionDefinition) -> Self {
-        let kind = match fd.attribute {
-            Some(Attribute::Builtin(_)) => FunctionKind::Builtin,
-            Some(Attribute::Foreign(_)) => FunctionKind::LowLevel,
-            Some(Attribute::Test) => FunctionKind::Normal,
-            None => FunctionKind::Normal,
-        };
+        let kind = fd
+            .attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Builtin(_) => Some(FunctionKind::Builtin),
+                Attribute::Foreign(_) => Some(FunctionKind::LowLevel),
+                Attribute::Test(_) | Attribute::MaxIterations(_) | Attribute::Custom(_) => None,
+            })
+            .unwrap_or(FunctionKind::Normal);
 
         NoirFunction { def: fd, kind }
     }
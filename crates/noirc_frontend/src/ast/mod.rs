@@ -8,6 +8,7 @@ mod expression;
 mod function;
 mod statement;
 mod structure;
+mod traits;
 
 pub use expression::*;
 pub use function::*;
@@ -15,6 +16,7 @@ pub use function::*;
 use noirc_errors::Span;
 pub use statement::*;
 pub use structure::*;
+pub use traits::*;
 
 use crate::{
     parser::{ParserError, ParserErrorReason},
@@ -50,7 +52,11 @@ pub enum UnresolvedType {
     // Note: Tuples have no visibility, instead each of their elements may have one.
     Tuple(Vec<UnresolvedType>),
 
-    Function(/*args:*/ Vec<UnresolvedType>, /*ret:*/ Box<UnresolvedType>),
+    Function(
+        /*args:*/ Vec<UnresolvedType>,
+        /*ret:*/ Box<UnresolvedType>,
+        /*env:*/ Box<UnresolvedType>,
+    ),
 
     Unspecified, // This is for when the user declares a variable without specifying it's type
     Error,
@@ -108,9 +114,13 @@ impl std::fmt::Display for UnresolvedType {
                 None => write!(f, "str[]"),
                 Some(len) => write!(f, "str[{len}]"),
             },
-            Function(args, ret) => {
+            Function(args, ret, env) => {
                 let args = vecmap(args, ToString::to_string);
-                write!(f, "fn({}) -> {ret}", args.join(", "))
+
+                match env.as_ref() {
+                    UnresolvedType::Unit => write!(f, "fn({}) -> {ret}", args.join(", ")),
+                    env => write!(f, "fn[{env}]({}) -> {ret}", args.join(", ")),
+                }
             }
             Vec(args, _span) => {
                 let args = vecmap(args, ToString::to_string);
@@ -151,6 +161,41 @@ pub enum Signedness {
     Signed,
 }
 
+/// Who may refer to an item such as a function, struct or global from outside its module.
+/// Private items are only visible within the module declaring them and its descendants.
+/// Visibilities are ordered from the most restrictive to the least restrictive.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash)]
+pub enum ItemVisibility {
+    Private,
+    /// `pub(crate)`: visible anywhere within the crate declaring the item
+    PublicCrate,
+    /// `pub`: visible to any crate
+    Public,
+}
+
+impl ItemVisibility {
+    /// Describes how an item with this visibility was declared, for errors about items which
+    /// are not visible from where they are referenced
+    pub fn describe_declaration(self) -> &'static str {
+        match self {
+            ItemVisibility::Private => "without `pub`",
+            ItemVisibility::PublicCrate => "as `pub(crate)`",
+            ItemVisibility::Public => "as `pub`",
+        }
+    }
+}
+
+impl std::fmt::Display for ItemVisibility {
+    /// Displays the modifier written before an item, followed by a space unless it is private
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemVisibility::Private => Ok(()),
+            ItemVisibility::PublicCrate => write!(f, "pub(crate) "),
+            ItemVisibility::Public => write!(f, "pub "),
+        }
+    }
+}
+
 impl UnresolvedTypeExpression {
     // This large error size is justified because it improves parsing speeds by around 40% in
     // release mode. See `ParserError` definition for further explanation.
@@ -3,7 +3,10 @@ use std::fmt::Display;
 use crate::lexer::token::SpannedToken;
 use crate::parser::{ParserError, ParserErrorReason};
 use crate::token::Token;
-use crate::{Expression, ExpressionKind, IndexExpression, MemberAccessExpression, UnresolvedType};
+use crate::{
+    Expression, ExpressionKind, IndexExpression, ItemVisibility, MemberAccessExpression,
+    UnresolvedType,
+};
 use iter_extended::vecmap;
 use noirc_errors::{Span, Spanned};
 
@@ -23,6 +26,8 @@ pub enum Statement {
     Constrain(ConstrainStatement),
     Expression(Expression),
     Assign(AssignStatement),
+    Break(Span),
+    Continue(Span),
     // This is an expression with a trailing semi-colon
     Semi(Expression),
     // This statement is the result of a recovered parse error.
@@ -57,6 +62,8 @@ impl Statement {
             Statement::Let(_)
             | Statement::Constrain(_)
             | Statement::Assign(_)
+            | Statement::Break(_)
+            | Statement::Continue(_)
             | Statement::Semi(_)
             | Statement::Error => {
                 // To match rust, statements always require a semicolon, even at the end of a block
@@ -71,7 +78,9 @@ impl Statement {
                     // Semicolons are optional for these expressions
                     (ExpressionKind::Block(_), semi, _)
                     | (ExpressionKind::For(_), semi, _)
-                    | (ExpressionKind::If(_), semi, _) => {
+                    | (ExpressionKind::While(_), semi, _)
+                    | (ExpressionKind::If(_), semi, _)
+                    | (ExpressionKind::Match(_), semi, _) => {
                         if semi.is_some() {
                             Statement::Semi(expr)
                         } else {
@@ -229,6 +238,8 @@ pub trait Recoverable {
 pub struct ImportStatement {
     pub path: Path,
     pub alias: Option<Ident>,
+    /// The visibility of the imported name, which a `pub use` re-exports from this module
+    pub visibility: ItemVisibility,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
@@ -342,7 +353,13 @@ pub enum LValue {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct ConstrainStatement(pub Expression);
+pub struct ConstrainStatement(pub Expression, pub Option<String>);
+
+impl Recoverable for ConstrainStatement {
+    fn error(span: Span) -> Self {
+        ConstrainStatement(Expression::error(span), None)
+    }
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Pattern {
@@ -359,6 +376,15 @@ impl Pattern {
             _ => panic!("only the identifier pattern can return a name"),
         }
     }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Pattern::Identifier(ident) => ident.span(),
+            Pattern::Mutable(_, span) | Pattern::Tuple(_, span) | Pattern::Struct(_, _, span) => {
+                *span
+            }
+        }
+    }
 }
 
 impl Recoverable for Pattern {
@@ -393,6 +419,8 @@ impl Display for Statement {
             Statement::Constrain(constrain) => constrain.fmt(f),
             Statement::Expression(expression) => expression.fmt(f),
             Statement::Assign(assign) => assign.fmt(f),
+            Statement::Break(_) => write!(f, "break"),
+            Statement::Continue(_) => write!(f, "continue"),
             Statement::Semi(semi) => write!(f, "{semi};"),
             Statement::Error => write!(f, "Error"),
         }
@@ -407,7 +435,11 @@ impl Display for LetStatement {
 
 impl Display for ConstrainStatement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "constrain {}", self.0)
+        write!(f, "constrain {}", self.0)?;
+        if let Some(message) = &self.1 {
+            write!(f, ", {message:?}")?;
+        }
+        Ok(())
     }
 }
 
@@ -446,7 +478,7 @@ impl Display for PathKind {
 
 impl Display for ImportStatement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "use {}", self.path)?;
+        write!(f, "{}use {}", self.visibility, self.path)?;
         if let Some(alias) = &self.alias {
             write!(f, " as {alias}")?;
         }
@@ -1,6 +1,9 @@
 use std::fmt::Display;
 
-use crate::{Ident, NoirFunction, UnresolvedGenerics, UnresolvedType};
+use crate::{
+    Ident, ItemVisibility, NoirFunction, UnresolvedGenerics, UnresolvedTraitConstraint,
+    UnresolvedType,
+};
 use iter_extended::vecmap;
 use noirc_errors::Span;
 
@@ -8,28 +11,41 @@ use noirc_errors::Span;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NoirStruct {
     pub name: Ident,
+    pub visibility: ItemVisibility,
     pub generics: UnresolvedGenerics,
-    pub fields: Vec<(Ident, UnresolvedType)>,
+    pub fields: Vec<(Ident, UnresolvedType, ItemVisibility)>,
     pub span: Span,
 }
 
 impl NoirStruct {
     pub fn new(
         name: Ident,
+        visibility: ItemVisibility,
         generics: Vec<Ident>,
-        fields: Vec<(Ident, UnresolvedType)>,
+        fields: Vec<(Ident, UnresolvedType, ItemVisibility)>,
         span: Span,
     ) -> NoirStruct {
-        NoirStruct { name, generics, fields, span }
+        NoirStruct { name, visibility, generics, fields, span }
     }
 }
 
+/// Ast node for an enum. Each variant may carry fields of its own, given by position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NoirEnum {
+    pub name: Ident,
+    pub visibility: ItemVisibility,
+    pub generics: UnresolvedGenerics,
+    pub variants: Vec<(Ident, Vec<UnresolvedType>)>,
+    pub span: Span,
+}
+
 /// Ast node for an impl
 #[derive(Clone, Debug)]
 pub struct NoirImpl {
     pub object_type: UnresolvedType,
     pub type_span: Span,
     pub generics: UnresolvedGenerics,
+    pub trait_constraints: Vec<UnresolvedTraitConstraint>,
     pub methods: Vec<NoirFunction>,
 }
 
@@ -38,10 +54,30 @@ impl Display for NoirStruct {
         let generics = vecmap(&self.generics, |generic| generic.to_string());
         let generics = if generics.is_empty() { "".into() } else { generics.join(", ") };
 
-        writeln!(f, "struct {}{} {{", self.name, generics)?;
+        writeln!(f, "{}struct {}{} {{", self.visibility, self.name, generics)?;
+
+        for (name, typ, visibility) in self.fields.iter() {
+            writeln!(f, "    {visibility}{name}: {typ},")?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl Display for NoirEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let generics = vecmap(&self.generics, |generic| generic.to_string());
+        let generics = if generics.is_empty() { "".into() } else { generics.join(", ") };
+
+        writeln!(f, "{}enum {}{} {{", self.visibility, self.name, generics)?;
 
-        for (name, typ) in self.fields.iter() {
-            writeln!(f, "    {name}: {typ},")?;
+        for (name, fields) in self.variants.iter() {
+            if fields.is_empty() {
+                writeln!(f, "    {name},")?;
+            } else {
+                let fields = vecmap(fields, ToString::to_string);
+                writeln!(f, "    {name}({}),", fields.join(", "))?;
+            }
         }
 
         write!(f, "}}")
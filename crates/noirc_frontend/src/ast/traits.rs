@@ -0,0 +1,88 @@
+use std::fmt::Display;
+
+use iter_extended::vecmap;
+use noirc_errors::Span;
+
+use crate::{Ident, ItemVisibility, NoirFunction, Path, UnresolvedGenerics, UnresolvedType};
+
+/// Ast node for a trait declaration.
+/// Traits only declare the signatures of their methods, which are given
+/// bodies by each `impl Trait for Type`.
+#[derive(Clone, Debug)]
+pub struct NoirTrait {
+    pub name: Ident,
+    pub visibility: ItemVisibility,
+    pub methods: Vec<TraitFunction>,
+    pub span: Span,
+}
+
+/// The signature of a method declared within a trait: `fn name(parameters) -> return_type;`
+#[derive(Clone, Debug)]
+pub struct TraitFunction {
+    pub name: Ident,
+    pub parameters: Vec<(Ident, UnresolvedType)>,
+    pub return_type: UnresolvedType,
+    pub span: Span,
+}
+
+/// Ast node for an implementation of a trait for a type
+#[derive(Clone, Debug)]
+pub struct NoirTraitImpl {
+    pub trait_name: Path,
+    pub object_type: UnresolvedType,
+    pub type_span: Span,
+    pub generics: UnresolvedGenerics,
+    pub trait_constraints: Vec<UnresolvedTraitConstraint>,
+    pub methods: Vec<NoirFunction>,
+}
+
+/// A bound requiring a type to implement a trait, such as the `T: Hash`
+/// in `fn foo<T: Hash>(x: T)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnresolvedTraitConstraint {
+    pub typ: UnresolvedType,
+    pub trait_name: Path,
+}
+
+impl Display for NoirTrait {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}trait {} {{", self.visibility, self.name)?;
+
+        for method in self.methods.iter() {
+            writeln!(f, "    {method}")?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl Display for TraitFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parameters = vecmap(&self.parameters, |(name, typ)| format!("{name}: {typ}"));
+        write!(f, "fn {}({}) -> {};", self.name, parameters.join(", "), self.return_type)
+    }
+}
+
+impl Display for NoirTraitImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let generics = vecmap(&self.generics, |generic| generic.to_string());
+        let generics = if generics.is_empty() { "".into() } else { generics.join(", ") };
+
+        writeln!(f, "impl{} {} for {} {{", generics, self.trait_name, self.object_type)?;
+
+        for method in self.methods.iter() {
+            let method = method.to_string();
+            for line in method.lines() {
+                writeln!(f, "    {line}")?;
+            }
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl Display for UnresolvedTraitConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.typ, self.trait_name)
+    }
+}
@@ -0,0 +1,399 @@
+use super::items::{format_match_pattern, format_path, format_pattern, format_type, pattern_start};
+use super::Printer;
+use crate::token::{Keyword, Token};
+use crate::{
+    ArrayLiteral, BinaryOpKind, BlockExpression, Expression, ExpressionKind, ForExpression, Ident,
+    IfExpression, LValue, Lambda, Literal, MatchExpression, Statement, UnaryOp, UnresolvedType,
+    WhileExpression,
+};
+
+/// How tightly an expression binds its operands. An operand binding less tightly than
+/// the operator it is used with has to be parenthesized.
+fn binding_power(expression: &Expression) -> u8 {
+    match &expression.kind {
+        // The body of a lambda extends as far as possible
+        ExpressionKind::Lambda(_) => 0,
+        ExpressionKind::Infix(infix) => 1 + infix_precedence(infix.operator.contents),
+        ExpressionKind::Prefix(_) => 10,
+        _ => 11,
+    }
+}
+
+/// The precedence levels of the binary operators, from the lowest to the highest
+fn infix_precedence(operator: BinaryOpKind) -> u8 {
+    use BinaryOpKind::*;
+    match operator {
+        Equal | NotEqual => 0,
+        Or => 1,
+        Xor => 2,
+        And => 3,
+        Less | LessEqual | Greater | GreaterEqual => 4,
+        ShiftLeft | ShiftRight => 5,
+        Add | Subtract => 6,
+        Multiply | Divide | Modulo => 7,
+    }
+}
+
+/// Returns the loop variable, the array and the body of a `for x in array { .. }` loop
+/// which the parser desugared into a loop over the indices of the array.
+fn desugared_for_in(block: &BlockExpression) -> Option<(String, &Expression, &Expression)> {
+    let (array, for_loop) = match block.0.as_slice() {
+        [Statement::Let(array), Statement::Expression(for_loop)] => (array, for_loop),
+        _ => return None,
+    };
+    // The parser names the array after a `$` so that it cannot clash with user variables
+    if !format_pattern(&array.pattern).starts_with('$') {
+        return None;
+    }
+    let for_loop = match &for_loop.kind {
+        ExpressionKind::For(for_loop) => for_loop,
+        _ => return None,
+    };
+    let for_block = match &for_loop.block.kind {
+        ExpressionKind::Block(block) => block,
+        _ => return None,
+    };
+    match for_block.0.as_slice() {
+        [Statement::Let(element), Statement::Expression(body)] => {
+            Some((format_pattern(&element.pattern), &array.expression, body))
+        }
+        _ => None,
+    }
+}
+
+impl<'a> Printer<'a> {
+    /// Writes a block whose closing brace is at `end` in the source
+    pub(super) fn block(&mut self, block: &BlockExpression, end: u32) {
+        if block.is_empty() && !self.has_comments_before(end) {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.newline();
+        self.indented(|printer| {
+            let last = block.len().saturating_sub(1);
+            for (i, statement) in block.0.iter().enumerate() {
+                printer.leading_trivia(printer.statement_start(statement));
+                printer.statement(statement, i == last);
+                printer.newline();
+            }
+            printer.emit_comments_before(end);
+        });
+        self.write("}");
+    }
+
+    fn statement_start(&self, statement: &Statement) -> u32 {
+        let keyword_before = |position: u32, keywords: &[Keyword]| {
+            let is_keyword = |token: &Token| matches!(token, Token::Keyword(keyword) if keywords.contains(keyword));
+            self.last_token_before(position, is_keyword).unwrap_or(position)
+        };
+
+        match statement {
+            Statement::Let(let_statement) => {
+                keyword_before(pattern_start(&let_statement.pattern), &[Keyword::Let])
+            }
+            Statement::Constrain(constrain) => {
+                keyword_before(constrain.0.span.start(), &[Keyword::Assert, Keyword::Constrain])
+            }
+            Statement::Assign(assign) => {
+                let mut lvalue = &assign.lvalue;
+                loop {
+                    match lvalue {
+                        LValue::Ident(name) => return name.span().start(),
+                        LValue::MemberAccess { object, .. } => lvalue = object.as_ref(),
+                        LValue::Index { array, .. } => lvalue = array.as_ref(),
+                    }
+                }
+            }
+            Statement::Break(span) | Statement::Continue(span) => span.start(),
+            // The span of an expression does not include its outermost parentheses
+            Statement::Expression(expression) | Statement::Semi(expression) => {
+                self.extend_back(expression.span.start(), |token| *token == Token::LeftParen)
+            }
+            Statement::Error => unreachable!("the formatter only runs on programs without errors"),
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement, is_last: bool) {
+        match statement {
+            Statement::Let(let_statement) => {
+                self.write(&format!("let {}", format_pattern(&let_statement.pattern)));
+                if let_statement.r#type != UnresolvedType::Unspecified {
+                    self.write(&format!(": {}", format_type(&let_statement.r#type)));
+                }
+                self.write(" = ");
+                self.expression(&let_statement.expression);
+                self.write(";");
+            }
+            Statement::Constrain(constrain) => {
+                self.write("assert(");
+                self.expression(&constrain.0);
+                if let Some(message) = &constrain.1 {
+                    self.write(&format!(", \"{message}\""));
+                }
+                self.write(");");
+            }
+            Statement::Assign(assign) => {
+                self.lvalue(&assign.lvalue);
+                match &assign.expression.kind {
+                    // The parser desugars `a += b` into `a = a + b`, where the lhs of the
+                    // operation spans the whole statement
+                    ExpressionKind::Infix(infix) if infix.lhs.span == assign.expression.span => {
+                        self.write(&format!(" {}= ", infix.operator.contents.as_string()));
+                        self.expression(&infix.rhs);
+                    }
+                    _ => {
+                        self.write(" = ");
+                        self.expression(&assign.expression);
+                    }
+                }
+                self.write(";");
+            }
+            Statement::Break(_) => self.write("break;"),
+            Statement::Continue(_) => self.write("continue;"),
+            Statement::Expression(expression) => {
+                self.expression(expression);
+                let is_block_like = matches!(
+                    expression.kind,
+                    ExpressionKind::Block(_)
+                        | ExpressionKind::If(_)
+                        | ExpressionKind::For(_)
+                        | ExpressionKind::While(_)
+                        | ExpressionKind::Match(_)
+                );
+                if !is_last && !is_block_like {
+                    self.write(";");
+                }
+            }
+            Statement::Semi(expression) => {
+                self.expression(expression);
+                self.write(";");
+            }
+            Statement::Error => unreachable!("the formatter only runs on programs without errors"),
+        }
+    }
+
+    fn lvalue(&mut self, lvalue: &LValue) {
+        match lvalue {
+            LValue::Ident(name) => self.write(&name.to_string()),
+            LValue::MemberAccess { object, field_name } => {
+                self.lvalue(object);
+                self.write(&format!(".{field_name}"));
+            }
+            LValue::Index { array, index } => {
+                self.lvalue(array);
+                self.write("[");
+                self.expression(index);
+                self.write("]");
+            }
+        }
+    }
+
+    pub(super) fn expression(&mut self, expression: &Expression) {
+        let end = expression.span.end();
+        match &expression.kind {
+            ExpressionKind::Literal(literal) => self.literal(literal, expression),
+            ExpressionKind::Block(block) => match desugared_for_in(block) {
+                Some((element, array, body)) => {
+                    self.write(&format!("for {element} in "));
+                    self.expression(array);
+                    self.write(" ");
+                    self.expression(body);
+                }
+                None => self.block(block, end),
+            },
+            ExpressionKind::Prefix(prefix) => {
+                let operator = match prefix.operator {
+                    UnaryOp::Minus => "-",
+                    UnaryOp::Not => "!",
+                };
+                self.write(operator);
+                self.operand(&prefix.rhs, binding_power(&prefix.rhs) < 10);
+            }
+            ExpressionKind::Index(index) => {
+                self.postfix_operand(&index.collection);
+                self.write("[");
+                self.expression(&index.index);
+                self.write("]");
+            }
+            ExpressionKind::Call(call) => {
+                self.postfix_operand(&call.func);
+                self.arguments(&call.arguments, end);
+            }
+            ExpressionKind::MethodCall(call) => {
+                self.postfix_operand(&call.object);
+                self.write(&format!(".{}", call.method_name));
+                self.arguments(&call.arguments, end);
+            }
+            ExpressionKind::MemberAccess(access) => {
+                self.postfix_operand(&access.lhs);
+                self.write(&format!(".{}", access.rhs));
+            }
+            ExpressionKind::Cast(cast) => {
+                self.postfix_operand(&cast.lhs);
+                self.write(&format!(" as {}", format_type(&cast.r#type)));
+            }
+            ExpressionKind::Constructor(constructor) => {
+                self.write(&format!("{} ", format_path(&constructor.type_name)));
+                let field_start = |(name, _): &(Ident, _)| Some(name.span().start());
+                self.list(
+                    ("{", "}"),
+                    &constructor.fields,
+                    Some(end),
+                    true,
+                    field_start,
+                    |printer, field| {
+                        let (name, value) = field;
+                        // The short form `Foo { x }` reuses the field name as the expression
+                        let is_short = value.span == name.span()
+                            && matches!(&value.kind, ExpressionKind::Variable(_));
+                        if !is_short {
+                            printer.write(&format!("{name}: "));
+                        }
+                        printer.expression(value);
+                    },
+                );
+            }
+            ExpressionKind::Infix(infix) => {
+                let power = binding_power(expression);
+                self.operand(&infix.lhs, binding_power(&infix.lhs) < power);
+                self.write(&format!(" {} ", infix.operator.contents.as_string()));
+                self.operand(&infix.rhs, binding_power(&infix.rhs) <= power);
+            }
+            ExpressionKind::For(for_loop) => self.for_loop(for_loop),
+            ExpressionKind::While(while_loop) => self.while_loop(while_loop),
+            ExpressionKind::If(if_expression) => self.if_expression(if_expression),
+            ExpressionKind::Variable(path) => self.write(&format_path(path)),
+            ExpressionKind::Tuple(elements) if elements.len() == 1 => {
+                self.write("(");
+                self.expression(&elements[0]);
+                self.write(",)");
+            }
+            ExpressionKind::Tuple(elements) => {
+                let start = |element: &Expression| Some(element.span.start());
+                self.list(("(", ")"), elements, Some(end), false, start, Self::expression);
+            }
+            ExpressionKind::Lambda(lambda) => self.lambda(lambda),
+            ExpressionKind::Match(match_expression) => self.match_expression(match_expression, end),
+            ExpressionKind::Error => {
+                unreachable!("the formatter only runs on programs without errors")
+            }
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal, expression: &Expression) {
+        match literal {
+            Literal::Array(ArrayLiteral::Standard(elements)) => {
+                let start = |element: &Expression| Some(element.span.start());
+                let end = Some(expression.span.end());
+                self.list(("[", "]"), elements, end, false, start, Self::expression);
+            }
+            Literal::Array(ArrayLiteral::Repeated { repeated_element, length }) => {
+                self.write("[");
+                self.expression(repeated_element);
+                self.write("; ");
+                self.expression(length);
+                self.write("]");
+            }
+            Literal::Bool(value) => self.write(&value.to_string()),
+            Literal::Integer(value) => {
+                let text = self.integer_literal_at(expression.span.start());
+                self.write(&text.unwrap_or_else(|| value.to_string()));
+            }
+            Literal::Str(string) => self.write(&format!("\"{string}\"")),
+        }
+    }
+
+    fn arguments(&mut self, arguments: &[Expression], end: u32) {
+        let start = |argument: &Expression| Some(argument.span.start());
+        self.list(("(", ")"), arguments, Some(end), false, start, Self::expression);
+    }
+
+    fn operand(&mut self, operand: &Expression, parenthesized: bool) {
+        if parenthesized {
+            self.write("(");
+            self.expression(operand);
+            self.write(")");
+        } else {
+            self.expression(operand);
+        }
+    }
+
+    /// Writes the lhs of an index, call, member access or cast, which bind the most tightly
+    fn postfix_operand(&mut self, operand: &Expression) {
+        self.operand(operand, binding_power(operand) < 11);
+    }
+
+    fn for_loop(&mut self, for_loop: &ForExpression) {
+        self.write(&format!("for {} in ", for_loop.identifier));
+        self.expression(&for_loop.start_range);
+        self.write("..");
+        self.expression(&for_loop.end_range);
+        self.write(" ");
+        self.expression(&for_loop.block);
+    }
+
+    fn while_loop(&mut self, while_loop: &WhileExpression) {
+        self.write(&format!("#[max_iterations({})]", while_loop.max_iterations));
+        self.newline();
+        self.write("while ");
+        self.expression(&while_loop.condition);
+        self.write(" ");
+        self.expression(&while_loop.body);
+    }
+
+    fn if_expression(&mut self, if_expression: &IfExpression) {
+        self.write("if ");
+        self.expression(&if_expression.condition);
+        self.write(" ");
+        self.expression(&if_expression.consequence);
+
+        let alternative = match &if_expression.alternative {
+            Some(alternative) => alternative,
+            None => return,
+        };
+        self.write(" else ");
+        // The parser wraps the `if` of an `else if` into a block with the same span
+        match &alternative.kind {
+            ExpressionKind::Block(BlockExpression(statements)) => match statements.as_slice() {
+                [Statement::Expression(inner)] if inner.span == alternative.span => {
+                    self.expression(inner);
+                }
+                _ => self.expression(alternative),
+            },
+            _ => self.expression(alternative),
+        }
+    }
+
+    /// Writes a match expression whose closing brace is at `end` in the source
+    fn match_expression(&mut self, match_expression: &MatchExpression, end: u32) {
+        self.write("match ");
+        self.expression(&match_expression.expression);
+        self.write(" {");
+        self.newline();
+        self.indented(|printer| {
+            for (pattern, branch) in &match_expression.rules {
+                printer.leading_trivia(pattern.span().start());
+                printer.write(&format!("{} => ", format_match_pattern(pattern)));
+                printer.expression(branch);
+                printer.write(",");
+                printer.newline();
+            }
+            printer.emit_comments_before(end);
+        });
+        self.write("}");
+    }
+
+    fn lambda(&mut self, lambda: &Lambda) {
+        let parameters = lambda.parameters.iter().map(|(pattern, typ)| match typ {
+            UnresolvedType::Unspecified => format_pattern(pattern),
+            typ => format!("{}: {}", format_pattern(pattern), format_type(typ)),
+        });
+        self.write(&format!("|{}| ", parameters.collect::<Vec<_>>().join(", ")));
+        if lambda.return_type != UnresolvedType::Unspecified {
+            self.write(&format!("-> {} ", format_type(&lambda.return_type)));
+        }
+        self.expression(&lambda.body);
+    }
+}
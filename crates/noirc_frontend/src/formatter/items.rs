@@ -0,0 +1,555 @@
+use iter_extended::vecmap;
+use noirc_abi::{AbiDistinctness, AbiVisibility};
+use noirc_errors::Span;
+
+use super::Printer;
+use crate::parser::SubModule;
+use crate::token::{Keyword, Token};
+use crate::{
+    BinaryTypeOperator, CompTime, Ident, ImportStatement, ItemVisibility, LetStatement,
+    MatchPattern, NoirEnum, NoirFunction, NoirImpl, NoirStruct, NoirTrait, NoirTraitImpl,
+    ParsedModule, Path, PathKind, Pattern, Signedness, TraitFunction, UnresolvedTraitConstraint,
+    UnresolvedType, UnresolvedTypeExpression,
+};
+
+/// A top level statement of a module. The parser groups these by kind,
+/// so they are collected back together to be printed in the order of the source.
+enum Item<'m> {
+    Import(&'m ImportStatement),
+    ModuleDecl(&'m Ident),
+    Global(&'m (LetStatement, ItemVisibility)),
+    Struct(&'m NoirStruct),
+    Enum(&'m NoirEnum),
+    Function(&'m NoirFunction),
+    Impl(&'m NoirImpl),
+    Trait(&'m NoirTrait),
+    TraitImpl(&'m NoirTraitImpl),
+    SubModule(&'m SubModule),
+}
+
+impl Item<'_> {
+    /// Items with a body are always separated from the surrounding items by an empty line
+    fn has_body(&self) -> bool {
+        !matches!(self, Item::Import(_) | Item::ModuleDecl(_) | Item::Global(_))
+    }
+}
+
+impl<'a> Printer<'a> {
+    pub(super) fn module(&mut self, module: &ParsedModule) {
+        let mut items = Vec::new();
+        items.extend(module.imports.iter().map(Item::Import));
+        items.extend(module.module_decls.iter().map(Item::ModuleDecl));
+        items.extend(module.globals.iter().map(Item::Global));
+        items.extend(module.types.iter().map(Item::Struct));
+        items.extend(module.enums.iter().map(Item::Enum));
+        items.extend(module.functions.iter().map(Item::Function));
+        items.extend(module.impls.iter().map(Item::Impl));
+        items.extend(module.traits.iter().map(Item::Trait));
+        items.extend(module.trait_impls.iter().map(Item::TraitImpl));
+        items.extend(module.submodules.iter().map(Item::SubModule));
+
+        let mut items = vecmap(items, |item| (self.item_start(&item), item));
+        items.sort_by_key(|(start, _)| *start);
+
+        let mut after_body = false;
+        for (start, item) in items {
+            if item.has_body() || after_body {
+                self.blank_line();
+            }
+            self.leading_trivia(start);
+            self.item(&item);
+            self.newline();
+            after_body = item.has_body();
+        }
+    }
+
+    /// Returns the position of the first token of an item, including its attributes and modifiers
+    fn item_start(&self, item: &Item) -> u32 {
+        let keyword_before = |position: u32, keyword: Keyword| {
+            self.last_token_before(position, |token| *token == Token::Keyword(keyword))
+                .unwrap_or(position)
+        };
+
+        let visibility_before = |position: u32| self.extend_back(position, is_visibility_token);
+
+        match item {
+            Item::Import(import) => {
+                visibility_before(keyword_before(import.path.span().start(), Keyword::Use))
+            }
+            Item::ModuleDecl(name) => keyword_before(name.span().start(), Keyword::Mod),
+            Item::Global((global, _)) => {
+                visibility_before(keyword_before(pattern_start(&global.pattern), Keyword::Global))
+            }
+            Item::Struct(noir_struct) => noir_struct.span.start(),
+            Item::Enum(noir_enum) => noir_enum.span.start(),
+            Item::Function(function) => self.function_start(function),
+            Item::Impl(noir_impl) => keyword_before(noir_impl.type_span.start(), Keyword::Impl),
+            Item::Trait(noir_trait) => noir_trait.span.start(),
+            Item::TraitImpl(trait_impl) => {
+                keyword_before(trait_impl.trait_name.span().start(), Keyword::Impl)
+            }
+            Item::SubModule(submodule) => {
+                let name = submodule.name.span().start();
+                let keyword = if submodule.is_contract { Keyword::Contract } else { Keyword::Mod };
+                keyword_before(name, keyword)
+            }
+        }
+    }
+
+    fn function_start(&self, function: &NoirFunction) -> u32 {
+        let name = function.name_ident().span().start();
+        let fn_keyword = self
+            .last_token_before(name, |token| *token == Token::Keyword(Keyword::Fn))
+            .unwrap_or(name);
+        self.extend_back(fn_keyword, |token| {
+            is_visibility_token(token)
+                || matches!(
+                    token,
+                    Token::Attribute(_)
+                        | Token::Keyword(Keyword::Open)
+                        | Token::Keyword(Keyword::Unconstrained)
+                )
+        })
+    }
+
+    fn item(&mut self, item: &Item) {
+        match item {
+            Item::Import(import) => {
+                self.write(&format!("{}use {}", import.visibility, format_path(&import.path)));
+                if let Some(alias) = &import.alias {
+                    self.write(&format!(" as {alias}"));
+                }
+                self.write(";");
+            }
+            Item::ModuleDecl(name) => self.write(&format!("mod {name};")),
+            Item::Global((global, visibility)) => self.global(global, *visibility),
+            Item::Struct(noir_struct) => self.noir_struct(noir_struct),
+            Item::Enum(noir_enum) => self.noir_enum(noir_enum),
+            Item::Function(function) => self.function(function),
+            Item::Impl(noir_impl) => self.noir_impl(noir_impl),
+            Item::Trait(noir_trait) => self.noir_trait(noir_trait),
+            Item::TraitImpl(trait_impl) => self.trait_impl(trait_impl),
+            Item::SubModule(submodule) => self.submodule(submodule),
+        }
+    }
+
+    fn global(&mut self, global: &LetStatement, visibility: ItemVisibility) {
+        self.write(&format!("{visibility}global {}", format_pattern(&global.pattern)));
+        // The parser marks the annotated type of a global as comptime by itself
+        let typ = match &global.r#type {
+            UnresolvedType::FieldElement(_) => {
+                Some(UnresolvedType::FieldElement(CompTime::No(None)))
+            }
+            UnresolvedType::Bool(_) => Some(UnresolvedType::Bool(CompTime::No(None))),
+            UnresolvedType::Integer(_, sign, size) => {
+                Some(UnresolvedType::Integer(CompTime::No(None), *sign, *size))
+            }
+            UnresolvedType::Unspecified => None,
+            other => Some(other.clone()),
+        };
+        if let Some(typ) = typ {
+            self.write(&format!(": {}", format_type(&typ)));
+        }
+        self.write(" = ");
+        self.expression(&global.expression);
+        self.write(";");
+    }
+
+    fn noir_struct(&mut self, noir_struct: &NoirStruct) {
+        let end = noir_struct.span.end();
+        let generics = format_generics(&noir_struct.generics);
+        self.write(&format!("{}struct {}{generics} ", noir_struct.visibility, noir_struct.name));
+
+        if noir_struct.fields.is_empty() && !self.has_comments_before(end) {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.newline();
+        self.indented(|printer| {
+            for (name, typ, visibility) in &noir_struct.fields {
+                printer.leading_trivia(name.span().start());
+                printer.write(&format!("{visibility}{name}: {},", format_type(typ)));
+                printer.newline();
+            }
+            printer.emit_comments_before(end);
+        });
+        self.write("}");
+    }
+
+    fn noir_enum(&mut self, noir_enum: &NoirEnum) {
+        let end = noir_enum.span.end();
+        let generics = format_generics(&noir_enum.generics);
+        self.write(&format!("{}enum {}{generics} ", noir_enum.visibility, noir_enum.name));
+
+        if noir_enum.variants.is_empty() && !self.has_comments_before(end) {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.newline();
+        self.indented(|printer| {
+            for (name, fields) in &noir_enum.variants {
+                printer.leading_trivia(name.span().start());
+                if fields.is_empty() {
+                    printer.write(&format!("{name},"));
+                } else {
+                    let fields = vecmap(fields, format_type).join(", ");
+                    printer.write(&format!("{name}({fields}),"));
+                }
+                printer.newline();
+            }
+            printer.emit_comments_before(end);
+        });
+        self.write("}");
+    }
+
+    fn function(&mut self, function: &NoirFunction) {
+        let def = function.def();
+        for attribute in &def.attributes {
+            self.write(&attribute.to_string());
+            self.newline();
+        }
+        self.write(&def.visibility.to_string());
+        if def.is_unconstrained {
+            self.write("unconstrained ");
+        }
+        if def.is_open {
+            self.write("open ");
+        }
+        let generics = format_generics_with_constraints(&def.generics, &def.trait_constraints);
+        self.write(&format!("fn {}{generics}", def.name));
+
+        let parameter_start = |(pattern, _, _): &(Pattern, _, _)| Some(pattern_start(pattern));
+        self.list(
+            ("(", ")"),
+            &def.parameters,
+            None,
+            false,
+            parameter_start,
+            |printer, parameter| {
+                let (pattern, typ, visibility) = parameter;
+                let name = format_pattern(pattern);
+                if is_self_parameter(pattern_span(pattern), typ) {
+                    printer.write(&name);
+                } else {
+                    let visibility = if *visibility == AbiVisibility::Public { "pub " } else { "" };
+                    printer.write(&format!("{name}: {visibility}{}", format_type(typ)));
+                }
+            },
+        );
+
+        let is_distinct = def.return_distinctness == AbiDistinctness::Distinct;
+        let is_public = def.return_visibility == AbiVisibility::Public;
+        if def.return_type != UnresolvedType::Unit || is_distinct || is_public {
+            self.write(" -> ");
+            if is_distinct {
+                self.write("distinct ");
+            }
+            if is_public {
+                self.write("pub ");
+            }
+            self.write(&format_type(&def.return_type));
+        }
+
+        self.write(" ");
+        let body_end = self.function_body_end(def.name.span().start());
+        self.block(&def.body, body_end);
+    }
+
+    /// Returns the position of the closing brace of the body of the function named at `name`.
+    /// Braces within the parameters, as in struct patterns, are skipped.
+    fn function_body_end(&self, name: u32) -> u32 {
+        let mut parentheses = 0;
+        for token in &self.tokens[self.token_index(name)..] {
+            match token.token() {
+                Token::LeftParen => parentheses += 1,
+                Token::RightParen => parentheses -= 1,
+                Token::LeftBrace if parentheses == 0 => {
+                    return self.closing_brace_after(token.to_span().start())
+                }
+                _ => (),
+            }
+        }
+        u32::MAX
+    }
+
+    fn noir_impl(&mut self, noir_impl: &NoirImpl) {
+        let end = self.closing_brace_after(noir_impl.type_span.start());
+        let generics =
+            format_generics_with_constraints(&noir_impl.generics, &noir_impl.trait_constraints);
+        self.write(&format!("impl{generics} {} ", format_type(&noir_impl.object_type)));
+        self.impl_methods(&noir_impl.methods, end);
+    }
+
+    fn trait_impl(&mut self, trait_impl: &NoirTraitImpl) {
+        let end = self.closing_brace_after(trait_impl.type_span.start());
+        let generics =
+            format_generics_with_constraints(&trait_impl.generics, &trait_impl.trait_constraints);
+        let trait_name = format_path(&trait_impl.trait_name);
+        let object_type = format_type(&trait_impl.object_type);
+        self.write(&format!("impl{generics} {trait_name} for {object_type} "));
+        self.impl_methods(&trait_impl.methods, end);
+    }
+
+    /// Prints the braced methods of an impl, whose closing brace is at `end`
+    fn impl_methods(&mut self, methods: &[NoirFunction], end: u32) {
+        if methods.is_empty() && !self.has_comments_before(end) {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.newline();
+        self.indented(|printer| {
+            for method in methods {
+                printer.blank_line();
+                printer.emit_comments_before(printer.function_start(method));
+                printer.function(method);
+                printer.newline();
+            }
+            printer.emit_comments_before(end);
+        });
+        self.write("}");
+    }
+
+    fn noir_trait(&mut self, noir_trait: &NoirTrait) {
+        let end = noir_trait.span.end();
+        self.write(&format!("{}trait {} ", noir_trait.visibility, noir_trait.name));
+
+        if noir_trait.methods.is_empty() && !self.has_comments_before(end) {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.newline();
+        self.indented(|printer| {
+            for method in &noir_trait.methods {
+                printer.leading_trivia(method.span.start());
+                printer.trait_function(method);
+                printer.newline();
+            }
+            printer.emit_comments_before(end);
+        });
+        self.write("}");
+    }
+
+    fn trait_function(&mut self, function: &TraitFunction) {
+        let parameters = vecmap(&function.parameters, |(name, typ)| {
+            if is_self_parameter(name.span(), typ) {
+                name.to_string()
+            } else {
+                format!("{name}: {}", format_type(typ))
+            }
+        });
+        self.write(&format!("fn {}({})", function.name, parameters.join(", ")));
+        if function.return_type != UnresolvedType::Unit {
+            self.write(&format!(" -> {}", format_type(&function.return_type)));
+        }
+        self.write(";");
+    }
+
+    fn submodule(&mut self, submodule: &SubModule) {
+        let end = self.closing_brace_after(submodule.name.span().start());
+        let keyword = if submodule.is_contract { "contract" } else { "mod" };
+        self.write(&format!("{keyword} {} {{", submodule.name));
+        self.newline();
+        self.indented(|printer| {
+            printer.module(&submodule.contents);
+            printer.emit_comments_before(end);
+        });
+        self.write("}");
+    }
+}
+
+/// True for the tokens of a `pub` or `pub(crate)` modifier
+fn is_visibility_token(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Keyword(Keyword::Pub | Keyword::Crate) | Token::LeftParen | Token::RightParen
+    )
+}
+
+/// The parser gives a `self` parameter the type `Self` spanning the parameter name
+fn is_self_parameter(name: Span, typ: &UnresolvedType) -> bool {
+    match typ {
+        UnresolvedType::Named(path, _) => path.span() == name,
+        _ => false,
+    }
+}
+
+pub(super) fn pattern_start(pattern: &Pattern) -> u32 {
+    pattern_span(pattern).start()
+}
+
+fn pattern_span(pattern: &Pattern) -> Span {
+    match pattern {
+        Pattern::Identifier(name) => name.span(),
+        Pattern::Mutable(_, span) | Pattern::Tuple(_, span) | Pattern::Struct(_, _, span) => *span,
+    }
+}
+
+pub(super) fn format_pattern(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Identifier(name) => name.to_string(),
+        Pattern::Mutable(pattern, _) => format!("mut {}", format_pattern(pattern)),
+        Pattern::Tuple(patterns, _) => format!("({})", vecmap(patterns, format_pattern).join(", ")),
+        Pattern::Struct(path, fields, _) => {
+            let fields = vecmap(fields, |(name, pattern)| match pattern {
+                // The short form `Foo { x }` reuses the field name as the pattern
+                Pattern::Identifier(ident) if ident.span() == name.span() => name.to_string(),
+                pattern => format!("{name}: {}", format_pattern(pattern)),
+            });
+            if fields.is_empty() {
+                format!("{} {{}}", format_path(path))
+            } else {
+                format!("{} {{ {} }}", format_path(path), fields.join(", "))
+            }
+        }
+    }
+}
+
+pub(super) fn format_match_pattern(pattern: &MatchPattern) -> String {
+    match pattern {
+        MatchPattern::Wildcard(_) => "_".to_owned(),
+        MatchPattern::Identifier(name) => name.to_string(),
+        MatchPattern::Bool(value, _) => value.to_string(),
+        MatchPattern::Integer(value, _) => value.to_u128().to_string(),
+        MatchPattern::Tuple(patterns, _) => {
+            format!("({})", vecmap(patterns, format_match_pattern).join(", "))
+        }
+        MatchPattern::Variant(path, fields, _) if fields.is_empty() => format_path(path),
+        MatchPattern::Variant(path, fields, _) => {
+            format!("{}({})", format_path(path), vecmap(fields, format_match_pattern).join(", "))
+        }
+    }
+}
+
+pub(super) fn format_path(path: &Path) -> String {
+    let segments = vecmap(&path.segments, ToString::to_string).join("::");
+    match path.kind {
+        PathKind::Crate => format!("crate::{segments}"),
+        PathKind::Dep => format!("dep::{segments}"),
+        PathKind::Plain => segments,
+    }
+}
+
+fn format_generics(generics: &[Ident]) -> String {
+    if generics.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", vecmap(generics, ToString::to_string).join(", "))
+    }
+}
+
+/// Formats generics along with their trait bounds, as in `<T: Hash + Eq, U>`
+fn format_generics_with_constraints(
+    generics: &[Ident],
+    constraints: &[UnresolvedTraitConstraint],
+) -> String {
+    if generics.is_empty() {
+        return String::new();
+    }
+
+    let generics = vecmap(generics, |generic| {
+        let bounds = constraints.iter().filter_map(|constraint| match &constraint.typ {
+            UnresolvedType::Named(path, _) if path.as_ident() == Some(generic) => {
+                Some(format_path(&constraint.trait_name))
+            }
+            _ => None,
+        });
+        let bounds = bounds.collect::<Vec<_>>();
+        if bounds.is_empty() {
+            generic.to_string()
+        } else {
+            format!("{generic}: {}", bounds.join(" + "))
+        }
+    });
+    format!("<{}>", generics.join(", "))
+}
+
+pub(super) fn format_type(typ: &UnresolvedType) -> String {
+    let comptime = |comptime: &CompTime| match comptime {
+        CompTime::Yes(_) => "comptime ",
+        CompTime::No(_) | CompTime::Maybe(..) => "",
+    };
+    fn type_list(types: &[UnresolvedType]) -> String {
+        vecmap(types, format_type).join(", ")
+    }
+
+    match typ {
+        UnresolvedType::FieldElement(is_comptime) => format!("{}Field", comptime(is_comptime)),
+        UnresolvedType::Bool(is_comptime) => format!("{}bool", comptime(is_comptime)),
+        UnresolvedType::Integer(is_comptime, sign, bits) => {
+            let sign = if *sign == Signedness::Signed { "i" } else { "u" };
+            format!("{}{sign}{bits}", comptime(is_comptime))
+        }
+        UnresolvedType::Array(None, element) => format!("[{}]", format_type(element)),
+        UnresolvedType::Array(Some(length), element) => {
+            format!("[{}; {}]", format_type(element), format_type_expression(length))
+        }
+        UnresolvedType::Expression(expression) => format_type_expression(expression),
+        UnresolvedType::String(None) => "str".to_owned(),
+        UnresolvedType::String(Some(length)) => format!("str<{}>", format_type_expression(length)),
+        UnresolvedType::Unit => "()".to_owned(),
+        UnresolvedType::Named(path, args) if args.is_empty() => format_path(path),
+        UnresolvedType::Named(path, args) => format!("{}<{}>", format_path(path), type_list(args)),
+        UnresolvedType::Vec(args, _) => format!("Vec<{}>", type_list(args)),
+        UnresolvedType::Tuple(elements) => format!("({})", type_list(elements)),
+        UnresolvedType::Function(args, ret, env) => match env.as_ref() {
+            UnresolvedType::Unit => format!("fn({}) -> {}", type_list(args), format_type(ret)),
+            env => format!("fn[{}]({}) -> {}", format_type(env), type_list(args), format_type(ret)),
+        },
+        UnresolvedType::Unspecified | UnresolvedType::Error => {
+            unreachable!("the formatter only prints types written in the source")
+        }
+    }
+}
+
+fn format_type_expression(expression: &UnresolvedTypeExpression) -> String {
+    use UnresolvedTypeExpression::*;
+
+    fn precedence(expression: &UnresolvedTypeExpression) -> u8 {
+        match expression {
+            BinaryOperation(lhs, _, _, span) if is_negation(lhs, *span) => 2,
+            BinaryOperation(
+                _,
+                BinaryTypeOperator::Addition | BinaryTypeOperator::Subtraction,
+                ..,
+            ) => 0,
+            BinaryOperation(..) => 1,
+            Variable(_) | Constant(..) => 3,
+        }
+    }
+
+    // The parser desugars `-x` into `0 - x` where the constant shares the span of the negation
+    fn is_negation(lhs: &UnresolvedTypeExpression, span: Span) -> bool {
+        matches!(lhs, Constant(0, lhs_span) if *lhs_span == span)
+    }
+
+    let operand = |operand: &UnresolvedTypeExpression, parenthesized: bool| {
+        let operand_string = format_type_expression(operand);
+        if parenthesized {
+            format!("({operand_string})")
+        } else {
+            operand_string
+        }
+    };
+
+    match expression {
+        Variable(path) => format_path(path),
+        Constant(value, _) => value.to_string(),
+        BinaryOperation(lhs, _, rhs, span) if is_negation(lhs, *span) => {
+            format!("-{}", operand(rhs, precedence(rhs) < 2))
+        }
+        BinaryOperation(lhs, operator, rhs, _) => {
+            let own_precedence = precedence(expression);
+            let lhs = operand(lhs, precedence(lhs) < own_precedence);
+            let rhs = operand(rhs, precedence(rhs) <= own_precedence);
+            format!("{lhs} {operator} {rhs}")
+        }
+    }
+}
@@ -0,0 +1,643 @@
+//! The formatter prints the Ast of a Noir source file back into source code in a canonical style.
+//! It is used by the `nargo fmt` command.
+//!
+//! Comments are not part of the Ast, so they are lexed separately as trivia and re-inserted while
+//! printing: each comment is written before the first item, statement, struct field or list element
+//! which follows it in the source. A comment which follows some code on the same line stays at the
+//! end of that line. Comments within parts of the source which are printed on a single line, such
+//! as function signatures, are moved after them.
+//!
+//! Besides whitespace, the formatter rewrites a few constructs into their canonical form:
+//! the deprecated `constrain x` statement becomes `assert(x)` and redundant parentheses are removed.
+mod expressions;
+mod items;
+
+use std::collections::VecDeque;
+
+use noirc_errors::CustomDiagnostic;
+
+use crate::lexer::Lexer;
+use crate::parser::parse_program;
+use crate::token::{SpannedToken, Token};
+
+/// The number of spaces of each level of indentation
+const INDENT_WIDTH: usize = 4;
+
+/// Lists which would not fit within this width are printed with one element per line
+const MAX_WIDTH: usize = 100;
+
+/// Formats the given source code of a Noir file.
+///
+/// The diagnostics of the source code are returned instead if it has any syntax errors,
+/// since the parts of the source which failed to parse are missing from the Ast.
+pub fn format_source(source: &str) -> Result<String, Vec<CustomDiagnostic>> {
+    let (module, diagnostics) = parse_program(source);
+    if diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+        return Err(diagnostics);
+    }
+
+    let (tokens, _) = Lexer::lex_with_comments(source);
+    let (comments, tokens): (Vec<_>, Vec<_>) =
+        tokens.0.into_iter().partition(|token| matches!(token.token(), Token::LineComment(_)));
+
+    // Spans are given in characters rather than in bytes
+    let source: Vec<char> = source.chars().collect();
+    let mut printer = Printer::new(&source, &tokens, comments.into());
+    printer.module(&module);
+    Ok(printer.finish())
+}
+
+struct Printer<'a> {
+    source: &'a [char],
+    /// The tokens of the source, without the comments
+    tokens: &'a [SpannedToken],
+    /// The comments which have not been printed yet, in the order of the source
+    comments: VecDeque<SpannedToken>,
+    output: String,
+    indentation: usize,
+}
+
+impl<'a> Printer<'a> {
+    fn new(
+        source: &'a [char],
+        tokens: &'a [SpannedToken],
+        comments: VecDeque<SpannedToken>,
+    ) -> Printer<'a> {
+        Printer { source, tokens, comments, output: String::new(), indentation: 0 }
+    }
+
+    fn finish(mut self) -> String {
+        self.emit_comments_before(u32::MAX);
+        let mut output = self.output.trim_end().to_owned();
+        output.push('\n');
+        output
+    }
+
+    /// Returns the output of `print` when printed on a fresh line without any comments.
+    /// This is used to decide whether some code fits on a single line.
+    fn flat(&self, print: impl FnOnce(&mut Printer<'a>)) -> String {
+        let mut printer = Printer::new(self.source, self.tokens, VecDeque::new());
+        print(&mut printer);
+        printer.output
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.output.is_empty() || self.output.ends_with('\n')
+    }
+
+    fn write(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if self.at_line_start() {
+            let indentation = " ".repeat(self.indentation * INDENT_WIDTH);
+            self.output.push_str(&indentation);
+        }
+        self.output.push_str(text);
+    }
+
+    /// Writes text which may span multiple lines, indenting each line to the current indentation
+    fn write_lines(&mut self, text: &str) {
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.newline();
+            }
+            self.write(line);
+        }
+    }
+
+    fn newline(&mut self) {
+        let trimmed_len = self.output.trim_end_matches(' ').len();
+        self.output.truncate(trimmed_len);
+        self.output.push('\n');
+    }
+
+    /// Ends the current line with an empty line, unless it is the first line of the file or of a block
+    fn blank_line(&mut self) {
+        if !self.at_line_start() {
+            self.newline();
+        }
+        let at_block_start = ["{\n", "(\n", "[\n"].iter().any(|start| self.output.ends_with(start));
+        if !self.output.is_empty() && !self.output.ends_with("\n\n") && !at_block_start {
+            self.output.push('\n');
+        }
+    }
+
+    fn indented(&mut self, print: impl FnOnce(&mut Self)) {
+        self.indentation += 1;
+        print(self);
+        self.indentation -= 1;
+    }
+
+    fn column(&self) -> usize {
+        if self.at_line_start() {
+            self.indentation * INDENT_WIDTH
+        } else {
+            let line_start = self.output.rfind('\n').map_or(0, |newline| newline + 1);
+            self.output[line_start..].chars().count()
+        }
+    }
+
+    /// Returns true if the single line `text` fits on the current line
+    fn fits(&self, text: &str) -> bool {
+        !text.contains('\n') && self.column() + text.chars().count() <= MAX_WIDTH
+    }
+
+    fn has_comments_before(&self, position: u32) -> bool {
+        self.comments.front().map_or(false, |comment| comment.to_span().start() < position)
+    }
+
+    /// Writes the comments preceding `position` followed by an empty line if there is one
+    /// in the source. This is called before each item or statement.
+    fn leading_trivia(&mut self, position: u32) {
+        self.emit_comments_before(position);
+        if self.blank_line_before(position) {
+            self.blank_line();
+        }
+    }
+
+    /// Writes the comments which start before `position`, each on its own line unless it
+    /// follows some code on the same line in the source.
+    fn emit_comments_before(&mut self, position: u32) {
+        while self.has_comments_before(position) {
+            let comment = self.comments.pop_front().unwrap();
+            let start = comment.to_span().start();
+            let text = comment.to_string();
+            let text = text.trim_end();
+
+            // Empty lines may already have been written after the code preceding the comment
+            let code_end = self.output.trim_end_matches('\n').len();
+            if self.is_trailing(start) && code_end > 0 && self.at_line_start() {
+                let newlines = self.output.split_off(code_end);
+                self.output.push(' ');
+                self.output.push_str(text);
+                self.output.push_str(&newlines);
+            } else {
+                if !self.at_line_start() {
+                    self.newline();
+                }
+                if self.blank_line_before(start) {
+                    self.blank_line();
+                }
+                self.write(text);
+                self.newline();
+            }
+        }
+    }
+
+    /// Returns true if there is some code before `position` on its line
+    fn is_trailing(&self, position: u32) -> bool {
+        let before = &self.source[..(position as usize).min(self.source.len())];
+        let previous = before.iter().rev().find(|char| **char != ' ' && **char != '\t');
+        previous.map_or(false, |char| *char != '\n' && *char != '\r')
+    }
+
+    /// Returns true if the whitespace preceding `position` contains an empty line
+    fn blank_line_before(&self, position: u32) -> bool {
+        let before = &self.source[..(position as usize).min(self.source.len())];
+        let whitespace = before.iter().rev().take_while(|char| char.is_whitespace());
+        whitespace.filter(|char| **char == '\n').count() >= 2
+    }
+
+    /// Returns the index of the first token starting at or after `position`
+    fn token_index(&self, position: u32) -> usize {
+        self.tokens.partition_point(|token| token.to_span().start() < position)
+    }
+
+    /// Returns the start of the last token before `position` which satisfies `predicate`
+    fn last_token_before(&self, position: u32, predicate: impl Fn(&Token) -> bool) -> Option<u32> {
+        let tokens = &self.tokens[..self.token_index(position)];
+        let token = tokens.iter().rev().find(|token| predicate(token.token()))?;
+        Some(token.to_span().start())
+    }
+
+    /// Returns the start of the sequence of tokens satisfying `is_prefix` which immediately
+    /// precedes the token at `position`, or `position` if there are none.
+    fn extend_back(&self, position: u32, is_prefix: impl Fn(&Token) -> bool) -> u32 {
+        let mut index = self.token_index(position);
+        while index > 0 && is_prefix(self.tokens[index - 1].token()) {
+            index -= 1;
+        }
+        match self.tokens.get(index) {
+            Some(token) if token.to_span().start() < position => token.to_span().start(),
+            _ => position,
+        }
+    }
+
+    /// Returns the position of the '}' closing the first '{' after `position`
+    fn closing_brace_after(&self, position: u32) -> u32 {
+        let mut depth = 0;
+        for token in &self.tokens[self.token_index(position)..] {
+            match token.token() {
+                Token::LeftBrace => depth += 1,
+                Token::RightBrace if depth == 1 => return token.to_span().start(),
+                Token::RightBrace => depth -= 1,
+                _ => (),
+            }
+        }
+        u32::MAX
+    }
+
+    /// Returns the integer literal starting at `position` as written in the source,
+    /// so that hexadecimal literals are kept as is.
+    fn integer_literal_at(&self, position: u32) -> Option<String> {
+        let token = self.tokens.get(self.token_index(position))?;
+        if token.to_span().start() != position || !matches!(token.token(), Token::Int(_)) {
+            return None;
+        }
+        let text = self.source[position as usize..].iter();
+        Some(text.take_while(|char| char.is_ascii_alphanumeric()).collect())
+    }
+
+    /// Writes `elements` separated by commas between the `open` and `close` delimiters.
+    ///
+    /// The list is written on a single line if it fits, and with one element per line otherwise.
+    /// `end` is the position of the closing delimiter in the source, if known, so that the comments
+    /// within the list can be written before the element following them.
+    /// If `padded` is set, single line lists have spaces within their delimiters, as in `Foo { x: 1 }`.
+    fn list<T>(
+        &mut self,
+        (open, close): (&str, &str),
+        elements: &[T],
+        end: Option<u32>,
+        padded: bool,
+        start_of: impl Fn(&T) -> Option<u32>,
+        print: impl Fn(&mut Printer<'a>, &T),
+    ) {
+        let flat = self.flat(|printer| {
+            for (i, element) in elements.iter().enumerate() {
+                if i > 0 {
+                    printer.write(", ");
+                }
+                print(printer, element);
+            }
+        });
+        let has_comments = end.map_or(false, |end| self.has_comments_before(end));
+        let padding = if padded && !flat.is_empty() { " " } else { "" };
+        let single_line = format!("{open}{padding}{flat}{padding}{close}");
+
+        if elements.is_empty() || (self.fits(&single_line) && !has_comments) {
+            self.write(&single_line);
+            return;
+        }
+
+        self.write(open);
+        self.newline();
+        self.indented(|printer| {
+            for element in elements {
+                if let Some(start) = start_of(element) {
+                    printer.emit_comments_before(start);
+                }
+                print(printer, element);
+                printer.write(",");
+                printer.newline();
+            }
+            if let Some(end) = end {
+                printer.emit_comments_before(end);
+            }
+        });
+        self.write(close);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::format_source;
+
+    /// Asserts that `source` is formatted as `expected`, and that `expected` is already formatted
+    fn assert_formats_to(source: &str, expected: &str) {
+        let formatted = format_source(source).expect("the source should parse");
+        assert_eq!(formatted, expected);
+        let reformatted = format_source(&formatted).expect("the formatted source should parse");
+        assert_eq!(reformatted, expected, "formatting should be idempotent");
+    }
+
+    #[test]
+    fn formats_functions() {
+        let source = "
+use dep::std;
+fn   main(x : Field,y:pub Field)->pub Field{
+    let z=x+y*2;
+    assert (z!=0);
+  z
+}
+#[test]
+fn test_main(){main(1,2);}
+";
+        let expected = "use dep::std;
+
+fn main(x: Field, y: pub Field) -> pub Field {
+    let z = x + y * 2;
+    assert(z != 0);
+    z
+}
+
+#[test]
+fn test_main() {
+    main(1, 2);
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn keeps_comments() {
+        let source = "// The entry point
+fn main(x: Field) { // trailing
+    // before the statement
+
+    let y = x; // after the statement
+    // at the end of the block
+}
+// at the end of the file
+";
+        let expected = "// The entry point
+fn main(x: Field) { // trailing
+    // before the statement
+
+    let y = x; // after the statement
+    // at the end of the block
+}
+// at the end of the file
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn keeps_blank_lines_between_statements() {
+        let source = "fn main() {
+    let a = 1;
+
+
+    let b = 2;
+    let c = 3;
+}
+";
+        let expected = "fn main() {
+    let a = 1;
+
+    let b = 2;
+    let c = 3;
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn formats_structs_and_impls() {
+        let source = "struct Foo<T>{a:T,
+// the second field
+b:[Field;2]}
+impl<T> Foo<T> {
+    fn new(a:T)->Self{Foo{a,b:[0;2]}}
+    fn get(self)->T{self.a}
+}
+";
+        let expected = "struct Foo<T> {
+    a: T,
+    // the second field
+    b: [Field; 2],
+}
+
+impl<T> Foo<T> {
+    fn new(a: T) -> Self {
+        Foo { a, b: [0; 2] }
+    }
+
+    fn get(self) -> T {
+        self.a
+    }
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn formats_traits() {
+        let source = "trait Hash{
+// hashes the value
+fn hash(self)->Field;
+fn hash_many(values:[Self;2])->Field;}
+impl<T:Hash+Eq> Hash for Foo<T> {fn hash(self)->Field{self.a.hash()}
+    fn hash_many(values:[Self;2])->Field{0}}
+fn hash_twice<T: Hash,U>(x:T,y:U)->Field{x.hash()}
+";
+        let expected = "trait Hash {
+    // hashes the value
+    fn hash(self) -> Field;
+    fn hash_many(values: [Self; 2]) -> Field;
+}
+
+impl<T: Hash + Eq> Hash for Foo<T> {
+    fn hash(self) -> Field {
+        self.a.hash()
+    }
+
+    fn hash_many(values: [Self; 2]) -> Field {
+        0
+    }
+}
+
+fn hash_twice<T: Hash, U>(x: T, y: U) -> Field {
+    x.hash()
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn formats_enums_and_matches() {
+        let source = "enum Shape<T>{Circle(T),
+// no fields
+Empty,Rect(T,T)}
+fn area(shape:Shape<Field>)->Field{match shape{Shape::Circle(r)=>3*r*r,
+    // only the width matters
+    Shape::Rect(w,_)=>{w},Shape::Empty=>0}}
+";
+        let expected = "enum Shape<T> {
+    Circle(T),
+    // no fields
+    Empty,
+    Rect(T, T),
+}
+
+fn area(shape: Shape<Field>) -> Field {
+    match shape {
+        Shape::Circle(r) => 3 * r * r,
+        // only the width matters
+        Shape::Rect(w, _) => {
+            w
+        },
+        Shape::Empty => 0,
+    }
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn keeps_item_visibility() {
+        let source = "pub(crate)   use foo::bar;
+pub  global N=3;
+pub struct Foo{pub a:Field,b:Field}
+pub(crate) unconstrained fn get(x:Foo)->Field{x.a}
+";
+        let expected = "pub(crate) use foo::bar;
+pub global N = 3;
+
+pub struct Foo {
+    pub a: Field,
+    b: Field,
+}
+
+pub(crate) unconstrained fn get(x: Foo) -> Field {
+    x.a
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn keeps_every_attribute() {
+        let source = "#[test] #[deploy( public,version = 2 )]
+fn foo(){}
+";
+        let expected = "#[test]
+#[deploy(public, version = 2)]
+fn foo() {}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn keeps_assertion_messages() {
+        let source = "fn main(x: Field) {
+    assert( x != 0 ,\"x must not be zero\" );
+}
+";
+        let expected = "fn main(x: Field) {
+    assert(x != 0, \"x must not be zero\");
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn keeps_sugared_syntax() {
+        let source = "fn main(array: [u8; 3]) {
+    let mut sum = 0;
+    for x in array { sum += x as u32; }
+    for i in 0..3 { sum >>= 1; }
+    if sum == 0 { sum = 1; } else if sum == 1 { sum = 2; } else { sum = 3; }
+    constrain sum != 0;
+}
+";
+        let expected = "fn main(array: [u8; 3]) {
+    let mut sum = 0;
+    for x in array {
+        sum += x as u32;
+    }
+    for i in 0..3 {
+        sum >>= 1;
+    }
+    if sum == 0 {
+        sum = 1;
+    } else if sum == 1 {
+        sum = 2;
+    } else {
+        sum = 3;
+    }
+    assert(sum != 0);
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn formats_while_loops_and_early_exits() {
+        let source = "fn main(mut x: u8) {
+    #[max_iterations(8)] while x < 10 { x += 1; if x == 5 { break; } }
+    for i in 0..3 { if i == 1 { continue; } x += i; }
+}
+";
+        let expected = "fn main(mut x: u8) {
+    #[max_iterations(8)]
+    while x < 10 {
+        x += 1;
+        if x == 5 {
+            break;
+        }
+    }
+    for i in 0..3 {
+        if i == 1 {
+            continue;
+        }
+        x += i;
+    }
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn keeps_required_parentheses() {
+        let source = "fn main(a: u8, b: u8) -> u8 {
+    let c = (a + b) * (a - (b - 1));
+    let d = ((a * b)) + -(a + b);
+    let e = (-a) as u8 >> (1 as u8);
+    c + d + e + 0x10
+}
+";
+        let expected = "fn main(a: u8, b: u8) -> u8 {
+    let c = (a + b) * (a - (b - 1));
+    let d = a * b + -(a + b);
+    let e = (-a) as u8 >> 1 as u8;
+    c + d + e + 0x10
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn breaks_long_lists() {
+        let source = "global TABLE: [Field; 5] = [1000000000000000, 2000000000000000, 3000000000000000, 4000000000000000, 5000000000000000];
+";
+        let expected = "global TABLE: [Field; 5] = [
+    1000000000000000,
+    2000000000000000,
+    3000000000000000,
+    4000000000000000,
+    5000000000000000,
+];
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn formats_modules() {
+        let source = "mod foo;
+use crate::foo::bar as baz;
+contract Token {
+    global SUPPLY = 10;
+    open fn mint(amount: Field) -> distinct pub Field { amount }
+}
+";
+        let expected = "mod foo;
+use crate::foo::bar as baz;
+
+contract Token {
+    global SUPPLY = 10;
+
+    open fn mint(amount: Field) -> distinct pub Field {
+        amount
+    }
+}
+";
+        assert_formats_to(source, expected);
+    }
+
+    #[test]
+    fn does_not_format_invalid_source() {
+        assert!(format_source("fn main() { let }").is_err());
+    }
+}
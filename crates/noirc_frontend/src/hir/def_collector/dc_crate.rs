@@ -1,6 +1,6 @@
 use super::dc_mod::collect_defs;
 use super::errors::DefCollectorErrorKind;
-use crate::graph::{CrateId, LOCAL_CRATE};
+use crate::graph::CrateId;
 use crate::hir::def_map::{CrateDefMap, LocalModuleId, ModuleId};
 use crate::hir::resolution::errors::ResolverError;
 use crate::hir::resolution::resolver::Resolver;
@@ -10,10 +10,12 @@ use crate::hir::resolution::{
 };
 use crate::hir::type_check::{type_check_func, TypeChecker};
 use crate::hir::Context;
-use crate::node_interner::{FuncId, NodeInterner, StmtId, StructId};
+use crate::hir_def::traits::{Trait, TraitImpl};
+use crate::node_interner::{FuncId, NodeInterner, StmtId, StructId, TraitId};
 use crate::{
-    ExpressionKind, Generics, Ident, LetStatement, NoirFunction, NoirStruct, ParsedModule, Shared,
-    Type, TypeBinding, UnresolvedGenerics, UnresolvedType,
+    ExpressionKind, Generics, Ident, ItemVisibility, LetStatement, NoirEnum, NoirFunction,
+    NoirStruct, NoirTrait, ParsedModule, Path, Shared, Type, TypeBinding, UnresolvedGenerics,
+    UnresolvedTraitConstraint, UnresolvedType,
 };
 use fm::FileId;
 use iter_extended::vecmap;
@@ -40,6 +42,33 @@ pub struct UnresolvedStruct {
     pub struct_def: NoirStruct,
 }
 
+pub struct UnresolvedEnum {
+    pub file_id: FileId,
+    pub module_id: LocalModuleId,
+    pub enum_def: NoirEnum,
+    /// The functions created to construct each variant of the enum, in declaration order
+    pub variant_ids: Vec<FuncId>,
+}
+
+pub struct UnresolvedTrait {
+    pub file_id: FileId,
+    pub module_id: LocalModuleId,
+    pub trait_def: NoirTrait,
+    /// The functions created for each method of the trait, in declaration order
+    pub method_ids: Vec<FuncId>,
+}
+
+pub struct UnresolvedTraitImpl {
+    pub file_id: FileId,
+    pub module_id: LocalModuleId,
+    pub trait_name: Path,
+    pub object_type: UnresolvedType,
+    pub type_span: Span,
+    pub generics: UnresolvedGenerics,
+    pub trait_constraints: Vec<UnresolvedTraitConstraint>,
+    pub methods: UnresolvedFunctions,
+}
+
 #[derive(Clone)]
 pub struct UnresolvedGlobal {
     pub file_id: FileId,
@@ -54,8 +83,11 @@ pub struct DefCollector {
     pub(crate) collected_imports: Vec<ImportDirective>,
     pub(crate) collected_functions: Vec<UnresolvedFunctions>,
     pub(crate) collected_types: HashMap<StructId, UnresolvedStruct>,
+    pub(crate) collected_enums: HashMap<StructId, UnresolvedEnum>,
     pub(crate) collected_globals: Vec<UnresolvedGlobal>,
     pub(crate) collected_impls: ImplMap,
+    pub(crate) collected_traits: HashMap<TraitId, UnresolvedTrait>,
+    pub(crate) collected_trait_impls: Vec<UnresolvedTraitImpl>,
 }
 
 /// Maps the type and the module id in which the impl is defined to the functions contained in that
@@ -71,19 +103,26 @@ impl DefCollector {
             collected_imports: vec![],
             collected_functions: vec![],
             collected_types: HashMap::new(),
+            collected_enums: HashMap::new(),
             collected_impls: HashMap::new(),
             collected_globals: vec![],
+            collected_traits: HashMap::new(),
+            collected_trait_impls: vec![],
         }
     }
 
     /// Collect all of the definitions in a given crate into a CrateDefMap
     /// Modules which are not a part of the module hierarchy starting with
     /// the root module, will be ignored.
+    ///
+    /// `local_crate` is the crate being compiled, which is either this crate
+    /// or one depending on it.
     pub fn collect(
         mut def_map: CrateDefMap,
         context: &mut Context,
         ast: ParsedModule,
         root_file_id: FileId,
+        local_crate: CrateId,
         errors: &mut Vec<FileDiagnostic>,
     ) {
         let crate_id = def_map.krate;
@@ -96,7 +135,7 @@ impl DefCollector {
         let crate_graph = &context.crate_graph[crate_id];
 
         for dep in crate_graph.dependencies.clone() {
-            CrateDefMap::collect_defs(dep.crate_id, context, errors);
+            CrateDefMap::collect_crate_defs(dep.crate_id, local_crate, context, errors);
 
             let dep_def_root =
                 context.def_map(dep.crate_id).expect("ice: def map was just created").root;
@@ -136,9 +175,14 @@ impl DefCollector {
         let current_def_map = context.def_maps.get_mut(&crate_id).unwrap();
         for resolved_import in resolved {
             let name = resolved_import.name;
-            for ns in resolved_import.resolved_namespace.iter_defs() {
-                let result = current_def_map.modules[resolved_import.module_scope.0]
-                    .import(name.clone(), ns);
+            for (ns, item_visibility) in resolved_import.resolved_namespace.iter_items() {
+                // A re-export cannot make an item more visible than it was declared
+                let visibility = resolved_import.visibility.min(item_visibility);
+                let result = current_def_map.modules[resolved_import.module_scope.0].import(
+                    name.clone(),
+                    visibility,
+                    ns,
+                );
 
                 if let Err((first_def, second_def)) = result {
                     let err = DefCollectorErrorKind::DuplicateImport { first_def, second_def };
@@ -158,7 +202,13 @@ impl DefCollector {
         let mut file_global_ids = resolve_globals(context, integer_globals, crate_id, errors);
 
         // Must resolve structs before we resolve globals.
-        resolve_structs(context, def_collector.collected_types, crate_id, errors);
+        resolve_structs(
+            context,
+            def_collector.collected_types,
+            def_collector.collected_enums,
+            crate_id,
+            errors,
+        );
 
         // We must wait to resolve non-integer globals until after we resolve structs since structs
         // globals will need to reference the struct type they're initialized to to ensure they are valid.
@@ -166,11 +216,15 @@ impl DefCollector {
 
         file_global_ids.append(&mut more_global_ids);
 
+        // Traits must be resolved before any function since functions may call their methods
+        resolve_traits(context, def_collector.collected_traits, crate_id, errors);
+
         // Before we resolve any function symbols we must go through our impls and
         // re-collect the methods within into their proper module. This cannot be
         // done before resolution since we need to be able to resolve the type of the
         // impl since that determines the module we should collect into.
-        collect_impls(context, crate_id, &def_collector.collected_impls, errors);
+        let is_local_crate = crate_id == local_crate;
+        collect_impls(context, crate_id, is_local_crate, &def_collector.collected_impls, errors);
 
         // Lower each function in the crate. This is now possible since imports have been resolved
         let file_func_ids = resolve_free_functions(
@@ -190,11 +244,22 @@ impl DefCollector {
             errors,
         );
 
-        type_check_globals(&mut context.def_interner, file_global_ids, errors);
+        let file_trait_method_ids = resolve_trait_impls(
+            &mut context.def_interner,
+            crate_id,
+            &context.def_maps,
+            def_collector.collected_trait_impls,
+            errors,
+        );
+
+        let interner = &mut context.def_interner;
+        let def_maps = &context.def_maps;
+        type_check_globals(interner, def_maps, file_global_ids, errors);
 
         // Type check all of the functions in the crate
-        type_check_functions(&mut context.def_interner, file_func_ids, errors);
-        type_check_functions(&mut context.def_interner, file_method_ids, errors);
+        type_check_functions(interner, def_maps, file_func_ids, errors);
+        type_check_functions(interner, def_maps, file_method_ids, errors);
+        type_check_functions(interner, def_maps, file_trait_method_ids, errors);
     }
 }
 
@@ -203,6 +268,7 @@ impl DefCollector {
 fn collect_impls(
     context: &mut Context,
     crate_id: CrateId,
+    is_local_crate: bool,
     collected_impls: &ImplMap,
     errors: &mut Vec<FileDiagnostic>,
 ) {
@@ -229,7 +295,9 @@ fn collect_impls(
                 let module = &mut def_maps.get_mut(&crate_id).unwrap().modules[type_module.0];
 
                 for (_, method_id, method) in &unresolved.functions {
-                    let result = module.declare_function(method.name_ident().clone(), *method_id);
+                    let name = method.name_ident().clone();
+                    let visibility = method.def.visibility;
+                    let result = module.declare_function(name, visibility, *method_id);
 
                     if let Err((first_def, second_def)) = result {
                         let err =
@@ -240,7 +308,7 @@ fn collect_impls(
             // Prohibit defining impls for primitive types if we're in the local crate.
             // We should really prevent it for all crates that aren't the noir stdlib but
             // there is no way of checking if the current crate is the stdlib currently.
-            } else if typ != Type::Error && crate_id == LOCAL_CRATE {
+            } else if typ != Type::Error && is_local_crate {
                 let span = *span;
                 let error = DefCollectorErrorKind::NonStructTypeInImpl { span };
                 errors.push(error.into_file_diagnostic(unresolved.file_id));
@@ -307,20 +375,24 @@ fn resolve_globals(
 
 fn type_check_globals(
     interner: &mut NodeInterner,
+    def_maps: &HashMap<CrateId, CrateDefMap>,
     global_ids: Vec<(FileId, StmtId)>,
     all_errors: &mut Vec<FileDiagnostic>,
 ) {
     for (file_id, stmt_id) in global_ids {
-        let errors = TypeChecker::check_global(&stmt_id, interner);
+        let errors = TypeChecker::check_global(&stmt_id, interner, def_maps);
         extend_errors(all_errors, file_id, errors);
     }
 }
 
 /// Create the mappings from TypeId -> StructType
 /// so that expressions can access the fields of structs
+/// Resolves the fields of each struct and the variants of each enum. Enums are represented
+/// by a StructType as well, so the two may refer to each other.
 fn resolve_structs(
     context: &mut Context,
     structs: HashMap<StructId, UnresolvedStruct>,
+    enums: HashMap<StructId, UnresolvedEnum>,
     crate_id: CrateId,
     errors: &mut Vec<FileDiagnostic>,
 ) {
@@ -331,6 +403,9 @@ fn resolve_structs(
     for (type_id, typ) in &structs {
         context.def_interner.push_empty_struct(*type_id, typ);
     }
+    for (type_id, typ) in &enums {
+        context.def_interner.push_empty_enum(*type_id, typ);
+    }
 
     for (type_id, typ) in structs {
         let (generics, fields) = resolve_struct_fields(context, crate_id, typ, errors);
@@ -339,6 +414,40 @@ fn resolve_structs(
             struct_def.generics = generics;
         });
     }
+
+    for (type_id, typ) in enums {
+        resolve_enum_variants(context, crate_id, type_id, typ, errors);
+    }
+}
+
+/// Resolves the fields of each variant of an enum, along with the function constructing
+/// each variant.
+fn resolve_enum_variants(
+    context: &mut Context,
+    krate: CrateId,
+    enum_id: StructId,
+    unresolved: UnresolvedEnum,
+    all_errors: &mut Vec<FileDiagnostic>,
+) {
+    let path_resolver =
+        StandardPathResolver::new(ModuleId { local_id: unresolved.module_id, krate });
+
+    let file = unresolved.file_id;
+
+    let (generics, variants, variant_metas, errors) =
+        Resolver::new(&mut context.def_interner, &path_resolver, &context.def_maps, file)
+            .resolve_enum_variants(unresolved.enum_def, enum_id, &unresolved.variant_ids);
+
+    context.def_interner.update_struct(enum_id, |enum_def| {
+        enum_def.set_variants(variants);
+        enum_def.generics = generics;
+    });
+
+    for (func_meta, func_id) in variant_metas.into_iter().zip(unresolved.variant_ids) {
+        context.def_interner.push_fn_meta(func_meta, func_id);
+    }
+
+    extend_errors(all_errors, file, errors);
 }
 
 fn resolve_struct_fields(
@@ -346,7 +455,7 @@ fn resolve_struct_fields(
     krate: CrateId,
     unresolved: UnresolvedStruct,
     all_errors: &mut Vec<FileDiagnostic>,
-) -> (Generics, Vec<(Ident, Type)>) {
+) -> (Generics, Vec<(Ident, Type, ItemVisibility)>) {
     let path_resolver =
         StandardPathResolver::new(ModuleId { local_id: unresolved.module_id, krate });
 
@@ -415,6 +524,180 @@ fn resolve_impls(
     file_method_ids
 }
 
+/// Resolve the signatures of the methods of each trait and store the traits in the interner
+fn resolve_traits(
+    context: &mut Context,
+    traits: HashMap<TraitId, UnresolvedTrait>,
+    crate_id: CrateId,
+    errors: &mut Vec<FileDiagnostic>,
+) {
+    for (trait_id, unresolved) in traits {
+        let path_resolver =
+            StandardPathResolver::new(ModuleId { local_id: unresolved.module_id, krate: crate_id });
+        let file = unresolved.file_id;
+        let NoirTrait { name, methods, span, .. } = unresolved.trait_def;
+
+        for (method, func_id) in methods.into_iter().zip(&unresolved.method_ids) {
+            let resolver =
+                Resolver::new(&mut context.def_interner, &path_resolver, &context.def_maps, file);
+            let (func_meta, errs) = resolver.resolve_trait_method(method, *func_id, trait_id);
+            context.def_interner.push_fn_meta(func_meta, *func_id);
+            extend_errors(errors, file, errs);
+        }
+
+        let methods = unresolved.method_ids;
+        context.def_interner.push_trait(Trait { id: trait_id, name, methods, span });
+    }
+}
+
+/// Resolve the methods of each impl of a trait, check them against the methods the trait
+/// declares, and record each impl so that calls to the trait's methods can be dispatched to it.
+fn resolve_trait_impls(
+    interner: &mut NodeInterner,
+    crate_id: CrateId,
+    def_maps: &HashMap<CrateId, CrateDefMap>,
+    trait_impls: Vec<UnresolvedTraitImpl>,
+    errors: &mut Vec<FileDiagnostic>,
+) -> Vec<(FileId, FuncId)> {
+    let mut file_method_ids = Vec::new();
+
+    for trait_impl in trait_impls {
+        let path_resolver =
+            StandardPathResolver::new(ModuleId { local_id: trait_impl.module_id, krate: crate_id });
+        let file = trait_impl.file_id;
+
+        let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
+        resolver.add_generics(&trait_impl.generics);
+        let generics = resolver.get_generics().to_vec();
+        let self_type = resolver.resolve_type(trait_impl.object_type);
+        let trait_id = resolver.lookup_trait_or_error(trait_impl.trait_name);
+        let where_clause = vecmap(trait_impl.trait_constraints, |constraint| {
+            resolver.resolve_trait_constraint(constraint)
+        });
+        extend_errors(errors, file, resolver.take_errors());
+
+        let mut file_func_ids = resolve_function_set(
+            interner,
+            crate_id,
+            def_maps,
+            trait_impl.methods,
+            Some(self_type.clone()),
+            generics.clone(),
+            errors,
+        );
+
+        if let Some(trait_id) = trait_id.filter(|_| self_type != Type::Error) {
+            let span = trait_impl.type_span;
+            let methods = check_trait_impl_methods(
+                interner,
+                trait_id,
+                &self_type,
+                &file_func_ids,
+                file,
+                span,
+                errors,
+            );
+
+            let generics = vecmap(generics, |(name, typevar, _)| match &*typevar.borrow() {
+                TypeBinding::Unbound(id) => (name, *id, typevar.clone()),
+                TypeBinding::Bound(binding) => {
+                    unreachable!("Expected {} to be unbound, but it is bound to {}", name, binding)
+                }
+            });
+
+            // Bounds whose trait could not be found have already been reported
+            let where_clause = where_clause.into_iter().flatten().collect();
+            let new_impl = TraitImpl {
+                trait_id,
+                typ: self_type.clone(),
+                generics,
+                methods,
+                where_clause,
+                span,
+            };
+            if let Some(first_span) = interner.add_trait_impl(new_impl).map(|first| first.span) {
+                let error = ResolverError::OverlappingTraitImpls {
+                    trait_name: interner.get_trait(trait_id).name.clone(),
+                    typ: self_type,
+                    first_span,
+                    second_span: span,
+                };
+                errors.push(error.into_file_diagnostic(file));
+            }
+        }
+
+        file_method_ids.append(&mut file_func_ids);
+    }
+
+    file_method_ids
+}
+
+/// Checks that the methods of an impl of a trait are exactly the methods the trait declares,
+/// with the same signatures once `Self` is replaced by the type the trait is implemented for.
+/// Returns the method implementing each method of the trait, by name.
+fn check_trait_impl_methods(
+    interner: &NodeInterner,
+    trait_id: TraitId,
+    self_type: &Type,
+    method_ids: &[(FileId, FuncId)],
+    file: FileId,
+    type_span: Span,
+    errors: &mut Vec<FileDiagnostic>,
+) -> HashMap<String, FuncId> {
+    let the_trait = interner.get_trait(trait_id);
+    let mut methods = HashMap::new();
+
+    for (file_id, method_id) in method_ids {
+        let name = interner.function_name(method_id).to_owned();
+        let method = interner.function_ident(method_id);
+
+        let trait_method = match interner.lookup_trait_method(trait_id, &name) {
+            Some(trait_method) => trait_method,
+            None => {
+                let trait_name = the_trait.name.clone();
+                let error = ResolverError::NotATraitMethod { method, trait_name };
+                errors.push(error.into_file_diagnostic(*file_id));
+                continue;
+            }
+        };
+
+        let expected = match interner.function_meta(&trait_method).typ {
+            Type::Forall(generics, typ) => {
+                let (self_id, self_var) = generics[0].clone();
+                typ.substitute(&HashMap::from([(self_id, (self_var, self_type.clone()))]))
+            }
+            other => other,
+        };
+
+        let actual = match interner.function_meta(method_id).typ {
+            Type::Forall(_, typ) => *typ,
+            other => other,
+        };
+
+        if actual != expected {
+            let error = ResolverError::TraitMethodSignatureMismatch { method, expected, actual };
+            errors.push(error.into_file_diagnostic(*file_id));
+        }
+
+        methods.insert(name, *method_id);
+    }
+
+    let missing_methods = vecmap(&the_trait.methods, |method| interner.function_name(method))
+        .into_iter()
+        .filter(|name| !methods.contains_key(*name))
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+
+    if !missing_methods.is_empty() {
+        let trait_name = the_trait.name.clone();
+        let error =
+            ResolverError::MissingTraitMethods { span: type_span, missing_methods, trait_name };
+        errors.push(error.into_file_diagnostic(file));
+    }
+
+    methods
+}
+
 fn resolve_free_functions(
     interner: &mut NodeInterner,
     crate_id: CrateId,
@@ -472,10 +755,11 @@ fn resolve_function_set(
 
 fn type_check_functions(
     interner: &mut NodeInterner,
+    def_maps: &HashMap<CrateId, CrateDefMap>,
     file_func_ids: Vec<(FileId, FuncId)>,
     errors: &mut Vec<FileDiagnostic>,
 ) {
     for (file, func) in file_func_ids {
-        extend_errors(errors, file, type_check_func(interner, func));
+        extend_errors(errors, file, type_check_func(interner, def_maps, func));
     }
 }
@@ -1,13 +1,20 @@
-use fm::FileId;
+use fm::{FileId, ModuleFileError};
 use noirc_errors::FileDiagnostic;
 
 use crate::{
-    graph::CrateId, hir::def_collector::dc_crate::UnresolvedStruct, node_interner::StructId,
-    parser::SubModule, Ident, LetStatement, NoirFunction, NoirImpl, NoirStruct, ParsedModule,
+    graph::CrateId,
+    hir::def_collector::dc_crate::UnresolvedStruct,
+    node_interner::{StructId, TraitId},
+    parser::SubModule,
+    Ident, ItemVisibility, LetStatement, NoirEnum, NoirFunction, NoirImpl, NoirStruct, NoirTrait,
+    NoirTraitImpl, ParsedModule, UnresolvedTraitConstraint,
 };
 
 use super::{
-    dc_crate::{DefCollector, UnresolvedFunctions, UnresolvedGlobal},
+    dc_crate::{
+        DefCollector, UnresolvedEnum, UnresolvedFunctions, UnresolvedGlobal, UnresolvedTrait,
+        UnresolvedTraitImpl,
+    },
     errors::DefCollectorErrorKind,
 };
 use crate::hir::def_map::{parse_file, LocalModuleId, ModuleData, ModuleId, ModuleOrigin};
@@ -48,6 +55,7 @@ pub fn collect_defs(
             module_id: collector.module_id,
             path: import.path,
             alias: import.alias,
+            visibility: import.visibility,
         });
     }
 
@@ -55,19 +63,25 @@ pub fn collect_defs(
 
     collector.collect_structs(ast.types, crate_id, errors);
 
+    collector.collect_enums(context, ast.enums, crate_id, errors);
+
+    collector.collect_traits(context, ast.traits, crate_id, errors);
+
     collector.collect_functions(context, ast.functions, errors);
 
     collector.collect_impls(context, ast.impls);
+
+    collector.collect_trait_impls(context, ast.trait_impls);
 }
 
 impl<'a> ModCollector<'a> {
     fn collect_globals(
         &mut self,
         context: &mut Context,
-        globals: Vec<LetStatement>,
+        globals: Vec<(LetStatement, ItemVisibility)>,
         errors: &mut Vec<FileDiagnostic>,
     ) {
-        for global in globals {
+        for (global, visibility) in globals {
             let name = global.pattern.name_ident().clone();
 
             // First create dummy function in the DefInterner
@@ -75,8 +89,8 @@ impl<'a> ModCollector<'a> {
             let stmt_id = context.def_interner.push_empty_global();
 
             // Add the statement to the scope so its path can be looked up later
-            let result =
-                self.def_collector.def_map.modules[self.module_id.0].declare_global(name, stmt_id);
+            let result = self.def_collector.def_map.modules[self.module_id.0]
+                .declare_global(name, visibility, stmt_id);
 
             if let Err((first_def, second_def)) = result {
                 let err = DefCollectorErrorKind::DuplicateGlobal { first_def, second_def };
@@ -97,7 +111,8 @@ impl<'a> ModCollector<'a> {
             let mut unresolved_functions =
                 UnresolvedFunctions { file_id: self.file_id, functions: Vec::new() };
 
-            for method in r#impl.methods {
+            for mut method in r#impl.methods {
+                add_impl_trait_constraints(&mut method, &r#impl.trait_constraints);
                 let func_id = context.def_interner.push_empty_fn();
                 context.def_interner.push_function_definition(method.name().to_owned(), func_id);
                 unresolved_functions.push_fn(self.module_id, func_id, method);
@@ -109,6 +124,31 @@ impl<'a> ModCollector<'a> {
         }
     }
 
+    fn collect_trait_impls(&mut self, context: &mut Context, trait_impls: Vec<NoirTraitImpl>) {
+        for trait_impl in trait_impls {
+            let mut unresolved_functions =
+                UnresolvedFunctions { file_id: self.file_id, functions: Vec::new() };
+
+            for mut method in trait_impl.methods {
+                add_impl_trait_constraints(&mut method, &trait_impl.trait_constraints);
+                let func_id = context.def_interner.push_empty_fn();
+                context.def_interner.push_function_definition(method.name().to_owned(), func_id);
+                unresolved_functions.push_fn(self.module_id, func_id, method);
+            }
+
+            self.def_collector.collected_trait_impls.push(UnresolvedTraitImpl {
+                file_id: self.file_id,
+                module_id: self.module_id,
+                trait_name: trait_impl.trait_name,
+                object_type: trait_impl.object_type,
+                type_span: trait_impl.type_span,
+                generics: trait_impl.generics,
+                trait_constraints: trait_impl.trait_constraints,
+                methods: unresolved_functions,
+            });
+        }
+    }
+
     fn collect_functions(
         &mut self,
         context: &mut Context,
@@ -120,6 +160,7 @@ impl<'a> ModCollector<'a> {
 
         for function in functions {
             let name = function.name_ident().clone();
+            let visibility = function.def.visibility;
 
             // First create dummy function in the DefInterner
             // So that we can get a FuncId
@@ -136,7 +177,7 @@ impl<'a> ModCollector<'a> {
 
             // Add function to scope/ns of the module
             let result = self.def_collector.def_map.modules[self.module_id.0]
-                .declare_function(name, func_id);
+                .declare_function(name, visibility, func_id);
 
             if let Err((first_def, second_def)) = result {
                 let error = DefCollectorErrorKind::DuplicateFunction { first_def, second_def };
@@ -165,8 +206,9 @@ impl<'a> ModCollector<'a> {
             };
 
             // Add the struct to scope so its path can be looked up later
-            let result =
-                self.def_collector.def_map.modules[self.module_id.0].declare_struct(name, id);
+            let visibility = struct_definition.visibility;
+            let result = self.def_collector.def_map.modules[self.module_id.0]
+                .declare_struct(name, visibility, id);
 
             if let Err((first_def, second_def)) = result {
                 let err = DefCollectorErrorKind::DuplicateFunction { first_def, second_def };
@@ -183,6 +225,120 @@ impl<'a> ModCollector<'a> {
         }
     }
 
+    /// Collect any enum definitions declared within the ast.
+    /// Enums are types like structs are, and each of their variants is declared as a function
+    /// in the enum's module which constructs that variant, e.g. `Option::Some(x)`.
+    fn collect_enums(
+        &mut self,
+        context: &mut Context,
+        enums: Vec<NoirEnum>,
+        krate: CrateId,
+        errors: &mut Vec<FileDiagnostic>,
+    ) {
+        for enum_definition in enums {
+            let name = enum_definition.name.clone();
+
+            // Create the corresponding module for the enum namespace
+            let id = match self.push_child_module(&name, self.file_id, false, false, errors) {
+                Some(local_id) => StructId(ModuleId { krate, local_id }),
+                None => continue,
+            };
+
+            let visibility = enum_definition.visibility;
+            let result = self.def_collector.def_map.modules[self.module_id.0]
+                .declare_struct(name, visibility, id);
+
+            if let Err((first_def, second_def)) = result {
+                let err = DefCollectorErrorKind::DuplicateFunction { first_def, second_def };
+                errors.push(err.into_file_diagnostic(self.file_id));
+            }
+
+            let mut variant_ids = Vec::new();
+            for (variant, _) in &enum_definition.variants {
+                let func_id = context.def_interner.push_empty_fn();
+                let name = variant.0.contents.clone();
+                context.def_interner.push_function_definition(name, func_id);
+                variant_ids.push(func_id);
+
+                let result = self.def_collector.def_map.modules[id.0.local_id.0].declare_function(
+                    variant.clone(),
+                    ItemVisibility::Public,
+                    func_id,
+                );
+
+                if let Err((first_def, second_def)) = result {
+                    let err = DefCollectorErrorKind::DuplicateFunction { first_def, second_def };
+                    errors.push(err.into_file_diagnostic(self.file_id));
+                }
+            }
+
+            let unresolved = UnresolvedEnum {
+                file_id: self.file_id,
+                module_id: self.module_id,
+                enum_def: enum_definition,
+                variant_ids,
+            };
+            self.def_collector.collected_enums.insert(id, unresolved);
+        }
+    }
+
+    /// Collect any trait definitions declared within the ast.
+    /// Like structs, each trait has a module of its own in which its methods are declared,
+    /// so that they may be called through the trait's path, e.g. `Hash::hash(x)`.
+    fn collect_traits(
+        &mut self,
+        context: &mut Context,
+        traits: Vec<NoirTrait>,
+        krate: CrateId,
+        errors: &mut Vec<FileDiagnostic>,
+    ) {
+        for trait_definition in traits {
+            let name = trait_definition.name.clone();
+
+            // Create the corresponding module for the trait namespace
+            let id = match self.push_child_module(&name, self.file_id, false, false, errors) {
+                Some(local_id) => TraitId(ModuleId { krate, local_id }),
+                None => continue,
+            };
+
+            let visibility = trait_definition.visibility;
+            let result = self.def_collector.def_map.modules[self.module_id.0]
+                .declare_trait(name, visibility, id);
+
+            if let Err((first_def, second_def)) = result {
+                let err = DefCollectorErrorKind::DuplicateFunction { first_def, second_def };
+                errors.push(err.into_file_diagnostic(self.file_id));
+            }
+
+            let mut method_ids = Vec::new();
+            for method in &trait_definition.methods {
+                let func_id = context.def_interner.push_empty_fn();
+                let name = method.name.0.contents.clone();
+                context.def_interner.push_function_definition(name, func_id);
+                method_ids.push(func_id);
+
+                let result = self.def_collector.def_map.modules[id.0.local_id.0].declare_function(
+                    method.name.clone(),
+                    ItemVisibility::Public,
+                    func_id,
+                );
+
+                if let Err((first_def, second_def)) = result {
+                    let err = DefCollectorErrorKind::DuplicateFunction { first_def, second_def };
+                    errors.push(err.into_file_diagnostic(self.file_id));
+                }
+            }
+
+            let unresolved = UnresolvedTrait {
+                file_id: self.file_id,
+                module_id: self.module_id,
+                trait_def: trait_definition,
+                method_ids,
+            };
+            self.def_collector.collected_traits.insert(id, unresolved);
+        }
+    }
+
     fn collect_submodules(
         &mut self,
         context: &mut Context,
@@ -225,9 +381,16 @@ impl<'a> ModCollector<'a> {
         let child_file_id =
             match context.file_manager.resolve_path(self.file_id, &mod_name.0.contents) {
                 Ok(child_file_id) => child_file_id,
-                Err(_) => {
-                    let err =
-                        DefCollectorErrorKind::UnresolvedModuleDecl { mod_name: mod_name.clone() };
+                Err(error) => {
+                    let mod_name = mod_name.clone();
+                    let err = match error {
+                        ModuleFileError::NotFound(candidates) => {
+                            DefCollectorErrorKind::UnresolvedModuleDecl { mod_name, candidates }
+                        }
+                        ModuleFileError::Ambiguous(candidates) => {
+                            DefCollectorErrorKind::AmbiguousModuleDecl { mod_name, candidates }
+                        }
+                    };
                     errors.push(err.into_file_diagnostic(self.file_id));
                     return;
                 }
@@ -296,3 +459,11 @@ impl<'a> ModCollector<'a> {
         Some(LocalModuleId(module_id))
     }
 }
+
+/// The bounds on the generics of an impl apply to each of its methods
+fn add_impl_trait_constraints(
+    method: &mut NoirFunction,
+    constraints: &[UnresolvedTraitConstraint],
+) {
+    method.def_mut().trait_constraints.extend(constraints.iter().cloned());
+}
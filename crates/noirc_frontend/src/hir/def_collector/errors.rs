@@ -1,6 +1,9 @@
 use crate::hir::resolution::import::PathResolutionError;
 use crate::Ident;
 
+use iter_extended::vecmap;
+use std::path::PathBuf;
+
 use noirc_errors::CustomDiagnostic as Diagnostic;
 use noirc_errors::FileDiagnostic;
 use noirc_errors::Span;
@@ -17,7 +20,9 @@ pub enum DefCollectorErrorKind {
     #[error("duplicate global found in namespace")]
     DuplicateGlobal { first_def: Ident, second_def: Ident },
     #[error("unresolved import")]
-    UnresolvedModuleDecl { mod_name: Ident },
+    UnresolvedModuleDecl { mod_name: Ident, candidates: Vec<PathBuf> },
+    #[error("ambiguous module declaration")]
+    AmbiguousModuleDecl { mod_name: Ident, candidates: Vec<PathBuf> },
     #[error("path resolution error")]
     PathResolutionError(PathResolutionError),
     #[error("Non-struct type used in impl")]
@@ -85,15 +90,34 @@ impl From<DefCollectorErrorKind> for Diagnostic {
                 diag.add_secondary("second global declaration found here".to_string(), second_span);
                 diag
             }
-            DefCollectorErrorKind::UnresolvedModuleDecl { mod_name } => {
+            DefCollectorErrorKind::UnresolvedModuleDecl { mod_name, candidates } => {
                 let span = mod_name.0.span();
                 let mod_name = &mod_name.0.contents;
 
-                Diagnostic::simple_error(
+                let mut diag = Diagnostic::simple_error(
                     format!("could not resolve module `{mod_name}` "),
                     String::new(),
                     span,
-                )
+                );
+                let candidates = vecmap(candidates, |path| path.display().to_string());
+                diag.add_note(format!("No file was found at {}", candidates.join(" or ")));
+                diag
+            }
+            DefCollectorErrorKind::AmbiguousModuleDecl { mod_name, candidates } => {
+                let span = mod_name.0.span();
+                let mod_name = &mod_name.0.contents;
+
+                let mut diag = Diagnostic::simple_error(
+                    format!("module `{mod_name}` is found in more than one file"),
+                    String::new(),
+                    span,
+                );
+                let candidates = vecmap(candidates, |path| path.display().to_string());
+                diag.add_note(format!(
+                    "Found both {}, delete one of them",
+                    candidates.join(" and ")
+                ));
+                diag
             }
             DefCollectorErrorKind::PathResolutionError(error) => error.into(),
             DefCollectorErrorKind::NonStructTypeInImpl { span } => Diagnostic::simple_error(
@@ -1,16 +1,11 @@
 use super::{namespace::PerNs, ModuleDefId, ModuleId};
-use crate::{node_interner::FuncId, Ident};
+use crate::{node_interner::FuncId, Ident, ItemVisibility};
 use std::collections::{hash_map::Entry, HashMap};
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
-pub enum Visibility {
-    Public,
-}
-
 #[derive(Default, Debug, PartialEq, Eq)]
 pub struct ItemScope {
-    types: HashMap<Ident, (ModuleDefId, Visibility)>,
-    values: HashMap<Ident, (ModuleDefId, Visibility)>,
+    types: HashMap<Ident, (ModuleDefId, ItemVisibility)>,
+    values: HashMap<Ident, (ModuleDefId, ItemVisibility)>,
 
     defs: Vec<ModuleDefId>,
 }
@@ -19,9 +14,10 @@ impl ItemScope {
     pub fn add_definition(
         &mut self,
         name: Ident,
+        visibility: ItemVisibility,
         mod_def: ModuleDefId,
     ) -> Result<(), (Ident, Ident)> {
-        self.add_item_to_namespace(name, mod_def)?;
+        self.add_item_to_namespace(name, visibility, mod_def)?;
         self.defs.push(mod_def);
         Ok(())
     }
@@ -32,14 +28,15 @@ impl ItemScope {
     pub fn add_item_to_namespace(
         &mut self,
         name: Ident,
+        visibility: ItemVisibility,
         mod_def: ModuleDefId,
     ) -> Result<(), (Ident, Ident)> {
-        let add_item = |map: &mut HashMap<Ident, (ModuleDefId, Visibility)>| {
+        let add_item = |map: &mut HashMap<Ident, (ModuleDefId, ItemVisibility)>| {
             if let Entry::Occupied(o) = map.entry(name.clone()) {
                 let old_ident = o.key();
                 Err((old_ident.clone(), name))
             } else {
-                map.insert(name, (mod_def, Visibility::Public));
+                map.insert(name, (mod_def, visibility));
                 Ok(())
             }
         };
@@ -48,6 +45,7 @@ impl ItemScope {
             ModuleDefId::ModuleId(_) => add_item(&mut self.types),
             ModuleDefId::FunctionId(_) => add_item(&mut self.values),
             ModuleDefId::TypeId(_) => add_item(&mut self.types),
+            ModuleDefId::TraitId(_) => add_item(&mut self.types),
             ModuleDefId::GlobalId(_) => add_item(&mut self.values),
         }
     }
@@ -72,15 +70,23 @@ impl ItemScope {
         PerNs { types: self.types.get(name).cloned(), values: self.values.get(name).cloned() }
     }
 
+    /// Returns the name an item was declared or imported with, whose span is that of the
+    /// declaration rather than of `name`.
+    pub fn find_declared_name(&self, name: &Ident) -> Option<&Ident> {
+        let (declared_name, _) =
+            self.types.get_key_value(name).or_else(|| self.values.get_key_value(name))?;
+        Some(declared_name)
+    }
+
     pub fn definitions(&self) -> Vec<ModuleDefId> {
         self.defs.clone()
     }
 
-    pub fn types(&self) -> &HashMap<Ident, (ModuleDefId, Visibility)> {
+    pub fn types(&self) -> &HashMap<Ident, (ModuleDefId, ItemVisibility)> {
         &self.types
     }
 
-    pub fn values(&self) -> &HashMap<Ident, (ModuleDefId, Visibility)> {
+    pub fn values(&self) -> &HashMap<Ident, (ModuleDefId, ItemVisibility)> {
         &self.values
     }
 }
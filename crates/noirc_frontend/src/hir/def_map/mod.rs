@@ -3,7 +3,7 @@ use crate::hir::def_collector::dc_crate::DefCollector;
 use crate::hir::Context;
 use crate::node_interner::{FuncId, NodeInterner};
 use crate::parser::{parse_program, ParsedModule};
-use crate::token::Attribute;
+use crate::token::TestScope;
 use arena::{Arena, Index};
 use fm::{FileId, FileManager};
 use noirc_errors::FileDiagnostic;
@@ -40,6 +40,10 @@ pub struct ModuleId {
 }
 
 impl ModuleId {
+    pub fn dummy_id() -> ModuleId {
+        ModuleId { krate: CrateId::dummy_id(), local_id: LocalModuleId::dummy_id() }
+    }
+
     pub fn module(self, def_maps: &HashMap<CrateId, CrateDefMap>) -> &ModuleData {
         &def_maps[&self.krate].modules()[self.local_id.0]
     }
@@ -65,6 +69,17 @@ impl CrateDefMap {
         crate_id: CrateId,
         context: &mut Context,
         errors: &mut Vec<FileDiagnostic>,
+    ) {
+        CrateDefMap::collect_crate_defs(crate_id, crate_id, context, errors);
+    }
+
+    /// Collect all definitions in the crate, which is a dependency of `local_crate`
+    /// unless it is `local_crate` itself
+    pub(crate) fn collect_crate_defs(
+        crate_id: CrateId,
+        local_crate: CrateId,
+        context: &mut Context,
+        errors: &mut Vec<FileDiagnostic>,
     ) {
         // Check if this Crate has already been compiled
         // XXX: There is probably a better alternative for this.
@@ -92,7 +107,7 @@ impl CrateDefMap {
         };
 
         // Now we want to populate the CrateDefMap using the DefCollector
-        DefCollector::collect(def_map, context, ast, root_file_id, errors);
+        DefCollector::collect(def_map, context, ast, root_file_id, local_crate, errors);
     }
 
     pub fn root(&self) -> LocalModuleId {
@@ -124,16 +139,17 @@ impl CrateDefMap {
     }
 
     /// Go through all modules in this crate, and find all functions in
-    /// each module with the #[test] attribute
+    /// each module with the #[test] attribute, along with whether they should fail
     pub fn get_all_test_functions<'a>(
         &'a self,
         interner: &'a NodeInterner,
-    ) -> impl Iterator<Item = FuncId> + 'a {
+    ) -> impl Iterator<Item = (FuncId, TestScope)> + 'a {
         self.modules.iter().flat_map(|(_, module)| {
-            module
-                .value_definitions()
-                .filter_map(|id| id.as_function())
-                .filter(|id| interner.function_meta(id).attributes == Some(Attribute::Test))
+            module.value_definitions().filter_map(|id| {
+                let id = id.as_function()?;
+                let scope = interner.function_meta(&id).test_scope()?.clone();
+                Some((id, scope))
+            })
         })
     }
 
@@ -3,8 +3,8 @@ use std::collections::HashMap;
 use fm::FileId;
 
 use crate::{
-    node_interner::{FuncId, StmtId, StructId},
-    Ident,
+    node_interner::{FuncId, StmtId, StructId, TraitId},
+    Ident, ItemVisibility,
 };
 
 use super::{ItemScope, LocalModuleId, ModuleDefId, ModuleId, PerNs};
@@ -45,46 +45,91 @@ impl ModuleData {
         }
     }
 
-    fn declare(&mut self, name: Ident, item_id: ModuleDefId) -> Result<(), (Ident, Ident)> {
-        self.scope.add_definition(name.clone(), item_id)?;
+    fn declare(
+        &mut self,
+        name: Ident,
+        visibility: ItemVisibility,
+        item_id: ModuleDefId,
+    ) -> Result<(), (Ident, Ident)> {
+        self.scope.add_definition(name.clone(), visibility, item_id)?;
 
         // definitions is a subset of self.scope so it is expected if self.scope.define_func_def
         // returns without error, so will self.definitions.define_func_def.
-        self.definitions.add_definition(name, item_id)
+        self.definitions.add_definition(name, visibility, item_id)
+    }
+
+    pub fn declare_function(
+        &mut self,
+        name: Ident,
+        visibility: ItemVisibility,
+        id: FuncId,
+    ) -> Result<(), (Ident, Ident)> {
+        self.declare(name, visibility, id.into())
     }
 
-    pub fn declare_function(&mut self, name: Ident, id: FuncId) -> Result<(), (Ident, Ident)> {
-        self.declare(name, id.into())
+    pub fn declare_global(
+        &mut self,
+        name: Ident,
+        visibility: ItemVisibility,
+        id: StmtId,
+    ) -> Result<(), (Ident, Ident)> {
+        self.declare(name, visibility, id.into())
     }
 
-    pub fn declare_global(&mut self, name: Ident, id: StmtId) -> Result<(), (Ident, Ident)> {
-        self.declare(name, id.into())
+    pub fn declare_struct(
+        &mut self,
+        name: Ident,
+        visibility: ItemVisibility,
+        id: StructId,
+    ) -> Result<(), (Ident, Ident)> {
+        self.declare(name, visibility, ModuleDefId::TypeId(id))
     }
 
-    pub fn declare_struct(&mut self, name: Ident, id: StructId) -> Result<(), (Ident, Ident)> {
-        self.declare(name, ModuleDefId::TypeId(id))
+    pub fn declare_trait(
+        &mut self,
+        name: Ident,
+        visibility: ItemVisibility,
+        id: TraitId,
+    ) -> Result<(), (Ident, Ident)> {
+        self.declare(name, visibility, ModuleDefId::TraitId(id))
     }
 
+    /// Modules are always public, their items are what may be hidden
     pub fn declare_child_module(
         &mut self,
         name: Ident,
         child_id: ModuleId,
     ) -> Result<(), (Ident, Ident)> {
-        self.declare(name, child_id.into())
+        self.declare(name, ItemVisibility::Public, child_id.into())
     }
 
     pub fn find_func_with_name(&self, name: &Ident) -> Option<FuncId> {
         self.scope.find_func_with_name(name)
     }
 
-    pub fn import(&mut self, name: Ident, id: ModuleDefId) -> Result<(), (Ident, Ident)> {
-        self.scope.add_item_to_namespace(name, id)
+    pub fn import(
+        &mut self,
+        name: Ident,
+        visibility: ItemVisibility,
+        id: ModuleDefId,
+    ) -> Result<(), (Ident, Ident)> {
+        self.scope.add_item_to_namespace(name, visibility, id)
     }
 
     pub fn find_name(&self, name: &Ident) -> PerNs {
         self.scope.find_name(name)
     }
 
+    pub fn find_declared_name(&self, name: &Ident) -> Option<&Ident> {
+        self.scope.find_declared_name(name)
+    }
+
+    /// Returns the visibility of a function or global defined within this module
+    pub fn value_visibility(&self, id: ModuleDefId) -> Option<ItemVisibility> {
+        let mut values = self.definitions.values().values();
+        values.find(|(value_id, _)| *value_id == id).map(|(_, visibility)| *visibility)
+    }
+
     /// Return an iterator over all definitions defined within this module,
     /// excluding any type definitions.
     pub fn value_definitions(&self) -> impl Iterator<Item = ModuleDefId> + '_ {
@@ -1,8 +1,8 @@
 pub use noirc_errors::Span;
-use noirc_errors::{CustomDiagnostic as Diagnostic, FileDiagnostic};
+use noirc_errors::{CustomDiagnostic as Diagnostic, FileDiagnostic, Location};
 use thiserror::Error;
 
-use crate::{parser::ParserError, Ident, Type};
+use crate::{parser::ParserError, Ident, ItemVisibility, Type};
 
 use super::import::PathResolutionError;
 
@@ -24,6 +24,13 @@ pub enum ResolverError {
     DuplicateField { field: Ident },
     #[error("No such field in struct")]
     NoSuchField { field: Ident, struct_definition: Ident },
+    #[error("Field of struct is private")]
+    PrivateField {
+        field: Ident,
+        struct_definition: Ident,
+        declaration: Location,
+        visibility: ItemVisibility,
+    },
     #[error("Missing fields from struct")]
     MissingFields { span: Span, missing_fields: Vec<String>, struct_definition: Ident },
     #[error("Unneeded 'mut', pattern is already marked as mutable")]
@@ -60,6 +67,20 @@ pub enum ResolverError {
     ParserError(Box<ParserError>),
     #[error("Function is not defined in a contract yet sets its contract visibility")]
     ContractFunctionTypeInNormalFunction { span: Span },
+    #[error("Missing methods from trait impl")]
+    MissingTraitMethods { span: Span, missing_methods: Vec<String>, trait_name: Ident },
+    #[error("Method is not a member of the trait")]
+    NotATraitMethod { method: Ident, trait_name: Ident },
+    #[error("Method signature does not match the trait")]
+    TraitMethodSignatureMismatch { method: Ident, expected: Type, actual: Type },
+    #[error("Overlapping trait impls")]
+    OverlappingTraitImpls { trait_name: Ident, typ: Type, first_span: Span, second_span: Span },
+    #[error("Expected an enum variant")]
+    NotAnEnumVariant { name: String, span: Span },
+    #[error("Loop control flow used outside of a loop")]
+    LoopControlOutsideLoop { keyword: &'static str, span: Span },
+    #[error("Nested arrays are not supported as inputs to the entry-point function")]
+    NestedArrayParameter { span: Span },
 }
 
 impl ResolverError {
@@ -126,6 +147,20 @@ impl From<ResolverError> for Diagnostic {
                 );
                 error
             }
+            ResolverError::PrivateField { field, struct_definition, declaration, visibility } => {
+                let mut error = Diagnostic::simple_error(
+                    format!("field {field} of struct {struct_definition} is private"),
+                    String::new(),
+                    field.span(),
+                );
+
+                error.add_secondary_in_file(
+                    format!("{field} is declared here {}", visibility.describe_declaration()),
+                    declaration.span,
+                    declaration.file,
+                );
+                error
+            }
             ResolverError::MissingFields { span, missing_fields, struct_definition } => {
                 let plural = if missing_fields.len() != 1 { "s" } else { "" };
                 let missing_fields = missing_fields.join(", ");
@@ -258,6 +293,52 @@ impl From<ResolverError> for Diagnostic {
                 "Non-contract functions cannot be 'open'".into(),
                 span,
             ),
+            ResolverError::MissingTraitMethods { span, missing_methods, trait_name } => {
+                let plural = if missing_methods.len() != 1 { "s" } else { "" };
+                let missing_methods = missing_methods.join(", ");
+
+                Diagnostic::simple_error(
+                    format!("missing method{plural} {missing_methods} in impl of trait {trait_name}"),
+                    format!("missing {missing_methods}"),
+                    span,
+                )
+            }
+            ResolverError::NotATraitMethod { method, trait_name } => Diagnostic::simple_error(
+                format!("method {method} is not a member of trait {trait_name}"),
+                "not a member of the trait".into(),
+                method.span(),
+            ),
+            ResolverError::TraitMethodSignatureMismatch { method, expected, actual } => {
+                Diagnostic::simple_error(
+                    format!("method {method} has an incompatible type for its trait"),
+                    format!("expected type {expected}, found {actual}"),
+                    method.span(),
+                )
+            }
+            ResolverError::OverlappingTraitImpls { trait_name, typ, first_span, second_span } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("conflicting implementations of trait {trait_name} for type {typ}"),
+                    "first implementation here".into(),
+                    first_span,
+                );
+                diag.add_secondary("conflicting implementation here".into(), second_span);
+                diag
+            }
+            ResolverError::NotAnEnumVariant { name, span } => Diagnostic::simple_error(
+                format!("expected an enum variant, found {name}"),
+                "not an enum variant".into(),
+                span,
+            ),
+            ResolverError::LoopControlOutsideLoop { keyword, span } => Diagnostic::simple_error(
+                format!("`{keyword}` outside of a loop"),
+                format!("`{keyword}` can only be used within a for or while loop"),
+                span,
+            ),
+            ResolverError::NestedArrayParameter { span } => Diagnostic::simple_error(
+                "Nested arrays and arrays of strings are not supported as inputs to the entry-point function".into(),
+                "Try flattening this parameter into a single array".into(),
+                span,
+            ),
         }
     }
 }
@@ -1,17 +1,20 @@
 use iter_extended::partition_results;
-use noirc_errors::CustomDiagnostic;
+use noirc_errors::{CustomDiagnostic, Location};
 
 use crate::graph::CrateId;
 use std::collections::HashMap;
 
 use crate::hir::def_map::{CrateDefMap, LocalModuleId, ModuleDefId, ModuleId, PerNs};
-use crate::{Ident, Path, PathKind};
+use crate::node_interner::FuncId;
+use crate::{Ident, ItemVisibility, Path, StructType};
 
 #[derive(Debug, Clone)]
 pub struct ImportDirective {
     pub module_id: LocalModuleId,
     pub path: Path,
     pub alias: Option<Ident>,
+    /// The visibility of the imported name within `module_id`
+    pub visibility: ItemVisibility,
 }
 
 pub type PathResolution = Result<PerNs, PathResolutionError>;
@@ -20,6 +23,13 @@ pub type PathResolution = Result<PerNs, PathResolutionError>;
 pub enum PathResolutionError {
     Unresolved(Ident),
     ExternalContractUsed(Ident),
+    /// The item named is not visible from the module of the path. The location is that of
+    /// the item's declaration, or of the import which brought it into its module.
+    Private {
+        name: Ident,
+        declaration: Location,
+        visibility: ItemVisibility,
+    },
 }
 
 #[derive(Debug)]
@@ -30,6 +40,8 @@ pub struct ResolvedImport {
     pub resolved_namespace: PerNs,
     // The module which we must add the resolved namespace to
     pub module_scope: LocalModuleId,
+    // The visibility of the name within that module
+    pub visibility: ItemVisibility,
 }
 
 impl From<PathResolutionError> for CustomDiagnostic {
@@ -45,6 +57,19 @@ impl From<PathResolutionError> for CustomDiagnostic {
                 "Contracts may only be referenced from within a contract".to_string(),
                 ident.span(),
             ),
+            PathResolutionError::Private { name, declaration, visibility } => {
+                let mut diagnostic = CustomDiagnostic::simple_error(
+                    format!("'{name}' is private and cannot be referenced from this module"),
+                    String::new(),
+                    name.span(),
+                );
+                diagnostic.add_secondary_in_file(
+                    format!("'{name}' is declared here {}", visibility.describe_declaration()),
+                    declaration.span,
+                    declaration.file,
+                );
+                diagnostic
+            }
         }
     }
 }
@@ -66,7 +91,8 @@ pub fn resolve_imports(
                 .map_err(|error| (error, module_scope))?;
 
         let name = resolve_path_name(&import_directive);
-        Ok(ResolvedImport { name, resolved_namespace, module_scope })
+        let visibility = import_directive.visibility;
+        Ok(ResolvedImport { name, resolved_namespace, module_scope, visibility })
     })
 }
 
@@ -85,11 +111,18 @@ pub fn resolve_path_to_ns(
     allow_contracts: bool,
 ) -> PathResolution {
     let import_path = &import_directive.path.segments;
+    let importing_module = ModuleId { krate: def_map.krate, local_id: import_directive.module_id };
 
     match import_directive.path.kind {
         crate::ast::PathKind::Crate => {
             // Resolve from the root of the crate
-            resolve_path_from_crate_root(def_map, import_path, def_maps, allow_contracts)
+            resolve_path_from_crate_root(
+                def_map,
+                import_path,
+                importing_module,
+                def_maps,
+                allow_contracts,
+            )
         }
         crate::ast::PathKind::Dep => {
             resolve_external_dep(def_map, import_directive, def_maps, allow_contracts)
@@ -101,6 +134,7 @@ pub fn resolve_path_to_ns(
                 def_map,
                 import_path,
                 import_directive.module_id,
+                importing_module,
                 def_maps,
                 allow_contracts,
             )
@@ -111,34 +145,47 @@ pub fn resolve_path_to_ns(
 fn resolve_path_from_crate_root(
     def_map: &CrateDefMap,
     import_path: &[Ident],
+    importing_module: ModuleId,
     def_maps: &HashMap<CrateId, CrateDefMap>,
     allow_contracts: bool,
 ) -> PathResolution {
-    resolve_name_in_module(def_map, import_path, def_map.root, def_maps, allow_contracts)
+    resolve_name_in_module(
+        def_map,
+        import_path,
+        def_map.root,
+        importing_module,
+        def_maps,
+        allow_contracts,
+    )
 }
 
+/// Resolves `import_path` starting from `starting_mod`, where each segment must be visible
+/// from `importing_module`, the module in which the path was written.
 fn resolve_name_in_module(
     def_map: &CrateDefMap,
     import_path: &[Ident],
     starting_mod: LocalModuleId,
+    importing_module: ModuleId,
     def_maps: &HashMap<CrateId, CrateDefMap>,
     allow_contracts: bool,
 ) -> PathResolution {
-    let mut current_mod = &def_map.modules[starting_mod.0];
+    let mut current_mod_id = ModuleId { krate: def_map.krate, local_id: starting_mod };
 
     // There is a possibility that the import path is empty
     // In that case, early return
     if import_path.is_empty() {
-        let mod_id = ModuleId { krate: def_map.krate, local_id: starting_mod };
-        return Ok(PerNs::types(mod_id.into()));
+        return Ok(PerNs::types(current_mod_id.into()));
     }
 
     let mut import_path = import_path.iter();
     let first_segment = import_path.next().expect("ice: could not fetch first segment");
-    let mut current_ns = current_mod.find_name(first_segment);
-    if current_ns.is_none() {
-        return Err(PathResolutionError::Unresolved(first_segment.clone()));
-    }
+    let mut current_ns = find_visible_name(
+        def_maps,
+        current_mod_id,
+        current_mod_id,
+        first_segment,
+        importing_module,
+    )?;
 
     for segment in import_path {
         let typ = match current_ns.take_types() {
@@ -152,19 +199,31 @@ fn resolve_name_in_module(
             ModuleDefId::FunctionId(_) => panic!("functions cannot be in the type namespace"),
             // TODO: If impls are ever implemented, types can be used in a path
             ModuleDefId::TypeId(id) => id.0,
+            ModuleDefId::TraitId(id) => id.0,
             ModuleDefId::GlobalId(_) => panic!("globals cannot be in the type namespace"),
         };
 
-        current_mod = &def_maps[&new_module_id.krate].modules[new_module_id.local_id.0];
+        current_mod_id = new_module_id;
+
+        // The methods of a type are visible as if they were declared alongside the type
+        let defining_module = match typ {
+            ModuleDefId::TypeId(_) | ModuleDefId::TraitId(_) => {
+                parent_module(def_maps, current_mod_id)
+            }
+            _ => current_mod_id,
+        };
 
         // Check if namespace
-        let found_ns = current_mod.find_name(segment);
-        if found_ns.is_none() {
-            return Err(PathResolutionError::Unresolved(segment.clone()));
-        }
+        let found_ns = find_visible_name(
+            def_maps,
+            current_mod_id,
+            defining_module,
+            segment,
+            importing_module,
+        )?;
 
         // Check if it is a contract and we're calling from a non-contract context
-        if current_mod.is_contract && !allow_contracts {
+        if current_mod_id.module(def_maps).is_contract && !allow_contracts {
             return Err(PathResolutionError::ExternalContractUsed(segment.clone()));
         }
 
@@ -174,6 +233,110 @@ fn resolve_name_in_module(
     Ok(current_ns)
 }
 
+/// Looks up `name` in `module_id`, keeping only the items visible from `importing_module`.
+/// The visibility of these items is that of items declared in `defining_module`.
+fn find_visible_name(
+    def_maps: &HashMap<CrateId, CrateDefMap>,
+    module_id: ModuleId,
+    defining_module: ModuleId,
+    name: &Ident,
+    importing_module: ModuleId,
+) -> PathResolution {
+    let module = module_id.module(def_maps);
+    let found_ns = module.find_name(name);
+    if found_ns.is_none() {
+        return Err(PathResolutionError::Unresolved(name.clone()));
+    }
+
+    let is_visible = |(_, visibility): &(ModuleDefId, ItemVisibility)| {
+        can_reference_item(def_maps, defining_module, *visibility, importing_module)
+    };
+    let visible_ns = PerNs {
+        types: found_ns.types.filter(is_visible),
+        values: found_ns.values.filter(is_visible),
+    };
+
+    if visible_ns.is_none() {
+        let declared_name = module.find_declared_name(name).expect("the name was just found");
+        let declaration = Location::new(declared_name.span(), module.origin.file_id());
+        let (_, visibility) = found_ns.iter_items().next().expect("the name was just found");
+        return Err(PathResolutionError::Private { name: name.clone(), declaration, visibility });
+    }
+    Ok(visible_ns)
+}
+
+/// Returns the parent of the module of a struct or trait, which is the module declaring it
+fn parent_module(def_maps: &HashMap<CrateId, CrateDefMap>, module_id: ModuleId) -> ModuleId {
+    let parent = module_id.module(def_maps).parent.expect("the module of a type has a parent");
+    ModuleId { krate: module_id.krate, local_id: parent }
+}
+
+/// Returns true if an item declared in `defining_module` with the given visibility may be
+/// referred to from `referencing_module`. Private items are visible within their module and
+/// its descendants.
+pub(crate) fn can_reference_item(
+    def_maps: &HashMap<CrateId, CrateDefMap>,
+    defining_module: ModuleId,
+    visibility: ItemVisibility,
+    referencing_module: ModuleId,
+) -> bool {
+    match visibility {
+        ItemVisibility::Public => true,
+        ItemVisibility::PublicCrate => defining_module.krate == referencing_module.krate,
+        ItemVisibility::Private => {
+            let modules = &def_maps[&referencing_module.krate].modules;
+            defining_module.krate == referencing_module.krate
+                && std::iter::successors(Some(referencing_module.local_id), |id| {
+                    modules[id.0].parent
+                })
+                .any(|id| id == defining_module.local_id)
+        }
+    }
+}
+
+/// Returns the location of the declaration of a field of the given struct, along with its
+/// visibility, if the field is not visible from `referencing_module`. Fields are visible
+/// wherever an item declared alongside their struct with the same visibility would be.
+pub(crate) fn find_private_field(
+    def_maps: &HashMap<CrateId, CrateDefMap>,
+    struct_type: &StructType,
+    field_name: &str,
+    referencing_module: ModuleId,
+) -> Option<(Location, ItemVisibility)> {
+    let (declared_name, visibility) = struct_type.field_visibility(field_name)?;
+
+    // Each struct has a module of its own, whose parent is the module declaring the struct
+    let struct_module = struct_type.id.0;
+    let defining_module = parent_module(def_maps, struct_module);
+
+    if can_reference_item(def_maps, defining_module, visibility, referencing_module) {
+        None
+    } else {
+        let file = struct_module.module(def_maps).origin.file_id();
+        Some((Location::new(declared_name.span(), file), visibility))
+    }
+}
+
+/// Returns the visibility of a method of the given struct if the method is not visible from
+/// `referencing_module`. Like fields, methods are visible wherever an item declared alongside
+/// their struct with the same visibility would be.
+pub(crate) fn find_private_method(
+    def_maps: &HashMap<CrateId, CrateDefMap>,
+    struct_type: &StructType,
+    method_id: FuncId,
+    referencing_module: ModuleId,
+) -> Option<ItemVisibility> {
+    let struct_module = struct_type.id.0;
+    let visibility = struct_module.module(def_maps).value_visibility(method_id.into())?;
+
+    let defining_module = parent_module(def_maps, struct_module);
+    if can_reference_item(def_maps, defining_module, visibility, referencing_module) {
+        None
+    } else {
+        Some(visibility)
+    }
+}
+
 fn resolve_path_name(import_directive: &ImportDirective) -> Ident {
     match &import_directive.alias {
         None => import_directive.path.segments.last().unwrap().clone(),
@@ -198,14 +361,19 @@ fn resolve_external_dep(
         .get(&crate_name)
         .unwrap_or_else(|| panic!("error reporter: could not find crate {crate_name}"));
 
-    // Create an import directive for the dependency crate
     let path_without_crate_name = &path[1..]; // XXX: This will panic if the path is of the form `use dep::std` Ideal algorithm will not distinguish between crate and module
 
-    let path = Path { segments: path_without_crate_name.to_vec(), kind: PathKind::Plain };
-    let dep_directive =
-        ImportDirective { module_id: dep_module.local_id, path, alias: directive.alias.clone() };
-
+    // The path is resolved within the dependency, but its items must be visible from the
+    // module of the directive
+    let importing_module = ModuleId { krate: current_def_map.krate, local_id: directive.module_id };
     let dep_def_map = def_maps.get(&dep_module.krate).unwrap();
 
-    resolve_path_to_ns(&dep_directive, dep_def_map, def_maps, allow_contracts)
+    resolve_name_in_module(
+        dep_def_map,
+        path_without_crate_name,
+        dep_module.local_id,
+        importing_module,
+        def_maps,
+        allow_contracts,
+    )
 }
@@ -14,8 +14,9 @@
 use crate::hir_def::expr::{
     HirArrayLiteral, HirBinaryOp, HirBlockExpression, HirCallExpression, HirCastExpression,
     HirConstructorExpression, HirExpression, HirForExpression, HirIdent, HirIfExpression,
-    HirIndexExpression, HirInfixExpression, HirLambda, HirLiteral, HirMemberAccess,
-    HirMethodCallExpression, HirPrefixExpression,
+    HirIndexExpression, HirInfixExpression, HirLambda, HirLiteral, HirMatchExpression,
+    HirMatchPattern, HirMemberAccess, HirMethodCallExpression, HirPrefixExpression,
+    HirWhileExpression,
 };
 use crate::token::Attribute;
 use std::collections::{HashMap, HashSet};
@@ -25,17 +26,17 @@ use crate::graph::CrateId;
 use crate::hir::def_map::{ModuleDefId, TryFromModuleDefId, MAIN_FUNCTION};
 use crate::hir_def::stmt::{HirAssignStatement, HirLValue, HirPattern};
 use crate::node_interner::{
-    DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, StmtId, StructId,
+    DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, StmtId, StructId, TraitId,
 };
 use crate::{
     hir::{def_map::CrateDefMap, resolution::path_resolver::PathResolver},
-    BlockExpression, Expression, ExpressionKind, FunctionKind, Ident, Literal, NoirFunction,
-    Statement,
+    BlockExpression, Expression, ExpressionKind, FunctionKind, Ident, ItemVisibility, Literal,
+    NoirFunction, Statement, TraitFunction, UnresolvedTraitConstraint,
 };
 use crate::{
-    ArrayLiteral, ContractFunctionType, Generics, LValue, NoirStruct, Path, Pattern, Shared,
-    StructType, Type, TypeBinding, TypeVariable, UnresolvedGenerics, UnresolvedType,
-    UnresolvedTypeExpression, ERROR_IDENT,
+    ArrayLiteral, ContractFunctionType, Generics, LValue, MatchPattern, NoirEnum, NoirStruct, Path,
+    Pattern, Shared, StructType, Type, TypeBinding, TypeVariable, UnresolvedGenerics,
+    UnresolvedType, UnresolvedTypeExpression, ERROR_IDENT,
 };
 use fm::FileId;
 use iter_extended::vecmap;
@@ -47,9 +48,11 @@ use crate::hir::scope::{
 use crate::hir_def::{
     function::{FuncMeta, HirFunction, Param},
     stmt::{HirConstrainStatement, HirLetStatement, HirStatement},
+    traits::TraitConstraint,
 };
 
 use super::errors::ResolverError;
+use super::import::find_private_field;
 
 const SELF_TYPE_NAME: &str = "Self";
 
@@ -83,9 +86,20 @@ pub struct Resolver<'a> {
     /// Lambdas share the function scope of the function they're defined in,
     /// so to identify whether they use any variables from the parent function
     /// we keep track of the scope index a variable is declared in. When a lambda
-    /// is declared we push a scope and set this lambda_index to the scope index.
-    /// Any variable from a scope less than that must be from the parent function.
-    lambda_index: usize,
+    /// is declared we push a scope and push a LambdaContext with its scope index.
+    /// Any variable from a scope less than that must be captured by the lambda.
+    lambda_stack: Vec<LambdaContext>,
+
+    /// The number of loops enclosing the expression being resolved, used to check that
+    /// `break` and `continue` are only used within loops. Lambdas reset this to zero.
+    loop_depth: usize,
+}
+
+/// The variables captured so far by a lambda being resolved
+struct LambdaContext {
+    captures: Vec<HirIdent>,
+    /// The index of the scope the lambda's parameters are declared in
+    scope_index: usize,
 }
 
 /// ResolverMetas are tagged onto each definition to track how many times they are used
@@ -111,7 +125,8 @@ impl<'a> Resolver<'a> {
             self_type: None,
             generics: Vec::new(),
             errors: Vec::new(),
-            lambda_index: 0,
+            lambda_stack: Vec::new(),
+            loop_depth: 0,
             file,
         }
     }
@@ -211,6 +226,7 @@ impl<'a> Resolver<'a> {
         let id = self.interner.push_definition(name.0.contents.clone(), mutable, definition);
         let location = Location::new(name.span(), self.file);
         let ident = HirIdent { location, id };
+        self.interner.push_declaration(ident);
         let resolver_meta = ResolverMeta { num_times_used: 0, ident, warn_if_unused };
 
         let scope = self.scopes.get_mut_scope();
@@ -255,6 +271,7 @@ impl<'a> Resolver<'a> {
             let id = self.interner.push_definition(name.0.contents.clone(), false, definition);
             let location = Location::new(name.span(), self.file);
             ident = HirIdent { location, id };
+            self.interner.push_declaration(ident);
             resolver_meta = ResolverMeta { num_times_used: 0, ident, warn_if_unused: true };
         }
 
@@ -291,10 +308,12 @@ impl<'a> Resolver<'a> {
         let variable = scope_tree.find(&name.0.contents);
 
         let location = Location::new(name.span(), self.file);
-        if let Some((variable_found, _)) = variable {
+        if let Some((variable_found, scope_index)) = variable {
             variable_found.num_times_used += 1;
-            let id = variable_found.ident.id;
-            Ok(HirIdent { location, id })
+            let ident = HirIdent { location, id: variable_found.ident.id };
+            self.interner.push_reference(ident);
+            self.capture_variable(ident, scope_index);
+            Ok(ident)
         } else {
             Err(ResolverError::VariableNotDeclared {
                 name: name.0.contents.clone(),
@@ -303,6 +322,31 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Records a local variable declared in the scope with the given index as captured by
+    /// each lambda being resolved which is declared after that scope.
+    fn capture_variable(&mut self, ident: HirIdent, scope_index: usize) {
+        let captured = self.lambda_stack.last().map_or(false, |lambda| {
+            let kind = &self.interner.definition(ident.id).kind;
+            scope_index < lambda.scope_index && matches!(kind, DefinitionKind::Local(_))
+        });
+
+        if !captured {
+            return;
+        }
+
+        // Closures capture variables by value, so changes to mutable variables could not be seen
+        if self.interner.definition(ident.id).mutable {
+            self.push_err(ResolverError::CapturedMutableVariable { span: ident.location.span });
+        }
+
+        let lambdas = self.lambda_stack.iter_mut().rev();
+        for lambda in lambdas.take_while(|lambda| scope_index < lambda.scope_index) {
+            if !lambda.captures.iter().any(|capture| capture.id == ident.id) {
+                lambda.captures.push(ident);
+            }
+        }
+    }
+
     fn intern_function(&mut self, func: NoirFunction, id: FuncId) -> (HirFunction, FuncMeta) {
         let func_meta = self.extract_meta(&func, id);
 
@@ -342,10 +386,11 @@ impl<'a> Resolver<'a> {
             UnresolvedType::Tuple(fields) => {
                 Type::Tuple(vecmap(fields, |field| self.resolve_type_inner(field, new_variables)))
             }
-            UnresolvedType::Function(args, ret) => {
+            UnresolvedType::Function(args, ret, env) => {
                 let args = vecmap(args, |arg| self.resolve_type_inner(arg, new_variables));
                 let ret = Box::new(self.resolve_type_inner(*ret, new_variables));
-                Type::Function(args, ret)
+                let env = Box::new(self.resolve_type_inner(*env, new_variables));
+                Type::Function(args, ret, env)
             }
             UnresolvedType::Vec(mut args, span) => {
                 let arg = if args.len() != 1 {
@@ -492,11 +537,11 @@ impl<'a> Resolver<'a> {
             Some(Ok(ident)) => return ident,
             // Try to look it up as a global, but still issue the first error if we fail
             Some(Err(error)) => match self.lookup_global(path) {
-                Ok(id) => return HirIdent { location, id },
+                Ok(id) => return self.global_reference(location, id),
                 Err(_) => error,
             },
             None => match self.lookup_global(path) {
-                Ok(id) => return HirIdent { location, id },
+                Ok(id) => return self.global_reference(location, id),
                 Err(error) => error,
             },
         };
@@ -505,6 +550,12 @@ impl<'a> Resolver<'a> {
         HirIdent { location, id }
     }
 
+    fn global_reference(&mut self, location: Location, id: DefinitionId) -> HirIdent {
+        let ident = HirIdent { location, id };
+        self.interner.push_reference(ident);
+        ident
+    }
+
     /// Translates an UnresolvedType to a Type
     pub fn resolve_type(&mut self, typ: UnresolvedType) -> Type {
         self.resolve_type_inner(typ, &mut vec![])
@@ -567,17 +618,93 @@ impl<'a> Resolver<'a> {
     pub fn resolve_struct_fields(
         mut self,
         unresolved: NoirStruct,
-    ) -> (Generics, Vec<(Ident, Type)>, Vec<ResolverError>) {
+    ) -> (Generics, Vec<(Ident, Type, ItemVisibility)>, Vec<ResolverError>) {
         let generics = self.add_generics(&unresolved.generics);
 
         // Check whether the struct definition has globals in the local module and add them to the scope
         self.resolve_local_globals();
 
-        let fields = vecmap(unresolved.fields, |(ident, typ)| (ident, self.resolve_type(typ)));
+        let fields = vecmap(unresolved.fields, |(ident, typ, visibility)| {
+            (ident, self.resolve_type(typ), visibility)
+        });
 
         (generics, fields, self.errors)
     }
 
+    /// Resolves the fields of each variant of an enum, along with the signature of the function
+    /// constructing each variant. These take the fields of their variant as parameters and are
+    /// generic over the generics of the enum.
+    pub fn resolve_enum_variants(
+        mut self,
+        unresolved: NoirEnum,
+        enum_id: StructId,
+        variant_ids: &[FuncId],
+    ) -> (Generics, Vec<(Ident, Vec<Type>)>, Vec<FuncMeta>, Vec<ResolverError>) {
+        let generics = self.add_generics(&unresolved.generics);
+
+        // Check whether the enum definition has globals in the local module and add them to the scope
+        self.resolve_local_globals();
+
+        let enum_type = self.get_struct(enum_id);
+        let generic_args = vecmap(&generics, |(_, typevar)| Type::TypeVariable(typevar.clone()));
+        let return_type = Type::Struct(enum_type, generic_args);
+
+        let mut variants = Vec::new();
+        let mut variant_metas = Vec::new();
+
+        for (index, ((name, fields), func_id)) in
+            unresolved.variants.into_iter().zip(variant_ids).enumerate()
+        {
+            let location = Location::new(name.span(), self.file);
+            let id = self.interner.function_definition_id(*func_id);
+            let name_ident = HirIdent { id, location };
+            self.interner.push_declaration(name_ident);
+
+            let fields = vecmap(fields, |typ| self.resolve_type(typ));
+
+            let parameters = vecmap(fields.iter().enumerate(), |(i, typ)| {
+                let id = self.interner.push_definition(
+                    format!("_{i}"),
+                    false,
+                    DefinitionKind::Local(None),
+                );
+                let pattern = HirPattern::Identifier(HirIdent { id, location });
+                Param(pattern, typ.clone(), noirc_abi::AbiVisibility::Private)
+            });
+
+            let function_type =
+                Type::Function(fields.clone(), Box::new(return_type.clone()), Box::new(Type::Unit));
+            let typ = if generics.is_empty() {
+                function_type
+            } else {
+                Type::Forall(generics.clone(), Box::new(function_type))
+            };
+            self.interner.push_definition_type(id, typ.clone());
+
+            variant_metas.push(FuncMeta {
+                name: name_ident,
+                kind: FunctionKind::Normal,
+                attributes: Vec::new(),
+                contract_function_type: None,
+                is_unconstrained: false,
+                location,
+                module_id: self.path_resolver.module_id(),
+                typ,
+                parameters: parameters.into(),
+                return_visibility: noirc_abi::AbiVisibility::Private,
+                return_distinctness: noirc_abi::AbiDistinctness::DuplicationAllowed,
+                has_body: false,
+                trait_constraints: Vec::new(),
+                trait_id: None,
+                enum_variant: Some((enum_id, index)),
+            });
+
+            variants.push((name, fields));
+        }
+
+        (generics, variants, variant_metas, self.errors)
+    }
+
     fn resolve_local_globals(&mut self) {
         for (stmt_id, global_info) in self.interner.get_all_globals() {
             if global_info.local_id == self.path_resolver.local_module_id() {
@@ -596,8 +723,9 @@ impl<'a> Resolver<'a> {
         let location = Location::new(func.name_ident().span(), self.file);
         let id = self.interner.function_definition_id(func_id);
         let name_ident = HirIdent { id, location };
+        self.interner.push_declaration(name_ident);
 
-        let attributes = func.attribute().cloned();
+        let attributes = func.attributes().to_vec();
 
         let mut generics =
             vecmap(self.generics.clone(), |(name, typevar, _)| match &*typevar.borrow() {
@@ -615,8 +743,12 @@ impl<'a> Resolver<'a> {
                 self.push_err(ResolverError::UnnecessaryPub { ident: func.name_ident().clone() });
             }
 
+            let span = pattern.span();
             let pattern = self.resolve_pattern(pattern, DefinitionKind::Local(None));
             let typ = self.resolve_type_inner(typ, &mut generics);
+            if self.pub_allowed(func) && has_nested_array(&typ, false) {
+                self.push_err(ResolverError::NestedArrayParameter { span });
+            }
             parameters.push(Param(pattern, typ.clone(), visibility));
             parameter_types.push(typ);
         }
@@ -625,6 +757,13 @@ impl<'a> Resolver<'a> {
 
         self.declare_numeric_generics(&parameter_types, &return_type);
 
+        let trait_constraints = func
+            .def
+            .trait_constraints
+            .iter()
+            .filter_map(|constraint| self.resolve_trait_constraint(constraint.clone()))
+            .collect();
+
         // 'pub_allowed' also implies 'pub' is required on return types
         if self.pub_allowed(func)
             && return_type.as_ref() != &Type::Unit
@@ -639,13 +778,14 @@ impl<'a> Resolver<'a> {
             self.push_err(ResolverError::DistinctNotAllowed { ident: func.name_ident().clone() });
         }
 
-        if attributes == Some(Attribute::Test) && !parameters.is_empty() {
+        let is_test = attributes.iter().any(|attribute| matches!(attribute, Attribute::Test(_)));
+        if is_test && !parameters.is_empty() {
             self.push_err(ResolverError::TestFunctionHasParameters {
                 span: func.name_ident().span(),
             });
         }
 
-        let mut typ = Type::Function(parameter_types, return_type);
+        let mut typ = Type::Function(parameter_types, return_type, Box::new(Type::Unit));
 
         if !generics.is_empty() {
             typ = Type::Forall(generics, Box::new(typ));
@@ -660,14 +800,92 @@ impl<'a> Resolver<'a> {
             contract_function_type: self.handle_function_type(func),
             is_unconstrained: func.def.is_unconstrained,
             location,
+            module_id: self.path_resolver.module_id(),
             typ,
             parameters: parameters.into(),
             return_visibility: func.def.return_visibility,
             return_distinctness: func.def.return_distinctness,
             has_body: !func.def.body.is_empty(),
+            trait_constraints,
+            trait_id: None,
+            enum_variant: None,
         }
     }
 
+    /// Resolves the signature of a method declared within a trait.
+    /// The method is given a function type generic over `Self`, the type implementing the trait,
+    /// which is constrained to implement the trait so that the method may be called on it.
+    pub fn resolve_trait_method(
+        mut self,
+        method: TraitFunction,
+        func_id: FuncId,
+        trait_id: TraitId,
+    ) -> (FuncMeta, Vec<ResolverError>) {
+        self.scopes.start_function();
+
+        let location = Location::new(method.name.span(), self.file);
+        let id = self.interner.function_definition_id(func_id);
+        let name_ident = HirIdent { id, location };
+        self.interner.push_declaration(name_ident);
+
+        let self_generic = Ident::new(SELF_TYPE_NAME.to_owned(), method.name.span());
+        let generics = self.add_generics(&vec![self_generic]);
+        let self_type = Type::NamedGeneric(generics[0].1.clone(), Rc::new(SELF_TYPE_NAME.into()));
+
+        let mut parameters = vec![];
+        let mut parameter_types = vec![];
+
+        for (name, typ) in method.parameters {
+            let pattern = HirPattern::Identifier(self.add_variable_decl(
+                name,
+                false,
+                false,
+                DefinitionKind::Local(None),
+            ));
+            let typ = self.resolve_type(typ);
+            parameters.push(Param(pattern, typ.clone(), noirc_abi::AbiVisibility::Private));
+            parameter_types.push(typ);
+        }
+
+        let return_type = Box::new(self.resolve_type(method.return_type));
+        let function_type = Type::Function(parameter_types, return_type, Box::new(Type::Unit));
+        let typ = Type::Forall(generics, Box::new(function_type));
+        self.interner.push_definition_type(name_ident.id, typ.clone());
+
+        // The parameters of a method without a body are never used
+        self.scopes.end_function();
+
+        let func_meta = FuncMeta {
+            name: name_ident,
+            kind: FunctionKind::Normal,
+            attributes: Vec::new(),
+            contract_function_type: None,
+            is_unconstrained: false,
+            location,
+            module_id: self.path_resolver.module_id(),
+            typ,
+            parameters: parameters.into(),
+            return_visibility: noirc_abi::AbiVisibility::Private,
+            return_distinctness: noirc_abi::AbiDistinctness::DuplicationAllowed,
+            has_body: false,
+            trait_constraints: vec![TraitConstraint { typ: self_type, trait_id }],
+            trait_id: Some(trait_id),
+            enum_variant: None,
+        };
+
+        (func_meta, self.errors)
+    }
+
+    /// Resolves a bound such as `T: Hash`, returning None if the trait could not be found
+    pub fn resolve_trait_constraint(
+        &mut self,
+        constraint: UnresolvedTraitConstraint,
+    ) -> Option<TraitConstraint> {
+        let typ = self.resolve_type(constraint.typ);
+        let trait_id = self.lookup_trait_or_error(constraint.trait_name)?;
+        Some(TraitConstraint { typ, trait_id })
+    }
+
     /// True if the 'pub' keyword is allowed on parameters in this function
     fn pub_allowed(&self, func: &NoirFunction) -> bool {
         if self.in_contract() {
@@ -762,11 +980,12 @@ impl<'a> Resolver<'a> {
                     Self::find_numeric_generics_in_type(field, found);
                 }
             }
-            Type::Function(parameters, return_type) => {
+            Type::Function(parameters, return_type, env) => {
                 for parameter in parameters {
                     Self::find_numeric_generics_in_type(parameter, found);
                 }
                 Self::find_numeric_generics_in_type(return_type, found);
+                Self::find_numeric_generics_in_type(env, found);
             }
             Type::Struct(struct_type, generics) => {
                 for (i, generic) in generics.iter().enumerate() {
@@ -807,7 +1026,7 @@ impl<'a> Resolver<'a> {
             }
             Statement::Constrain(constrain_stmt) => {
                 let expr_id = self.resolve_expression(constrain_stmt.0);
-                HirStatement::Constrain(HirConstrainStatement(expr_id, self.file))
+                HirStatement::Constrain(HirConstrainStatement(expr_id, self.file, constrain_stmt.1))
             }
             Statement::Expression(expr) => HirStatement::Expression(self.resolve_expression(expr)),
             Statement::Semi(expr) => HirStatement::Semi(self.resolve_expression(expr)),
@@ -817,10 +1036,32 @@ impl<'a> Resolver<'a> {
                 let stmt = HirAssignStatement { lvalue: identifier, expression };
                 HirStatement::Assign(stmt)
             }
+            Statement::Break(span) => {
+                self.check_in_loop("break", span);
+                HirStatement::Break
+            }
+            Statement::Continue(span) => {
+                self.check_in_loop("continue", span);
+                HirStatement::Continue
+            }
             Statement::Error => HirStatement::Error,
         }
     }
 
+    fn check_in_loop(&mut self, keyword: &'static str, span: Span) {
+        if self.loop_depth == 0 {
+            self.push_err(ResolverError::LoopControlOutsideLoop { keyword, span });
+        }
+    }
+
+    /// Resolves the body of a loop, within which `break` and `continue` may be used
+    fn resolve_loop_body(&mut self, body: Expression) -> ExprId {
+        self.loop_depth += 1;
+        let body = self.resolve_expression(body);
+        self.loop_depth -= 1;
+        body
+    }
+
     pub fn intern_stmt(&mut self, stmt: Statement) -> StmtId {
         let hir_stmt = self.resolve_stmt(stmt);
         self.interner.push_stmt(hir_stmt)
@@ -874,7 +1115,16 @@ impl<'a> Resolver<'a> {
                 // This lookup allows support of such statements: let x = foo::bar::SOME_GLOBAL + 10;
                 // If the expression is a singular indent, we search the resolver's current scope as normal.
                 let hir_ident = self.get_ident_from_path(path);
-                HirExpression::Ident(hir_ident)
+
+                // A variant without fields is a value of its enum rather than a function
+                if self.is_unit_enum_variant(hir_ident) {
+                    let func = self.interner.push_expr(HirExpression::Ident(hir_ident));
+                    self.interner.push_expr_location(func, expr.span, self.file);
+                    let location = Location::new(expr.span, self.file);
+                    HirExpression::Call(HirCallExpression { func, arguments: Vec::new(), location })
+                } else {
+                    HirExpression::Ident(hir_ident)
+                }
             }
             ExpressionKind::Prefix(prefix) => {
                 let operator = prefix.operator;
@@ -928,7 +1178,7 @@ impl<'a> Resolver<'a> {
                         false,
                         DefinitionKind::Local(None),
                     );
-                    (decl, this.resolve_expression(block))
+                    (decl, this.resolve_loop_body(block))
                 });
 
                 HirExpression::For(HirForExpression {
@@ -938,6 +1188,11 @@ impl<'a> Resolver<'a> {
                     identifier,
                 })
             }
+            ExpressionKind::While(while_expr) => HirExpression::While(HirWhileExpression {
+                condition: self.resolve_expression(while_expr.condition),
+                body: self.resolve_loop_body(while_expr.body),
+                max_iterations: while_expr.max_iterations,
+            }),
             ExpressionKind::If(if_expr) => HirExpression::If(HirIfExpression {
                 condition: self.resolve_expression(if_expr.condition),
                 consequence: self.resolve_expression(if_expr.consequence),
@@ -952,7 +1207,7 @@ impl<'a> Resolver<'a> {
                 let span = constructor.type_name.span();
 
                 match self.lookup_type_or_error(constructor.type_name) {
-                    Some(Type::Struct(r#type, struct_generics)) => {
+                    Some(Type::Struct(r#type, struct_generics)) if !r#type.borrow().is_enum() => {
                         let typ = r#type.clone();
                         let fields = constructor.fields;
                         let resolve_expr = Resolver::resolve_expression;
@@ -987,8 +1242,8 @@ impl<'a> Resolver<'a> {
             // We must stay in the same function scope as the parent function to allow for closures
             // to capture variables. This is currently limited to immutable variables.
             ExpressionKind::Lambda(lambda) => self.in_new_scope(|this| {
-                let new_index = this.current_lambda_index();
-                let old_index = std::mem::replace(&mut this.lambda_index, new_index);
+                let scope_index = this.current_lambda_index();
+                this.lambda_stack.push(LambdaContext { captures: Vec::new(), scope_index });
 
                 let parameters = vecmap(lambda.parameters, |(pattern, typ)| {
                     let parameter = DefinitionKind::Local(None);
@@ -996,11 +1251,25 @@ impl<'a> Resolver<'a> {
                 });
 
                 let return_type = this.resolve_inferred_type(lambda.return_type);
+
+                // A lambda's body is a new function, so it cannot exit loops around the lambda
+                let loop_depth = std::mem::take(&mut this.loop_depth);
                 let body = this.resolve_expression(lambda.body);
+                this.loop_depth = loop_depth;
 
-                this.lambda_index = old_index;
-                HirExpression::Lambda(HirLambda { parameters, return_type, body })
+                let captures = this.lambda_stack.pop().expect("Expected a lambda context").captures;
+                HirExpression::Lambda(HirLambda { parameters, return_type, body, captures })
             }),
+            ExpressionKind::Match(match_expr) => {
+                let expression = self.resolve_expression(match_expr.expression);
+                let rules = vecmap(match_expr.rules, |(pattern, branch)| {
+                    self.in_new_scope(|this| {
+                        let pattern = this.resolve_match_pattern(pattern);
+                        (pattern, this.resolve_expression(branch))
+                    })
+                });
+                HirExpression::Match(HirMatchExpression { expression, rules })
+            }
         };
 
         let expr_id = self.interner.push_expr(hir_expr);
@@ -1054,7 +1323,11 @@ impl<'a> Resolver<'a> {
                 };
 
                 let (struct_type, generics) = match self.lookup_type_or_error(name) {
-                    Some(Type::Struct(struct_type, generics)) => (struct_type, generics),
+                    Some(Type::Struct(struct_type, generics))
+                        if !struct_type.borrow().is_enum() =>
+                    {
+                        (struct_type, generics)
+                    }
                     None => return error_identifier(self),
                     Some(typ) => {
                         self.push_err(ResolverError::NonStructUsedInConstructor { typ, span });
@@ -1075,6 +1348,78 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    fn resolve_match_pattern(&mut self, pattern: MatchPattern) -> HirMatchPattern {
+        match pattern {
+            MatchPattern::Wildcard(span) => HirMatchPattern::Wildcard(span),
+            MatchPattern::Bool(value, span) => HirMatchPattern::Bool(value, span),
+            MatchPattern::Integer(value, span) => HirMatchPattern::Integer(value, span),
+            MatchPattern::Tuple(fields, span) => {
+                let fields = vecmap(fields, |field| self.resolve_match_pattern(field));
+                HirMatchPattern::Tuple(fields, span)
+            }
+            MatchPattern::Identifier(name) => {
+                // A name referring to a variant without fields matches that variant
+                // rather than binding a new variable
+                let variant = match self.resolve_path(Path::from_ident(name.clone())) {
+                    Ok(id) => self.enum_variant_of(id),
+                    Err(_) => None,
+                };
+                let is_unit_variant = |this: &Self, variant: &FuncId| {
+                    this.interner.function_meta(variant).parameters.is_empty()
+                };
+
+                match variant.filter(|variant| is_unit_variant(self, variant)) {
+                    Some(variant) => HirMatchPattern::Variant(variant, Vec::new(), name.span()),
+                    None => {
+                        let definition = DefinitionKind::Local(None);
+                        HirMatchPattern::Identifier(
+                            self.add_variable_decl(name, false, false, definition),
+                        )
+                    }
+                }
+            }
+            MatchPattern::Variant(path, fields, span) => {
+                let name = path.to_string();
+                let variant = match self.resolve_path(path) {
+                    Ok(id) => self.enum_variant_of(id),
+                    Err(error) => {
+                        self.push_err(error);
+                        return HirMatchPattern::Wildcard(span);
+                    }
+                };
+
+                let fields = vecmap(fields, |field| self.resolve_match_pattern(field));
+                match variant {
+                    Some(variant) => HirMatchPattern::Variant(variant, fields, span),
+                    None => {
+                        self.push_err(ResolverError::NotAnEnumVariant { name, span });
+                        HirMatchPattern::Wildcard(span)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the function constructing an enum variant if the given definition is one
+    fn enum_variant_of(&self, id: ModuleDefId) -> Option<FuncId> {
+        let func_id = TryFromModuleDefId::try_from(id)?;
+        self.interner.try_function_meta(&func_id)?.enum_variant.map(|_| func_id)
+    }
+
+    /// True if the given identifier refers to a variant of an enum which has no fields
+    fn is_unit_enum_variant(&self, ident: HirIdent) -> bool {
+        if ident.id == DefinitionId::dummy_id() {
+            return false;
+        }
+        match self.interner.definition(ident.id).kind {
+            DefinitionKind::Function(func_id) => self
+                .interner
+                .try_function_meta(&func_id)
+                .map_or(false, |meta| meta.enum_variant.is_some() && meta.parameters.is_empty()),
+            _ => false,
+        }
+    }
+
     /// Resolve all the fields of a struct constructor expression.
     /// Ensures all fields are present, none are repeated, and all
     /// are part of the struct.
@@ -1098,6 +1443,7 @@ impl<'a> Resolver<'a> {
             if unseen_fields.contains(&field) {
                 unseen_fields.remove(&field);
                 seen_fields.insert(field.clone());
+                self.check_field_visibility(&struct_type.borrow(), &field);
             } else if seen_fields.contains(&field) {
                 // duplicate field
                 self.push_err(ResolverError::DuplicateField { field: field.clone() });
@@ -1123,6 +1469,22 @@ impl<'a> Resolver<'a> {
         ret
     }
 
+    /// Reports an error if the given field of a struct is private to the current module
+    fn check_field_visibility(&mut self, struct_type: &StructType, field: &Ident) {
+        let module = self.path_resolver.module_id();
+        let name = &field.0.contents;
+        let private_field = find_private_field(self.def_maps, struct_type, name, module);
+        if let Some((declaration, visibility)) = private_field {
+            let struct_definition = struct_type.name.clone();
+            self.push_err(ResolverError::PrivateField {
+                field: field.clone(),
+                struct_definition,
+                declaration,
+                visibility,
+            });
+        }
+    }
+
     pub fn get_struct(&self, type_id: StructId) -> Shared<StructType> {
         self.interner.get_struct(type_id)
     }
@@ -1166,6 +1528,17 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Lookup a given trait by name.
+    pub fn lookup_trait_or_error(&mut self, path: Path) -> Option<TraitId> {
+        match self.lookup(path) {
+            Ok(trait_id) => Some(trait_id),
+            Err(error) => {
+                self.push_err(error);
+                None
+            }
+        }
+    }
+
     /// Looks up a given type by name.
     /// This will also instantiate any struct types found.
     fn lookup_type_or_error(&mut self, path: Path) -> Option<Type> {
@@ -1245,6 +1618,22 @@ impl<'a> Resolver<'a> {
     }
 }
 
+/// Returns true if `typ` holds an array or string within the elements of an array. The
+/// monomorphizer can only represent arrays of scalars, structs and tuples.
+fn has_nested_array(typ: &Type, in_array: bool) -> bool {
+    match typ {
+        Type::Array(_, element) => in_array || has_nested_array(element, true),
+        Type::String(_) => in_array,
+        Type::Struct(struct_type, generics) => struct_type
+            .borrow()
+            .get_fields(generics)
+            .iter()
+            .any(|(_, field)| has_nested_array(field, in_array)),
+        Type::Tuple(fields) => fields.iter().any(|field| has_nested_array(field, in_array)),
+        _ => false,
+    }
+}
+
 // XXX: These tests repeat a lot of code
 // what we should do is have test cases which are passed to a test harness
 // A test harness will allow for more expressive and readable tests
@@ -1482,6 +1871,73 @@ mod test {
         assert!(errors.is_empty());
     }
 
+    #[test]
+    fn resolve_closure_captures() {
+        let src = r#"
+            fn main(x : Field) {
+                let y = 2;
+                let f = |z| (|w| x + y + z + w)(1);
+                assert(f(3) == x + 6);
+            }
+        "#;
+
+        let errors = resolve_src_code(src, vec!["main"]);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn captured_mutable_variable() {
+        let src = r#"
+            fn main(x : Field) {
+                let mut y = x;
+                let f = || y;
+                y = 3;
+                assert(f() == y);
+            }
+        "#;
+
+        let errors = resolve_src_code(src, vec!["main"]);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ResolverError::CapturedMutableVariable { .. }));
+    }
+
+    #[test]
+    fn loop_control_outside_loop() {
+        let src = r#"
+            fn main(x : Field) {
+                for i in 0..2 {
+                    if i == x { break; }
+                    let f = || { continue; };
+                    f();
+                }
+                #[max_iterations(4)]
+                while x == 0 { continue; }
+                break;
+            }
+        "#;
+
+        let errors = resolve_src_code(src, vec!["main"]);
+        assert_eq!(errors.len(), 2);
+        for error in errors {
+            assert!(matches!(error, ResolverError::LoopControlOutsideLoop { .. }));
+        }
+    }
+
+    #[test]
+    fn resolve_nested_array_parameter() {
+        let src = r#"
+            fn main(_x : [[Field; 2]; 3], _y : [str<4>; 2], _z : [Field; 3]) {}
+
+            fn foo(_x : [[Field; 2]; 3]) {}
+        "#;
+
+        let errors = resolve_src_code(src, vec!["main", "foo"]);
+        assert_eq!(errors.len(), 2);
+        for error in errors {
+            assert!(matches!(error, ResolverError::NestedArrayParameter { .. }));
+        }
+    }
+
     fn path_unresolved_error(err: ResolverError, expected_unresolved_path: &str) {
         match err {
             ResolverError::PathResolutionError(PathResolutionError::Unresolved(name)) => {
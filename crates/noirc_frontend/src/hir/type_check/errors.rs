@@ -1,9 +1,10 @@
 use noirc_errors::CustomDiagnostic as Diagnostic;
-use noirc_errors::Span;
+use noirc_errors::{Location, Span};
 use thiserror::Error;
 
 use crate::hir_def::expr::HirBinaryOp;
 use crate::hir_def::types::Type;
+use crate::ItemVisibility;
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum TypeCheckError {
@@ -34,6 +35,26 @@ pub enum TypeCheckError {
     },
     #[error("Cannot infer type of expression, type annotations needed before this point")]
     TypeAnnotationsNeeded { span: Span },
+    #[error("Trait {trait_name} is not implemented for {typ}")]
+    TraitNotImplemented { typ: String, trait_name: String, span: Span },
+    #[error("Match is not exhaustive")]
+    NonExhaustiveMatch { missing: String, span: Span },
+    #[error("Field {field} of struct {struct_name} is private")]
+    PrivateField {
+        field: String,
+        struct_name: String,
+        span: Span,
+        declaration: Location,
+        visibility: ItemVisibility,
+    },
+    #[error("Method {method} of struct {struct_name} is private")]
+    PrivateMethod {
+        method: String,
+        struct_name: String,
+        span: Span,
+        declaration: Location,
+        visibility: ItemVisibility,
+    },
 }
 
 impl TypeCheckError {
@@ -103,6 +124,50 @@ impl From<TypeCheckError> for Diagnostic {
                 "Type must be known at this point".to_string(),
                 span,
             ),
+            TypeCheckError::TraitNotImplemented { typ, trait_name, span } => {
+                Diagnostic::simple_error(
+                    format!("The trait {trait_name} is not implemented for type {typ}"),
+                    format!("{typ} is required to implement {trait_name} here"),
+                    span,
+                )
+            }
+            TypeCheckError::NonExhaustiveMatch { missing, span } => Diagnostic::simple_error(
+                "The patterns of this match do not cover every value".into(),
+                format!("Pattern {missing} is not covered"),
+                span,
+            ),
+            TypeCheckError::PrivateField { field, struct_name, span, declaration, visibility } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("Field {field} of struct {struct_name} is private"),
+                    String::new(),
+                    span,
+                );
+                diag.add_secondary_in_file(
+                    format!("{field} is declared here {}", visibility.describe_declaration()),
+                    declaration.span,
+                    declaration.file,
+                );
+                diag
+            }
+            TypeCheckError::PrivateMethod {
+                method,
+                struct_name,
+                span,
+                declaration,
+                visibility,
+            } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("Method {method} of struct {struct_name} is private"),
+                    String::new(),
+                    span,
+                );
+                diag.add_secondary_in_file(
+                    format!("{method} is declared here {}", visibility.describe_declaration()),
+                    declaration.span,
+                    declaration.file,
+                );
+                diag
+            }
         }
     }
 }
@@ -0,0 +1,212 @@
+//! Checks that the patterns of a `match` expression cover every value of the matched type.
+//!
+//! This follows the usefulness algorithm of "Warnings for pattern matching" (Maranget, 2007):
+//! the patterns are exhaustive unless some value is matched by none of them, which is found by
+//! splitting the rows of patterns by each constructor of the type of their first column.
+//! Types with too many values to list their constructors, such as integers, can only be
+//! covered by a pattern matching any value.
+use acvm::FieldElement;
+use iter_extended::vecmap;
+
+use crate::hir_def::expr::HirMatchPattern;
+use crate::node_interner::NodeInterner;
+use crate::Type;
+
+/// A pattern reduced to what matters for exhaustiveness: either any value,
+/// or a constructor along with patterns for each of its fields.
+#[derive(Debug, Clone)]
+enum Pattern {
+    Wildcard,
+    Constructor(Constructor, Vec<Pattern>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Constructor {
+    Bool(bool),
+    Integer(FieldElement),
+    Tuple,
+    /// The index of a variant of an enum
+    Variant(usize),
+}
+
+/// Returns a pattern for some value of type `typ` which none of the given patterns match,
+/// or None if the patterns are exhaustive.
+pub(super) fn missing_pattern(
+    interner: &NodeInterner,
+    typ: &Type,
+    patterns: &[&HirMatchPattern],
+) -> Option<String> {
+    let rows = vecmap(patterns, |pattern| vec![simplify(interner, pattern)]);
+    let mut missing = find_missing(rows, &[typ.clone()])?;
+    Some(missing.remove(0))
+}
+
+fn simplify(interner: &NodeInterner, pattern: &HirMatchPattern) -> Pattern {
+    let fields = |fields: &[HirMatchPattern]| vecmap(fields, |field| simplify(interner, field));
+
+    match pattern {
+        HirMatchPattern::Wildcard(_) | HirMatchPattern::Identifier(_) => Pattern::Wildcard,
+        HirMatchPattern::Bool(value, _) => Pattern::Constructor(Constructor::Bool(*value), vec![]),
+        HirMatchPattern::Integer(value, _) => {
+            Pattern::Constructor(Constructor::Integer(*value), vec![])
+        }
+        HirMatchPattern::Tuple(elements, _) => {
+            Pattern::Constructor(Constructor::Tuple, fields(elements))
+        }
+        HirMatchPattern::Variant(variant, variant_fields, _) => {
+            let (_, index) = interner
+                .function_meta(variant)
+                .enum_variant
+                .expect("Expected the function of a variant pattern to construct a variant");
+            Pattern::Constructor(Constructor::Variant(index), fields(variant_fields))
+        }
+    }
+}
+
+/// Returns the constructors of the given type along with the types of their fields,
+/// or None if the type has too many values to list them.
+fn constructors(typ: &Type) -> Option<Vec<(Constructor, Vec<Type>)>> {
+    match typ.follow_bindings() {
+        Type::Bool(_) => Some(vec![
+            (Constructor::Bool(false), Vec::new()),
+            (Constructor::Bool(true), Vec::new()),
+        ]),
+        Type::Tuple(fields) => Some(vec![(Constructor::Tuple, fields)]),
+        Type::Struct(definition, generics) if definition.borrow().is_enum() => {
+            let variants = definition.borrow().get_variants(&generics);
+            let variants = variants.into_iter().enumerate();
+            Some(
+                variants
+                    .map(|(index, (_, fields))| (Constructor::Variant(index), fields))
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Each row holds a pattern for each of the given types. Returns patterns for one value
+/// of each type such that none of the rows match them all, if there are any.
+fn find_missing(rows: Vec<Vec<Pattern>>, types: &[Type]) -> Option<Vec<String>> {
+    let (typ, rest_types) = match types.split_first() {
+        Some(split) => split,
+        None if rows.is_empty() => return Some(Vec::new()),
+        None => return None,
+    };
+
+    match constructors(typ) {
+        Some(constructors) => constructors.into_iter().find_map(|(constructor, field_types)| {
+            let arity = field_types.len();
+            let rows = rows.iter().filter_map(|row| specialize(row, &constructor, arity)).collect();
+
+            let mut types = field_types;
+            types.extend_from_slice(rest_types);
+
+            let mut fields = find_missing(rows, &types)?;
+            let rest = fields.split_off(arity);
+            let mut missing = vec![display_constructor(typ, &constructor, fields)];
+            missing.extend(rest);
+            Some(missing)
+        }),
+        None => {
+            let rows = rows.into_iter().filter_map(|row| match row.split_first() {
+                Some((Pattern::Wildcard, rest)) => Some(rest.to_vec()),
+                _ => None,
+            });
+            let mut missing = find_missing(rows.collect(), rest_types)?;
+            missing.insert(0, "_".to_owned());
+            Some(missing)
+        }
+    }
+}
+
+/// Returns the rest of the given row if its first pattern may match values built with the
+/// given constructor, with that pattern replaced by patterns for the constructor's fields.
+fn specialize(row: &[Pattern], constructor: &Constructor, arity: usize) -> Option<Vec<Pattern>> {
+    let (first, rest) = row.split_first()?;
+    let mut fields = match first {
+        Pattern::Wildcard => vec![Pattern::Wildcard; arity],
+        Pattern::Constructor(other, fields) if other == constructor && fields.len() == arity => {
+            fields.clone()
+        }
+        Pattern::Constructor(..) => return None,
+    };
+    fields.extend_from_slice(rest);
+    Some(fields)
+}
+
+fn display_constructor(typ: &Type, constructor: &Constructor, fields: Vec<String>) -> String {
+    match constructor {
+        Constructor::Bool(value) => value.to_string(),
+        Constructor::Integer(value) => value.to_u128().to_string(),
+        Constructor::Tuple => format!("({})", fields.join(", ")),
+        Constructor::Variant(index) => {
+            let name = match typ.follow_bindings() {
+                Type::Struct(definition, generics) => {
+                    let definition = definition.borrow();
+                    let (variant, _) = definition.get_variants(&generics).swap_remove(*index);
+                    format!("{}::{variant}", definition.name)
+                }
+                other => unreachable!("Expected an enum type, found {other}"),
+            };
+
+            if fields.is_empty() {
+                name
+            } else {
+                format!("{name}({})", fields.join(", "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find_missing, Constructor, Pattern};
+    use crate::{CompTime, Type};
+
+    fn bool_pattern(value: bool) -> Pattern {
+        Pattern::Constructor(Constructor::Bool(value), vec![])
+    }
+
+    #[test]
+    fn bools_need_both_values() {
+        let bool_type = Type::Bool(CompTime::No(None));
+        let types = [bool_type];
+
+        let rows = vec![vec![bool_pattern(true)]];
+        assert_eq!(find_missing(rows, &types), Some(vec!["false".to_owned()]));
+
+        let rows = vec![vec![bool_pattern(true)], vec![bool_pattern(false)]];
+        assert_eq!(find_missing(rows, &types), None);
+    }
+
+    #[test]
+    fn tuples_are_checked_column_by_column() {
+        let bool_type = Type::Bool(CompTime::No(None));
+        let types = [Type::Tuple(vec![bool_type.clone(), bool_type])];
+        let tuple = |a, b| Pattern::Constructor(Constructor::Tuple, vec![a, b]);
+
+        let rows = vec![
+            vec![tuple(bool_pattern(true), Pattern::Wildcard)],
+            vec![tuple(bool_pattern(false), bool_pattern(true))],
+        ];
+        assert_eq!(find_missing(rows.clone(), &types), Some(vec!["(false, false)".to_owned()]));
+
+        let mut rows = rows;
+        rows.push(vec![tuple(Pattern::Wildcard, bool_pattern(false))]);
+        assert_eq!(find_missing(rows, &types), None);
+    }
+
+    #[test]
+    fn integers_need_a_wildcard() {
+        let types = [Type::FieldElement(CompTime::No(None))];
+        let integer =
+            |value: u128| Pattern::Constructor(Constructor::Integer(value.into()), vec![]);
+
+        let rows = vec![vec![integer(0)], vec![integer(1)]];
+        assert_eq!(find_missing(rows, &types), Some(vec!["_".to_owned()]));
+
+        let rows = vec![vec![integer(0)], vec![Pattern::Wildcard]];
+        assert_eq!(find_missing(rows, &types), None);
+    }
+}
@@ -2,15 +2,17 @@ use iter_extended::vecmap;
 use noirc_errors::Span;
 
 use crate::{
+    hir::resolution::import::{find_private_field, find_private_method},
     hir_def::{
         expr::{self, HirArrayLiteral, HirBinaryOp, HirExpression, HirLiteral},
+        traits::TraitConstraint,
         types::Type,
     },
-    node_interner::{ExprId, FuncId},
-    CompTime, Shared, TypeBinding,
+    node_interner::{DefinitionKind, ExprId, FuncId},
+    CompTime, Shared, StructType, TypeBinding, TypeBindings,
 };
 
-use super::{errors::TypeCheckError, TypeChecker};
+use super::{errors::TypeCheckError, exhaustiveness, TypeChecker};
 
 impl<'interner> TypeChecker<'interner> {
     /// Infers a type for a given expression, and return this type.
@@ -30,6 +32,12 @@ impl<'interner> TypeChecker<'interner> {
                 // variable to handle generic functions.
                 let t = self.interner.id_type(ident.id);
                 let (typ, bindings) = t.instantiate(self.interner);
+
+                if let DefinitionKind::Function(func_id) = self.interner.definition(ident.id).kind {
+                    let span = self.interner.expr_span(expr_id);
+                    self.add_trait_constraints(&func_id, &bindings, span);
+                }
+
                 self.interner.store_instantiation_bindings(*expr_id, bindings);
                 typ
             }
@@ -178,6 +186,20 @@ impl<'interner> TypeChecker<'interner> {
                 self.check_expression(&for_expr.block);
                 Type::Unit
             }
+            HirExpression::While(while_expr) => {
+                let condition_type = self.check_expression(&while_expr.condition);
+                let span = self.interner.expr_span(&while_expr.condition);
+
+                let bool_type = Type::Bool(CompTime::new(self.interner));
+                self.unify(&condition_type, &bool_type, span, || TypeCheckError::TypeMismatch {
+                    expected_typ: Type::Bool(CompTime::No(None)).to_string(),
+                    expr_typ: condition_type.to_string(),
+                    expr_span: span,
+                });
+
+                self.check_expression(&while_expr.body);
+                Type::Unit
+            }
             HirExpression::Block(block_expr) => {
                 let mut block_type = Type::Unit;
 
@@ -218,6 +240,7 @@ impl<'interner> TypeChecker<'interner> {
                 }
             }
             HirExpression::If(if_expr) => self.check_if_expr(&if_expr, expr_id),
+            HirExpression::Match(match_expr) => self.check_match_expr(&match_expr, expr_id),
             HirExpression::Constructor(constructor) => self.check_constructor(constructor, expr_id),
             HirExpression::MemberAccess(access) => self.check_member_access(access, *expr_id),
             HirExpression::Error => Type::Error,
@@ -240,7 +263,17 @@ impl<'interner> TypeChecker<'interner> {
                         expr_span: span,
                     }
                 });
-                Type::Function(params, Box::new(lambda.return_type))
+
+                // A closure's environment is the tuple of the variables it captures
+                let env = if lambda.captures.is_empty() {
+                    Type::Unit
+                } else {
+                    Type::Tuple(vecmap(&lambda.captures, |capture| {
+                        self.interner.id_type(capture.id)
+                    }))
+                };
+
+                Type::Function(params, Box::new(lambda.return_type), Box::new(env))
             }
         };
 
@@ -365,6 +398,7 @@ impl<'interner> TypeChecker<'interner> {
             }
 
             let (function_type, instantiation_bindings) = func_meta.typ.instantiate(self.interner);
+            self.add_trait_constraints(func_id, &instantiation_bindings, span);
 
             self.interner.store_instantiation_bindings(*function_ident_id, instantiation_bindings);
             self.interner.push_expr_type(function_ident_id, function_type.clone());
@@ -415,6 +449,101 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
+    fn check_match_expr(
+        &mut self,
+        match_expr: &expr::HirMatchExpression,
+        expr_id: &ExprId,
+    ) -> Type {
+        let matched_type = self.check_expression(&match_expr.expression);
+
+        let mut result_type = None;
+        for (pattern, branch) in &match_expr.rules {
+            self.check_match_pattern(pattern, &matched_type);
+            let branch_type = self.check_expression(branch);
+
+            match &result_type {
+                None => result_type = Some(branch_type),
+                Some(first_type) => {
+                    let expr_span = self.interner.expr_span(branch);
+                    self.unify(&branch_type, first_type, expr_span, || {
+                        TypeCheckError::TypeMismatch {
+                            expected_typ: first_type.to_string(),
+                            expr_typ: branch_type.to_string(),
+                            expr_span,
+                        }
+                        .add_context("Expected the types of all match branches to be equal")
+                    });
+                }
+            }
+        }
+
+        let patterns = vecmap(&match_expr.rules, |(pattern, _)| pattern);
+        if let Some(missing) =
+            exhaustiveness::missing_pattern(self.interner, &matched_type, &patterns)
+        {
+            let span = self.interner.expr_span(expr_id);
+            self.errors.push(TypeCheckError::NonExhaustiveMatch { missing, span });
+        }
+
+        result_type.unwrap_or(Type::Unit)
+    }
+
+    /// Unifies the type of the values the given pattern matches with the type of the matched
+    /// value, and binds each variable declared by the pattern to its type.
+    fn check_match_pattern(&mut self, pattern: &expr::HirMatchPattern, expected: &Type) {
+        let span = pattern.span();
+        let pattern_type = match pattern {
+            expr::HirMatchPattern::Wildcard(_) => return,
+            expr::HirMatchPattern::Identifier(ident) => {
+                self.interner.push_definition_type(ident.id, expected.clone());
+                return;
+            }
+            expr::HirMatchPattern::Bool(..) => Type::Bool(CompTime::new(self.interner)),
+            expr::HirMatchPattern::Integer(..) => {
+                let id = self.interner.next_type_variable_id();
+                Type::PolymorphicInteger(
+                    CompTime::new(self.interner),
+                    Shared::new(TypeBinding::Unbound(id)),
+                )
+            }
+            expr::HirMatchPattern::Tuple(fields, _) => {
+                let field_types = vecmap(fields, |_| self.interner.next_type_variable());
+                for (field, field_type) in fields.iter().zip(&field_types) {
+                    self.check_match_pattern(field, field_type);
+                }
+                Type::Tuple(field_types)
+            }
+            expr::HirMatchPattern::Variant(variant, fields, _) => {
+                let (typ, _) = self.interner.function_meta(variant).typ.instantiate(self.interner);
+                let (field_types, enum_type) = match typ {
+                    Type::Function(field_types, enum_type, _) => (field_types, *enum_type),
+                    other => {
+                        unreachable!("Expected an enum variant to be a function, found {other}")
+                    }
+                };
+
+                if field_types.len() != fields.len() {
+                    self.errors.push(TypeCheckError::ArityMisMatch {
+                        expected: field_types.len() as u16,
+                        found: fields.len() as u16,
+                        span,
+                    });
+                }
+
+                for (field, field_type) in fields.iter().zip(&field_types) {
+                    self.check_match_pattern(field, field_type);
+                }
+                enum_type
+            }
+        };
+
+        self.unify(&pattern_type, expected, span, || TypeCheckError::TypeMismatch {
+            expected_typ: expected.to_string(),
+            expr_typ: pattern_type.to_string(),
+            expr_span: span,
+        });
+    }
+
     fn check_constructor(
         &mut self,
         constructor: expr::HirConstructorExpression,
@@ -484,6 +613,7 @@ impl<'interner> TypeChecker<'interner> {
         if let Type::Struct(s, args) = &lhs_type {
             let s = s.borrow();
             if let Some((field, index)) = s.get_field(field_name, args) {
+                self.check_field_visibility(&s, field_name, span);
                 return Some((field, index));
             }
         } else if let Type::Tuple(elements) = &lhs_type {
@@ -513,6 +643,44 @@ impl<'interner> TypeChecker<'interner> {
         None
     }
 
+    /// Pushes an error if the given field of a struct is private to the module of the
+    /// current function
+    fn check_field_visibility(&mut self, struct_type: &StructType, field_name: &str, span: Span) {
+        if let Some(current_function) = self.current_function {
+            let module = self.interner.function_meta(&current_function).module_id;
+            let private_field = find_private_field(self.def_maps, struct_type, field_name, module);
+
+            if let Some((declaration, visibility)) = private_field {
+                self.errors.push(TypeCheckError::PrivateField {
+                    field: field_name.to_owned(),
+                    struct_name: struct_type.name.to_string(),
+                    span,
+                    declaration,
+                    visibility,
+                });
+            }
+        }
+    }
+
+    /// Pushes an error if the given method of a struct is private to the module of the
+    /// current function
+    fn check_method_visibility(&mut self, struct_type: &StructType, method_id: FuncId, span: Span) {
+        if let Some(current_function) = self.current_function {
+            let module = self.interner.function_meta(&current_function).module_id;
+            let private_method = find_private_method(self.def_maps, struct_type, method_id, module);
+
+            if let Some(visibility) = private_method {
+                self.errors.push(TypeCheckError::PrivateMethod {
+                    method: self.interner.function_name(&method_id).to_owned(),
+                    struct_name: struct_type.name.to_string(),
+                    span,
+                    declaration: self.interner.function_meta(&method_id).location,
+                    visibility,
+                });
+            }
+        }
+    }
+
     fn comparator_operand_type_rules(
         &mut self,
         lhs_type: &Type,
@@ -630,37 +798,61 @@ impl<'interner> TypeChecker<'interner> {
         method_name: &str,
         expr_id: &ExprId,
     ) -> Option<FuncId> {
-        match &object_type {
+        let method = match &object_type {
             Type::Struct(typ, _args) => {
-                match self.interner.lookup_method(typ.borrow().id, method_name) {
-                    Some(method_id) => Some(method_id),
-                    None => {
-                        self.errors.push(TypeCheckError::Unstructured {
-                            span: self.interner.expr_span(expr_id),
-                            msg: format!(
-                                "No method named '{method_name}' found for type '{object_type}'",
-                            ),
-                        });
-                        None
-                    }
+                let typ = typ.borrow();
+                let method = self.interner.lookup_method(typ.id, method_name);
+                if let Some(method_id) = method {
+                    let span = self.interner.expr_span(expr_id);
+                    self.check_method_visibility(&typ, method_id, span);
                 }
+                method
             }
             // If we fail to resolve the object to a struct type, we have no way of type
             // checking its arguments as we can't even resolve the name of the function
-            Type::Error => None,
+            Type::Error => return None,
+
+            // The methods of a generic are those of the traits it is required to implement
+            Type::NamedGeneric(..) => {
+                self.lookup_trait_constraint_method(&object_type, method_name)
+            }
 
             // In the future we could support methods for non-struct types if we have a context
             // (in the interner?) essentially resembling HashMap<Type, Methods>
-            other => match self.interner.lookup_primitive_method(other, method_name) {
-                Some(method_id) => Some(method_id),
-                None => {
-                    self.errors.push(TypeCheckError::Unstructured {
-                        span: self.interner.expr_span(expr_id),
-                        msg: format!("No method named '{method_name}' found for type '{other}'",),
-                    });
-                    None
-                }
-            },
+            other => self.interner.lookup_primitive_method(other, method_name),
+        };
+
+        // Otherwise the method may come from an impl of a trait for the type
+        let method =
+            method.or_else(|| self.interner.lookup_trait_impl_method(&object_type, method_name));
+
+        if method.is_none() {
+            self.errors.push(TypeCheckError::Unstructured {
+                span: self.interner.expr_span(expr_id),
+                msg: format!("No method named '{method_name}' found for type '{object_type}'"),
+            });
+        }
+        method
+    }
+
+    /// Searches the traits the given generic is required to implement by the current function
+    /// for a method with the given name.
+    fn lookup_trait_constraint_method(&self, generic: &Type, method_name: &str) -> Option<FuncId> {
+        let current_function = self.current_function?;
+        let constraints = self.interner.function_meta(&current_function).trait_constraints;
+
+        constraints.iter().filter(|constraint| &constraint.typ == generic).find_map(|constraint| {
+            self.interner.lookup_trait_method(constraint.trait_id, method_name)
+        })
+    }
+
+    /// Remembers the trait constraints of the given function, instantiated with the given
+    /// bindings, to check they are satisfied once the types of the current function are known.
+    fn add_trait_constraints(&mut self, func_id: &FuncId, bindings: &TypeBindings, span: Span) {
+        for constraint in self.interner.function_meta(func_id).trait_constraints {
+            let typ = constraint.typ.substitute(bindings);
+            let constraint = TraitConstraint { typ, trait_id: constraint.trait_id };
+            self.trait_constraints.push((constraint, span));
         }
     }
 
@@ -675,12 +867,13 @@ impl<'interner> TypeChecker<'interner> {
 
                 let ret = self.interner.next_type_variable();
                 let args = vecmap(args, |(arg, _)| arg);
-                let expected = Type::Function(args, Box::new(ret.clone()));
+                let env = Box::new(self.interner.next_type_variable());
+                let expected = Type::Function(args, Box::new(ret.clone()), env);
                 *binding.borrow_mut() = TypeBinding::Bound(expected);
 
                 ret
             }
-            Type::Function(parameters, ret) => {
+            Type::Function(parameters, ret, _env) => {
                 if parameters.len() != args.len() {
                     let empty_or_s = if parameters.len() == 1 { "" } else { "s" };
                     let was_or_were = if args.len() == 1 { "was" } else { "were" };
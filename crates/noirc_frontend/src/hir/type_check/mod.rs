@@ -11,13 +11,18 @@
 //! all functions are required to give their full signatures. Closures are inferred but are
 //! never generalized and thus cannot be used polymorphically.
 mod errors;
+mod exhaustiveness;
 mod expr;
 mod stmt;
 
 pub use errors::TypeCheckError;
 use noirc_errors::Span;
+use std::collections::HashMap;
 
 use crate::{
+    graph::CrateId,
+    hir::def_map::CrateDefMap,
+    hir_def::traits::TraitConstraint,
     node_interner::{ExprId, FuncId, NodeInterner, StmtId},
     Type,
 };
@@ -25,12 +30,24 @@ use crate::{
 pub struct TypeChecker<'interner> {
     current_function: Option<FuncId>,
     interner: &'interner mut NodeInterner,
+
+    /// Used to check that the fields accessed are visible from the current function
+    def_maps: &'interner HashMap<CrateId, CrateDefMap>,
+
     errors: Vec<TypeCheckError>,
+
+    /// The trait constraints of each generic function called, instantiated with the types
+    /// of the call. These can only be checked once every type in the function is inferred.
+    trait_constraints: Vec<(TraitConstraint, Span)>,
 }
 
 /// Type checks a function and assigns the
 /// appropriate types to expressions in a side table
-pub fn type_check_func(interner: &mut NodeInterner, func_id: FuncId) -> Vec<TypeCheckError> {
+pub fn type_check_func(
+    interner: &mut NodeInterner,
+    def_maps: &HashMap<CrateId, CrateDefMap>,
+    func_id: FuncId,
+) -> Vec<TypeCheckError> {
     let meta = interner.function_meta(&func_id);
     let declared_return_type = meta.return_type().clone();
     let can_ignore_ret = meta.can_ignore_return_type();
@@ -38,7 +55,7 @@ pub fn type_check_func(interner: &mut NodeInterner, func_id: FuncId) -> Vec<Type
     let function_body = interner.function(&func_id);
     let function_body_id = function_body.as_expr();
 
-    let mut type_checker = TypeChecker::new(func_id, interner);
+    let mut type_checker = TypeChecker::new(func_id, interner, def_maps);
 
     // Bind each parameter to its annotated type.
     // This is locally obvious, but it must be bound here so that the
@@ -72,21 +89,64 @@ pub fn type_check_func(interner: &mut NodeInterner, func_id: FuncId) -> Vec<Type
 }
 
 impl<'interner> TypeChecker<'interner> {
-    fn new(current_function: FuncId, interner: &'interner mut NodeInterner) -> Self {
-        Self { current_function: Some(current_function), interner, errors: vec![] }
+    fn new(
+        current_function: FuncId,
+        interner: &'interner mut NodeInterner,
+        def_maps: &'interner HashMap<CrateId, CrateDefMap>,
+    ) -> Self {
+        Self {
+            current_function: Some(current_function),
+            interner,
+            def_maps,
+            errors: vec![],
+            trait_constraints: vec![],
+        }
     }
 
     fn check_function_body(mut self, body: &ExprId) -> (Type, Vec<TypeCheckError>) {
         let body_type = self.check_expression(body);
+        self.check_trait_constraints();
         (body_type, self.errors)
     }
 
-    pub fn check_global(id: &StmtId, interner: &'interner mut NodeInterner) -> Vec<TypeCheckError> {
-        let mut this = Self { current_function: None, interner, errors: vec![] };
+    pub fn check_global(
+        id: &StmtId,
+        interner: &'interner mut NodeInterner,
+        def_maps: &'interner HashMap<CrateId, CrateDefMap>,
+    ) -> Vec<TypeCheckError> {
+        let mut this = Self {
+            current_function: None,
+            interner,
+            def_maps,
+            errors: vec![],
+            trait_constraints: vec![],
+        };
         this.check_statement(id);
+        this.check_trait_constraints();
         this.errors
     }
 
+    /// Checks that each trait constraint of the functions called is satisfied now that the
+    /// types they were called with are known. A generic satisfies a constraint if the current
+    /// function requires it to, any other type must have an impl of the trait whose own
+    /// bounds are satisfied in turn.
+    fn check_trait_constraints(&mut self) {
+        let assumed = self
+            .current_function
+            .map_or_else(Vec::new, |id| self.interner.function_meta(&id).trait_constraints);
+
+        for (constraint, span) in std::mem::take(&mut self.trait_constraints) {
+            let typ = constraint.typ.follow_bindings();
+            let trait_id = constraint.trait_id;
+
+            if !self.interner.implements_trait(trait_id, &typ, &assumed) {
+                let trait_name = self.interner.get_trait(trait_id).name.to_string();
+                let typ = typ.to_string();
+                self.errors.push(TypeCheckError::TraitNotImplemented { typ, trait_name, span });
+            }
+        }
+    }
+
     fn is_unconstrained(&self) -> bool {
         self.current_function.map_or(false, |current_function| {
             self.interner.function_meta(&current_function).is_unconstrained
@@ -208,11 +268,16 @@ mod test {
         let func_meta = FuncMeta {
             name,
             kind: FunctionKind::Normal,
-            attributes: None,
+            attributes: Vec::new(),
             location,
+            module_id: ModuleId::dummy_id(),
             contract_function_type: None,
             is_unconstrained: false,
-            typ: Type::Function(vec![Type::field(None), Type::field(None)], Box::new(Type::Unit)),
+            typ: Type::Function(
+                vec![Type::field(None), Type::field(None)],
+                Box::new(Type::Unit),
+                Box::new(Type::Unit),
+            ),
             parameters: vec![
                 Param(Identifier(x), Type::field(None), noirc_abi::AbiVisibility::Private),
                 Param(Identifier(y), Type::field(None), noirc_abi::AbiVisibility::Private),
@@ -221,10 +286,13 @@ mod test {
             return_visibility: noirc_abi::AbiVisibility::Private,
             return_distinctness: noirc_abi::AbiDistinctness::DuplicationAllowed,
             has_body: true,
+            trait_constraints: Vec::new(),
+            trait_id: None,
+            enum_variant: None,
         };
         interner.push_fn_meta(func_meta, func_id);
 
-        let errors = super::type_check_func(&mut interner, func_id);
+        let errors = super::type_check_func(&mut interner, &HashMap::new(), func_id);
         assert!(errors.is_empty());
     }
 
@@ -367,7 +435,8 @@ mod test {
         }
 
         // Type check section
-        let errors = super::type_check_func(&mut interner, func_ids.first().cloned().unwrap());
+        let main_id = func_ids.first().cloned().unwrap();
+        let errors = super::type_check_func(&mut interner, &def_maps, main_id);
         assert_eq!(errors, vec![]);
     }
 }
@@ -1,6 +1,6 @@
 use acvm::FieldElement;
 use fm::FileId;
-use noirc_errors::Location;
+use noirc_errors::{Location, Span};
 
 use crate::node_interner::{DefinitionId, ExprId, FuncId, NodeInterner, StmtId};
 use crate::{BinaryOp, BinaryOpKind, Ident, Shared, UnaryOp};
@@ -27,9 +27,11 @@ pub enum HirExpression {
     MethodCall(HirMethodCallExpression),
     Cast(HirCastExpression),
     For(HirForExpression),
+    While(HirWhileExpression),
     If(HirIfExpression),
     Tuple(Vec<ExprId>),
     Lambda(HirLambda),
+    Match(HirMatchExpression),
     Error,
 }
 
@@ -55,6 +57,13 @@ pub struct HirForExpression {
     pub block: ExprId,
 }
 
+#[derive(Debug, Clone)]
+pub struct HirWhileExpression {
+    pub condition: ExprId,
+    pub body: ExprId,
+    pub max_iterations: u64,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct HirBinaryOp {
     pub kind: BinaryOpKind,
@@ -196,4 +205,41 @@ pub struct HirLambda {
     pub parameters: Vec<(HirPattern, Type)>,
     pub return_type: Type,
     pub body: ExprId,
+
+    /// The variables of enclosing functions used within the lambda, in the order they are
+    /// first used. These are passed to the lambda in its environment.
+    pub captures: Vec<HirIdent>,
+}
+
+/// A `match` expression. Its rules are tried in order and the first whose
+/// pattern fits the matched value gives the value of the whole expression.
+#[derive(Debug, Clone)]
+pub struct HirMatchExpression {
+    pub expression: ExprId,
+    pub rules: Vec<(HirMatchPattern, ExprId)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum HirMatchPattern {
+    Wildcard(Span),
+    Identifier(HirIdent),
+    Bool(bool, Span),
+    Integer(FieldElement, Span),
+    Tuple(Vec<HirMatchPattern>, Span),
+    /// An enum variant, identified by the function constructing it,
+    /// along with patterns for each of its fields
+    Variant(FuncId, Vec<HirMatchPattern>, Span),
+}
+
+impl HirMatchPattern {
+    pub fn span(&self) -> Span {
+        match self {
+            HirMatchPattern::Identifier(ident) => ident.location.span,
+            HirMatchPattern::Wildcard(span)
+            | HirMatchPattern::Bool(_, span)
+            | HirMatchPattern::Integer(_, span)
+            | HirMatchPattern::Tuple(_, span)
+            | HirMatchPattern::Variant(_, _, span) => *span,
+        }
+    }
 }
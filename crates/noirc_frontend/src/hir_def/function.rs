@@ -4,8 +4,11 @@ use noirc_errors::{Location, Span};
 
 use super::expr::{HirBlockExpression, HirExpression, HirIdent};
 use super::stmt::HirPattern;
-use crate::node_interner::{ExprId, NodeInterner};
-use crate::{token::Attribute, FunctionKind};
+use super::traits::TraitConstraint;
+use crate::hir::def_map::ModuleId;
+use crate::node_interner::{ExprId, NodeInterner, StructId, TraitId};
+use crate::token::{Attribute, CustomAttribute, TestScope};
+use crate::FunctionKind;
 use crate::{ContractFunctionType, Type};
 
 /// A Hir function is a block expression
@@ -117,9 +120,8 @@ pub struct FuncMeta {
     pub kind: FunctionKind,
 
     /// A function's attributes are the `#[...]` items above the function
-    /// definition, if any. Currently, this is limited to a maximum of only one
-    /// Attribute per function.
-    pub attributes: Option<Attribute>,
+    /// definition, in the order they were written.
+    pub attributes: Vec<Attribute>,
 
     /// This function's visibility in its contract.
     /// If this function is not in a contract, this is always 'Secret'.
@@ -137,8 +139,22 @@ pub struct FuncMeta {
     /// or a Type::Forall for generic functions.
     pub typ: Type,
 
+    /// The traits the generics of this function are required to implement
+    pub trait_constraints: Vec<TraitConstraint>,
+
+    /// If this function is a method declared by a trait, this is the trait. Such methods
+    /// have no body, calls to them are dispatched to an implementation of the trait instead.
+    pub trait_id: Option<TraitId>,
+
+    /// If this function constructs a variant of an enum, this is the enum along with the index
+    /// of the variant. Such functions have no body, the variant is built during monomorphization.
+    pub enum_variant: Option<(StructId, usize)>,
+
     pub location: Location,
 
+    /// The module this function is resolved in, whose private items it may refer to
+    pub module_id: ModuleId,
+
     // This flag is needed for the attribute check pass
     pub has_body: bool,
 }
@@ -155,6 +171,26 @@ impl FuncMeta {
         }
     }
 
+    pub fn is_test(&self) -> bool {
+        self.test_scope().is_some()
+    }
+
+    /// Returns whether this test is expected to pass or fail, or None if it is not a test
+    pub fn test_scope(&self) -> Option<&TestScope> {
+        self.attributes.iter().find_map(|attribute| match attribute {
+            Attribute::Test(scope) => Some(scope),
+            _ => None,
+        })
+    }
+
+    /// The attributes of this function which have no meaning to the compiler
+    pub fn custom_attributes(&self) -> impl Iterator<Item = &CustomAttribute> {
+        self.attributes.iter().filter_map(|attribute| match attribute {
+            Attribute::Custom(attribute) => Some(attribute),
+            _ => None,
+        })
+    }
+
     pub fn into_function_signature(
         self,
         interner: &NodeInterner,
@@ -172,9 +208,9 @@ impl FuncMeta {
     /// Gives the (uninstantiated) return type of this function.
     pub fn return_type(&self) -> &Type {
         match &self.typ {
-            Type::Function(_, ret) => ret,
+            Type::Function(_, ret, _env) => ret,
             Type::Forall(_, typ) => match typ.as_ref() {
-                Type::Function(_, ret) => ret,
+                Type::Function(_, ret, _env) => ret,
                 _ => unreachable!(),
             },
             _ => unreachable!(),
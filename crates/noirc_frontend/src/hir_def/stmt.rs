@@ -13,6 +13,8 @@ pub enum HirStatement {
     Let(HirLetStatement),
     Constrain(HirConstrainStatement),
     Assign(HirAssignStatement),
+    Break,
+    Continue,
     Expression(ExprId),
     Semi(ExprId),
     Error,
@@ -41,12 +43,13 @@ pub struct HirAssignStatement {
     pub expression: ExprId,
 }
 
-/// Corresponds to `constrain expr;` in the source code.
+/// Corresponds to `assert(expr, "message");` in the source code.
 /// This node also contains the FileId of the file the constrain
 /// originates from. This is used later in the SSA pass to issue
 /// an error if a constrain is found to be always false.
+/// The optional message is reported when the assertion fails.
 #[derive(Debug, Clone)]
-pub struct HirConstrainStatement(pub ExprId, pub FileId);
+pub struct HirConstrainStatement(pub ExprId, pub FileId, pub Option<String>);
 
 #[derive(Debug, Clone)]
 pub enum HirPattern {
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use noirc_errors::Span;
+
+use crate::node_interner::{FuncId, TraitId};
+use crate::{Ident, Type, TypeVariable, TypeVariableId};
+
+/// A trait, along with the methods it declares.
+///
+/// Each method is given a function whose type is generic over `Self`, the type implementing
+/// the trait. Calls to these functions are dispatched to the implementation of the trait for
+/// the type they are instantiated with during monomorphization.
+#[derive(Debug, Clone)]
+pub struct Trait {
+    pub id: TraitId,
+    pub name: Ident,
+    pub methods: Vec<FuncId>,
+    pub span: Span,
+}
+
+/// An implementation of a trait for a type
+#[derive(Debug, Clone)]
+pub struct TraitImpl {
+    pub trait_id: TraitId,
+
+    /// The type the trait is implemented for. This may refer to the generics of the impl,
+    /// in which case the impl applies to each type obtained by replacing them.
+    pub typ: Type,
+
+    /// The generics declared on the impl, along with the name each is referred to by in `typ`
+    pub generics: Vec<(Rc<String>, TypeVariableId, TypeVariable)>,
+
+    /// The method implementing each method of the trait, by name
+    pub methods: HashMap<String, FuncId>,
+
+    /// The bounds on the generics of the impl, such as the `T: Hash` in
+    /// `impl<T: Hash> Hash for [T; 2]`. The impl only applies to the types satisfying them.
+    pub where_clause: Vec<TraitConstraint>,
+
+    /// The span of the type the trait is implemented for
+    pub span: Span,
+}
+
+/// A requirement that a type implements a trait, from a bound such as `T: Hash`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitConstraint {
+    pub typ: Type,
+    pub trait_id: TraitId,
+}
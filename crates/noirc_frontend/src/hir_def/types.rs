@@ -9,7 +9,7 @@ use iter_extended::vecmap;
 use noirc_abi::AbiType;
 use noirc_errors::Span;
 
-use crate::{node_interner::StructId, Ident, Signedness};
+use crate::{node_interner::StructId, Ident, ItemVisibility, Signedness};
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum Type {
@@ -67,8 +67,10 @@ pub enum Type {
     /// like `fn foo<T, U>(...) {}`. Unlike TypeVariables, they cannot be bound over.
     NamedGeneric(TypeVariable, Rc<String>),
 
-    /// A functions with arguments, and a return type.
-    Function(Vec<Type>, Box<Type>),
+    /// A functions with arguments, a return type, and the type of its environment.
+    /// The environment is the tuple of variables captured by a closure, or Unit
+    /// for all other functions.
+    Function(Vec<Type>, Box<Type>, Box<Type>),
 
     /// A variable-sized Vector type.
     /// Unlike arrays, this type can have a dynamic size and can grow/shrink dynamically via .push,
@@ -112,7 +114,11 @@ pub struct StructType {
     /// Fields are ordered and private, they should only
     /// be accessed through get_field(), get_fields(), or instantiate()
     /// since these will handle applying generic arguments to fields as well.
-    fields: Vec<(Ident, Type)>,
+    fields: Vec<(Ident, Type, ItemVisibility)>,
+
+    /// If this type is an enum rather than a struct, these are its variants along with the
+    /// types of their fields. Like fields, they are private and accessed through get_variants().
+    variants: Option<Vec<(Ident, Vec<Type>)>>,
 
     pub generics: Generics,
     pub span: Span,
@@ -141,31 +147,64 @@ impl StructType {
         id: StructId,
         name: Ident,
         span: Span,
-        fields: Vec<(Ident, Type)>,
+        fields: Vec<(Ident, Type, ItemVisibility)>,
         generics: Generics,
     ) -> StructType {
-        StructType { id, fields, name, span, generics }
+        StructType { id, fields, variants: None, name, span, generics }
     }
 
     /// To account for cyclic references between structs, a struct's
     /// fields are resolved strictly after the struct itself is initially
     /// created. Therefore, this method is used to set the fields once they
     /// become known.
-    pub fn set_fields(&mut self, fields: Vec<(Ident, Type)>) {
+    pub fn set_fields(&mut self, fields: Vec<(Ident, Type, ItemVisibility)>) {
         assert!(self.fields.is_empty());
         self.fields = fields;
     }
 
+    /// Like fields, the variants of an enum are resolved after the type itself is created.
+    pub fn set_variants(&mut self, variants: Vec<(Ident, Vec<Type>)>) {
+        assert!(self.variants.is_none());
+        self.variants = Some(variants);
+    }
+
     pub fn num_fields(&self) -> usize {
         self.fields.len()
     }
 
+    /// True if this type was declared as an enum rather than a struct
+    pub fn is_enum(&self) -> bool {
+        self.variants.is_some()
+    }
+
+    /// Returns the name of each variant of this enum along with the types of its fields,
+    /// after being applied to the given generic arguments.
+    /// This is empty if this type is a struct.
+    pub fn get_variants(&self, generic_args: &[Type]) -> Vec<(String, Vec<Type>)> {
+        assert_eq!(self.generics.len(), generic_args.len());
+
+        let substitutions = self
+            .generics
+            .iter()
+            .zip(generic_args)
+            .map(|((old_id, old_var), new)| (*old_id, (old_var.clone(), new.clone())))
+            .collect();
+
+        let variants = self.variants.iter().flatten();
+        variants
+            .map(|(name, fields)| {
+                let fields = vecmap(fields, |typ| typ.substitute(&substitutions));
+                (name.0.contents.clone(), fields)
+            })
+            .collect()
+    }
+
     /// Returns the field matching the given field name, as well as its field index.
     pub fn get_field(&self, field_name: &str, generic_args: &[Type]) -> Option<(Type, usize)> {
         assert_eq!(self.generics.len(), generic_args.len());
 
-        self.fields.iter().enumerate().find(|(_, (name, _))| name.0.contents == field_name).map(
-            |(i, (_, typ))| {
+        self.fields.iter().enumerate().find(|(_, (name, ..))| name.0.contents == field_name).map(
+            |(i, (_, typ, _))| {
                 let substitutions = self
                     .generics
                     .iter()
@@ -189,14 +228,22 @@ impl StructType {
             .map(|((old_id, old_var), new)| (*old_id, (old_var.clone(), new.clone())))
             .collect();
 
-        vecmap(&self.fields, |(name, typ)| {
+        vecmap(&self.fields, |(name, typ, _)| {
             let name = name.0.contents.clone();
             (name, typ.substitute(&substitutions))
         })
     }
 
     pub fn field_names(&self) -> BTreeSet<Ident> {
-        self.fields.iter().map(|(name, _)| name.clone()).collect()
+        self.fields.iter().map(|(name, ..)| name.clone()).collect()
+    }
+
+    /// Returns the visibility of the field with the given name along with the name it was
+    /// declared with, which spans its declaration.
+    pub fn field_visibility(&self, field_name: &str) -> Option<(&Ident, ItemVisibility)> {
+        let (name, _, visibility) =
+            self.fields.iter().find(|(name, ..)| name.0.contents == field_name)?;
+        Some((name, *visibility))
     }
 
     /// True if the given index is the same index as a generic type of this struct
@@ -204,7 +251,12 @@ impl StructType {
     /// This is needed because we infer type kinds in Noir and don't have extensive kind checking.
     pub fn generic_is_numeric(&self, index_of_generic: usize) -> bool {
         let target_id = self.generics[index_of_generic].0;
-        self.fields.iter().any(|(_, field)| field.contains_numeric_typevar(target_id))
+        let variant_fields = self.variants.iter().flatten().flat_map(|(_, fields)| fields);
+        self.fields
+            .iter()
+            .map(|(_, field, _)| field)
+            .chain(variant_fields)
+            .any(|field| field.contains_numeric_typevar(target_id))
     }
 
     /// Instantiate this struct type, returning a Vec of the new generic args (in
@@ -578,9 +630,10 @@ impl Type {
             Type::Tuple(fields) => {
                 fields.iter().any(|field| field.contains_numeric_typevar(target_id))
             }
-            Type::Function(parameters, return_type) => {
+            Type::Function(parameters, return_type, env) => {
                 parameters.iter().any(|parameter| parameter.contains_numeric_typevar(target_id))
                     || return_type.contains_numeric_typevar(target_id)
+                    || env.contains_numeric_typevar(target_id)
             }
             Type::Struct(struct_type, generics) => {
                 generics.iter().enumerate().any(|(i, generic)| {
@@ -644,9 +697,12 @@ impl std::fmt::Display for Type {
                 let typevars = vecmap(typevars, |(var, _)| var.to_string());
                 write!(f, "forall {}. {}", typevars.join(" "), typ)
             }
-            Type::Function(args, ret) => {
+            Type::Function(args, ret, env) => {
                 let args = vecmap(args, ToString::to_string);
-                write!(f, "fn({}) -> {}", args.join(", "), ret)
+                match env.as_ref() {
+                    Type::Unit => write!(f, "fn({}) -> {}", args.join(", "), ret),
+                    env => write!(f, "fn[{}]({}) -> {}", env, args.join(", "), ret),
+                }
             }
             Type::Vec(element) => {
                 write!(f, "Vec<{element}>")
@@ -969,12 +1025,13 @@ impl Type {
                 }
             }
 
-            (Function(params_a, ret_a), Function(params_b, ret_b)) => {
+            (Function(params_a, ret_a, env_a), Function(params_b, ret_b, env_b)) => {
                 if params_a.len() == params_b.len() {
                     for (a, b) in params_a.iter().zip(params_b) {
                         a.try_unify(b, span)?;
                     }
 
+                    env_a.try_unify(env_b, span)?;
                     ret_b.try_unify(ret_a, span)
                 } else {
                     Err(SpanKind::None)
@@ -1101,12 +1158,14 @@ impl Type {
                 }
             }
 
-            (Function(params_a, ret_a), Function(params_b, ret_b)) => {
+            (Function(params_a, ret_a, env_a), Function(params_b, ret_b, env_b)) => {
                 if params_a.len() == params_b.len() {
                     for (a, b) in params_a.iter().zip(params_b) {
                         a.is_subtype_of(b, span)?;
                     }
 
+                    env_a.is_subtype_of(env_b, span)?;
+
                     // return types are contravariant, so this must be ret_b <: ret_a instead of the reverse
                     ret_b.is_subtype_of(ret_a, span)
                 } else {
@@ -1181,11 +1240,14 @@ impl Type {
                 let fields = vecmap(fields, |(name, typ)| (name, typ.as_abi_type()));
                 AbiType::Struct { fields }
             }
-            Type::Tuple(_) => todo!("as_abi_type not yet implemented for tuple types"),
+            Type::Tuple(fields) => {
+                let fields = vecmap(fields, |typ| typ.as_abi_type());
+                AbiType::Tuple { fields }
+            }
             Type::TypeVariable(_) => unreachable!(),
             Type::NamedGeneric(..) => unreachable!(),
             Type::Forall(..) => unreachable!(),
-            Type::Function(_, _) => unreachable!(),
+            Type::Function(_, _, _) => unreachable!(),
             Type::Vec(_) => unreachable!("Vecs cannot be used in the abi"),
         }
     }
@@ -1198,7 +1260,7 @@ impl Type {
             // only to have to call .into_iter again afterward. Trying to elide
             // collecting to a Vec leads to us dropping the temporary Ref before
             // the iterator is returned
-            Type::Struct(def, args) => vecmap(&def.borrow().fields, |(name, _)| {
+            Type::Struct(def, args) => vecmap(&def.borrow().fields, |(name, ..)| {
                 let name = &name.0.contents;
                 let typ = def.borrow().get_field(name, args).unwrap().0;
                 (name.clone(), typ)
@@ -1295,10 +1357,11 @@ impl Type {
                 let typ = Box::new(typ.substitute(type_bindings));
                 Type::Forall(typevars.clone(), typ)
             }
-            Type::Function(args, ret) => {
+            Type::Function(args, ret, env) => {
                 let args = vecmap(args, |arg| arg.substitute(type_bindings));
                 let ret = Box::new(ret.substitute(type_bindings));
-                Type::Function(args, ret)
+                let env = Box::new(env.substitute(type_bindings));
+                Type::Function(args, ret, env)
             }
             Type::Vec(element) => Type::Vec(Box::new(element.substitute(type_bindings))),
 
@@ -1327,8 +1390,10 @@ impl Type {
             Type::Forall(typevars, typ) => {
                 !typevars.iter().any(|(id, _)| *id == target_id) && typ.occurs(target_id)
             }
-            Type::Function(args, ret) => {
-                args.iter().any(|arg| arg.occurs(target_id)) || ret.occurs(target_id)
+            Type::Function(args, ret, env) => {
+                args.iter().any(|arg| arg.occurs(target_id))
+                    || ret.occurs(target_id)
+                    || env.occurs(target_id)
             }
             Type::Vec(element) => element.occurs(target_id),
 
@@ -1367,10 +1432,11 @@ impl Type {
                 self.clone()
             }
 
-            Function(args, ret) => {
+            Function(args, ret, env) => {
                 let args = vecmap(args, |arg| arg.follow_bindings());
                 let ret = Box::new(ret.follow_bindings());
-                Function(args, ret)
+                let env = Box::new(env.follow_bindings());
+                Function(args, ret, env)
             }
             Vec(element) => Vec(Box::new(element.follow_bindings())),
 
@@ -1382,6 +1448,128 @@ impl Type {
             }
         }
     }
+
+    /// Checks whether this type, which may refer to named generics, can be made equal to
+    /// `other` by replacing each of its named generics with some type. The type each generic
+    /// is replaced with is recorded in `generics` by name.
+    ///
+    /// This is used to find the implementation of a trait for a type, so unlike unification it
+    /// never binds type variables and ignores whether types are comptime. Named generics within
+    /// `self` stand for any type even while they are bound, as they are during monomorphization.
+    /// Type variables of `other` which are still unbound are treated as `Field`, the type they
+    /// default to during monomorphization.
+    pub fn matches_instance(&self, other: &Type, generics: &mut HashMap<Rc<String>, Type>) -> bool {
+        self.matches_instance_inner(&other.follow_bindings(), generics)
+    }
+
+    fn matches_instance_inner(
+        &self,
+        other: &Type,
+        generics: &mut HashMap<Rc<String>, Type>,
+    ) -> bool {
+        use Type::*;
+        let all_match = |these: &[Type], others: &[Type], generics: &mut HashMap<_, _>| {
+            these.len() == others.len()
+                && these.iter().zip(others).all(|(a, b)| a.matches_instance_inner(b, generics))
+        };
+
+        match (self, other) {
+            (Error, _) | (_, Error) => true,
+
+            (NamedGeneric(_, name), other) => match generics.get(name).cloned() {
+                // A generic used more than once must stand for the same type each time
+                Some(previous) => previous.matches_instance_inner(other, &mut HashMap::new()),
+                None => {
+                    generics.insert(name.clone(), other.clone());
+                    true
+                }
+            },
+
+            (TypeVariable(binding) | PolymorphicInteger(_, binding), other) => {
+                match &*binding.borrow() {
+                    TypeBinding::Bound(typ) => typ.matches_instance_inner(other, generics),
+                    TypeBinding::Unbound(_) => false,
+                }
+            }
+
+            (_, TypeVariable(_) | PolymorphicInteger(..)) => {
+                self.matches_instance_inner(&Type::field(None), generics)
+            }
+
+            (FieldElement(_), FieldElement(_)) | (Bool(_), Bool(_)) | (Unit, Unit) => true,
+
+            (Integer(_, sign_a, bits_a), Integer(_, sign_b, bits_b)) => {
+                sign_a == sign_b && bits_a == bits_b
+            }
+
+            (Array(len_a, elem_a), Array(len_b, elem_b)) => {
+                len_a.matches_instance_inner(len_b, generics)
+                    && elem_a.matches_instance_inner(elem_b, generics)
+            }
+
+            (String(len_a), String(len_b)) => len_a.matches_instance_inner(len_b, generics),
+
+            (Vec(elem_a), Vec(elem_b)) => elem_a.matches_instance_inner(elem_b, generics),
+
+            (Tuple(elements_a), Tuple(elements_b)) => all_match(elements_a, elements_b, generics),
+
+            (Struct(def_a, args_a), Struct(def_b, args_b)) => {
+                def_a == def_b && all_match(args_a, args_b, generics)
+            }
+
+            (Function(args_a, ret_a, env_a), Function(args_b, ret_b, env_b)) => {
+                all_match(args_a, args_b, generics)
+                    && ret_a.matches_instance_inner(ret_b, generics)
+                    && env_a.matches_instance_inner(env_b, generics)
+            }
+
+            (Constant(a), Constant(b)) => a == b,
+
+            _ => false,
+        }
+    }
+
+    /// Replaces each named generic within `self` by the type given for its name, if any.
+    ///
+    /// This is the counterpart of `matches_instance`: like it, named generics are replaced
+    /// by name whether or not they are currently bound.
+    pub fn substitute_named_generics(&self, generics: &HashMap<Rc<String>, Type>) -> Type {
+        let substitute_all =
+            |types: &[Type]| vecmap(types, |typ| typ.substitute_named_generics(generics));
+
+        match self {
+            Type::NamedGeneric(_, name) if generics.contains_key(name) => generics[name].clone(),
+            Type::TypeVariable(binding) | Type::PolymorphicInteger(_, binding) => {
+                match &*binding.borrow() {
+                    TypeBinding::Bound(typ) => typ.substitute_named_generics(generics),
+                    TypeBinding::Unbound(_) => self.clone(),
+                }
+            }
+            Type::Array(len, elem) => Type::Array(
+                Box::new(len.substitute_named_generics(generics)),
+                Box::new(elem.substitute_named_generics(generics)),
+            ),
+            Type::String(len) => Type::String(Box::new(len.substitute_named_generics(generics))),
+            Type::Vec(elem) => Type::Vec(Box::new(elem.substitute_named_generics(generics))),
+            Type::Tuple(elements) => Type::Tuple(substitute_all(elements)),
+            Type::Struct(def, args) => Type::Struct(def.clone(), substitute_all(args)),
+            Type::Function(args, ret, env) => Type::Function(
+                substitute_all(args),
+                Box::new(ret.substitute_named_generics(generics)),
+                Box::new(env.substitute_named_generics(generics)),
+            ),
+            Type::Forall(typevars, typ) => {
+                Type::Forall(typevars.clone(), Box::new(typ.substitute_named_generics(generics)))
+            }
+            Type::NamedGeneric(..)
+            | Type::FieldElement(_)
+            | Type::Integer(_, _, _)
+            | Type::Bool(_)
+            | Type::Constant(_)
+            | Type::Error
+            | Type::Unit => self.clone(),
+        }
+    }
 }
 
 impl BinaryTypeOperator {
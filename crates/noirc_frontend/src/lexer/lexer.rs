@@ -17,6 +17,7 @@ pub struct Lexer<'a> {
     char_iter: Peekable<Zip<Chars<'a>, RangeFrom<u32>>>,
     position: Position,
     done: bool,
+    skip_comments: bool,
 }
 
 pub type SpannedTokenResult = Result<SpannedToken, LexerErrorKind>;
@@ -25,10 +26,22 @@ impl<'a> Lexer<'a> {
     /// Given a source file of noir code, return all the tokens in the file
     /// in order, along with any lexing errors that occurred.
     pub fn lex(source: &'a str) -> (Tokens, Vec<LexerErrorKind>) {
-        let lexer = Lexer::new(source);
+        Lexer::new(source).collect_tokens()
+    }
+
+    /// Same as `lex`, except that comments are kept in the token stream as `Token::LineComment`s.
+    /// The parser does not accept comments, so this is meant for tools which need to preserve
+    /// them, such as the formatter.
+    pub fn lex_with_comments(source: &'a str) -> (Tokens, Vec<LexerErrorKind>) {
+        let mut lexer = Lexer::new(source);
+        lexer.skip_comments = false;
+        lexer.collect_tokens()
+    }
+
+    fn collect_tokens(self) -> (Tokens, Vec<LexerErrorKind>) {
         let mut tokens = vec![];
         let mut errors = vec![];
-        for result in lexer {
+        for result in self {
             match result {
                 Ok(token) => tokens.push(token),
                 Err(error) => errors.push(error),
@@ -43,6 +56,7 @@ impl<'a> Lexer<'a> {
             char_iter: source.chars().zip(0..).peekable(),
             position: 0,
             done: false,
+            skip_comments: true,
         }
     }
 
@@ -169,7 +183,18 @@ impl<'a> Lexer<'a> {
                 }
             }
             Token::Bang => self.single_double_peek_token('=', prev_token, Token::NotEqual),
-            Token::Assign => self.single_double_peek_token('=', prev_token, Token::Equal),
+            Token::Assign => {
+                let start = self.position;
+                if self.peek_char_is('=') {
+                    self.next_char();
+                    Ok(Token::Equal.into_span(start, start + 1))
+                } else if self.peek_char_is('>') {
+                    self.next_char();
+                    Ok(Token::FatArrow.into_span(start, start + 1))
+                } else {
+                    Ok(prev_token.into_single_span(start))
+                }
+            }
             Token::Minus => self.single_double_peek_token('>', prev_token, Token::Arrow),
             Token::Colon => self.single_double_peek_token(':', prev_token, Token::DoubleColon),
             Token::Slash => {
@@ -203,10 +228,10 @@ impl<'a> Lexer<'a> {
     }
 
     /// Keeps consuming tokens as long as the predicate is satisfied
-    fn eat_while<F: Fn(char) -> bool>(
+    fn eat_while<F: FnMut(char) -> bool>(
         &mut self,
         initial_char: Option<char>,
-        predicate: F,
+        mut predicate: F,
     ) -> (String, Position, Position) {
         let start = self.position;
 
@@ -257,9 +282,20 @@ impl<'a> Lexer<'a> {
         }
         self.next_char();
 
+        // Custom attributes may hold arbitrary arguments, including brackets and strings, so
+        // everything up to the matching closing bracket on the same line belongs to the attribute
+        let mut depth = 0;
+        let mut in_string = false;
         let (word, start, end) = self.eat_while(None, |ch| {
-            (ch.is_ascii_alphabetic() || ch.is_numeric() || ch == '_' || ch == '(' || ch == ')')
-                && (ch != ']')
+            match ch {
+                '\n' => return false,
+                '"' => in_string = !in_string,
+                '[' if !in_string => depth += 1,
+                ']' if !in_string && depth == 0 => return false,
+                ']' if !in_string => depth -= 1,
+                _ => (),
+            }
+            true
         });
 
         if !self.peek_char_is(']') {
@@ -331,8 +367,13 @@ impl<'a> Lexer<'a> {
     }
 
     fn parse_comment(&mut self) -> SpannedTokenResult {
-        let _ = self.eat_while(None, |ch| ch != '\n');
-        self.next_token()
+        // The leading '//' has already been consumed
+        let start = self.position - 1;
+        let (comment, _, end) = self.eat_while(None, |ch| ch != '\n');
+        if self.skip_comments {
+            return self.next_token();
+        }
+        Ok(Token::LineComment(comment).into_span(start, end))
     }
 
     /// Skips white space. They are not significant in the source language
@@ -354,7 +395,7 @@ impl<'a> Iterator for Lexer<'a> {
 
 #[test]
 fn test_single_double_char() {
-    let input = "! != + ( ) { } [ ] | , ; : :: < <= > >= & - -> . .. % / * = == << >>";
+    let input = "! != + ( ) { } [ ] | , ; : :: < <= > >= & - -> . .. % / * = == => << >>";
 
     let expected = vec![
         Token::Bang,
@@ -385,6 +426,7 @@ fn test_single_double_char() {
         Token::Star,
         Token::Assign,
         Token::Equal,
+        Token::FatArrow,
         Token::ShiftLeft,
         Token::Greater,
         Token::Greater,
@@ -416,6 +458,81 @@ fn test_custom_gate_syntax() {
     }
 }
 
+#[test]
+fn test_max_iterations_attribute() {
+    let mut lexer = Lexer::new("#[max_iterations(64)]");
+    assert_eq!(lexer.next_token().unwrap(), Token::Attribute(Attribute::MaxIterations(64)));
+
+    let mut lexer = Lexer::new("#[max_iterations(n)]");
+    assert!(lexer.next_token().is_err());
+}
+
+#[test]
+fn test_custom_attribute() {
+    use super::token::CustomAttribute;
+
+    let input = "#[deploy(\"public\", version = 2)]#[inline]";
+
+    let expected = vec![
+        Token::Attribute(Attribute::Custom(CustomAttribute {
+            name: "deploy".to_string(),
+            arguments: vec!["\"public\"".to_string(), "version = 2".to_string()],
+        })),
+        Token::Attribute(Attribute::Custom(CustomAttribute {
+            name: "inline".to_string(),
+            arguments: Vec::new(),
+        })),
+    ];
+
+    let mut lexer = Lexer::new(input);
+    for token in expected.into_iter() {
+        let got = lexer.next_token().unwrap();
+        assert_eq!(got, token);
+    }
+
+    let mut lexer = Lexer::new("#[not an attribute]");
+    assert!(lexer.next_token().is_err());
+}
+
+#[test]
+fn test_custom_attribute_with_brackets() {
+    use super::token::CustomAttribute;
+
+    let input = "#[selectors([1, 2], \"]\")] fn";
+    let mut lexer = Lexer::new(input);
+    assert_eq!(
+        lexer.next_token().unwrap(),
+        Token::Attribute(Attribute::Custom(CustomAttribute {
+            name: "selectors".to_string(),
+            arguments: vec!["[1, 2]".to_string(), "\"]\"".to_string()],
+        }))
+    );
+    assert_eq!(lexer.next_token().unwrap(), Token::Keyword(Keyword::Fn));
+}
+
+#[test]
+fn test_test_attribute() {
+    use super::token::TestScope;
+
+    let cases = [
+        ("#[test]", TestScope::None),
+        ("#[test(should_fail)]", TestScope::ShouldFail { reason: None }),
+        (
+            "#[test(should_fail_with = \"out of range\")]",
+            TestScope::ShouldFail { reason: Some("out of range".to_owned()) },
+        ),
+    ];
+    for (input, scope) in cases {
+        let mut lexer = Lexer::new(input);
+        assert_eq!(lexer.next_token().unwrap(), Token::Attribute(Attribute::Test(scope)));
+    }
+
+    for input in ["#[test(should_pass)]", "#[test(should_fail_with = reason)]"] {
+        let mut lexer = Lexer::new(input);
+        assert!(lexer.next_token().is_err());
+    }
+}
+
 #[test]
 fn test_int_type() {
     let input = "u16 i16 i108 u104.5";
@@ -480,6 +597,28 @@ fn test_comment() {
     }
 }
 
+#[test]
+fn test_comment_kept_as_trivia() {
+    let input = "// hello
+        let x = 5 // five
+    ";
+
+    let expected = vec![
+        Token::LineComment(" hello".to_string()),
+        Token::Keyword(Keyword::Let),
+        Token::Ident("x".to_string()),
+        Token::Assign,
+        Token::Int(FieldElement::from(5_i128)),
+        Token::LineComment(" five".to_string()),
+        Token::EOF,
+    ];
+
+    let (tokens, errors) = Lexer::lex_with_comments(input);
+    assert!(errors.is_empty());
+    let tokens: Vec<Token> = tokens.0.into_iter().map(SpannedToken::into_token).collect();
+    assert_eq!(tokens, expected);
+}
+
 #[test]
 fn test_eat_string_literal() {
     let input = "let _word = \"hello\"";
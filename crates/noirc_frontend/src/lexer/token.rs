@@ -1,5 +1,6 @@
 use acvm::FieldElement;
 use noirc_errors::{Position, Span, Spanned};
+use serde::{Deserialize, Serialize};
 use std::{fmt, iter::Map, vec::IntoIter};
 
 use crate::lexer::errors::LexerErrorKind;
@@ -18,6 +19,10 @@ pub enum Token {
     Keyword(Keyword),
     IntType(IntType),
     Attribute(Attribute),
+    /// A `//` comment, without the leading slashes.
+    ///
+    /// Comments are only kept in the token stream by `Lexer::lex_with_comments`.
+    LineComment(String),
     /// <
     Less,
     /// <=
@@ -66,6 +71,8 @@ pub enum Token {
     RightBracket,
     /// ->
     Arrow,
+    /// =>
+    FatArrow,
     /// |
     Pipe,
     /// #
@@ -149,6 +156,7 @@ impl fmt::Display for Token {
             Token::Str(ref b) => write!(f, "{b}"),
             Token::Keyword(k) => write!(f, "{k}"),
             Token::Attribute(ref a) => write!(f, "{a}"),
+            Token::LineComment(ref c) => write!(f, "//{c}"),
             Token::IntType(ref i) => write!(f, "{i}"),
             Token::Less => write!(f, "<"),
             Token::LessEqual => write!(f, "<="),
@@ -174,6 +182,7 @@ impl fmt::Display for Token {
             Token::LeftBracket => write!(f, "["),
             Token::RightBracket => write!(f, "]"),
             Token::Arrow => write!(f, "->"),
+            Token::FatArrow => write!(f, "=>"),
             Token::Pipe => write!(f, "|"),
             Token::Pound => write!(f, "#"),
             Token::Comma => write!(f, ","),
@@ -324,7 +333,81 @@ impl IntType {
 pub enum Attribute {
     Foreign(String),
     Builtin(String),
-    Test,
+    Test(TestScope),
+    /// Bounds the number of iterations of a `while` loop
+    MaxIterations(u64),
+    /// Any attribute the compiler does not know of, kept for tooling to query
+    Custom(CustomAttribute),
+}
+
+/// Whether a test is expected to pass or to fail, as given by its `#[test]` attribute
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
+pub enum TestScope {
+    /// `#[test]`
+    None,
+    /// `#[test(should_fail)]`, or `#[test(should_fail_with = "reason")]` to require the
+    /// failure message to contain the given reason
+    ShouldFail { reason: Option<String> },
+}
+
+impl TestScope {
+    fn lookup_str(string: &str) -> Option<TestScope> {
+        match string.split_once('=') {
+            None if string == "should_fail" => Some(TestScope::ShouldFail { reason: None }),
+            Some((name, reason)) if name.trim_end() == "should_fail_with" => {
+                let reason = reason.trim_start().strip_prefix('"')?.strip_suffix('"')?;
+                Some(TestScope::ShouldFail { reason: Some(reason.to_owned()) })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TestScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TestScope::None => write!(f, ""),
+            TestScope::ShouldFail { reason: None } => write!(f, "(should_fail)"),
+            TestScope::ShouldFail { reason: Some(reason) } => {
+                write!(f, "(should_fail_with = \"{reason}\")")
+            }
+        }
+    }
+}
+
+/// An attribute such as `#[name]` or `#[name(arguments, ...)]` which has no meaning to the
+/// compiler. Its arguments are kept as written, with surrounding whitespace removed.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CustomAttribute {
+    pub name: String,
+    pub arguments: Vec<String>,
+}
+
+/// The names of the attributes which have a meaning to the compiler
+const KNOWN_ATTRIBUTES: [&str; 4] = ["test", "foreign", "builtin", "max_iterations"];
+
+impl CustomAttribute {
+    /// Returns the name of the known attribute this attribute is likely a misspelling of,
+    /// such as `test` for `#[tset]`
+    pub fn misspelled_attribute(&self) -> Option<&'static str> {
+        KNOWN_ATTRIBUTES.into_iter().find(|known| edit_distance(&self.name, known) <= 2)
+    }
+}
+
+/// The number of characters to insert, remove or replace to turn one string into the other
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let replace = previous[j] + usize::from(a_char != *b_char);
+            current.push(replace.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
 }
 
 impl fmt::Display for Attribute {
@@ -332,7 +415,19 @@ impl fmt::Display for Attribute {
         match *self {
             Attribute::Foreign(ref k) => write!(f, "#[foreign({k})]"),
             Attribute::Builtin(ref k) => write!(f, "#[builtin({k})]"),
-            Attribute::Test => write!(f, "#[test]"),
+            Attribute::Test(ref scope) => write!(f, "#[test{scope}]"),
+            Attribute::MaxIterations(max) => write!(f, "#[max_iterations({max})]"),
+            Attribute::Custom(ref attribute) => write!(f, "{attribute}"),
+        }
+    }
+}
+
+impl fmt::Display for CustomAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.arguments.is_empty() {
+            write!(f, "#[{}]", self.name)
+        } else {
+            write!(f, "#[{}({})]", self.name, self.arguments.join(", "))
         }
     }
 }
@@ -341,33 +436,37 @@ impl Attribute {
     /// If the string is a fixed attribute return that, else
     /// return the custom attribute
     pub(crate) fn lookup_attribute(word: &str, span: Span) -> Result<Token, LexerErrorKind> {
-        let word_segments: Vec<&str> = word
-            .split(|c| c == '(' || c == ')')
-            .filter(|string_segment| !string_segment.is_empty())
-            .collect();
-
-        if word_segments.len() != 2 {
-            if word_segments.len() == 1 && word_segments[0] == "test" {
-                return Ok(Token::Attribute(Attribute::Test));
-            } else {
-                return Err(LexerErrorKind::MalformedFuncAttribute {
-                    span,
-                    found: word.to_owned(),
-                });
+        let malformed = || LexerErrorKind::MalformedFuncAttribute { span, found: word.to_owned() };
+
+        let trimmed = word.trim();
+        let (name, arguments) = match trimmed.split_once('(') {
+            Some((name, rest)) => {
+                let arguments = rest.strip_suffix(')').ok_or_else(malformed)?;
+                (name.trim_end(), split_attribute_arguments(arguments))
             }
-        }
+            None => (trimmed, Vec::new()),
+        };
 
-        let attribute_type = word_segments[0];
-        let attribute_name = word_segments[1];
+        let is_identifier = name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+            && name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+        if !is_identifier {
+            return Err(malformed());
+        }
 
-        let tok = match attribute_type {
-            "foreign" => Token::Attribute(Attribute::Foreign(attribute_name.to_string())),
-            "builtin" => Token::Attribute(Attribute::Builtin(attribute_name.to_string())),
-            _ => {
-                return Err(LexerErrorKind::MalformedFuncAttribute { span, found: word.to_owned() })
+        let attribute = match (name, arguments.as_slice()) {
+            ("test", []) => Attribute::Test(TestScope::None),
+            ("test", [scope]) => {
+                Attribute::Test(TestScope::lookup_str(scope).ok_or_else(malformed)?)
             }
+            ("foreign", [name]) => Attribute::Foreign(name.clone()),
+            ("builtin", [name]) => Attribute::Builtin(name.clone()),
+            ("max_iterations", [max]) => {
+                Attribute::MaxIterations(max.parse().map_err(|_| malformed())?)
+            }
+            ("test" | "foreign" | "builtin" | "max_iterations", _) => return Err(malformed()),
+            _ => Attribute::Custom(CustomAttribute { name: name.to_owned(), arguments }),
         };
-        Ok(tok)
+        Ok(Token::Attribute(attribute))
     }
 
     pub fn builtin(self) -> Option<String> {
@@ -393,12 +492,43 @@ impl Attribute {
     }
 }
 
+/// Splits the arguments of an attribute at each comma which is not nested within
+/// brackets or a string
+fn split_attribute_arguments(arguments: &str) -> Vec<String> {
+    if arguments.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut in_string = false;
+
+    for ch in arguments.chars() {
+        match ch {
+            '"' => in_string = !in_string,
+            '(' | '[' | '{' if !in_string => depth += 1,
+            ')' | ']' | '}' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                result.push(current.trim().to_owned());
+                current.clear();
+                continue;
+            }
+            _ => (),
+        }
+        current.push(ch);
+    }
+    result.push(current.trim().to_owned());
+    result
+}
+
 impl AsRef<str> for Attribute {
     fn as_ref(&self) -> &str {
         match self {
             Attribute::Foreign(string) => string,
             Attribute::Builtin(string) => string,
-            Attribute::Test => "",
+            Attribute::Custom(attribute) => &attribute.name,
+            Attribute::Test(_) | Attribute::MaxIterations(_) => "",
         }
     }
 }
@@ -412,14 +542,17 @@ pub enum Keyword {
     As,
     Assert,
     Bool,
+    Break,
     Char,
     CompTime,
     Constrain,
+    Continue,
     Contract,
     Crate,
     Dep,
     Distinct,
     Else,
+    Enum,
     Field,
     Fn,
     For,
@@ -428,6 +561,7 @@ pub enum Keyword {
     If,
     In,
     Let,
+    Match,
     Mod,
     Mut,
     Open,
@@ -435,6 +569,7 @@ pub enum Keyword {
     String,
     Return,
     Struct,
+    Trait,
     Unconstrained,
     Use,
     Vec,
@@ -447,14 +582,17 @@ impl fmt::Display for Keyword {
             Keyword::As => write!(f, "as"),
             Keyword::Assert => write!(f, "assert"),
             Keyword::Bool => write!(f, "bool"),
+            Keyword::Break => write!(f, "break"),
             Keyword::Char => write!(f, "char"),
             Keyword::CompTime => write!(f, "comptime"),
             Keyword::Constrain => write!(f, "constrain"),
+            Keyword::Continue => write!(f, "continue"),
             Keyword::Contract => write!(f, "contract"),
             Keyword::Crate => write!(f, "crate"),
             Keyword::Dep => write!(f, "dep"),
             Keyword::Distinct => write!(f, "distinct"),
             Keyword::Else => write!(f, "else"),
+            Keyword::Enum => write!(f, "enum"),
             Keyword::Field => write!(f, "Field"),
             Keyword::Fn => write!(f, "fn"),
             Keyword::For => write!(f, "for"),
@@ -463,6 +601,7 @@ impl fmt::Display for Keyword {
             Keyword::If => write!(f, "if"),
             Keyword::In => write!(f, "in"),
             Keyword::Let => write!(f, "let"),
+            Keyword::Match => write!(f, "match"),
             Keyword::Mod => write!(f, "mod"),
             Keyword::Mut => write!(f, "mut"),
             Keyword::Open => write!(f, "open"),
@@ -470,6 +609,7 @@ impl fmt::Display for Keyword {
             Keyword::String => write!(f, "str"),
             Keyword::Return => write!(f, "return"),
             Keyword::Struct => write!(f, "struct"),
+            Keyword::Trait => write!(f, "trait"),
             Keyword::Unconstrained => write!(f, "unconstrained"),
             Keyword::Use => write!(f, "use"),
             Keyword::Vec => write!(f, "Vec"),
@@ -485,14 +625,17 @@ impl Keyword {
             "as" => Keyword::As,
             "assert" => Keyword::Assert,
             "bool" => Keyword::Bool,
+            "break" => Keyword::Break,
             "char" => Keyword::Char,
             "comptime" => Keyword::CompTime,
             "constrain" => Keyword::Constrain,
+            "continue" => Keyword::Continue,
             "contract" => Keyword::Contract,
             "crate" => Keyword::Crate,
             "dep" => Keyword::Dep,
             "distinct" => Keyword::Distinct,
             "else" => Keyword::Else,
+            "enum" => Keyword::Enum,
             "Field" => Keyword::Field,
             "fn" => Keyword::Fn,
             "for" => Keyword::For,
@@ -501,6 +644,7 @@ impl Keyword {
             "if" => Keyword::If,
             "in" => Keyword::In,
             "let" => Keyword::Let,
+            "match" => Keyword::Match,
             "mod" => Keyword::Mod,
             "mut" => Keyword::Mut,
             "open" => Keyword::Open,
@@ -508,6 +652,7 @@ impl Keyword {
             "str" => Keyword::String,
             "return" => Keyword::Return,
             "struct" => Keyword::Struct,
+            "trait" => Keyword::Trait,
             "unconstrained" => Keyword::Unconstrained,
             "use" => Keyword::Use,
             "Vec" => Keyword::Vec,
@@ -11,6 +11,7 @@
 #![warn(clippy::semicolon_if_nothing_returned)]
 
 pub mod ast;
+pub mod formatter;
 pub mod graph;
 pub mod lexer;
 pub mod monomorphization;
@@ -0,0 +1,145 @@
+//! This module implements the call graph of the monomorphized AST, which is used to detect
+//! recursive functions before they are compiled into SSA.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use noirc_errors::Location;
+
+use super::ast::{Definition, Expression, FuncId, LValue, Literal, Program};
+
+/// The functions referenced by each function of a monomorphized program.
+///
+/// Any reference to a function counts as a call, since a function passed around as a value
+/// may be called by any function it is passed to.
+pub struct CallGraph {
+    callees: BTreeMap<FuncId, Vec<(FuncId, Location)>>,
+}
+
+/// A call from `caller` to `callee` where `caller` is in turn reachable from `callee`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RecursiveCall {
+    pub caller: FuncId,
+    pub callee: FuncId,
+    pub location: Location,
+}
+
+impl CallGraph {
+    pub fn new(program: &Program) -> CallGraph {
+        let callees = program
+            .functions
+            .iter()
+            .map(|function| {
+                let mut calls = Vec::new();
+                collect_calls(&function.body, &mut calls);
+                (function.id, calls)
+            })
+            .collect();
+        CallGraph { callees }
+    }
+
+    /// Returns every function which can be called, directly or not, from `function`.
+    pub fn reachable_from(&self, function: FuncId) -> BTreeSet<FuncId> {
+        let mut reachable = BTreeSet::new();
+        let mut queue = vec![function];
+        while let Some(caller) = queue.pop() {
+            for (callee, _) in self.callees.get(&caller).into_iter().flatten() {
+                if reachable.insert(*callee) {
+                    queue.push(*callee);
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Returns each call which is part of a cycle in the call graph, in the order of the
+    /// functions of the program.
+    pub fn recursive_calls(&self) -> Vec<RecursiveCall> {
+        let mut recursive_calls = Vec::new();
+        for (caller, calls) in &self.callees {
+            let reachable = self.reachable_from(*caller);
+            if !reachable.contains(caller) {
+                continue;
+            }
+            for (callee, location) in calls {
+                if self.reachable_from(*callee).contains(caller) {
+                    recursive_calls.push(RecursiveCall {
+                        caller: *caller,
+                        callee: *callee,
+                        location: *location,
+                    });
+                }
+            }
+        }
+        recursive_calls
+    }
+}
+
+/// Pushes the function and location of each function referenced within `expr` to `calls`.
+fn collect_calls(expr: &Expression, calls: &mut Vec<(FuncId, Location)>) {
+    match expr {
+        Expression::Ident(ident) => {
+            if let (Definition::Function(id), Some(location)) = (&ident.definition, ident.location)
+            {
+                calls.push((*id, location));
+            }
+        }
+        Expression::Literal(Literal::Array(array)) => {
+            array.contents.iter().for_each(|element| collect_calls(element, calls));
+        }
+        Expression::Literal(_) => (),
+        Expression::Block(exprs) | Expression::Tuple(exprs) => {
+            exprs.iter().for_each(|expr| collect_calls(expr, calls));
+        }
+        Expression::Unary(unary) => collect_calls(&unary.rhs, calls),
+        Expression::Binary(binary) => {
+            collect_calls(&binary.lhs, calls);
+            collect_calls(&binary.rhs, calls);
+        }
+        Expression::Index(index) => {
+            collect_calls(&index.collection, calls);
+            collect_calls(&index.index, calls);
+        }
+        Expression::Cast(cast) => collect_calls(&cast.lhs, calls),
+        Expression::For(for_expr) => {
+            collect_calls(&for_expr.start_range, calls);
+            collect_calls(&for_expr.end_range, calls);
+            collect_calls(&for_expr.block, calls);
+        }
+        Expression::If(if_expr) => {
+            collect_calls(&if_expr.condition, calls);
+            collect_calls(&if_expr.consequence, calls);
+            if let Some(alternative) = &if_expr.alternative {
+                collect_calls(alternative, calls);
+            }
+        }
+        Expression::ExtractTupleField(expr, _) => collect_calls(expr, calls),
+        Expression::Call(call) => {
+            match call.func.as_ref() {
+                // Direct calls are reported at the location of the whole call expression
+                Expression::Ident(ident) => match &ident.definition {
+                    Definition::Function(id) => calls.push((*id, call.location)),
+                    _ => collect_calls(&call.func, calls),
+                },
+                func => collect_calls(func, calls),
+            }
+            call.arguments.iter().for_each(|argument| collect_calls(argument, calls));
+        }
+        Expression::Let(let_expr) => collect_calls(&let_expr.expression, calls),
+        Expression::Constrain(expr, ..) | Expression::Semi(expr) => collect_calls(expr, calls),
+        Expression::Assign(assign) => {
+            collect_lvalue_calls(&assign.lvalue, calls);
+            collect_calls(&assign.expression, calls);
+        }
+    }
+}
+
+fn collect_lvalue_calls(lvalue: &LValue, calls: &mut Vec<(FuncId, Location)>) {
+    match lvalue {
+        LValue::Ident(_) => (),
+        LValue::Index { array, index, .. } => {
+            collect_lvalue_calls(array, calls);
+            collect_calls(index, calls);
+        }
+        LValue::MemberAccess { object, .. } => collect_lvalue_calls(object, calls),
+    }
+}
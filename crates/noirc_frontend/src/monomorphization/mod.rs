@@ -21,12 +21,14 @@ use crate::{
         stmt::{HirAssignStatement, HirLValue, HirLetStatement, HirPattern, HirStatement},
     },
     node_interner::{self, DefinitionKind, NodeInterner, StmtId},
-    CompTime, FunctionKind, TypeBinding, TypeBindings,
+    token::Attribute,
+    BinaryOpKind, CompTime, FunctionKind, TypeBinding, TypeBindings, UnaryOp,
 };
 
 use self::ast::{Definition, FuncId, Function, LocalId, Program};
 
 pub mod ast;
+pub mod call_graph;
 pub mod printer;
 
 /// The context struct for the monomorphization pass.
@@ -59,8 +61,27 @@ struct Monomorphizer<'interner> {
 
     next_local_id: u32,
     next_function_id: u32,
+
+    /// The early exits of each loop enclosing the expression being monomorphized
+    loops: Vec<LoopExits>,
+}
+
+/// Loops are fully unrolled, so rather than jumping out of the loop, `break` and `continue`
+/// set a flag which predicates the rest of the loop on not having been exited.
+struct LoopExits {
+    broken: LocalId,
+    continued: LocalId,
+    uses_break: bool,
+    uses_continue: bool,
+
+    /// The number of `break` and `continue` statements monomorphized so far, used
+    /// to find the statements after which the rest of a block must be predicated.
+    count: usize,
 }
 
+const BREAK_FLAG: &str = "$break";
+const CONTINUE_FLAG: &str = "$continue";
+
 type HirType = crate::Type;
 
 /// Starting from the given `main` function, monomorphize the entire program,
@@ -101,6 +122,7 @@ impl<'interner> Monomorphizer<'interner> {
             finished_functions: BTreeMap::new(),
             next_local_id: 0,
             next_function_id: 0,
+            loops: Vec::new(),
             interner,
         }
     }
@@ -135,19 +157,23 @@ impl<'interner> Monomorphizer<'interner> {
                 let meta = self.interner.function_meta(&id);
                 match meta.kind {
                     FunctionKind::LowLevel => {
-                        let attribute = meta.attributes.expect("all low level functions must contain an attribute which contains the opcode which it links to");
-                        let opcode = attribute.foreign().expect(
-                            "ice: function marked as foreign, but attribute kind does not match this",
-                        );
+                        let opcode =
+                            meta.attributes.into_iter().find_map(Attribute::foreign).expect(
+                                "ice: function marked as foreign, but has no foreign attribute",
+                            );
                         Definition::LowLevel(opcode)
                     }
                     FunctionKind::Builtin => {
-                        let attribute = meta.attributes.expect("all low level functions must contain an attribute which contains the opcode which it links to");
-                        let opcode = attribute.builtin().expect(
-                            "ice: function marked as builtin, but attribute kind does not match this",
-                        );
+                        let opcode =
+                            meta.attributes.into_iter().find_map(Attribute::builtin).expect(
+                                "ice: function marked as builtin, but has no builtin attribute",
+                            );
                         Definition::Builtin(opcode)
                     }
+                    // Calls to the methods of a trait are dispatched to the trait's impl
+                    FunctionKind::Normal if meta.trait_id.is_some() => {
+                        self.lookup_trait_impl_method(id, expr_id, typ)
+                    }
                     FunctionKind::Normal => {
                         let id = self.queue_function(id, expr_id, typ);
                         Definition::Function(id)
@@ -157,6 +183,56 @@ impl<'interner> Monomorphizer<'interner> {
         }
     }
 
+    /// Finds the method implementing the trait method `id` for the type it is called on
+    /// in the given expression, queueing it to be monomorphized if it hasn't been already.
+    /// Prerequisite: typ = typ.follow_bindings()
+    fn lookup_trait_impl_method(
+        &mut self,
+        id: node_interner::FuncId,
+        expr_id: node_interner::ExprId,
+        typ: HirType,
+    ) -> Definition {
+        let interner = self.interner;
+        let trait_id = interner.function_meta(&id).trait_id.expect("Expected a trait method");
+
+        // The first generic of a trait method is Self, the type implementing the trait
+        let self_id = match interner.function_meta(&id).typ {
+            HirType::Forall(generics, _) => generics[0].0,
+            other => unreachable!("Expected a trait method to be generic over Self, found {other}"),
+        };
+        let bindings = interner.get_instantiation_bindings(expr_id);
+        let (_, self_type) = &bindings[&self_id];
+        let self_type = self_type.follow_bindings();
+
+        // The type checker ensured the impl exists and its bounds are satisfied
+        let (trait_impl, generics) = interner
+            .lookup_trait_impl(trait_id, &self_type, &[])
+            .unwrap_or_else(|| unreachable!("Expected an impl of the trait for {self_type}"));
+        let method_id = trait_impl.methods[interner.function_name(&id)];
+
+        if let Some(new_id) = self.globals.get(&method_id).and_then(|inner_map| inner_map.get(&typ))
+        {
+            return Definition::Function(*new_id);
+        }
+
+        // Bind each generic of the impl to the type it stands for in Self
+        let bindings = trait_impl
+            .generics
+            .iter()
+            .map(|(name, id, var)| {
+                let binding = generics
+                    .get(name)
+                    .map_or(HirType::FieldElement(CompTime::No(None)), HirType::follow_bindings);
+                (*id, (var.clone(), binding))
+            })
+            .collect();
+
+        let new_id = self.next_function_id();
+        self.define_global(method_id, typ, new_id);
+        self.queue.push_back((method_id, new_id, bindings));
+        Definition::Function(new_id)
+    }
+
     fn define_local(&mut self, id: node_interner::DefinitionId, new_id: LocalId) {
         self.locals.insert(id, new_id);
     }
@@ -181,13 +257,53 @@ impl<'interner> Monomorphizer<'interner> {
 
         let return_type = Self::convert_type(meta.return_type());
         let parameters = self.parameters(meta.parameters);
-        let body = self.expr(*self.interner.function(&f).as_expr());
+        let body = match meta.enum_variant {
+            Some((_, index)) => self.enum_variant(index, &parameters, &return_type),
+            None => self.expr(*self.interner.function(&f).as_expr()),
+        };
         let unconstrained = meta.is_unconstrained;
 
         let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
         self.push_function(id, function);
     }
 
+    /// Enum variants have no body in the HIR. Construct the variant with the given index
+    /// as its tag followed by the fields of every variant, where only those of the
+    /// constructed variant are taken from the parameters and the rest are zeroed.
+    fn enum_variant(
+        &mut self,
+        index: usize,
+        parameters: &[(ast::LocalId, bool, String, ast::Type)],
+        enum_type: &ast::Type,
+    ) -> ast::Expression {
+        let variant_types = match enum_type {
+            ast::Type::Tuple(fields) => &fields[1..],
+            other => unreachable!("Expected an enum to be a tuple, found {other}"),
+        };
+
+        let tag = ast::Literal::Integer((index as u128).into(), ast::Type::Field);
+        let mut fields = vec![ast::Expression::Literal(tag)];
+
+        for (i, variant_type) in variant_types.iter().enumerate() {
+            if i == index {
+                let variant_fields = vecmap(parameters, |(id, mutable, name, typ)| {
+                    ast::Expression::Ident(ast::Ident {
+                        location: None,
+                        definition: Definition::Local(*id),
+                        mutable: *mutable,
+                        name: name.clone(),
+                        typ: typ.clone(),
+                    })
+                });
+                fields.push(ast::Expression::Tuple(variant_fields));
+            } else {
+                fields.push(self.zeroed_value_of_type(variant_type));
+            }
+        }
+
+        ast::Expression::Tuple(fields)
+    }
+
     fn push_function(&mut self, id: FuncId, function: ast::Function) {
         let existing = self.finished_functions.insert(id, function);
         assert!(existing.is_none());
@@ -263,7 +379,7 @@ impl<'interner> Monomorphizer<'interner> {
                     self.repeated_array(repeated_element, length)
                 }
             },
-            HirExpression::Block(block) => self.block(block.0),
+            HirExpression::Block(block) => self.block(&block.0),
 
             HirExpression::Prefix(prefix) => ast::Expression::Unary(ast::Unary {
                 operator: prefix.operator,
@@ -299,18 +415,29 @@ impl<'interner> Monomorphizer<'interner> {
                 let index_variable = self.next_local_id();
                 self.define_local(for_expr.identifier.id, index_variable);
 
-                let block = Box::new(self.expr(for_expr.block));
+                self.push_loop();
+                let block = self.expr(for_expr.block);
+                let (block, exits) = self.pop_loop(block);
 
-                ast::Expression::For(ast::For {
+                let for_loop = ast::Expression::For(ast::For {
                     index_variable,
                     index_name: self.interner.definition_name(for_expr.identifier.id).to_owned(),
                     index_type: Self::convert_type(&self.interner.id_type(for_expr.start_range)),
                     start_range: Box::new(start),
                     end_range: Box::new(end),
-                    block,
-                })
+                    block: Box::new(block),
+                });
+
+                if exits.uses_break {
+                    let declare_break = declare_flag(exits.broken, BREAK_FLAG);
+                    ast::Expression::Block(vec![declare_break, for_loop])
+                } else {
+                    for_loop
+                }
             }
 
+            HirExpression::While(while_expr) => self.while_loop(while_expr),
+
             HirExpression::If(if_expr) => {
                 let cond = self.expr(if_expr.condition);
                 let then = self.expr(if_expr.consequence);
@@ -328,6 +455,7 @@ impl<'interner> Monomorphizer<'interner> {
                 ast::Expression::Tuple(fields)
             }
             HirExpression::Constructor(constructor) => self.constructor(constructor, expr),
+            HirExpression::Match(match_expr) => self.match_expression(match_expr, expr),
 
             HirExpression::Lambda(lambda) => self.lambda(lambda),
 
@@ -446,11 +574,13 @@ impl<'interner> Monomorphizer<'interner> {
             HirStatement::Constrain(constrain) => {
                 let expr = self.expr(constrain.0);
                 let location = self.interner.expr_location(&constrain.0);
-                ast::Expression::Constrain(Box::new(expr), location)
+                ast::Expression::Constrain(Box::new(expr), location, constrain.2)
             }
             HirStatement::Assign(assign) => self.assign(assign),
             HirStatement::Expression(expr) => self.expr(expr),
             HirStatement::Semi(expr) => ast::Expression::Semi(Box::new(self.expr(expr))),
+            HirStatement::Break => self.exit_loop(true),
+            HirStatement::Continue => self.exit_loop(false),
             HirStatement::Error => unreachable!(),
         }
     }
@@ -510,8 +640,328 @@ impl<'interner> Monomorphizer<'interner> {
         ast::Expression::Block(new_exprs)
     }
 
-    fn block(&mut self, statement_ids: Vec<StmtId>) -> ast::Expression {
-        ast::Expression::Block(vecmap(statement_ids, |id| self.statement(id)))
+    /// Lowers a match into a chain of if expressions checking the pattern of each rule in turn.
+    /// The matched value is bound to a variable first so that it is only evaluated once:
+    /// `{ let $match = value; if check0 { bindings0; branch0 } else ... { bindingsN; branchN } }`
+    /// The match is known to be exhaustive, so the pattern of the last rule is never checked.
+    fn match_expression(
+        &mut self,
+        match_expr: HirMatchExpression,
+        id: node_interner::ExprId,
+    ) -> ast::Expression {
+        let typ = Self::convert_type(&self.interner.id_type(id));
+        let location = self.interner.expr_location(&match_expr.expression);
+
+        let matched_id = self.next_local_id();
+        let name = "$match".to_owned();
+        let expression = Box::new(self.expr(match_expr.expression));
+        let matched_type = Self::convert_type(&self.interner.id_type(match_expr.expression));
+        let let_matched = ast::Expression::Let(ast::Let {
+            id: matched_id,
+            mutable: false,
+            name: name.clone(),
+            expression,
+        });
+
+        let definition = Definition::Local(matched_id);
+        let matched = ast::Expression::Ident(ast::Ident {
+            location: Some(location),
+            definition,
+            mutable: false,
+            name,
+            typ: matched_type.clone(),
+        });
+
+        let mut result = None;
+        for (pattern, branch) in match_expr.rules.into_iter().rev() {
+            let mut statements = Vec::new();
+            let condition = self.match_pattern(
+                pattern,
+                matched.clone(),
+                &matched_type,
+                &mut statements,
+                location,
+            );
+
+            statements.push(self.expr(branch));
+            let branch = ast::Expression::Block(statements);
+
+            result = Some(match (condition, result) {
+                (Some(condition), Some(alternative)) => ast::Expression::If(ast::If {
+                    condition: Box::new(condition),
+                    consequence: Box::new(branch),
+                    alternative: Some(Box::new(alternative)),
+                    typ: typ.clone(),
+                }),
+                _ => branch,
+            });
+        }
+
+        // A match without any rules can only match an enum without any variants, which has no values
+        let result = result.unwrap_or_else(|| self.zeroed_value_of_type(&typ));
+        ast::Expression::Block(vec![let_matched, result])
+    }
+
+    /// Pushes a let statement onto `bindings` for each variable bound by the given pattern and
+    /// returns the condition for `value` to match it, if there is one.
+    fn match_pattern(
+        &mut self,
+        pattern: HirMatchPattern,
+        value: ast::Expression,
+        typ: &ast::Type,
+        bindings: &mut Vec<ast::Expression>,
+        location: Location,
+    ) -> Option<ast::Expression> {
+        let equals = |lhs, rhs| {
+            let (lhs, rhs) = (Box::new(lhs), Box::new(rhs));
+            ast::Expression::Binary(ast::Binary {
+                lhs,
+                operator: BinaryOpKind::Equal,
+                rhs,
+                location,
+            })
+        };
+
+        match pattern {
+            HirMatchPattern::Wildcard(_) => None,
+            HirMatchPattern::Identifier(ident) => {
+                let new_id = self.next_local_id();
+                self.define_local(ident.id, new_id);
+                let name = self.interner.definition_name(ident.id).to_owned();
+                let expression = Box::new(value);
+                bindings.push(ast::Expression::Let(ast::Let {
+                    id: new_id,
+                    mutable: false,
+                    name,
+                    expression,
+                }));
+                None
+            }
+            HirMatchPattern::Bool(expected, _) => {
+                Some(equals(value, ast::Expression::Literal(ast::Literal::Bool(expected))))
+            }
+            HirMatchPattern::Integer(expected, _) => {
+                let expected = ast::Literal::Integer(expected, typ.clone());
+                Some(equals(value, ast::Expression::Literal(expected)))
+            }
+            HirMatchPattern::Tuple(fields, _) => {
+                let field_types = unwrap_tuple_ast_type(typ);
+                self.match_fields(fields, value, field_types, bindings, location)
+            }
+            HirMatchPattern::Variant(variant, fields, _) => {
+                let (_, index) = self.interner.function_meta(&variant).enum_variant.unwrap();
+                let enum_types = unwrap_tuple_ast_type(typ);
+
+                let tag = ast::Expression::ExtractTupleField(Box::new(value.clone()), 0);
+                let expected = ast::Literal::Integer((index as u128).into(), ast::Type::Field);
+                let tag_matches = equals(tag, ast::Expression::Literal(expected));
+
+                let variant_value = ast::Expression::ExtractTupleField(Box::new(value), index + 1);
+                let field_types = unwrap_tuple_ast_type(&enum_types[index + 1]);
+                match self.match_fields(fields, variant_value, field_types, bindings, location) {
+                    Some(fields_match) => Some(Self::and(tag_matches, fields_match, location)),
+                    None => Some(tag_matches),
+                }
+            }
+        }
+    }
+
+    /// Returns the conjunction of the conditions for each field of `value` to match its pattern.
+    fn match_fields(
+        &mut self,
+        fields: Vec<HirMatchPattern>,
+        value: ast::Expression,
+        field_types: &[ast::Type],
+        bindings: &mut Vec<ast::Expression>,
+        location: Location,
+    ) -> Option<ast::Expression> {
+        let mut condition = None;
+        for (i, (field, field_type)) in fields.into_iter().zip(field_types).enumerate() {
+            let field_value = ast::Expression::ExtractTupleField(Box::new(value.clone()), i);
+            if let Some(field_matches) =
+                self.match_pattern(field, field_value, field_type, bindings, location)
+            {
+                condition = Some(match condition {
+                    Some(condition) => Self::and(condition, field_matches, location),
+                    None => field_matches,
+                });
+            }
+        }
+        condition
+    }
+
+    fn and(lhs: ast::Expression, rhs: ast::Expression, location: Location) -> ast::Expression {
+        let (lhs, rhs) = (Box::new(lhs), Box::new(rhs));
+        ast::Expression::Binary(ast::Binary { lhs, operator: BinaryOpKind::And, rhs, location })
+    }
+
+    fn block(&mut self, statement_ids: &[StmtId]) -> ast::Expression {
+        let mut statements = Vec::with_capacity(statement_ids.len());
+
+        for (i, id) in statement_ids.iter().enumerate() {
+            let exits_before = self.loop_exit_count();
+            statements.push(self.statement(*id));
+
+            // The rest of a block after a statement which may exit the innermost loop
+            // only runs if the loop was not exited.
+            let rest = &statement_ids[i + 1..];
+            if self.loop_exit_count() != exits_before && !rest.is_empty() {
+                let typ = self.block_type(rest);
+                let rest = self.block(rest);
+                statements.push(self.unless_loop_exited(rest, typ));
+                break;
+            }
+        }
+
+        ast::Expression::Block(statements)
+    }
+
+    /// The type of a block made of the given statements
+    fn block_type(&self, statement_ids: &[StmtId]) -> ast::Type {
+        match statement_ids.last().map(|id| self.interner.statement(id)) {
+            Some(HirStatement::Expression(expr)) => {
+                Self::convert_type(&self.interner.id_type(expr))
+            }
+            _ => ast::Type::Unit,
+        }
+    }
+
+    fn push_loop(&mut self) {
+        let broken = self.next_local_id();
+        let continued = self.next_local_id();
+        let exits =
+            LoopExits { broken, continued, uses_break: false, uses_continue: false, count: 0 };
+        self.loops.push(exits);
+    }
+
+    /// Finishes monomorphizing the body of the innermost loop. Each iteration declares a fresh
+    /// `continue` flag, and every iteration after a `break` is skipped entirely.
+    fn pop_loop(&mut self, body: ast::Expression) -> (ast::Expression, LoopExits) {
+        let exits = self.loops.pop().expect("Expected to be within a loop");
+        let mut body = body;
+
+        if exits.uses_continue {
+            body = ast::Expression::Block(vec![declare_flag(exits.continued, CONTINUE_FLAG), body]);
+        }
+
+        if exits.uses_break {
+            body = ast::Expression::If(ast::If {
+                condition: Box::new(not(flag(exits.broken, BREAK_FLAG))),
+                consequence: Box::new(ast::Expression::Semi(Box::new(body))),
+                alternative: None,
+                typ: ast::Type::Unit,
+            });
+        }
+
+        (body, exits)
+    }
+
+    fn loop_exit_count(&self) -> usize {
+        self.loops.last().map_or(0, |exits| exits.count)
+    }
+
+    /// `break` and `continue` set the corresponding flag of the innermost loop
+    fn exit_loop(&mut self, is_break: bool) -> ast::Expression {
+        let exits = self.loops.last_mut().expect("Expected break and continue to be within a loop");
+        exits.count += 1;
+
+        let (id, name) = if is_break {
+            exits.uses_break = true;
+            (exits.broken, BREAK_FLAG)
+        } else {
+            exits.uses_continue = true;
+            (exits.continued, CONTINUE_FLAG)
+        };
+
+        let lvalue = match flag(id, name) {
+            ast::Expression::Ident(ident) => ast::LValue::Ident(ident),
+            _ => unreachable!(),
+        };
+        let expression = Box::new(ast::Expression::Literal(ast::Literal::Bool(true)));
+        ast::Expression::Assign(ast::Assign { lvalue, expression })
+    }
+
+    /// Predicates `expression` on the innermost loop not having been exited during the current
+    /// iteration. If `expression` is not a unit, it is zeroed when the loop was exited.
+    fn unless_loop_exited(
+        &mut self,
+        expression: ast::Expression,
+        typ: ast::Type,
+    ) -> ast::Expression {
+        let exits = self.loops.last().expect("Expected to be within a loop");
+        let flags = [
+            (exits.uses_break, exits.broken, BREAK_FLAG),
+            (exits.uses_continue, exits.continued, CONTINUE_FLAG),
+        ];
+
+        let mut expression = expression;
+        for (_, id, name) in flags.into_iter().filter(|(used, ..)| *used) {
+            let alternative = if typ == ast::Type::Unit {
+                None
+            } else {
+                Some(Box::new(self.zeroed_value_of_type(&typ)))
+            };
+
+            expression = ast::Expression::If(ast::If {
+                condition: Box::new(not(flag(id, name))),
+                consequence: Box::new(expression),
+                alternative,
+                typ: typ.clone(),
+            });
+        }
+        expression
+    }
+
+    /// Lowers a while loop into a for loop over its maximum number of iterations,
+    /// which is exited through its `break` flag once the condition no longer holds:
+    /// `{ let mut $break = false; for _ in 0..max { if !$break { if cond { body } else { $break = true } } } }`
+    /// The loop is followed by `if !$break { assert(!cond) }`, failing if the condition still holds
+    /// after running the maximum number of iterations.
+    fn while_loop(&mut self, while_expr: HirWhileExpression) -> ast::Expression {
+        let location = self.interner.expr_location(&while_expr.condition);
+        let condition = self.expr(while_expr.condition);
+
+        self.push_loop();
+        let body = self.expr(while_expr.body);
+        let exit = self.exit_loop(true);
+        let body = ast::Expression::If(ast::If {
+            condition: Box::new(condition.clone()),
+            consequence: Box::new(ast::Expression::Semi(Box::new(body))),
+            alternative: Some(Box::new(exit)),
+            typ: ast::Type::Unit,
+        });
+        let (body, exits) = self.pop_loop(body);
+
+        let iteration = |value: u64| {
+            let value = FieldElement::from(u128::from(value));
+            Box::new(ast::Expression::Literal(ast::Literal::Integer(value, ast::Type::Field)))
+        };
+
+        let for_loop = ast::Expression::For(ast::For {
+            index_variable: self.next_local_id(),
+            index_name: "$i".to_owned(),
+            index_type: ast::Type::Field,
+            start_range: iteration(0),
+            end_range: iteration(while_expr.max_iterations),
+            block: Box::new(body),
+        });
+
+        // The condition is checked once more after the last iteration
+        let within_bound = ast::Expression::If(ast::If {
+            condition: Box::new(not(flag(exits.broken, BREAK_FLAG))),
+            consequence: Box::new(ast::Expression::Constrain(
+                Box::new(not(condition)),
+                location,
+                Some(format!(
+                    "while loop exceeded its maximum of {} iterations",
+                    while_expr.max_iterations
+                )),
+            )),
+            alternative: None,
+            typ: ast::Type::Unit,
+        });
+
+        ast::Expression::Block(vec![declare_flag(exits.broken, BREAK_FLAG), for_loop, within_bound])
     }
 
     fn unpack_pattern(
@@ -669,6 +1119,17 @@ impl<'interner> Monomorphizer<'interner> {
                 ast::Type::Field
             }
 
+            // An enum is the index of its variant followed by the fields of every variant,
+            // of which only those of the indexed variant are meaningful.
+            HirType::Struct(def, args) if def.borrow().is_enum() => {
+                let mut fields = vec![ast::Type::Field];
+                for (_, variant_fields) in def.borrow().get_variants(args) {
+                    let variant_fields = vecmap(variant_fields, |field| Self::convert_type(&field));
+                    fields.push(ast::Type::Tuple(variant_fields));
+                }
+                ast::Type::Tuple(fields)
+            }
+
             HirType::Struct(def, args) => {
                 let fields = def.borrow().get_fields(args);
                 let fields = vecmap(fields, |(_, field)| Self::convert_type(&field));
@@ -680,10 +1141,23 @@ impl<'interner> Monomorphizer<'interner> {
                 ast::Type::Tuple(fields)
             }
 
-            HirType::Function(args, ret) => {
-                let args = vecmap(args, Self::convert_type);
+            HirType::Function(args, ret, env) => {
+                let mut args = vecmap(args, Self::convert_type);
                 let ret = Box::new(Self::convert_type(ret));
-                ast::Type::Function(args, ret)
+
+                // An environment which is never constrained cannot have been captured
+                if let HirType::TypeVariable(binding) = env.follow_bindings() {
+                    *binding.borrow_mut() = TypeBinding::Bound(HirType::Unit);
+                }
+
+                // Closures are a tuple of their environment and a function taking it
+                match Self::convert_type(env) {
+                    ast::Type::Unit => ast::Type::Function(args, ret),
+                    env => {
+                        args.insert(0, env.clone());
+                        ast::Type::Tuple(vec![env, ast::Type::Function(args, ret)])
+                    }
+                }
             }
 
             HirType::Vec(element) => {
@@ -723,11 +1197,39 @@ impl<'interner> Monomorphizer<'interner> {
         id: node_interner::ExprId,
     ) -> ast::Expression {
         let func = Box::new(self.expr(call.func));
-        let arguments = vecmap(&call.arguments, |id| self.expr(*id));
+        let mut arguments = vecmap(&call.arguments, |id| self.expr(*id));
         let return_type = self.interner.id_type(id);
         let return_type = Self::convert_type(&return_type);
         let location = call.location;
 
+        // Calling a closure calls its function with its environment as the first argument
+        let func_type = Self::convert_type(&self.interner.id_type(call.func));
+        if let ast::Type::Tuple(_) = func_type {
+            let closure_id = self.next_local_id();
+            let name = "closure".to_owned();
+            let closure = ast::Expression::Ident(ast::Ident {
+                location: None,
+                definition: Definition::Local(closure_id),
+                mutable: false,
+                name: name.clone(),
+                typ: func_type,
+            });
+
+            let closure_let = ast::Expression::Let(ast::Let {
+                id: closure_id,
+                mutable: false,
+                name,
+                expression: func,
+            });
+
+            let env = ast::Expression::ExtractTupleField(Box::new(closure.clone()), 0);
+            let func = Box::new(ast::Expression::ExtractTupleField(Box::new(closure), 1));
+            arguments.insert(0, env);
+
+            let call = ast::Expression::Call(ast::Call { func, arguments, return_type, location });
+            return ast::Expression::Block(vec![closure_let, call]);
+        }
+
         self.try_evaluate_call(&func, &call.arguments, &return_type)
             .unwrap_or(ast::Expression::Call(ast::Call { func, arguments, return_type, location }))
     }
@@ -908,18 +1410,77 @@ impl<'interner> Monomorphizer<'interner> {
         }
     }
 
+    /// Lambdas are lowered into a new function. A closure, which is a lambda capturing
+    /// variables, is lowered into a tuple of its environment and a function which takes
+    /// the environment as its first parameter. The environment is the tuple of the values
+    /// of the captured variables when the closure is created.
     fn lambda(&mut self, lambda: HirLambda) -> ast::Expression {
         let ret_type = Self::convert_type(&lambda.return_type);
         let lambda_name = "lambda";
-        let parameter_types = vecmap(&lambda.parameters, |(_, typ)| Self::convert_type(typ));
+        let mut parameter_types = vecmap(&lambda.parameters, |(_, typ)| Self::convert_type(typ));
+
+        let captures = vecmap(&lambda.captures, |capture| {
+            self.local_ident(capture).expect("Expected captured variables to be locals")
+        });
+        let env_type = ast::Type::Tuple(vecmap(&captures, |capture| capture.typ.clone()));
+        let env_id = self.next_local_id();
+
+        let mut parameters = Vec::new();
+        if !captures.is_empty() {
+            parameters.push((env_id, false, "env".to_owned(), env_type.clone()));
+            parameter_types.insert(0, env_type.clone());
+        }
 
         // Manually convert to Parameters type so we can reuse the self.parameters method
-        let parameters = Parameters(vecmap(lambda.parameters, |(pattern, typ)| {
+        let lambda_parameters = Parameters(vecmap(lambda.parameters, |(pattern, typ)| {
             Param(pattern, typ, noirc_abi::AbiVisibility::Private)
         }));
 
-        let parameters = self.parameters(parameters);
+        parameters.append(&mut self.parameters(lambda_parameters));
+
+        // Within the body, each captured variable refers to a new local unpacked from the
+        // environment rather than to the variable of the enclosing function.
+        let mut statements = Vec::new();
+        let mut shadowed_locals = Vec::new();
+
+        for (index, (capture, captured_ident)) in lambda.captures.iter().zip(&captures).enumerate()
+        {
+            let env = ast::Expression::Ident(ast::Ident {
+                location: None,
+                definition: Definition::Local(env_id),
+                mutable: false,
+                name: "env".to_owned(),
+                typ: env_type.clone(),
+            });
+
+            let new_id = self.next_local_id();
+            statements.push(ast::Expression::Let(ast::Let {
+                id: new_id,
+                mutable: false,
+                name: captured_ident.name.clone(),
+                expression: Box::new(ast::Expression::ExtractTupleField(Box::new(env), index)),
+            }));
+
+            shadowed_locals.push((capture.id, self.locals.insert(capture.id, new_id)));
+        }
+
+        // The lambda is a separate function, so its body cannot exit loops around it
+        let loops = std::mem::take(&mut self.loops);
         let body = self.expr(lambda.body);
+        self.loops = loops;
+
+        for (definition_id, local_id) in shadowed_locals {
+            if let Some(local_id) = local_id {
+                self.define_local(definition_id, local_id);
+            }
+        }
+
+        let body = if statements.is_empty() {
+            body
+        } else {
+            statements.push(body);
+            ast::Expression::Block(statements)
+        };
 
         let id = self.next_function_id();
         let return_type = ret_type.clone();
@@ -932,13 +1493,20 @@ impl<'interner> Monomorphizer<'interner> {
         let typ = ast::Type::Function(parameter_types, Box::new(ret_type));
 
         let name = lambda_name.to_owned();
-        ast::Expression::Ident(ast::Ident {
+        let function = ast::Expression::Ident(ast::Ident {
             definition: Definition::Function(id),
             mutable: false,
             location: None,
             name,
             typ,
-        })
+        });
+
+        if captures.is_empty() {
+            function
+        } else {
+            let env = ast::Expression::Tuple(vecmap(captures, ast::Expression::Ident));
+            ast::Expression::Tuple(vec![env, function])
+        }
     }
 
     /// Implements std::unsafe::zeroed by returning an appropriate zeroed
@@ -1021,6 +1589,28 @@ fn unwrap_tuple_type(typ: &HirType) -> Vec<HirType> {
     }
 }
 
+fn flag(id: LocalId, name: &str) -> ast::Expression {
+    let definition = Definition::Local(id);
+    let (name, typ) = (name.to_owned(), ast::Type::Bool);
+    ast::Expression::Ident(ast::Ident { location: None, definition, mutable: true, name, typ })
+}
+
+fn declare_flag(id: LocalId, name: &str) -> ast::Expression {
+    let expression = Box::new(ast::Expression::Literal(ast::Literal::Bool(false)));
+    ast::Expression::Let(ast::Let { id, mutable: true, name: name.to_owned(), expression })
+}
+
+fn not(rhs: ast::Expression) -> ast::Expression {
+    ast::Expression::Unary(ast::Unary { operator: UnaryOp::Not, rhs: Box::new(rhs) })
+}
+
+fn unwrap_tuple_ast_type(typ: &ast::Type) -> &[ast::Type] {
+    match typ {
+        ast::Type::Tuple(fields) => fields,
+        other => unreachable!("unwrap_tuple_ast_type: expected tuple, found {other}"),
+    }
+}
+
 fn unwrap_struct_type(typ: &HirType) -> Vec<(String, HirType)> {
     match typ {
         HirType::Struct(def, args) => def.borrow().get_fields(args),
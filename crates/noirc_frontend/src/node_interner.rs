@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::rc::Rc;
 
 use arena::{Arena, Index};
 use fm::FileId;
@@ -7,17 +8,19 @@ use noirc_errors::{Location, Span, Spanned};
 
 use crate::ast::Ident;
 use crate::graph::CrateId;
-use crate::hir::def_collector::dc_crate::UnresolvedStruct;
+use crate::hir::def_collector::dc_crate::{UnresolvedEnum, UnresolvedStruct};
 use crate::hir::def_map::{LocalModuleId, ModuleId};
 use crate::hir::type_check::TypeCheckError;
 use crate::hir::StorageSlot;
 use crate::hir_def::stmt::HirLetStatement;
+use crate::hir_def::traits::{Trait, TraitConstraint, TraitImpl};
 use crate::hir_def::types::{StructType, Type};
 use crate::hir_def::{
-    expr::HirExpression,
+    expr::{HirExpression, HirIdent},
     function::{FuncMeta, HirFunction},
     stmt::HirStatement,
 };
+use crate::token::{Attribute, CustomAttribute};
 use crate::{Shared, TypeBinding, TypeBindings, TypeVariable, TypeVariableId};
 
 /// The node interner is the central storage location of all nodes in Noir's Hir (the
@@ -53,6 +56,13 @@ pub struct NodeInterner {
     // methods from impls to the type.
     structs: HashMap<StructId, Shared<StructType>>,
 
+    /// Each trait definition, by its id
+    traits: HashMap<TraitId, Trait>,
+
+    /// Every implementation of a trait, for any type.
+    /// These are searched by type rather than keyed since an impl may be generic.
+    trait_impls: Vec<TraitImpl>,
+
     /// Map from ExprId (referring to a Function/Method call) to its corresponding TypeBindings,
     /// filled out during type checking from instantiated variables. Used during monomorphization
     /// to map call site types back onto function parameter types, and undo this binding as needed.
@@ -73,6 +83,13 @@ pub struct NodeInterner {
 
     /// Methods on primitive types defined in the stdlib.
     primitive_methods: HashMap<(TypeMethodKey, String), FuncId>,
+
+    /// Each name resolved to a definition, including the names declaring them.
+    /// These are only used by tooling such as the language server.
+    references: Vec<HirIdent>,
+
+    /// The location of the name declaring each definition
+    definition_locations: HashMap<DefinitionId, Location>,
 }
 
 type TypeCheckFn = Box<dyn FnOnce() -> Result<(), TypeCheckError>>;
@@ -137,6 +154,18 @@ impl StructId {
     }
 }
 
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct TraitId(pub ModuleId);
+
+impl TraitId {
+    //dummy id for error reporting
+    // This can be anything, as the program will ultimately fail
+    // after resolution
+    pub fn dummy_id() -> TraitId {
+        TraitId(ModuleId { krate: CrateId::dummy_id(), local_id: LocalModuleId::dummy_id() })
+    }
+}
+
 macro_rules! into_index {
     ($id_type:ty) => {
         impl From<$id_type> for Index {
@@ -248,6 +277,8 @@ impl Default for NodeInterner {
             definitions: vec![],
             id_to_type: HashMap::new(),
             structs: HashMap::new(),
+            traits: HashMap::new(),
+            trait_impls: Vec::new(),
             instantiation_bindings: HashMap::new(),
             field_indices: HashMap::new(),
             next_type_variable_id: 0,
@@ -255,6 +286,8 @@ impl Default for NodeInterner {
             delayed_type_checks: vec![],
             struct_methods: HashMap::new(),
             primitive_methods: HashMap::new(),
+            references: vec![],
+            definition_locations: HashMap::new(),
         };
 
         // An empty block expression is used often, we add this into the `node` on startup
@@ -264,6 +297,11 @@ impl Default for NodeInterner {
     }
 }
 
+/// Spans of names end at their last character, so the end of a span is considered part of it
+fn contains(span: Span, position: u32) -> bool {
+    span.start() <= position && position <= span.end()
+}
+
 // XXX: Add check that insertions are not overwrites for maps
 // XXX: Maybe change push to intern, and remove comments
 impl NodeInterner {
@@ -292,14 +330,24 @@ impl NodeInterner {
     }
 
     pub fn push_empty_struct(&mut self, type_id: StructId, typ: &UnresolvedStruct) {
+        let definition = &typ.struct_def;
+        self.push_empty_type(type_id, &definition.name, definition.span, definition.generics.len());
+    }
+
+    pub fn push_empty_enum(&mut self, type_id: StructId, typ: &UnresolvedEnum) {
+        let definition = &typ.enum_def;
+        self.push_empty_type(type_id, &definition.name, definition.span, definition.generics.len());
+    }
+
+    fn push_empty_type(&mut self, type_id: StructId, name: &Ident, span: Span, generics: usize) {
         self.structs.insert(
             type_id,
             Shared::new(StructType::new(
                 type_id,
-                typ.struct_def.name.clone(),
-                typ.struct_def.span,
+                name.clone(),
+                span,
                 Vec::new(),
-                vecmap(&typ.struct_def.generics, |_| {
+                vecmap(0..generics, |_| {
                     // Temporary type variable ids before the struct is resolved to its actual ids.
                     // This lets us record how many arguments the type expects so that other types
                     // can refer to it with generic arguments before the generic parameters themselves
@@ -439,6 +487,18 @@ impl NodeInterner {
         self.definition_name(name_id)
     }
 
+    /// Returns the attributes of the given function in the order they were written
+    pub fn function_attributes(&self, func_id: &FuncId) -> &[Attribute] {
+        &self.func_meta.get(func_id).expect("ice: all function ids should have metadata").attributes
+    }
+
+    /// Returns the attributes of the given function which have no meaning to the compiler,
+    /// such as metadata for other tools to read
+    pub fn custom_attributes(&self, func_id: &FuncId) -> Vec<CustomAttribute> {
+        let meta = self.func_meta.get(func_id).expect("ice: all function ids should have metadata");
+        meta.custom_attributes().cloned().collect()
+    }
+
     /// Returns the interned statement corresponding to `stmt_id`
     pub fn statement(&self, stmt_id: &StmtId) -> HirStatement {
         let def =
@@ -490,6 +550,44 @@ impl NodeInterner {
         &self.definition(id).name
     }
 
+    /// Remembers the name declaring a definition
+    pub fn push_declaration(&mut self, ident: HirIdent) {
+        self.definition_locations.insert(ident.id, ident.location);
+        self.references.push(ident);
+    }
+
+    /// Remembers a name which was resolved to a definition
+    pub fn push_reference(&mut self, ident: HirIdent) {
+        self.references.push(ident);
+    }
+
+    /// Returns the location of the name declaring the given definition, if it has one
+    pub fn definition_location(&self, id: DefinitionId) -> Option<Location> {
+        self.definition_locations.get(&id).copied()
+    }
+
+    /// Returns the name at the given position of a file, along with the definition it refers to
+    pub fn find_reference(&self, file: FileId, position: u32) -> Option<HirIdent> {
+        self.references
+            .iter()
+            .filter(|ident| ident.location.file == file && contains(ident.location.span, position))
+            .min_by_key(|ident| ident.location.span.end() - ident.location.span.start())
+            .copied()
+    }
+
+    /// Returns the innermost expression containing the given position of a file
+    pub fn find_expression(&self, file: FileId, position: u32) -> Option<ExprId> {
+        self.id_to_location
+            .iter()
+            .filter(|(index, location)| {
+                location.file == file
+                    && contains(location.span, position)
+                    && matches!(self.nodes.get(**index), Some(Node::Expression(_)))
+            })
+            .min_by_key(|(_, location)| location.span.end() - location.span.start())
+            .map(|(index, _)| ExprId(*index))
+    }
+
     pub fn expr_span(&self, expr_id: &ExprId) -> Span {
         self.id_location(expr_id).span
     }
@@ -502,6 +600,14 @@ impl NodeInterner {
         self.structs[&id].clone()
     }
 
+    pub fn push_trait(&mut self, noir_trait: Trait) {
+        self.traits.insert(noir_trait.id, noir_trait);
+    }
+
+    pub fn get_trait(&self, id: TraitId) -> &Trait {
+        &self.traits[&id]
+    }
+
     pub fn get_global(&self, stmt_id: &StmtId) -> Option<GlobalInfo> {
         self.globals.get(stmt_id).cloned()
     }
@@ -604,6 +710,74 @@ impl NodeInterner {
         get_type_method_key(typ)
             .and_then(|key| self.primitive_methods.get(&(key, method_name.to_owned())).copied())
     }
+
+    /// Search by name for a method declared by the given trait
+    pub fn lookup_trait_method(&self, id: TraitId, method_name: &str) -> Option<FuncId> {
+        let methods = &self.traits[&id].methods;
+        methods.iter().find(|method| self.function_name(method) == method_name).copied()
+    }
+
+    /// Adds an implementation of a trait.
+    /// If it overlaps with an existing implementation of the same trait, the existing
+    /// implementation is returned and the new one is not added.
+    pub fn add_trait_impl(&mut self, trait_impl: TraitImpl) -> Option<&TraitImpl> {
+        let overlapping = self.trait_impls.iter().position(|existing| {
+            existing.trait_id == trait_impl.trait_id
+                && (existing.typ.matches_instance(&trait_impl.typ, &mut HashMap::new())
+                    || trait_impl.typ.matches_instance(&existing.typ, &mut HashMap::new()))
+        });
+
+        match overlapping {
+            Some(index) => Some(&self.trait_impls[index]),
+            None => {
+                self.trait_impls.push(trait_impl);
+                None
+            }
+        }
+    }
+
+    /// Finds the implementation of a trait for the given type, along with the type each
+    /// generic of the implementation stands for in it.
+    ///
+    /// The bounds of the implementation must be satisfied as well, either by implementations
+    /// of their own or by one of the `assumed` constraints, which are those of the generics
+    /// in scope where the trait is required.
+    pub fn lookup_trait_impl(
+        &self,
+        id: TraitId,
+        typ: &Type,
+        assumed: &[TraitConstraint],
+    ) -> Option<(&TraitImpl, HashMap<Rc<String>, Type>)> {
+        self.trait_impls.iter().filter(|trait_impl| trait_impl.trait_id == id).find_map(
+            |trait_impl| {
+                let mut generics = HashMap::new();
+                let satisfied = trait_impl.typ.matches_instance(typ, &mut generics)
+                    && trait_impl.where_clause.iter().all(|constraint| {
+                        let typ = constraint.typ.substitute_named_generics(&generics);
+                        self.implements_trait(constraint.trait_id, &typ, assumed)
+                    });
+                satisfied.then_some((trait_impl, generics))
+            },
+        )
+    }
+
+    /// True if `typ` implements the given trait, or is assumed to by one of the `assumed`
+    /// constraints.
+    pub fn implements_trait(&self, id: TraitId, typ: &Type, assumed: &[TraitConstraint]) -> bool {
+        let typ = typ.follow_bindings();
+        typ == Type::Error
+            || assumed.iter().any(|constraint| constraint.trait_id == id && constraint.typ == typ)
+            || self.lookup_trait_impl(id, &typ, assumed).is_some()
+    }
+
+    /// Searches the implementations of all traits for the given type for a method with the
+    /// given name. This is used for method calls on a type which aren't one of its own methods.
+    pub fn lookup_trait_impl_method(&self, typ: &Type, method_name: &str) -> Option<FuncId> {
+        self.trait_impls.iter().find_map(|trait_impl| {
+            let method = trait_impl.methods.get(method_name)?;
+            trait_impl.typ.matches_instance(typ, &mut HashMap::new()).then_some(*method)
+        })
+    }
 }
 
 /// These are the primitive type variants that we support adding methods to
@@ -633,7 +807,7 @@ fn get_type_method_key(typ: &Type) -> Option<TypeMethodKey> {
         Type::String(_) => Some(String),
         Type::Unit => Some(Unit),
         Type::Tuple(_) => Some(Tuple),
-        Type::Function(_, _) => Some(Function),
+        Type::Function(_, _, _) => Some(Function),
         Type::Vec(_) => Some(Vec),
 
         // We do not support adding methods to these types
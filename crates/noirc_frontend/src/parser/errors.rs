@@ -1,4 +1,4 @@
-use crate::lexer::token::Token;
+use crate::lexer::token::{Attribute, Token};
 use crate::Expression;
 use small_ord_set::SmallOrdSet;
 use thiserror::Error;
@@ -23,6 +23,14 @@ pub enum ParserErrorReason {
     InvalidArrayLengthExpression(Expression),
     #[error("Early 'return' is unsupported")]
     EarlyReturn,
+    #[error("while loops must be bounded by a #[max_iterations(N)] attribute")]
+    UnboundedWhileLoop,
+    #[error("The attribute {0} is not allowed here")]
+    MisplacedAttribute(Attribute),
+    #[error("Unknown attribute '{name}', did you mean '{suggestion}'?")]
+    UnknownAttribute { name: String, suggestion: &'static str },
+    #[error("The attribute {1} conflicts with the attribute {0}")]
+    ConflictingAttributes(Attribute, Attribute),
 }
 
 /// Represents a parsing error, or a parsing error in the making.
@@ -105,6 +113,11 @@ impl From<ParserError> for Diagnostic {
                         "The 'constrain' keyword has been deprecated. Please use the 'assert' function instead.".into(),
                         error.span,
                     ),
+                    ParserErrorReason::UnknownAttribute { .. } => Diagnostic::simple_warning(
+                        format!("{reason}"),
+                        "This attribute is kept as a custom attribute".into(),
+                        error.span,
+                    ),
                     other => {
 
                         Diagnostic::simple_error(format!("{other}"), String::new(), error.span)
@@ -14,11 +14,11 @@ mod parser;
 use std::sync::atomic::{AtomicU32, Ordering};
 
 use crate::token::{Keyword, Token};
-use crate::{ast::ImportStatement, Expression, NoirStruct};
+use crate::{ast::ImportStatement, Expression, NoirEnum, NoirStruct};
 use crate::{
-    BlockExpression, ExpressionKind, ForExpression, Ident, IndexExpression, LetStatement,
-    MethodCallExpression, NoirFunction, NoirImpl, Path, PathKind, Pattern, Recoverable, Statement,
-    UnresolvedType,
+    BlockExpression, ExpressionKind, ForExpression, Ident, IndexExpression, ItemVisibility,
+    LetStatement, MethodCallExpression, NoirFunction, NoirImpl, NoirTrait, NoirTraitImpl, Path,
+    PathKind, Pattern, Recoverable, Statement, UnresolvedType,
 };
 
 use acvm::FieldElement;
@@ -40,9 +40,12 @@ pub(crate) enum TopLevelStatement {
     Module(Ident),
     Import(ImportStatement),
     Struct(NoirStruct),
+    Enum(NoirEnum),
     Impl(NoirImpl),
+    Trait(NoirTrait),
+    TraitImpl(NoirTraitImpl),
     SubModule(SubModule),
-    Global(LetStatement),
+    Global(LetStatement, ItemVisibility),
     Error,
 }
 
@@ -220,8 +223,11 @@ pub struct ParsedModule {
     pub imports: Vec<ImportStatement>,
     pub functions: Vec<NoirFunction>,
     pub types: Vec<NoirStruct>,
+    pub enums: Vec<NoirEnum>,
     pub impls: Vec<NoirImpl>,
-    pub globals: Vec<LetStatement>,
+    pub traits: Vec<NoirTrait>,
+    pub trait_impls: Vec<NoirTraitImpl>,
+    pub globals: Vec<(LetStatement, ItemVisibility)>,
 
     /// Module declarations like `mod foo;`
     pub module_decls: Vec<Ident>,
@@ -248,10 +254,22 @@ impl ParsedModule {
         self.types.push(typ);
     }
 
+    fn push_enum(&mut self, noir_enum: NoirEnum) {
+        self.enums.push(noir_enum);
+    }
+
     fn push_impl(&mut self, r#impl: NoirImpl) {
         self.impls.push(r#impl);
     }
 
+    fn push_trait(&mut self, noir_trait: NoirTrait) {
+        self.traits.push(noir_trait);
+    }
+
+    fn push_trait_impl(&mut self, trait_impl: NoirTraitImpl) {
+        self.trait_impls.push(trait_impl);
+    }
+
     fn push_import(&mut self, import_stmt: ImportStatement) {
         self.imports.push(import_stmt);
     }
@@ -264,8 +282,8 @@ impl ParsedModule {
         self.submodules.push(submodule);
     }
 
-    fn push_global(&mut self, global: LetStatement) {
-        self.globals.push(global);
+    fn push_global(&mut self, global: LetStatement, visibility: ItemVisibility) {
+        self.globals.push((global, visibility));
     }
 }
 
@@ -448,9 +466,12 @@ impl std::fmt::Display for TopLevelStatement {
             TopLevelStatement::Module(m) => write!(f, "mod {m}"),
             TopLevelStatement::Import(i) => i.fmt(f),
             TopLevelStatement::Struct(s) => s.fmt(f),
+            TopLevelStatement::Enum(e) => e.fmt(f),
             TopLevelStatement::Impl(i) => i.fmt(f),
+            TopLevelStatement::Trait(t) => t.fmt(f),
+            TopLevelStatement::TraitImpl(i) => i.fmt(f),
             TopLevelStatement::SubModule(s) => s.fmt(f),
-            TopLevelStatement::Global(c) => c.fmt(f),
+            TopLevelStatement::Global(c, visibility) => write!(f, "{visibility}{c}"),
             TopLevelStatement::Error => write!(f, "error"),
         }
     }
@@ -466,14 +487,22 @@ impl std::fmt::Display for ParsedModule {
             write!(f, "{import}")?;
         }
 
-        for global_const in &self.globals {
-            write!(f, "{global_const}")?;
+        for (global_const, visibility) in &self.globals {
+            write!(f, "{visibility}{global_const}")?;
         }
 
         for type_ in &self.types {
             write!(f, "{type_}")?;
         }
 
+        for noir_enum in &self.enums {
+            write!(f, "{noir_enum}")?;
+        }
+
+        for noir_trait in &self.traits {
+            write!(f, "{noir_trait}")?;
+        }
+
         for function in &self.functions {
             write!(f, "{function}")?;
         }
@@ -482,6 +511,10 @@ impl std::fmt::Display for ParsedModule {
             write!(f, "{impl_}")?;
         }
 
+        for trait_impl in &self.trait_impls {
+            write!(f, "{trait_impl}")?;
+        }
+
         for submodule in &self.submodules {
             write!(f, "{submodule}")?;
         }
@@ -35,8 +35,10 @@ use crate::parser::{force, ignore_then_commit, statement_recovery};
 use crate::token::{Attribute, Keyword, Token, TokenKind};
 use crate::{
     BinaryOp, BinaryOpKind, BlockExpression, CompTime, ConstrainStatement, FunctionDefinition,
-    Ident, IfExpression, ImportStatement, InfixExpression, LValue, Lambda, NoirFunction, NoirImpl,
-    NoirStruct, Path, PathKind, Pattern, Recoverable, UnaryOp, UnresolvedTypeExpression,
+    Ident, IfExpression, ImportStatement, InfixExpression, ItemVisibility, LValue, Lambda,
+    MatchExpression, MatchPattern, NoirEnum, NoirFunction, NoirImpl, NoirStruct, NoirTrait,
+    NoirTraitImpl, Path, PathKind, Pattern, Recoverable, TraitFunction, UnaryOp,
+    UnresolvedGenerics, UnresolvedTraitConstraint, UnresolvedTypeExpression, WhileExpression,
 };
 
 use chumsky::prelude::*;
@@ -78,9 +80,12 @@ fn module() -> impl NoirParser<ParsedModule> {
                     TopLevelStatement::Module(m) => program.push_module_decl(m),
                     TopLevelStatement::Import(i) => program.push_import(i),
                     TopLevelStatement::Struct(s) => program.push_type(s),
+                    TopLevelStatement::Enum(e) => program.push_enum(e),
                     TopLevelStatement::Impl(i) => program.push_impl(i),
+                    TopLevelStatement::Trait(t) => program.push_trait(t),
+                    TopLevelStatement::TraitImpl(i) => program.push_trait_impl(i),
                     TopLevelStatement::SubModule(s) => program.push_submodule(s),
-                    TopLevelStatement::Global(c) => program.push_global(c),
+                    TopLevelStatement::Global(c, visibility) => program.push_global(c, visibility),
                     TopLevelStatement::Error => (),
                 }
                 program
@@ -90,6 +95,7 @@ fn module() -> impl NoirParser<ParsedModule> {
 
 /// top_level_statement: function_definition
 ///                    | struct_definition
+///                    | enum_definition
 ///                    | implementation
 ///                    | submodule
 ///                    | module_declaration
@@ -101,6 +107,9 @@ fn top_level_statement(
     choice((
         function_definition(false).map(TopLevelStatement::Function),
         struct_definition(),
+        enum_definition(),
+        trait_definition(),
+        trait_implementation(),
         implementation(),
         submodule(module_parser.clone()),
         contract(module_parser),
@@ -111,7 +120,7 @@ fn top_level_statement(
     .recover_via(top_level_statement_recovery())
 }
 
-/// global_declaration: 'global' ident global_type_annotation '=' literal
+/// global_declaration: item_visibility 'global' ident global_type_annotation '=' literal
 fn global_declaration() -> impl NoirParser<TopLevelStatement> {
     let p = ignore_then_commit(
         keyword(Keyword::Global).labelled(ParsingRuleLabel::Global),
@@ -120,7 +129,10 @@ fn global_declaration() -> impl NoirParser<TopLevelStatement> {
     let p = then_commit(p, global_type_annotation());
     let p = then_commit_ignore(p, just(Token::Assign));
     let p = then_commit(p, literal_or_collection(expression()).map_with_span(Expression::new));
-    p.map(LetStatement::new_let).map(TopLevelStatement::Global)
+    let p = p.map(LetStatement::new_let);
+    item_visibility()
+        .then(p)
+        .map(|(visibility, global)| TopLevelStatement::Global(global, visibility))
 }
 
 /// submodule: 'mod' ident '{' module '}'
@@ -147,22 +159,27 @@ fn contract(module_parser: impl NoirParser<ParsedModule>) -> impl NoirParser<Top
         })
 }
 
-/// function_definition: attribute function_modifiers 'fn' ident generics '(' function_parameters ')' function_return_type block
-///                      function_modifiers 'fn' ident generics '(' function_parameters ')' function_return_type block
+/// function_definition: attributes item_visibility function_modifiers 'fn' ident generics '(' function_parameters ')' function_return_type block
 fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
-    attribute()
-        .or_not()
+    function_attributes()
+        .then(item_visibility())
         .then(function_modifiers())
         .then_ignore(keyword(Keyword::Fn))
         .then(ident())
-        .then(generics())
+        .then(generics_with_constraints())
         .then(parenthesized(function_parameters(allow_self)))
         .then(function_return_type())
         .then(block(expression()))
         .map(
             |(
                 (
-                    ((((attribute, (is_unconstrained, is_open)), name), generics), parameters),
+                    (
+                        (
+                            (((attributes, visibility), (is_unconstrained, is_open)), name),
+                            (generics, constraints),
+                        ),
+                        parameters,
+                    ),
                     ((return_distinctness, return_visibility), return_type),
                 ),
                 body,
@@ -170,10 +187,12 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
                 FunctionDefinition {
                     span: name.0.span(),
                     name,
-                    attribute, // XXX: Currently we only have one attribute defined. If more attributes are needed per function, we can make this a vector and make attribute definition more expressive
+                    visibility,
+                    attributes,
                     is_open,
                     is_unconstrained,
                     generics,
+                    trait_constraints: constraints,
                     parameters,
                     body,
                     return_type,
@@ -185,6 +204,22 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
         )
 }
 
+/// item_visibility: 'pub' '(' 'crate' ')'
+///                | 'pub'
+///                | %empty
+fn item_visibility() -> impl NoirParser<ItemVisibility> {
+    let crate_only =
+        keyword(Keyword::Crate).delimited_by(just(Token::LeftParen), just(Token::RightParen));
+
+    keyword(Keyword::Pub).ignore_then(crate_only.or_not()).or_not().map(|visibility| {
+        match visibility {
+            None => ItemVisibility::Private,
+            Some(None) => ItemVisibility::Public,
+            Some(Some(_)) => ItemVisibility::PublicCrate,
+        }
+    })
+}
+
 /// function_modifiers: 'unconstrained' 'open' | 'unconstrained' | 'open' | %empty
 ///
 /// returns (is_unconstrained, is_open) for whether each keyword was present
@@ -210,6 +245,45 @@ fn generics() -> impl NoirParser<Vec<Ident>> {
         .map(|opt| opt.unwrap_or_default())
 }
 
+/// generic: ident
+///        | ident ':' trait_bounds
+///
+/// generics_with_constraints: '<' generic (',' generic)* '>'
+///                          | %empty
+///
+/// Like `generics`, but each generic may also be bounded by traits.
+/// Returns the generics along with a constraint for each of their bounds.
+fn generics_with_constraints(
+) -> impl NoirParser<(UnresolvedGenerics, Vec<UnresolvedTraitConstraint>)> {
+    let bounds = just(Token::Colon).ignore_then(trait_bounds()).or_not();
+
+    ident()
+        .then(bounds.map(|bounds| bounds.unwrap_or_default()))
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+        .at_least(1)
+        .delimited_by(just(Token::Less), just(Token::Greater))
+        .or_not()
+        .map(|generics| {
+            let mut constraints = Vec::new();
+            let generics = vecmap(generics.unwrap_or_default(), |(generic, bounds)| {
+                for trait_name in bounds {
+                    let typ = UnresolvedType::Named(Path::from_ident(generic.clone()), vec![]);
+                    constraints.push(UnresolvedTraitConstraint { typ, trait_name });
+                }
+                generic
+            });
+            (generics, constraints)
+        })
+}
+
+/// trait_bounds: path '+' trait_bounds
+///             | path
+fn trait_bounds() -> impl NoirParser<Vec<Path>> {
+    path().separated_by(just(Token::Plus)).at_least(1)
+}
+
+/// struct_definition: item_visibility 'struct' ident generics '{' struct_fields '}'
 fn struct_definition() -> impl NoirParser<TopLevelStatement> {
     use self::Keyword::Struct;
     use Token::*;
@@ -223,11 +297,54 @@ fn struct_definition() -> impl NoirParser<TopLevelStatement> {
         ),
     );
 
-    keyword(Struct).ignore_then(ident()).then(generics()).then(fields).map_with_span(
-        |((name, generics), fields), span| {
-            TopLevelStatement::Struct(NoirStruct { name, generics, fields, span })
-        },
-    )
+    item_visibility()
+        .then_ignore(keyword(Struct))
+        .then(ident())
+        .then(generics())
+        .then(fields)
+        .map_with_span(|(((visibility, name), generics), fields), span| {
+            TopLevelStatement::Struct(NoirStruct { name, visibility, generics, fields, span })
+        })
+}
+
+/// enum_definition: item_visibility 'enum' ident generics '{' enum_variants '}'
+///
+/// enum_variants: enum_variant ',' enum_variants
+///              | enum_variant
+///              | %empty
+///
+/// enum_variant: ident '(' type (',' type)* ')'
+///             | ident
+fn enum_definition() -> impl NoirParser<TopLevelStatement> {
+    use Token::*;
+
+    let variant_fields = parse_type()
+        .separated_by(just(Comma))
+        .allow_trailing()
+        .delimited_by(just(LeftParen), just(RightParen))
+        .or_not()
+        .map(Option::unwrap_or_default);
+
+    let variants = ident()
+        .then(variant_fields)
+        .separated_by(just(Comma))
+        .allow_trailing()
+        .delimited_by(just(LeftBrace), just(RightBrace))
+        .recover_with(nested_delimiters(
+            LeftBrace,
+            RightBrace,
+            [(LeftParen, RightParen), (LeftBracket, RightBracket)],
+            |_| vec![],
+        ));
+
+    item_visibility()
+        .then_ignore(keyword(Keyword::Enum))
+        .then(ident())
+        .then(generics())
+        .then(variants)
+        .map_with_span(|(((visibility, name), generics), variants), span| {
+            TopLevelStatement::Enum(NoirEnum { name, visibility, generics, variants, span })
+        })
 }
 
 fn lambda_return_type() -> impl NoirParser<UnresolvedType> {
@@ -258,10 +375,54 @@ fn attribute() -> impl NoirParser<Attribute> {
     })
 }
 
-fn struct_fields() -> impl NoirParser<Vec<(Ident, UnresolvedType)>> {
-    ident()
+/// attributes: attribute attributes
+///           | %empty
+///
+/// The attributes of a function. Attributes meant for loops are reported and ignored, and
+/// custom attributes which look like a misspelled known attribute are warned about.
+/// A function can only be given one implementation by `#[builtin]` or `#[foreign]`.
+fn function_attributes() -> impl NoirParser<Vec<Attribute>> {
+    attribute()
+        .validate(|attribute, span, emit| match attribute {
+            Attribute::MaxIterations(_) => {
+                let reason = ParserErrorReason::MisplacedAttribute(attribute);
+                emit(ParserError::with_reason(reason, span));
+                None
+            }
+            Attribute::Custom(custom) => {
+                if let Some(suggestion) = custom.misspelled_attribute() {
+                    let name = custom.name.clone();
+                    let reason = ParserErrorReason::UnknownAttribute { name, suggestion };
+                    emit(ParserError::with_reason(reason, span));
+                }
+                Some(Attribute::Custom(custom))
+            }
+            attribute => Some(attribute),
+        })
+        .repeated()
+        .validate(|attributes, span, emit| {
+            let attributes: Vec<_> = attributes.into_iter().flatten().collect();
+            let mut implementations = attributes.iter().filter(|attribute| {
+                matches!(attribute, Attribute::Builtin(_) | Attribute::Foreign(_))
+            });
+            if let (Some(first), Some(second)) = (implementations.next(), implementations.next()) {
+                let reason =
+                    ParserErrorReason::ConflictingAttributes(first.clone(), second.clone());
+                emit(ParserError::with_reason(reason, span));
+            }
+            attributes
+        })
+}
+
+/// struct_fields: item_visibility ident ':' type ',' struct_fields
+///              | item_visibility ident ':' type
+///              | %empty
+fn struct_fields() -> impl NoirParser<Vec<(Ident, UnresolvedType, ItemVisibility)>> {
+    item_visibility()
+        .then(ident())
         .then_ignore(just(Token::Colon))
         .then(parse_type())
+        .map(|((visibility, name), typ)| (name, typ, visibility))
         .separated_by(just(Token::Comma))
         .allow_trailing()
 }
@@ -321,16 +482,92 @@ fn self_parameter() -> impl NoirParser<(Pattern, UnresolvedType, AbiVisibility)>
 
 fn implementation() -> impl NoirParser<TopLevelStatement> {
     keyword(Keyword::Impl)
-        .ignore_then(generics())
+        .ignore_then(generics_with_constraints())
         .then(parse_type().map_with_span(|typ, span| (typ, span)))
         .then_ignore(just(Token::LeftBrace))
         .then(function_definition(true).repeated())
         .then_ignore(just(Token::RightBrace))
-        .map(|((generics, (object_type, type_span)), methods)| {
-            TopLevelStatement::Impl(NoirImpl { generics, object_type, type_span, methods })
+        .map(|(((generics, trait_constraints), (object_type, type_span)), methods)| {
+            TopLevelStatement::Impl(NoirImpl {
+                generics,
+                trait_constraints,
+                object_type,
+                type_span,
+                methods,
+            })
+        })
+}
+
+/// trait_definition: item_visibility 'trait' ident '{' trait_function* '}'
+fn trait_definition() -> impl NoirParser<TopLevelStatement> {
+    item_visibility()
+        .then_ignore(keyword(Keyword::Trait))
+        .then(ident())
+        .then_ignore(just(Token::LeftBrace))
+        .then(trait_function().repeated())
+        .then_ignore(just(Token::RightBrace))
+        .map_with_span(|((visibility, name), methods), span| {
+            TopLevelStatement::Trait(NoirTrait { name, visibility, methods, span })
         })
 }
 
+/// trait_function: 'fn' ident '(' trait_function_parameters ')' ('->' type)? ';'
+fn trait_function() -> impl NoirParser<TraitFunction> {
+    let return_type = just(Token::Arrow).ignore_then(parse_type()).or_not();
+
+    keyword(Keyword::Fn)
+        .ignore_then(ident())
+        .then(parenthesized(trait_function_parameters()))
+        .then(return_type.map(|typ| typ.unwrap_or(UnresolvedType::Unit)))
+        .then_ignore(just(Token::Semicolon))
+        .map_with_span(|((name, parameters), return_type), span| TraitFunction {
+            name,
+            parameters,
+            return_type,
+            span,
+        })
+}
+
+/// Trait methods have no bodies, so their parameters are only names rather than patterns
+fn trait_function_parameters() -> impl NoirParser<Vec<(Ident, UnresolvedType)>> {
+    let full_parameter = ident().then_ignore(just(Token::Colon)).then(parse_type());
+
+    let self_parameter = self_parameter().map(|(pattern, typ, _)| match pattern {
+        Pattern::Identifier(ident) => (ident, typ),
+        _ => unreachable!("self parameters are always identifiers"),
+    });
+
+    full_parameter
+        .or(self_parameter)
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+        .labelled(ParsingRuleLabel::Parameter)
+}
+
+/// trait_implementation: 'impl' generics path 'for' type '{' function_definition* '}'
+fn trait_implementation() -> impl NoirParser<TopLevelStatement> {
+    keyword(Keyword::Impl)
+        .ignore_then(generics_with_constraints())
+        .then(path())
+        .then_ignore(keyword(Keyword::For))
+        .then(parse_type().map_with_span(|typ, span| (typ, span)))
+        .then_ignore(just(Token::LeftBrace))
+        .then(function_definition(true).repeated())
+        .then_ignore(just(Token::RightBrace))
+        .map(
+            |((((generics, trait_constraints), trait_name), (object_type, type_span)), methods)| {
+                TopLevelStatement::TraitImpl(NoirTraitImpl {
+                    trait_name,
+                    object_type,
+                    type_span,
+                    generics,
+                    trait_constraints,
+                    methods,
+                })
+            },
+        )
+}
+
 fn block_expr<'a, P>(expr_parser: P) -> impl NoirParser<Expression> + 'a
 where
     P: ExprParser + 'a,
@@ -395,13 +632,15 @@ fn module_declaration() -> impl NoirParser<TopLevelStatement> {
     keyword(Keyword::Mod).ignore_then(ident()).map(TopLevelStatement::Module)
 }
 
+/// use_statement: item_visibility 'use' path ('as' ident)?
 fn use_statement() -> impl NoirParser<TopLevelStatement> {
     let rename = ignore_then_commit(keyword(Keyword::As), ident()).or_not();
 
-    keyword(Keyword::Use)
-        .ignore_then(path())
-        .then(rename)
-        .map(|(path, alias)| TopLevelStatement::Import(ImportStatement { path, alias }))
+    item_visibility().then_ignore(keyword(Keyword::Use)).then(path()).then(rename).map(
+        |((visibility, path), alias)| {
+            TopLevelStatement::Import(ImportStatement { path, alias, visibility })
+        },
+    )
 }
 
 fn keyword(keyword: Keyword) -> impl NoirParser<Token> {
@@ -450,10 +689,20 @@ where
         declaration(expr_parser.clone()),
         assignment(expr_parser.clone()),
         return_statement(expr_parser.clone()),
+        loop_control(),
         expr_parser.map(Statement::Expression),
     ))
 }
 
+/// loop_control: 'break' | 'continue'
+fn loop_control() -> impl NoirParser<Statement> {
+    let break_statement = keyword(Keyword::Break).map_with_span(|_, span| Statement::Break(span));
+    let continue_statement =
+        keyword(Keyword::Continue).map_with_span(|_, span| Statement::Continue(span));
+
+    break_statement.or(continue_statement).labelled(ParsingRuleLabel::Statement)
+}
+
 fn constrain<'a, P>(expr_parser: P) -> impl NoirParser<Statement> + 'a
 where
     P: ExprParser + 'a,
@@ -462,7 +711,7 @@ where
         keyword(Keyword::Constrain).labelled(ParsingRuleLabel::Statement),
         expr_parser,
     )
-    .map(|expr| Statement::Constrain(ConstrainStatement(expr)))
+    .map(|expr| Statement::Constrain(ConstrainStatement(expr, None)))
     .validate(|expr, span, emit| {
         emit(ParserError::with_reason(ParserErrorReason::ConstrainDeprecated, span));
         expr
@@ -473,9 +722,20 @@ fn assertion<'a, P>(expr_parser: P) -> impl NoirParser<Statement> + 'a
 where
     P: ExprParser + 'a,
 {
-    ignore_then_commit(keyword(Keyword::Assert), parenthesized(expr_parser))
+    let message = just(Token::Comma).ignore_then(string_literal()).or_not();
+    let arguments =
+        expr_parser.then(message).map(|(expr, message)| ConstrainStatement(expr, message));
+
+    ignore_then_commit(keyword(Keyword::Assert), parenthesized(arguments))
         .labelled(ParsingRuleLabel::Statement)
-        .map(|expr| Statement::Constrain(ConstrainStatement(expr)))
+        .map(Statement::Constrain)
+}
+
+fn string_literal() -> impl NoirParser<String> {
+    filter_map(|span, found: Token| match found {
+        Token::Str(message) => Ok(message),
+        _ => Err(ParserError::expected_label(ParsingRuleLabel::String, found, span)),
+    })
 }
 
 fn declaration<'a, P>(expr_parser: P) -> impl NoirParser<Statement> + 'a
@@ -520,6 +780,46 @@ fn pattern() -> impl NoirParser<Pattern> {
     .labelled(ParsingRuleLabel::Pattern)
 }
 
+/// match_pattern: '_'
+///              | literal
+///              | '(' match_pattern (',' match_pattern)* ')'
+///              | path '(' match_pattern (',' match_pattern)* ')'
+///              | path
+///
+/// A path of a single identifier is a variable binding unless it names an enum variant,
+/// which is decided during name resolution.
+fn match_pattern() -> impl NoirParser<MatchPattern> {
+    recursive(|pattern| {
+        let wildcard =
+            just(Token::Underscore).map_with_span(|_, span| MatchPattern::Wildcard(span));
+
+        let literal = filter_map(|span, found: Token| match found {
+            Token::Int(value) => Ok(MatchPattern::Integer(value, span)),
+            Token::Bool(value) => Ok(MatchPattern::Bool(value, span)),
+            _ => Err(ParserError::expected_label(ParsingRuleLabel::Pattern, found, span)),
+        });
+
+        let fields = pattern
+            .separated_by(just(Token::Comma))
+            .allow_trailing()
+            .delimited_by(just(Token::LeftParen), just(Token::RightParen));
+
+        let tuple_pattern = fields.clone().map_with_span(MatchPattern::Tuple);
+
+        let variant_pattern =
+            path().then(fields.or_not()).map_with_span(|(path, fields), span| match fields {
+                Some(fields) => MatchPattern::Variant(path, fields, span),
+                None if path.segments.len() == 1 && path.kind == PathKind::Plain => {
+                    MatchPattern::Identifier(path.segments.into_iter().next().unwrap())
+                }
+                None => MatchPattern::Variant(path, Vec::new(), span),
+            });
+
+        choice((wildcard, literal, tuple_pattern, variant_pattern))
+    })
+    .labelled(ParsingRuleLabel::Pattern)
+}
+
 fn assignment<'a, P>(expr_parser: P) -> impl NoirParser<Statement> + 'a
 where
     P: ExprParser + 'a,
@@ -703,11 +1003,20 @@ where
     T: NoirParser<UnresolvedType>,
 {
     let args = parenthesized(type_parser.clone().separated_by(just(Token::Comma)).allow_trailing());
+
+    // The type of the environment captured by a closure, as in `fn[Env](Field) -> Field`
+    let env = type_parser
+        .clone()
+        .delimited_by(just(Token::LeftBracket), just(Token::RightBracket))
+        .or_not()
+        .map(|env| env.unwrap_or(UnresolvedType::Unit));
+
     keyword(Keyword::Fn)
-        .ignore_then(args)
+        .ignore_then(env)
+        .then(args)
         .then_ignore(just(Token::Arrow))
         .then(type_parser)
-        .map(|(args, ret)| UnresolvedType::Function(args, Box::new(ret)))
+        .map(|((env, args), ret)| UnresolvedType::Function(args, Box::new(ret), Box::new(env)))
 }
 
 fn expression() -> impl ExprParser {
@@ -881,6 +1190,27 @@ where
     })
 }
 
+/// match_expr: 'match' expression '{' match_rules '}'
+///
+/// match_rules: match_pattern '=>' expression ',' match_rules
+///            | match_pattern '=>' expression
+///            | %empty
+fn match_expr<'a, P>(expr_parser: P) -> impl NoirParser<ExpressionKind> + 'a
+where
+    P: ExprParser + 'a,
+{
+    let rule = match_pattern().then_ignore(just(Token::FatArrow)).then(expr_parser.clone());
+
+    let rules = rule
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+        .delimited_by(just(Token::LeftBrace), just(Token::RightBrace));
+
+    keyword(Keyword::Match).ignore_then(expr_parser).then(rules).map(|(expression, rules)| {
+        ExpressionKind::Match(Box::new(MatchExpression { expression, rules }))
+    })
+}
+
 fn lambda<'a>(
     expr_parser: impl NoirParser<Expression> + 'a,
 ) -> impl NoirParser<ExpressionKind> + 'a {
@@ -905,6 +1235,35 @@ where
         .map_with_span(|((identifier, range), block), span| range.into_for(identifier, block, span))
 }
 
+/// while_expr: attribute 'while' expression block
+///
+/// The attribute must be `#[max_iterations(N)]`, bounding how many times the loop may run.
+fn while_expr<'a, P>(expr_parser: P) -> impl NoirParser<ExpressionKind> + 'a
+where
+    P: ExprParser + 'a,
+{
+    attribute()
+        .or_not()
+        .then_ignore(keyword(Keyword::While))
+        .then(expr_parser.clone())
+        .then(block_expr(expr_parser))
+        .validate(|((attribute, condition), body), span, emit| {
+            let max_iterations = match attribute {
+                Some(Attribute::MaxIterations(max_iterations)) => max_iterations,
+                Some(attribute) => {
+                    let reason = ParserErrorReason::MisplacedAttribute(attribute);
+                    emit(ParserError::with_reason(reason, span));
+                    0
+                }
+                None => {
+                    emit(ParserError::with_reason(ParserErrorReason::UnboundedWhileLoop, span));
+                    0
+                }
+            };
+            ExpressionKind::While(Box::new(WhileExpression { condition, body, max_iterations }))
+        })
+}
+
 /// The 'range' of a for loop. Either an actual range `start .. end` or an array expression.
 fn for_range<P>(expr_parser: P) -> impl NoirParser<ForRange>
 where
@@ -981,7 +1340,9 @@ where
 {
     choice((
         if_expr(expr_parser.clone()),
+        match_expr(expr_parser.clone()),
         for_expr(expr_parser.clone()),
+        while_expr(expr_parser.clone()),
         array_expr(expr_parser.clone()),
         constructor(expr_parser.clone()),
         lambda(expr_parser.clone()),
@@ -1301,6 +1662,8 @@ mod test {
     #[test]
     fn parse_assert() {
         parse_with(assertion(expression()), "assert(x == y)").unwrap();
+        parse_with(assertion(expression()), "assert(x == y, \"x must equal y\")").unwrap();
+        parse_with(assertion(expression()), "assert(x == y, 3)").unwrap_err();
 
         // Currently we disallow constrain statements where the outer infix operator
         // produces a value. This would require an implicit `==` which
@@ -1372,6 +1735,27 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_while_loop() {
+        parse_all(
+            while_expr(expression()),
+            vec![
+                "#[max_iterations(10)] while x < 10 { x += 1; }",
+                "#[max_iterations(3)] while true { if done { break; } continue; }",
+            ],
+        );
+
+        parse_all_failing(
+            while_expr(expression()),
+            vec![
+                "while x < 10 { x += 1; }",                // A bound is required
+                "#[test] while x < 10 { x += 1; }",        // Only max_iterations bounds a loop
+                "#[max_iterations(10)] while { x += 1; }", // The condition is missing
+            ],
+        );
+        parse_all_failing(function_definition(false), vec!["#[max_iterations(2)] fn foo() {}"]);
+    }
+
     #[test]
     fn parse_function() {
         parse_all(
@@ -1534,6 +1918,135 @@ mod test {
         parse_all_failing(struct_definition(), failing);
     }
 
+    #[test]
+    fn parse_item_visibility() {
+        let cases = vec![
+            ("fn foo() {}", ItemVisibility::Private),
+            ("pub fn foo() {}", ItemVisibility::Public),
+            ("#[test] pub(crate) unconstrained fn foo() {}", ItemVisibility::PublicCrate),
+        ];
+        for (src, expected) in cases {
+            let function = parse_with(function_definition(false), src).unwrap();
+            assert_eq!(function.def.visibility, expected);
+        }
+
+        let src = "pub struct Foo { pub a: Field, b: Field, pub(crate) c: Field }";
+        match parse_with(struct_definition(), src).unwrap() {
+            TopLevelStatement::Struct(noir_struct) => {
+                assert_eq!(noir_struct.visibility, ItemVisibility::Public);
+                let fields = vecmap(noir_struct.fields, |(_, _, visibility)| visibility);
+                let expected =
+                    [ItemVisibility::Public, ItemVisibility::Private, ItemVisibility::PublicCrate];
+                assert_eq!(fields, expected);
+            }
+            _ => unreachable!(),
+        }
+
+        parse_all(use_statement(), vec!["pub use foo::bar", "pub(crate) use foo as bar"]);
+        parse_all(global_declaration(), vec!["pub global X = 1", "pub(crate) global Y = 2"]);
+        parse_all_failing(use_statement(), vec!["pub(foo) use bar", "pub() use bar"]);
+    }
+
+    #[test]
+    fn parse_multiple_attributes() {
+        use crate::token::{CustomAttribute, TestScope};
+
+        let src = "#[test] #[deploy(public, version = 2)] fn foo() {}";
+        let function = parse_with(function_definition(false), src).unwrap();
+        let custom = CustomAttribute {
+            name: "deploy".to_owned(),
+            arguments: vec!["public".to_owned(), "version = 2".to_owned()],
+        };
+        assert_eq!(
+            function.def.attributes,
+            vec![Attribute::Test(TestScope::None), Attribute::Custom(custom)]
+        );
+
+        let src = "#[foreign(sha256)] #[inline] fn sha256() {}";
+        let function = parse_with(function_definition(false), src).unwrap();
+        assert_eq!(function.kind, crate::FunctionKind::LowLevel);
+
+        // Loop attributes are not allowed on functions, misspelled attributes are warned about
+        // and a function can't be both builtin and foreign
+        let failing = vec![
+            "#[max_iterations(2)] fn foo() {}",
+            "#[tset] fn foo() {}",
+            "#[foreign(sha256)] #[builtin(sha256)] fn sha256() {}",
+            "#[builtin(to_le_bits)] #[builtin(to_be_bits)] fn to_bits() {}",
+        ];
+        parse_all_failing(function_definition(false), failing);
+    }
+
+    #[test]
+    fn parse_enums() {
+        let cases = vec![
+            "enum Empty { }",
+            "enum Direction { Up, Down, }",
+            "enum Option<T> { None, Some(T) }",
+            "enum Shape { Circle(Field), Rect(Field, Field), Unit }",
+        ];
+        parse_all(enum_definition(), cases);
+
+        let failing = vec!["enum { A }", "enum Foo { A: Field }", "enum Foo { A(Field }"];
+        parse_all_failing(enum_definition(), failing);
+    }
+
+    #[test]
+    fn parse_match_expr() {
+        let cases = vec![
+            "match x { }",
+            "match x { _ => 1 }",
+            "match x + 1 { 0 => a, 1 => b, n => n, }",
+            "match (a, b) { (true, _) => 1, (false, y) => y }",
+            "match shape { Shape::Circle(r) => r, Shape::Rect(w, _) => w, Shape::Unit => 0 }",
+            "match opt { Some(Some(x)) => { x }, _ => 0 }",
+        ];
+        parse_all(match_expr(expression()), cases);
+
+        let failing =
+            vec!["match x { 1 }", "match x { _ => }", "match { _ => 1 }", "match x { 1 + 2 => 3 }"];
+        parse_all_failing(match_expr(expression()), failing);
+    }
+
+    #[test]
+    fn parse_traits() {
+        let cases = vec![
+            "trait Empty { }",
+            "trait Hash { fn hash(self) -> Field; }",
+            "trait Combine { fn combine(self, other: Self) -> Self; fn reset(x: Field); }",
+        ];
+        parse_all(trait_definition(), cases);
+
+        let failing = vec!["trait { }", "trait Hash { fn hash(self) -> Field { 0 } }"];
+        parse_all_failing(trait_definition(), failing);
+    }
+
+    #[test]
+    fn parse_trait_impls() {
+        let cases = vec![
+            "impl Hash for Foo { fn hash(self) -> Field { self.x } }",
+            "impl<T: Hash> dep::std::Hash for Bar<T> { }",
+            "impl Eq for [Field; 2] { }",
+        ];
+        parse_all(trait_implementation(), cases);
+
+        let failing = vec!["impl Foo { }", "impl for Foo { }"];
+        parse_all_failing(trait_implementation(), failing);
+    }
+
+    #[test]
+    fn parse_trait_bounds() {
+        let cases = vec![
+            "fn foo<T: Hash>(x: T) { }",
+            "fn foo<T: Hash + crate::Eq, U, V: Eq>(x: T, y: U, z: V) { }",
+            "fn foo<T: Hash,>(x: T) { }",
+        ];
+        parse_all(function_definition(false), cases);
+
+        let failing = vec!["fn foo<T:>(x: T) { }", "fn foo<T: Hash +>(x: T) { }"];
+        parse_all_failing(function_definition(false), failing);
+    }
+
     #[test]
     fn parse_member_access() {
         let cases = vec!["a.b", "a + b.c", "foo.bar as i32"];